@@ -1,3 +1,6 @@
+use std::io::IsTerminal;
+
+use effigy::runner::TaskPickerOutcome;
 use effigy::ui::{MessageBlock, OutputMode, PlainRenderer, Renderer};
 use effigy::{
     apply_global_json_flag, command_requests_json, parse_command, render_cli_header, render_help,
@@ -9,6 +12,10 @@ fn main() {
     let raw_args: Vec<String> = std::env::args().skip(1).collect();
     let (args, global_json_mode) = strip_global_json_flags(raw_args);
     let output_mode = OutputMode::from_env();
+    let picker_eligible = args.is_empty()
+        && !global_json_mode
+        && std::io::stdin().is_terminal()
+        && std::io::stdout().is_terminal();
     let parsed = match parse_command(args) {
         Ok(cmd) => cmd,
         Err(err) => {
@@ -40,6 +47,23 @@ fn main() {
         }
     };
     let cmd = apply_global_json_flag(parsed, global_json_mode);
+    let cmd = if picker_eligible {
+        match effigy::runner::pick_task() {
+            Ok(TaskPickerOutcome::Selected(task)) => Command::Task(task),
+            Ok(TaskPickerOutcome::Cancelled) => return,
+            Ok(TaskPickerOutcome::Unavailable) => cmd,
+            Err(err) => {
+                let mut err_renderer = PlainRenderer::stderr(output_mode);
+                let _ = err_renderer.error_block(
+                    &MessageBlock::new("Task picker unavailable", err.to_string())
+                        .with_hint("Run `effigy tasks` to list tasks"),
+                );
+                cmd
+            }
+        }
+    } else {
+        cmd
+    };
     let suppress_header = command_requests_json(&cmd, global_json_mode);
     let emit_json_envelope = suppress_header;
     let (command_kind, command_name) = command_kind_and_name(&cmd);
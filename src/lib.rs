@@ -1,4 +1,6 @@
+pub mod notify;
 pub mod process_manager;
+pub mod recording;
 pub mod resolver;
 pub mod runner;
 pub mod tasks;
@@ -45,6 +47,9 @@ pub struct TasksArgs {
     pub resolve_selector: Option<String>,
     pub output_json: bool,
     pub pretty_json: bool,
+    /// Also scan directories excluded by `.gitignore`, `.ignore` and
+    /// `.effigyignore`.
+    pub no_ignore: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -221,6 +226,7 @@ where
     let mut resolve_selector: Option<String> = None;
     let mut output_json = false;
     let mut pretty_json = true;
+    let mut no_ignore = false;
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -245,6 +251,9 @@ where
             "--json" => {
                 output_json = true;
             }
+            "--no-ignore" => {
+                no_ignore = true;
+            }
             "--pretty" => {
                 let Some(value) = args.next() else {
                     return Err(CliParseError::MissingPrettyValue);
@@ -266,6 +275,7 @@ where
         resolve_selector,
         output_json,
         pretty_json,
+        no_ignore,
     }))
 }
 
@@ -419,6 +429,10 @@ fn render_general_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
                 "effigy unlock".to_owned(),
                 "Manually clear lock scopes (`workspace`, `task:*`, `profile:*/*`)".to_owned(),
             ],
+            vec![
+                "effigy replay".to_owned(),
+                "Replay a managed TUI session recorded with `--record <file>`".to_owned(),
+            ],
             vec![
                 "effigy <task>".to_owned(),
                 "Resolve task across discovered catalogs".to_owned(),
@@ -437,6 +451,10 @@ fn render_general_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
     renderer.key_values(&[
         ui::KeyValue::new("-h, --help", "Print this help panel"),
         ui::KeyValue::new("--json", "Render command-envelope JSON for CI/tooling"),
+        ui::KeyValue::new(
+            "--no-ignore",
+            "Also resolve <task> from catalogs in ignored directories",
+        ),
     ])?;
     Ok(())
 }
@@ -508,7 +526,7 @@ fn render_tasks_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
 
     renderer.section("Usage")?;
     renderer.text(
-        "effigy tasks [--repo <PATH>] [--task <TASK_NAME>] [--resolve <SELECTOR>] [--json] [--pretty true|false]",
+        "effigy tasks [--repo <PATH>] [--task <TASK_NAME>] [--resolve <SELECTOR>] [--no-ignore] [--json] [--pretty true|false]",
     )?;
     renderer.text("")?;
 
@@ -529,6 +547,10 @@ fn render_tasks_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
                 "Probe task routing evidence for a selector (for example `<catalog>/task` or `test`)"
                     .to_owned(),
             ],
+            vec![
+                "--no-ignore".to_owned(),
+                "Also discover catalogs in directories excluded by `.gitignore`, `.ignore` or `.effigyignore`".to_owned(),
+            ],
             vec![
                 "--json".to_owned(),
                 "Render machine-readable task catalog payload".to_owned(),
@@ -570,7 +592,9 @@ fn render_test_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
     renderer.text("")?;
 
     renderer.section("Usage")?;
-    renderer.text("effigy test [--plan] [--verbose-results] [--tui] [suite] [runner args]")?;
+    renderer.text(
+        "effigy test [--plan] [--verbose-results] [--tui] [--junit <PATH>] [suite] [runner args]",
+    )?;
     renderer.text("effigy test --help")?;
     renderer.text("")?;
     renderer.notice(
@@ -608,6 +632,10 @@ fn render_test_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
                 "Force TUI mode when interactive (auto-enabled when multiple suites are detected)"
                     .to_owned(),
             ],
+            vec![
+                "--junit <PATH>".to_owned(),
+                "Write a merged JUnit XML report of every target to PATH".to_owned(),
+            ],
             vec!["-h, --help".to_owned(), "Print command help".to_owned()],
         ],
     ))?;
@@ -618,8 +646,14 @@ fn render_test_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
         "runners",
         &[
             "vitest (package/config/bin markers)".to_owned(),
+            "jest (package/config/bin markers)".to_owned(),
+            "bun test (`bun test` script or bunfig.toml [test])".to_owned(),
             "cargo nextest run (when Cargo.toml exists and cargo-nextest is available)".to_owned(),
             "cargo test (Rust fallback)".to_owned(),
+            "pytest (pytest.ini, conftest.py or pytest config sections)".to_owned(),
+            "go test ./... (go.mod)".to_owned(),
+            "pest, then phpunit (composer/config/bin markers)".to_owned(),
+            "one runner per ecosystem (JS, Rust, Python, Go, PHP) runs for each target".to_owned(),
         ],
     )?;
     renderer.text("")?;
@@ -660,6 +694,7 @@ fn render_test_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
             "effigy test --plan viteest user-service".to_owned(),
             "effigy test --verbose-results".to_owned(),
             "effigy test --tui".to_owned(),
+            "effigy test --junit reports/junit.xml".to_owned(),
             "effigy test -- --runInBand".to_owned(),
             "effigy test -- --watch".to_owned(),
         ],
@@ -713,8 +748,14 @@ fn render_watch_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
     renderer.text("")?;
     renderer.section("Usage")?;
     renderer.text(
-        "effigy watch --owner <effigy|external> [--debounce-ms <MS>] [--include <GLOB>] [--exclude <GLOB>] <task> [task args]",
+        "effigy watch --owner <effigy|external> [--debounce-ms <MS>] [--include <GLOB>] [--exclude <GLOB>] [--poll] [--no-ignore] <task> [task args]",
     )?;
+    renderer.text(
+        "effigy watch --owner effigy --restart [--signal <SIG>] [--kill-timeout-ms <MS>] <task> [task args]",
+    )?;
+    renderer.text("effigy watch --owner effigy --restart --run <CMD>")?;
+    renderer.text("effigy watch --owner effigy [--map <GLOB>=<TASK>]...")?;
+    renderer.text("effigy watch --owner effigy --tui [--on-change <MODE>] <task> [task args]")?;
     renderer.text("effigy watch --owner effigy --once <task> [task args]")?;
     renderer.text("")?;
     renderer.section("Options")?;
@@ -738,6 +779,50 @@ fn render_watch_help<R: Renderer>(renderer: &mut R) -> UiResult<()> {
                 "--exclude <GLOB>".to_owned(),
                 "Optional repeatable exclude glob set, merged with default excludes (`.git/**`, `node_modules/**`, `target/**`).".to_owned(),
             ],
+            vec![
+                "--no-ignore".to_owned(),
+                "Also watch paths excluded by `.gitignore`, `.ignore` and `.effigyignore`, and resolve the target from catalogs there.".to_owned(),
+            ],
+            vec![
+                "--poll".to_owned(),
+                "Detect changes by rescanning the tree instead of inotify events (inotify is the Linux default; other platforms always poll).".to_owned(),
+            ],
+            vec![
+                "--restart".to_owned(),
+                "Keep the target running as a supervised child; on change, stop its process group and start it again (for servers and other long-running tasks).".to_owned(),
+            ],
+            vec![
+                "--signal <SIG>".to_owned(),
+                "Signal sent to the target's process group on restart or cancel (default: SIGTERM). Requires `--restart`, `--on-change cancel` or `--tui`.".to_owned(),
+            ],
+            vec![
+                "--kill-timeout-ms <MS>".to_owned(),
+                "How long to wait after the stop signal before sending SIGKILL (default: 5000). Requires `--restart`, `--on-change cancel` or `--tui`.".to_owned(),
+            ],
+            vec![
+                "--run <CMD>".to_owned(),
+                "Watch a shell command instead of a task (used for managed processes with `watch = {...}`).".to_owned(),
+            ],
+            vec![
+                "--on-change <MODE>".to_owned(),
+                "Changes during a run: `queue` (default) reruns once it finishes, `cancel` stops it and starts a fresh run, `ignore` drops them.".to_owned(),
+            ],
+            vec![
+                "--tui".to_owned(),
+                "Interactive dashboard: recent runs with status, duration and triggering files, plus live output. Keys: r rerun, p pause, c clear, q quit.".to_owned(),
+            ],
+            vec![
+                "--map <GLOB>=<TASK>".to_owned(),
+                "Watch group: rerun TASK when files matching GLOB change (repeatable). Without `--map` or a target, the root `[watch]` table's `map` is used.".to_owned(),
+            ],
+            vec![
+                "[tasks.<task>.watch]".to_owned(),
+                "Manifest defaults: `include`, `exclude`, `debounce_ms`, `restart`. `--include` replaces configured includes, `--exclude` adds to configured excludes.".to_owned(),
+            ],
+            vec![
+                "{changed}".to_owned(),
+                "Files that triggered the rerun (shell-quoted) in `run` or `--run`; also exported newline-separated as EFFIGY_CHANGED_FILES.".to_owned(),
+            ],
             vec![
                 "--once".to_owned(),
                 "Run target once with watch policy checks, then exit (useful for CI/contracts)."
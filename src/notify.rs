@@ -0,0 +1,128 @@
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::process::{Command as ProcessCommand, Stdio};
+use std::thread;
+
+pub const NOTIFY_ENV: &str = "EFFIGY_NOTIFY";
+pub const NOTIFY_COMMAND_ENV: &str = "EFFIGY_NOTIFY_COMMAND";
+
+/// Terminal notification mechanisms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyChannel {
+    /// Terminal bell (`BEL`).
+    Bell,
+    /// OSC 9 desktop notification (iTerm2, WezTerm, Windows Terminal, kitty).
+    Osc9,
+    /// OSC 777 desktop notification (foot, urxvt, VTE-based terminals).
+    Osc777,
+}
+
+impl NotifyChannel {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "bell" => Some(NotifyChannel::Bell),
+            "osc9" => Some(NotifyChannel::Osc9),
+            "osc777" => Some(NotifyChannel::Osc777),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    /// A managed process exited non-zero.
+    ProcessFailed,
+    /// A task command (including a run sequence) failed.
+    TaskFailed,
+    /// A `watch` rerun finished.
+    WatchRunFinished,
+}
+
+impl NotifyEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            NotifyEvent::ProcessFailed => "process-failed",
+            NotifyEvent::TaskFailed => "task-failed",
+            NotifyEvent::WatchRunFinished => "watch-run",
+        }
+    }
+}
+
+/// Where a task's notifications go.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notifier {
+    pub channels: Vec<NotifyChannel>,
+    /// Shell command run for each notification, with `EFFIGY_NOTIFY_EVENT` and
+    /// `EFFIGY_NOTIFY_MESSAGE` set.
+    pub command: Option<String>,
+}
+
+impl Notifier {
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty() && self.command.is_none()
+    }
+
+    /// Sends a notification. Terminal channels write to stderr when it is a
+    /// terminal; the command hook runs in the background from `cwd`.
+    pub fn send(&self, cwd: &Path, event: NotifyEvent, message: &str) {
+        let stderr = io::stderr();
+        if !self.channels.is_empty() && stderr.is_terminal() {
+            let _ = self.write_terminal(&mut stderr.lock(), message);
+        }
+        if let Some(command) = self.command.as_deref() {
+            let child = ProcessCommand::new("sh")
+                .arg("-c")
+                .arg(command)
+                .current_dir(cwd)
+                .env("EFFIGY_NOTIFY_EVENT", event.name())
+                .env("EFFIGY_NOTIFY_MESSAGE", message)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            if let Ok(mut child) = child {
+                thread::spawn(move || {
+                    let _ = child.wait();
+                });
+            }
+        }
+    }
+
+    /// Writes the escape sequences for the terminal channels.
+    pub fn write_terminal(&self, out: &mut impl Write, message: &str) -> io::Result<()> {
+        let message = message
+            .chars()
+            .filter(|ch| !ch.is_control())
+            .collect::<String>();
+        for channel in &self.channels {
+            match channel {
+                NotifyChannel::Bell => write!(out, "\u{7}")?,
+                NotifyChannel::Osc9 => write!(out, "\u{1b}]9;effigy: {message}\u{7}")?,
+                NotifyChannel::Osc777 => write!(out, "\u{1b}]777;notify;effigy;{message}\u{7}")?,
+            }
+        }
+        out.flush()
+    }
+}
+
+/// Parses `EFFIGY_NOTIFY`-style channel lists (`bell,osc9`).
+pub fn parse_channels(value: &str) -> Result<Vec<NotifyChannel>, String> {
+    let mut channels = Vec::new();
+    for name in value.split(',').filter(|name| !name.trim().is_empty()) {
+        let channel = NotifyChannel::parse(name).ok_or_else(|| {
+            format!(
+                "unknown notification channel `{}` (expected bell, osc9 or osc777)",
+                name.trim()
+            )
+        })?;
+        if !channels.contains(&channel) {
+            channels.push(channel);
+        }
+    }
+    Ok(channels)
+}
+
+#[cfg(test)]
+#[path = "tests/notify_tests.rs"]
+mod tests;
@@ -1,20 +1,31 @@
 use std::collections::HashMap;
+#[cfg(unix)]
+use std::fs::File;
 use std::io::{ErrorKind, Read, Write};
 #[cfg(unix)]
+use std::os::fd::AsRawFd;
+#[cfg(unix)]
 use std::os::unix::process::CommandExt;
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command as ProcessCommand, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::recording::{SessionRecorder, SessionRecording};
+
+#[cfg(unix)]
+use nix::pty::{openpty, Winsize};
+#[cfg(unix)]
+use nix::sys::resource::{getrlimit, getrusage, setrlimit, Resource, UsageWho};
 #[cfg(unix)]
 use nix::sys::signal::{kill, Signal};
 #[cfg(unix)]
-use nix::unistd::{setpgid, Pid};
+use nix::unistd::{setpgid, setsid, Pid};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProcessSpec {
@@ -22,7 +33,87 @@ pub struct ProcessSpec {
     pub run: String,
     pub cwd: PathBuf,
     pub start_after_ms: u64,
+    /// Runs the command on its own pseudo-terminal. Stdout and stderr then
+    /// arrive together as stdout events.
     pub pty: bool,
+    pub env: Vec<(String, String)>,
+    pub limits: ProcessLimits,
+    /// How the process is asked to stop on shutdown, stop and restart.
+    pub stop: ProcessStop,
+    /// Processes that keep running until this one has stopped at shutdown.
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProcessLimits {
+    /// Applied as `RLIMIT_DATA` (heap and private mappings), not `RLIMIT_AS`,
+    /// so runtimes that reserve large address ranges still start.
+    pub memory_mb: Option<u64>,
+    pub cpu_seconds: Option<u64>,
+    pub nofile: Option<u64>,
+}
+
+impl ProcessLimits {
+    pub fn is_empty(&self) -> bool {
+        self.memory_mb.is_none() && self.cpu_seconds.is_none() && self.nofile.is_none()
+    }
+}
+
+/// How a process is stopped. The process group gets `signal` (`SIGTERM` when
+/// unset) unless `run` names a stop command. With both, the command runs
+/// first and `signal` follows if the process outlives it. Either way it is
+/// force-killed once `timeout_ms` has passed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProcessStop {
+    pub signal: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub run: Option<String>,
+}
+
+impl ProcessStop {
+    fn timeout(&self, default: Duration) -> Duration {
+        self.timeout_ms.map_or(default, Duration::from_millis)
+    }
+
+    fn describe(&self) -> String {
+        match (&self.run, &self.signal) {
+            (Some(run), Some(signal)) => format!("run `{run}`, then {signal}"),
+            (Some(run), None) => format!("run `{run}`"),
+            (None, signal) => signal.as_deref().unwrap_or("SIGTERM").to_owned(),
+        }
+    }
+}
+
+/// Normalizes a stop signal name (`int`, `SIGINT`, `sigint`) to its `SIG`
+/// form. Returns `None` for names the platform does not know.
+pub fn parse_stop_signal(raw: &str) -> Option<String> {
+    let upper = raw.trim().to_ascii_uppercase();
+    let name = if upper.starts_with("SIG") {
+        upper
+    } else {
+        format!("SIG{upper}")
+    };
+    #[cfg(unix)]
+    {
+        name.parse::<Signal>().ok().map(|_| name)
+    }
+    #[cfg(not(unix))]
+    {
+        matches!(name.as_str(), "SIGTERM" | "SIGKILL").then_some(name)
+    }
+}
+
+/// Window size of a process's pseudo-terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtySize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for PtySize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,6 +123,10 @@ pub enum ProcessEventKind {
     StdoutChunk,
     StderrChunk,
     Exit,
+    /// A process joined a running session through [`ProcessSupervisor::add_process`].
+    Added,
+    /// A process left a running session through [`ProcessSupervisor::remove_process`].
+    Removed,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -59,6 +154,12 @@ pub enum ProcessManagerError {
     ProcessNotFound {
         process: String,
     },
+    ProcessExists {
+        process: String,
+    },
+    ProcessStopping {
+        process: String,
+    },
 }
 
 impl std::fmt::Display for ProcessManagerError {
@@ -81,25 +182,142 @@ impl std::fmt::Display for ProcessManagerError {
             ProcessManagerError::ProcessNotFound { process } => {
                 write!(f, "process `{process}` not found in managed supervisor")
             }
+            ProcessManagerError::ProcessExists { process } => {
+                write!(
+                    f,
+                    "process `{process}` is already managed by the supervisor"
+                )
+            }
+            ProcessManagerError::ProcessStopping { process } => {
+                write!(f, "process `{process}` is still stopping")
+            }
         }
     }
 }
 
 impl std::error::Error for ProcessManagerError {}
 
+type ProcessMap = Arc<Mutex<HashMap<String, Arc<Mutex<Child>>>>>;
+type StoppingChildren = Arc<Mutex<Vec<(String, Arc<Mutex<Child>>)>>>;
+
 pub struct ProcessSupervisor {
-    processes: Arc<Mutex<HashMap<String, Arc<Mutex<Child>>>>>,
-    specs: HashMap<String, ProcessSpec>,
+    processes: ProcessMap,
+    specs: Mutex<HashMap<String, ProcessSpec>>,
+    /// Master side of each running pseudo-terminal, keyed by process.
+    #[cfg(unix)]
+    ptys: Arc<Mutex<HashMap<String, File>>>,
+    /// Children a stop worker is still stopping, with the process they ran as.
+    stopping: StoppingChildren,
+    /// Set once shutdown starts so stop workers do not start replacements.
+    shutting_down: Arc<AtomicBool>,
+    /// Last size requested per process; restarts reuse it.
+    pty_sizes: Mutex<HashMap<String, PtySize>>,
     events_tx: Sender<ProcessEvent>,
     events_rx: Receiver<ProcessEvent>,
+    recorder: Mutex<Option<SessionRecorder>>,
+    /// Set for supervisors that replay a recording instead of running processes.
+    replaying: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ShutdownProgress {
-    SendingTerm,
-    Waiting,
-    ForceKilling,
-    Complete { total: usize, forced: usize },
+    /// A stop signal was sent or a stop command started; `method` says which.
+    Stopping {
+        process: String,
+        method: String,
+    },
+    Waiting {
+        processes: Vec<String>,
+    },
+    ForceKilling {
+        process: String,
+    },
+    Stopped {
+        process: String,
+        forced: bool,
+    },
+    Complete {
+        total: usize,
+        forced: usize,
+    },
+}
+
+/// A child handed to a stop worker, with the spec that says how to stop it.
+struct BackgroundStop {
+    process: String,
+    child: Arc<Mutex<Child>>,
+    spec: Option<ProcessSpec>,
+}
+
+/// A process a stop worker starts once its stops are done.
+struct BackgroundStart {
+    spec: ProcessSpec,
+    pty_size: PtySize,
+    honor_start_delay: bool,
+}
+
+/// The supervisor state a stop worker needs, shared so the worker can start
+/// replacements without borrowing the supervisor.
+#[derive(Clone)]
+struct ProcessTracker {
+    processes: ProcessMap,
+    #[cfg(unix)]
+    ptys: Arc<Mutex<HashMap<String, File>>>,
+    stopping: StoppingChildren,
+    shutting_down: Arc<AtomicBool>,
+    events_tx: Sender<ProcessEvent>,
+}
+
+impl ProcessTracker {
+    /// Adds a spawned process to the maps. During shutdown the process is
+    /// killed instead, so a late replacement cannot outlive the session.
+    fn track(&self, process: &str, spawned: SpawnedProcess) {
+        let mut processes = self.processes.lock().expect("process map lock");
+        if self.shutting_down.load(Ordering::SeqCst) {
+            force_stop(&spawned.child, None);
+            return;
+        }
+        processes.insert(process.to_owned(), spawned.child);
+        #[cfg(unix)]
+        {
+            let mut ptys = self.ptys.lock().expect("pty map lock");
+            match spawned.pty {
+                Some(master) => ptys.insert(process.to_owned(), master),
+                None => ptys.remove(process),
+            };
+        }
+    }
+
+    /// Starts a process from a stop worker. A spawn failure is reported as
+    /// the process's exit event, since no caller is waiting for a result.
+    fn start(&self, start: BackgroundStart) {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        match spawn_process_instance(
+            &start.spec,
+            &self.events_tx,
+            start.honor_start_delay,
+            start.pty_size,
+        ) {
+            Ok(spawned) => self.track(&start.spec.name, spawned),
+            Err(error) => {
+                let _ = self.events_tx.send(ProcessEvent {
+                    process: start.spec.name.clone(),
+                    kind: ProcessEventKind::Exit,
+                    payload: format!("start failed: {error}"),
+                    chunk: None,
+                });
+            }
+        }
+    }
+}
+
+struct PendingStop {
+    process: String,
+    child: Arc<Mutex<Child>>,
+    command: Option<Child>,
+    deadline: Instant,
 }
 
 impl ProcessSupervisor {
@@ -108,28 +326,102 @@ impl ProcessSupervisor {
         processes: Vec<ProcessSpec>,
     ) -> Result<Self, ProcessManagerError> {
         let (events_tx, events_rx) = mpsc::channel::<ProcessEvent>();
-        let mut process_map: HashMap<String, Arc<Mutex<Child>>> = HashMap::new();
-        let mut specs_map: HashMap<String, ProcessSpec> = HashMap::new();
-
+        let supervisor = Self {
+            processes: Arc::new(Mutex::new(HashMap::new())),
+            specs: Mutex::new(HashMap::new()),
+            #[cfg(unix)]
+            ptys: Arc::new(Mutex::new(HashMap::new())),
+            stopping: Arc::new(Mutex::new(Vec::new())),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            pty_sizes: Mutex::new(HashMap::new()),
+            events_tx,
+            events_rx,
+            recorder: Mutex::new(None),
+            replaying: false,
+        };
         for spec in processes {
-            let child = spawn_process_instance(&spec, &events_tx, true)?;
-            specs_map.insert(spec.name.clone(), spec.clone());
-            process_map.insert(spec.name.clone(), child);
+            let spawned =
+                spawn_process_instance(&spec, &supervisor.events_tx, true, PtySize::default())?;
+            supervisor.track(&spec.name, spawned);
+            supervisor
+                .specs
+                .lock()
+                .expect("spec map lock")
+                .insert(spec.name.clone(), spec);
         }
+        Ok(supervisor)
+    }
 
-        Ok(Self {
-            processes: Arc::new(Mutex::new(process_map)),
-            specs: specs_map,
+    /// Feeds a recorded session back as events without spawning processes.
+    /// Event timing is divided by `speed`; `None` sends everything at once.
+    pub fn replay(recording: SessionRecording, speed: Option<f64>) -> Self {
+        let (events_tx, events_rx) = mpsc::channel::<ProcessEvent>();
+        let tx = events_tx.clone();
+        thread::spawn(move || {
+            let started_at = Instant::now();
+            for recorded in recording.events {
+                if let Some(speed) = speed.filter(|speed| *speed > 0.0) {
+                    let due = Duration::from_millis(recorded.at_ms).div_f64(speed);
+                    if let Some(wait) = due.checked_sub(started_at.elapsed()) {
+                        thread::sleep(wait);
+                    }
+                }
+                if tx.send(recorded.event).is_err() {
+                    return;
+                }
+            }
+        });
+        Self {
+            processes: Arc::new(Mutex::new(HashMap::new())),
+            specs: Mutex::new(HashMap::new()),
+            #[cfg(unix)]
+            ptys: Arc::new(Mutex::new(HashMap::new())),
+            stopping: Arc::new(Mutex::new(Vec::new())),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            pty_sizes: Mutex::new(HashMap::new()),
             events_tx,
             events_rx,
-        })
+            recorder: Mutex::new(None),
+            replaying: true,
+        }
+    }
+
+    /// Records every event returned by [`Self::next_event_timeout`] to `path`.
+    pub fn record_to(&self, path: &Path, processes: &[String]) -> std::io::Result<()> {
+        let recorder = SessionRecorder::create(path, processes)?;
+        *self.recorder.lock().expect("recorder lock") = Some(recorder);
+        Ok(())
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.replaying
     }
 
     pub fn next_event_timeout(&self, timeout: Duration) -> Option<ProcessEvent> {
-        self.events_rx.recv_timeout(timeout).ok()
+        let event = self.events_rx.recv_timeout(timeout).ok()?;
+        let mut recorder = self.recorder.lock().expect("recorder lock");
+        if let Some(active) = recorder.as_mut() {
+            if active.record(&event).is_err() {
+                *recorder = None;
+            }
+        }
+        Some(event)
     }
 
     pub fn send_input(&self, process: &str, input: &str) -> Result<(), ProcessManagerError> {
+        #[cfg(unix)]
+        {
+            let mut ptys = self.ptys.lock().expect("pty map lock");
+            if let Some(master) = ptys.get_mut(process) {
+                return master
+                    .write_all(input.as_bytes())
+                    .and_then(|_| master.flush())
+                    .map_err(|error| ProcessManagerError::InputWrite {
+                        process: process.to_owned(),
+                        error,
+                    });
+            }
+        }
         let child = {
             let processes = self.processes.lock().expect("process map lock");
             processes.get(process).cloned()
@@ -153,16 +445,29 @@ impl ProcessSupervisor {
     }
 
     pub fn terminate_all(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
         let children = {
             let processes = self.processes.lock().expect("process map lock");
             processes.values().cloned().collect::<Vec<_>>()
         };
-        for child in children {
+        let stopping = self
+            .stopping
+            .lock()
+            .expect("stopping lock")
+            .iter()
+            .map(|(_, child)| child.clone())
+            .collect::<Vec<_>>();
+        for child in children.into_iter().chain(stopping) {
             let _ = child.lock().expect("child lock").kill();
         }
     }
 
+    /// Asks a process to stop on a worker thread and returns straight away.
+    /// The process keeps its tab; its exit arrives as a normal exit event.
     pub fn terminate_process(&self, process: &str) -> Result<(), ProcessManagerError> {
+        if self.replaying {
+            return Ok(());
+        }
         let child = {
             let processes = self.processes.lock().expect("process map lock");
             processes.get(process).cloned()
@@ -170,94 +475,354 @@ impl ProcessSupervisor {
         .ok_or_else(|| ProcessManagerError::ProcessNotFound {
             process: process.to_owned(),
         })?;
-        terminate_child_graceful(&child, Duration::from_millis(800));
+        self.ensure_not_stopping(process)?;
+        self.stop_in_background(
+            vec![BackgroundStop {
+                process: process.to_owned(),
+                child,
+                spec: self.spec(process),
+            }],
+            Vec::new(),
+        );
         Ok(())
     }
 
+    /// Stops a process and starts it again, both on a worker thread, so a
+    /// long stop timeout or stop command does not block the caller.
     pub fn restart_process(&self, process: &str) -> Result<(), ProcessManagerError> {
-        let spec = self.specs.get(process).cloned().ok_or_else(|| {
-            ProcessManagerError::ProcessNotFound {
+        if self.replaying {
+            return Ok(());
+        }
+        let spec = self
+            .spec(process)
+            .ok_or_else(|| ProcessManagerError::ProcessNotFound {
+                process: process.to_owned(),
+            })?;
+        self.ensure_not_stopping(process)?;
+        let child = self
+            .processes
+            .lock()
+            .expect("process map lock")
+            .remove(process);
+        let mut restart_spec = spec.clone();
+        restart_spec.start_after_ms = 0;
+        self.stop_in_background(
+            child
+                .map(|child| BackgroundStop {
+                    process: process.to_owned(),
+                    child,
+                    spec: Some(spec),
+                })
+                .into_iter()
+                .collect(),
+            vec![BackgroundStart {
+                spec: restart_spec,
+                pty_size: self.pty_size(process),
+                honor_start_delay: false,
+            }],
+        );
+        Ok(())
+    }
+
+    /// Whether a stop worker is still stopping (or restarting) `process`.
+    pub fn is_stopping(&self, process: &str) -> bool {
+        self.stopping
+            .lock()
+            .expect("stopping lock")
+            .iter()
+            .any(|(name, _)| name == process)
+    }
+
+    /// Names of processes a stop worker is still stopping.
+    pub fn stopping_processes(&self) -> Vec<String> {
+        let mut names = self
+            .stopping
+            .lock()
+            .expect("stopping lock")
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<String>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn ensure_not_stopping(&self, process: &str) -> Result<(), ProcessManagerError> {
+        if self.is_stopping(process) {
+            return Err(ProcessManagerError::ProcessStopping {
                 process: process.to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Stops `stops` in parallel on a worker thread, then starts `starts`
+    /// once every stop has finished (so a replacement never races the old
+    /// process for its port). The caller only waits for the bookkeeping.
+    fn stop_in_background(&self, stops: Vec<BackgroundStop>, starts: Vec<BackgroundStart>) {
+        self.stopping.lock().expect("stopping lock").extend(
+            stops
+                .iter()
+                .map(|stop| (stop.process.clone(), stop.child.clone())),
+        );
+        let tracker = self.tracker();
+        thread::spawn(move || {
+            thread::scope(|scope| {
+                for stop in &stops {
+                    scope.spawn(|| {
+                        stop_child(&stop.child, stop.spec.as_ref(), PROCESS_STOP_TIMEOUT)
+                    });
+                }
+            });
+            for start in starts {
+                tracker.start(start);
             }
-        })?;
+            tracker
+                .stopping
+                .lock()
+                .expect("stopping lock")
+                .retain(|(_, child)| !stops.iter().any(|stop| Arc::ptr_eq(child, &stop.child)));
+        });
+    }
+
+    fn tracker(&self) -> ProcessTracker {
+        ProcessTracker {
+            processes: self.processes.clone(),
+            #[cfg(unix)]
+            ptys: self.ptys.clone(),
+            stopping: self.stopping.clone(),
+            shutting_down: self.shutting_down.clone(),
+            events_tx: self.events_tx.clone(),
+        }
+    }
+
+    /// Sets the window size of a process's pseudo-terminal. The kernel sends
+    /// `SIGWINCH` to the process when the size changes. The size is kept for
+    /// restarts, and is ignored for processes without a pseudo-terminal.
+    pub fn resize_process(&self, process: &str, size: PtySize) {
+        if size.rows == 0 || size.cols == 0 {
+            return;
+        }
+        self.pty_sizes
+            .lock()
+            .expect("pty size lock")
+            .insert(process.to_owned(), size);
+        #[cfg(unix)]
+        if let Some(master) = self.ptys.lock().expect("pty map lock").get(process) {
+            set_pty_size(master, size);
+        }
+    }
+
+    fn pty_size(&self, process: &str) -> PtySize {
+        self.pty_sizes
+            .lock()
+            .expect("pty size lock")
+            .get(process)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn track(&self, process: &str, spawned: SpawnedProcess) {
+        self.tracker().track(process, spawned);
+    }
+
+    pub fn spec(&self, process: &str) -> Option<ProcessSpec> {
+        self.specs
+            .lock()
+            .expect("spec map lock")
+            .get(process)
+            .cloned()
+    }
+
+    /// Starts another process in a running session. Its events arrive on the
+    /// same channel as the processes the supervisor was spawned with. The
+    /// process starts on a worker thread, after its `start_after_ms`, so the
+    /// caller does not wait for the delay; a spawn failure arrives as its
+    /// exit event.
+    pub fn add_process(&self, spec: ProcessSpec) -> Result<(), ProcessManagerError> {
+        self.replace_processes(&[], vec![spec])
+    }
+
+    /// Forgets a process straight away and stops it (if it is still
+    /// running) on a worker thread, so it no longer appears in shutdown or
+    /// exit diagnostics and the caller does not wait for the stop.
+    pub fn remove_process(&self, process: &str) -> Result<(), ProcessManagerError> {
+        self.replace_processes(&[process.to_owned()], Vec::new())
+    }
+
+    /// Removes `remove` and adds `add` in one step: the removed processes
+    /// are forgotten at once and stopped on a worker thread, which then
+    /// starts the added ones, so a new process never races an old one for
+    /// its port. Every name is checked before anything changes.
+    pub fn replace_processes(
+        &self,
+        remove: &[String],
+        add: Vec<ProcessSpec>,
+    ) -> Result<(), ProcessManagerError> {
+        if self.replaying {
+            return Ok(());
+        }
         {
-            let processes = self.processes.lock().expect("process map lock");
-            if let Some(child) = processes.get(process) {
-                terminate_child_graceful(child, Duration::from_millis(800));
+            let specs = self.specs.lock().expect("spec map lock");
+            if let Some(process) = remove.iter().find(|name| !specs.contains_key(*name)) {
+                return Err(ProcessManagerError::ProcessNotFound {
+                    process: process.clone(),
+                });
+            }
+            if let Some(spec) = add
+                .iter()
+                .find(|spec| specs.contains_key(&spec.name) && !remove.contains(&spec.name))
+            {
+                return Err(ProcessManagerError::ProcessExists {
+                    process: spec.name.clone(),
+                });
             }
         }
-        let mut restart_spec = spec;
-        restart_spec.start_after_ms = 0;
-        let replacement = spawn_process_instance(&restart_spec, &self.events_tx, false)?;
-        let mut processes = self.processes.lock().expect("process map lock");
-        processes.insert(process.to_owned(), replacement);
+        for spec in &add {
+            self.ensure_not_stopping(&spec.name)?;
+            preflight_process(spec)?;
+        }
+
+        let mut stops = Vec::new();
+        for process in remove {
+            let spec = self.specs.lock().expect("spec map lock").remove(process);
+            let child = self
+                .processes
+                .lock()
+                .expect("process map lock")
+                .remove(process);
+            #[cfg(unix)]
+            self.ptys.lock().expect("pty map lock").remove(process);
+            self.pty_sizes
+                .lock()
+                .expect("pty size lock")
+                .remove(process);
+            self.send_membership(process, ProcessEventKind::Removed);
+            if let Some(child) = child {
+                stops.push(BackgroundStop {
+                    process: process.clone(),
+                    child,
+                    spec,
+                });
+            }
+        }
+        let mut starts = Vec::new();
+        for spec in add {
+            // Sent before the spawn so recordings see the tab ahead of its output.
+            self.send_membership(&spec.name, ProcessEventKind::Added);
+            self.specs
+                .lock()
+                .expect("spec map lock")
+                .insert(spec.name.clone(), spec.clone());
+            starts.push(BackgroundStart {
+                pty_size: self.pty_size(&spec.name),
+                spec,
+                honor_start_delay: true,
+            });
+        }
+        self.stop_in_background(stops, starts);
         Ok(())
     }
 
+    fn send_membership(&self, process: &str, kind: ProcessEventKind) {
+        let _ = self.events_tx.send(ProcessEvent {
+            process: process.to_owned(),
+            kind,
+            payload: String::new(),
+            chunk: None,
+        });
+    }
+
     pub fn terminate_all_graceful(&self, timeout: Duration) {
         self.terminate_all_graceful_with_progress(timeout, |_| {});
     }
 
+    /// Stops every process, dependents before the processes they depend on.
+    /// Each process gets its own stop method and timeout; `timeout` applies
+    /// when its spec sets none.
     pub fn terminate_all_graceful_with_progress<F>(&self, timeout: Duration, mut on_progress: F)
     where
         F: FnMut(ShutdownProgress),
     {
-        on_progress(ShutdownProgress::SendingTerm);
-        let children = {
-            let processes = self.processes.lock().expect("process map lock");
-            processes.values().cloned().collect::<Vec<_>>()
-        };
-        for child in &children {
-            let mut child = child.lock().expect("child lock");
-            #[cfg(unix)]
-            {
-                let _ = signal_process_group(&mut child, Signal::SIGTERM);
-            }
-            #[cfg(not(unix))]
-            {
-                let _ = child.kill();
-            }
+        self.shutting_down.store(true, Ordering::SeqCst);
+        let mut children = self
+            .processes
+            .lock()
+            .expect("process map lock")
+            .iter()
+            .map(|(name, child)| (name.clone(), child.clone()))
+            .collect::<HashMap<String, Arc<Mutex<Child>>>>();
+        // Children a stop worker has not finished with yet are stopped here
+        // too, so quitting mid-restart does not leave them running.
+        for (name, child) in self.stopping.lock().expect("stopping lock").iter() {
+            children
+                .entry(name.clone())
+                .or_insert_with(|| child.clone());
         }
-
-        on_progress(ShutdownProgress::Waiting);
-        let deadline = Instant::now() + timeout;
-        while Instant::now() < deadline {
-            let all_exited = children.iter().all(|child| {
-                child
-                    .lock()
-                    .expect("child lock")
-                    .try_wait()
-                    .ok()
-                    .flatten()
-                    .is_some()
-            });
-            if all_exited {
-                on_progress(ShutdownProgress::Complete {
-                    total: children.len(),
-                    forced: 0,
+        let specs = self.specs.lock().expect("spec map lock").clone();
+        let mut forced = 0usize;
+        for wave in shutdown_waves(&children, &specs) {
+            let mut pending = Vec::<PendingStop>::new();
+            for process in wave {
+                let child = children[&process].clone();
+                if child_exited(&child) {
+                    continue;
+                }
+                let spec = specs.get(&process);
+                on_progress(ShutdownProgress::Stopping {
+                    process: process.clone(),
+                    method: spec.map_or_else(|| "SIGTERM".to_owned(), |spec| spec.stop.describe()),
+                });
+                let command = begin_stop(&child, spec);
+                let deadline =
+                    Instant::now() + spec.map_or(timeout, |spec| spec.stop.timeout(timeout));
+                pending.push(PendingStop {
+                    process,
+                    child,
+                    command,
+                    deadline,
                 });
-                return;
             }
-            thread::sleep(Duration::from_millis(40));
-        }
-
-        on_progress(ShutdownProgress::ForceKilling);
-        let mut forced = 0usize;
-        for child in &children {
-            let mut child = child.lock().expect("child lock");
-            let still_running = child.try_wait().ok().flatten().is_none();
-            if !still_running {
+            if pending.is_empty() {
                 continue;
             }
-            #[cfg(unix)]
-            {
-                let _ = signal_process_group(&mut child, Signal::SIGKILL);
-                forced += 1;
-            }
-            #[cfg(not(unix))]
-            {
-                let _ = child.kill();
-                forced += 1;
+            on_progress(ShutdownProgress::Waiting {
+                processes: pending.iter().map(|stop| stop.process.clone()).collect(),
+            });
+            while !pending.is_empty() {
+                let now = Instant::now();
+                pending.retain_mut(|stop| {
+                    follow_stop_command(&stop.child, specs.get(&stop.process), &mut stop.command);
+                    let done = child_exited(&stop.child)
+                        && stop
+                            .command
+                            .as_mut()
+                            .is_none_or(|command| command.try_wait().ok().flatten().is_some());
+                    if done {
+                        on_progress(ShutdownProgress::Stopped {
+                            process: stop.process.clone(),
+                            forced: false,
+                        });
+                        return false;
+                    }
+                    if now < stop.deadline {
+                        return true;
+                    }
+                    on_progress(ShutdownProgress::ForceKilling {
+                        process: stop.process.clone(),
+                    });
+                    let was_running = !child_exited(&stop.child);
+                    force_stop(&stop.child, stop.command.take());
+                    if was_running {
+                        forced += 1;
+                    }
+                    on_progress(ShutdownProgress::Stopped {
+                        process: stop.process.clone(),
+                        forced: was_running,
+                    });
+                    false
+                });
+                thread::sleep(Duration::from_millis(40));
             }
         }
         on_progress(ShutdownProgress::Complete {
@@ -266,15 +831,23 @@ impl ProcessSupervisor {
         });
     }
 
+    pub fn process_ids(&self) -> Vec<(String, u32)> {
+        let process_map = self.processes.lock().expect("process map lock");
+        process_map
+            .iter()
+            .map(|(name, child)| (name.clone(), child.lock().expect("child lock").id()))
+            .collect()
+    }
+
     pub fn exit_diagnostics(&self) -> Vec<(String, String)> {
         let process_map = self.processes.lock().expect("process map lock");
-        let mut diagnostics = self
-            .specs
+        let specs = self.specs.lock().expect("spec map lock");
+        let mut diagnostics = specs
             .iter()
-            .map(|(name, _)| {
+            .map(|(name, spec)| {
                 let diagnostic = if let Some(child) = process_map.get(name) {
                     match child.lock().expect("child lock").try_wait() {
-                        Ok(Some(status)) => format_exit_diagnostic(status),
+                        Ok(Some(status)) => format_exit_diagnostic(status, spec.limits),
                         Ok(None) => "running".to_owned(),
                         Err(err) => format!("wait-error={err}"),
                     }
@@ -289,36 +862,210 @@ impl ProcessSupervisor {
     }
 }
 
-fn format_exit_diagnostic(status: std::process::ExitStatus) -> String {
+fn format_exit_diagnostic(status: std::process::ExitStatus, limits: ProcessLimits) -> String {
     #[cfg(unix)]
     {
         if let Some(code) = status.code() {
             return format!("exit={code}");
         }
         if let Some(signal) = status.signal() {
-            return format!("signal={signal}");
+            if let Some(breach) = describe_limit_breach(status, limits, None) {
+                return format!("signal={signal} ({breach})");
+            }
+            return match describe_limit_crash(status, limits) {
+                Some(note) => format!("signal={signal}, {note}"),
+                None => format!("signal={signal}"),
+            };
         }
         "exit=unknown".to_owned()
     }
     #[cfg(not(unix))]
     {
+        let _ = limits;
         format!("exit={}", status.code().unwrap_or(-1))
     }
 }
 
+/// Share of `memory_mb` the peak RSS must reach before a crash is blamed on the limit.
+const MEMORY_BREACH_PEAK_PERCENT: u64 = 90;
+
+/// Maps a terminating signal back to the configured limit that raised it. CPU breaches
+/// are exact (`SIGXCPU`). A memory breach is only claimed when `peak_rss_bytes` shows
+/// the process got close to `memory_mb` before it was killed or crashed.
+pub fn describe_limit_breach(
+    status: std::process::ExitStatus,
+    limits: ProcessLimits,
+    peak_rss_bytes: Option<u64>,
+) -> Option<String> {
+    #[cfg(not(unix))]
+    {
+        let _ = (status, limits, peak_rss_bytes);
+        None
+    }
+    #[cfg(unix)]
+    {
+        let signal = status.signal()?;
+        limit_breach_for_signal(signal, limits, peak_rss_bytes)
+    }
+}
+
+/// Notes a crash signal that arrived while a memory limit was set, without claiming
+/// the limit caused it, e.g. `crashed with SIGSEGV (a memory limit of 64MB is set)`.
+pub fn describe_limit_crash(
+    status: std::process::ExitStatus,
+    limits: ProcessLimits,
+) -> Option<String> {
+    #[cfg(not(unix))]
+    {
+        let _ = (status, limits);
+        None
+    }
+    #[cfg(unix)]
+    {
+        let signal = status.signal()?;
+        if !is_crash_signal(signal) {
+            return None;
+        }
+        let memory_mb = limits.memory_mb?;
+        let name = Signal::try_from(signal).ok()?;
+        Some(format!(
+            "crashed with {} (a memory limit of {memory_mb}MB is set)",
+            name.as_str()
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn is_crash_signal(signal: i32) -> bool {
+    signal == Signal::SIGABRT as i32
+        || signal == Signal::SIGSEGV as i32
+        || signal == Signal::SIGBUS as i32
+}
+
+#[cfg(unix)]
+fn limit_breach_for_signal(
+    signal: i32,
+    limits: ProcessLimits,
+    peak_rss_bytes: Option<u64>,
+) -> Option<String> {
+    if signal == Signal::SIGXCPU as i32 {
+        return limits
+            .cpu_seconds
+            .map(|seconds| format!("cpu limit of {seconds}s exceeded"));
+    }
+    if signal != Signal::SIGKILL as i32 && !is_crash_signal(signal) {
+        return None;
+    }
+    let memory_mb = limits.memory_mb?;
+    let peak_rss_bytes = peak_rss_bytes?;
+    let limit_bytes = memory_mb.saturating_mul(1024 * 1024);
+    if peak_rss_bytes.saturating_mul(100) < limit_bytes.saturating_mul(MEMORY_BREACH_PEAK_PERCENT) {
+        return None;
+    }
+    Some(format!(
+        "memory limit of {memory_mb}MB likely exceeded (peak rss {}MB)",
+        peak_rss_bytes / (1024 * 1024)
+    ))
+}
+
+/// Peak resident set size of the largest child this process has waited for. Linux
+/// reports `ru_maxrss` in kilobytes, macOS in bytes.
+pub fn children_peak_rss_bytes() -> Option<u64> {
+    #[cfg(not(unix))]
+    {
+        None
+    }
+    #[cfg(unix)]
+    {
+        let usage = getrusage(UsageWho::RUSAGE_CHILDREN).ok()?;
+        let max_rss = u64::try_from(usage.max_rss()).ok()?;
+        if cfg!(target_os = "macos") {
+            Some(max_rss)
+        } else {
+            Some(max_rss.saturating_mul(1024))
+        }
+    }
+}
+
+/// Applies `limits` as soft rlimits in the child before exec. Hard limits are left
+/// untouched so `SIGXCPU` is delivered before the kernel escalates to `SIGKILL`.
+pub fn apply_process_limits(process: &mut ProcessCommand, limits: ProcessLimits) {
+    if limits.is_empty() {
+        return;
+    }
+    #[cfg(unix)]
+    unsafe {
+        process.pre_exec(move || {
+            let requested = [
+                (
+                    Resource::RLIMIT_DATA,
+                    limits
+                        .memory_mb
+                        .map(|memory_mb| memory_mb.saturating_mul(1024 * 1024)),
+                ),
+                (Resource::RLIMIT_CPU, limits.cpu_seconds),
+                (Resource::RLIMIT_NOFILE, limits.nofile),
+            ];
+            for (resource, value) in requested {
+                let Some(value) = value else {
+                    continue;
+                };
+                let (_, hard) = getrlimit(resource).map_err(std::io::Error::from)?;
+                setrlimit(resource, value.min(hard), hard).map_err(std::io::Error::from)?;
+            }
+            Ok(())
+        });
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = process;
+    }
+}
+
+/// Stop timeout for a single process (stop, restart, close) when its spec
+/// sets none.
+const PROCESS_STOP_TIMEOUT: Duration = Duration::from_millis(800);
+
+struct SpawnedProcess {
+    child: Arc<Mutex<Child>>,
+    #[cfg(unix)]
+    pty: Option<File>,
+}
+
+/// Catches the spawn failures that can be seen before stopping anything, so
+/// a profile switch does not tear down processes it cannot replace.
+pub fn preflight_process(spec: &ProcessSpec) -> Result<(), ProcessManagerError> {
+    if spec.cwd.is_dir() {
+        return Ok(());
+    }
+    Err(ProcessManagerError::Spawn {
+        process: spec.name.clone(),
+        command: spec.run.clone(),
+        error: std::io::Error::new(
+            ErrorKind::NotFound,
+            format!("working directory `{}` does not exist", spec.cwd.display()),
+        ),
+    })
+}
+
 fn spawn_process_instance(
     spec: &ProcessSpec,
     events_tx: &Sender<ProcessEvent>,
     honor_start_delay: bool,
-) -> Result<Arc<Mutex<Child>>, ProcessManagerError> {
+    pty_size: PtySize,
+) -> Result<SpawnedProcess, ProcessManagerError> {
     if honor_start_delay && spec.start_after_ms > 0 {
         thread::sleep(Duration::from_millis(spec.start_after_ms));
     }
-    let mut process = if spec.pty {
-        spawn_with_pty_wrapper(spec)
-    } else {
-        spawn_plain_shell(spec)
-    };
+    #[cfg(unix)]
+    if spec.pty {
+        return spawn_pty_instance(spec, events_tx, pty_size);
+    }
+    #[cfg(not(unix))]
+    let _ = pty_size;
+    let mut process = spawn_plain_shell(spec);
+    process.envs(spec.env.iter().map(|(key, value)| (key, value)));
+    apply_process_limits(&mut process, spec.limits);
     let mut child = process
         .spawn()
         .map_err(|error| ProcessManagerError::Spawn {
@@ -341,60 +1088,137 @@ fn spawn_process_instance(
         })?;
 
     let child = Arc::new(Mutex::new(child));
-    attach_child_stream_threads(spec.name.clone(), child.clone(), stdout, stderr, events_tx);
-    Ok(child)
-}
-
-fn attach_child_stream_threads(
-    process_name: String,
-    child: Arc<Mutex<Child>>,
-    stdout: impl std::io::Read + Send + 'static,
-    stderr: impl std::io::Read + Send + 'static,
-    events_tx: &Sender<ProcessEvent>,
-) {
     spawn_stream_thread(
-        process_name.clone(),
+        spec.name.clone(),
         stdout,
         ProcessEventKind::Stdout,
         ProcessEventKind::StdoutChunk,
         events_tx.clone(),
     );
     spawn_stream_thread(
-        process_name.clone(),
+        spec.name.clone(),
         stderr,
         ProcessEventKind::Stderr,
         ProcessEventKind::StderrChunk,
         events_tx.clone(),
     );
+    spawn_exit_thread(spec.name.clone(), child.clone(), spec.limits, events_tx);
+    Ok(SpawnedProcess {
+        child,
+        #[cfg(unix)]
+        pty: None,
+    })
+}
 
-    {
-        let tx = events_tx.clone();
-        thread::spawn(move || loop {
-            let status = child.lock().expect("child lock").try_wait();
-            match status {
-                Ok(Some(status)) => {
-                    let payload = format_exit_diagnostic(status);
-                    let _ = tx.send(ProcessEvent {
-                        process: process_name.clone(),
-                        kind: ProcessEventKind::Exit,
-                        payload,
-                        chunk: None,
-                    });
-                    break;
-                }
-                Ok(None) => thread::sleep(Duration::from_millis(40)),
-                Err(err) => {
-                    let _ = tx.send(ProcessEvent {
-                        process: process_name.clone(),
-                        kind: ProcessEventKind::Exit,
-                        payload: format!("wait-error={err}"),
-                        chunk: None,
-                    });
-                    break;
-                }
+/// Runs `spec` on a new pseudo-terminal. The child starts its own session with
+/// the terminal as controlling tty, so it sees `isatty`, receives `SIGWINCH` on
+/// resize, and its session id doubles as the process group we signal.
+#[cfg(unix)]
+fn spawn_pty_instance(
+    spec: &ProcessSpec,
+    events_tx: &Sender<ProcessEvent>,
+    size: PtySize,
+) -> Result<SpawnedProcess, ProcessManagerError> {
+    let spawn_error = |error: std::io::Error| ProcessManagerError::Spawn {
+        process: spec.name.clone(),
+        command: spec.run.clone(),
+        error,
+    };
+    let pty = openpty(&pty_winsize(size), None).map_err(|error| spawn_error(error.into()))?;
+    let master = File::from(pty.master);
+    let slave = File::from(pty.slave);
+
+    let mut process = ProcessCommand::new("sh");
+    process
+        .arg("-lc")
+        .arg(&spec.run)
+        .current_dir(&spec.cwd)
+        .stdin(Stdio::from(slave.try_clone().map_err(spawn_error)?))
+        .stdout(Stdio::from(slave.try_clone().map_err(spawn_error)?))
+        .stderr(Stdio::from(slave));
+    unsafe {
+        process.pre_exec(|| {
+            setsid().map_err(std::io::Error::from)?;
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(std::io::Error::last_os_error());
             }
+            Ok(())
         });
     }
+    with_local_node_bin_path(&mut process, &spec.cwd);
+    process.envs(spec.env.iter().map(|(key, value)| (key, value)));
+    apply_process_limits(&mut process, spec.limits);
+    let child = process.spawn().map_err(spawn_error)?;
+    // The command holds the parent's copies of the slave; the reader only sees
+    // end-of-file once they are closed.
+    drop(process);
+
+    let reader = master.try_clone().map_err(spawn_error)?;
+    let child = Arc::new(Mutex::new(child));
+    spawn_stream_thread(
+        spec.name.clone(),
+        reader,
+        ProcessEventKind::Stdout,
+        ProcessEventKind::StdoutChunk,
+        events_tx.clone(),
+    );
+    spawn_exit_thread(spec.name.clone(), child.clone(), spec.limits, events_tx);
+    Ok(SpawnedProcess {
+        child,
+        pty: Some(master),
+    })
+}
+
+#[cfg(unix)]
+fn pty_winsize(size: PtySize) -> Winsize {
+    Winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+#[cfg(unix)]
+fn set_pty_size(master: &File, size: PtySize) {
+    let winsize = pty_winsize(size);
+    unsafe {
+        libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ as _, &winsize);
+    }
+}
+
+fn spawn_exit_thread(
+    process_name: String,
+    child: Arc<Mutex<Child>>,
+    limits: ProcessLimits,
+    events_tx: &Sender<ProcessEvent>,
+) {
+    let tx = events_tx.clone();
+    thread::spawn(move || loop {
+        let status = child.lock().expect("child lock").try_wait();
+        match status {
+            Ok(Some(status)) => {
+                let payload = format_exit_diagnostic(status, limits);
+                let _ = tx.send(ProcessEvent {
+                    process: process_name.clone(),
+                    kind: ProcessEventKind::Exit,
+                    payload,
+                    chunk: None,
+                });
+                break;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(40)),
+            Err(err) => {
+                let _ = tx.send(ProcessEvent {
+                    process: process_name.clone(),
+                    kind: ProcessEventKind::Exit,
+                    payload: format!("wait-error={err}"),
+                    chunk: None,
+                });
+                break;
+            }
+        }
+    });
 }
 
 fn spawn_stream_thread(
@@ -468,32 +1292,95 @@ fn decode_line(line: &[u8]) -> String {
     String::from_utf8_lossy(slice).into_owned()
 }
 
-fn terminate_child_graceful(child: &Arc<Mutex<Child>>, timeout: Duration) {
-    {
-        let mut child = child.lock().expect("child lock");
-        #[cfg(unix)]
-        {
-            let _ = signal_process_group(&mut child, Signal::SIGTERM);
-        }
-        #[cfg(not(unix))]
-        {
-            let _ = child.kill();
+/// Groups processes into shutdown waves. A process is stopped in an earlier
+/// wave than every process it depends on; a dependency cycle ends the
+/// ordering and stops the rest together.
+fn shutdown_waves(
+    children: &HashMap<String, Arc<Mutex<Child>>>,
+    specs: &HashMap<String, ProcessSpec>,
+) -> Vec<Vec<String>> {
+    let mut remaining = children.keys().cloned().collect::<Vec<String>>();
+    remaining.sort();
+    let mut waves = Vec::new();
+    while !remaining.is_empty() {
+        let needed = |name: &String| {
+            remaining.iter().any(|other| {
+                specs
+                    .get(other)
+                    .is_some_and(|spec| spec.depends_on.contains(name))
+            })
+        };
+        let (blocked, wave): (Vec<String>, Vec<String>) =
+            remaining.iter().cloned().partition(|name| needed(name));
+        if wave.is_empty() {
+            waves.push(blocked);
+            break;
         }
+        waves.push(wave);
+        remaining = blocked;
     }
-    let deadline = Instant::now() + timeout;
-    while Instant::now() < deadline {
-        let exited = child
-            .lock()
-            .expect("child lock")
-            .try_wait()
-            .ok()
-            .flatten()
-            .is_some();
-        if exited {
-            return;
+    waves
+}
+
+fn child_exited(child: &Arc<Mutex<Child>>) -> bool {
+    child
+        .lock()
+        .expect("child lock")
+        .try_wait()
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Sends the process its stop signal, or starts its stop command. Falls back
+/// to the stop signal (`SIGTERM` when unset) when the stop command cannot be
+/// spawned.
+fn begin_stop(child: &Arc<Mutex<Child>>, spec: Option<&ProcessSpec>) -> Option<Child> {
+    if let Some((spec, run)) = spec.and_then(|spec| Some((spec, spec.stop.run.as_ref()?))) {
+        if let Ok(command) = spawn_stop_command(spec, run) {
+            return Some(command);
         }
-        thread::sleep(Duration::from_millis(30));
     }
+    send_stop_signal(child, spec);
+    None
+}
+
+/// Once the stop command has exited, sends `stop.signal` if one is set and
+/// the process is still running.
+fn follow_stop_command(
+    child: &Arc<Mutex<Child>>,
+    spec: Option<&ProcessSpec>,
+    command: &mut Option<Child>,
+) {
+    let finished = command
+        .as_mut()
+        .is_some_and(|command| command.try_wait().ok().flatten().is_some());
+    if !finished {
+        return;
+    }
+    *command = None;
+    if spec.is_some_and(|spec| spec.stop.signal.is_some()) && !child_exited(child) {
+        send_stop_signal(child, spec);
+    }
+}
+
+fn send_stop_signal(child: &Arc<Mutex<Child>>, spec: Option<&ProcessSpec>) {
+    let mut child = child.lock().expect("child lock");
+    #[cfg(unix)]
+    {
+        let signal = spec
+            .and_then(|spec| spec.stop.signal.as_deref())
+            .and_then(|name| name.parse::<Signal>().ok())
+            .unwrap_or(Signal::SIGTERM);
+        let _ = signal_process_group(&mut child, signal);
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = child.kill();
+    }
+}
+
+fn force_stop(child: &Arc<Mutex<Child>>, command: Option<Child>) {
     let mut child = child.lock().expect("child lock");
     #[cfg(unix)]
     {
@@ -503,6 +1390,54 @@ fn terminate_child_graceful(child: &Arc<Mutex<Child>>, timeout: Duration) {
     {
         let _ = child.kill();
     }
+    if let Some(mut command) = command {
+        #[cfg(unix)]
+        {
+            let _ = signal_process_group(&mut command, Signal::SIGKILL);
+        }
+        let _ = command.kill();
+        let _ = command.wait();
+    }
+}
+
+/// Stops one process and waits for it, force-killing it after its timeout.
+fn stop_child(child: &Arc<Mutex<Child>>, spec: Option<&ProcessSpec>, default_timeout: Duration) {
+    if child_exited(child) {
+        return;
+    }
+    let mut command = begin_stop(child, spec);
+    let deadline =
+        Instant::now() + spec.map_or(default_timeout, |spec| spec.stop.timeout(default_timeout));
+    while Instant::now() < deadline {
+        follow_stop_command(child, spec, &mut command);
+        let command_done = command
+            .as_mut()
+            .is_none_or(|command| command.try_wait().ok().flatten().is_some());
+        if child_exited(child) && command_done {
+            return;
+        }
+        thread::sleep(Duration::from_millis(30));
+    }
+    force_stop(child, command);
+}
+
+fn spawn_stop_command(spec: &ProcessSpec, run: &str) -> std::io::Result<Child> {
+    let mut process = ProcessCommand::new("sh");
+    process
+        .arg("-lc")
+        .arg(run)
+        .current_dir(&spec.cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    unsafe {
+        process
+            .pre_exec(|| setpgid(Pid::from_raw(0), Pid::from_raw(0)).map_err(std::io::Error::from));
+    }
+    with_local_node_bin_path(&mut process, &spec.cwd);
+    process.envs(spec.env.iter().map(|(key, value)| (key, value)));
+    process.spawn()
 }
 
 fn spawn_plain_shell(spec: &ProcessSpec) -> ProcessCommand {
@@ -525,35 +1460,6 @@ fn spawn_plain_shell(spec: &ProcessSpec) -> ProcessCommand {
     process
 }
 
-fn spawn_with_pty_wrapper(spec: &ProcessSpec) -> ProcessCommand {
-    #[cfg(target_os = "macos")]
-    {
-        let mut process = ProcessCommand::new("script");
-        process
-            .arg("-q")
-            .arg("/dev/null")
-            .arg("sh")
-            .arg("-lc")
-            .arg(&spec.run)
-            .current_dir(&spec.cwd)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-        #[cfg(unix)]
-        unsafe {
-            process.pre_exec(|| {
-                setpgid(Pid::from_raw(0), Pid::from_raw(0))
-                    .map_err(|error| std::io::Error::new(ErrorKind::Other, error.to_string()))
-            });
-        }
-        with_local_node_bin_path(&mut process, &spec.cwd);
-        return process;
-    }
-
-    #[allow(unreachable_code)]
-    spawn_plain_shell(spec)
-}
-
 fn with_local_node_bin_path(process: &mut ProcessCommand, cwd: &Path) {
     let local_bin = cwd.join("node_modules/.bin");
     if !local_bin.is_dir() {
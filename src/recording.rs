@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use serde_json::{json, Value};
+
+use crate::process_manager::{ProcessEvent, ProcessEventKind};
+
+pub const RECORDING_SCHEMA: &str = "effigy.tui.recording.v1";
+
+/// A process event and the time it was received, relative to the start of
+/// the recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent {
+    pub at_ms: u64,
+    pub event: ProcessEvent,
+}
+
+/// A recorded managed session: the tab order at the start plus every event in
+/// arrival order. Tabs opened or closed later show up as `added`/`removed`
+/// events.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SessionRecording {
+    pub processes: Vec<String>,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl SessionRecording {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+        Self::parse(&source).map_err(|detail| io::Error::new(io::ErrorKind::InvalidData, detail))
+    }
+
+    /// Parses the JSON-lines format written by [`SessionRecorder`]: a header
+    /// line with the schema and process names, then one line per event.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut lines = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let Some((_, header)) = lines.next() else {
+            return Err("recording is empty".to_owned());
+        };
+        let header = serde_json::from_str::<Value>(header)
+            .map_err(|error| format!("line 1: invalid header: {error}"))?;
+        if header.get("schema").and_then(Value::as_str) != Some(RECORDING_SCHEMA) {
+            return Err(format!(
+                "line 1: expected schema `{RECORDING_SCHEMA}` in the header"
+            ));
+        }
+        let processes = header
+            .get("processes")
+            .and_then(Value::as_array)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_owned)
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+
+        let mut events = Vec::new();
+        for (index, line) in lines {
+            let event =
+                parse_event_line(line).map_err(|detail| format!("line {}: {detail}", index + 1))?;
+            events.push(event);
+        }
+        Ok(Self { processes, events })
+    }
+
+    /// Tabs open when a replay starts: the header's processes, plus any
+    /// process with events but no earlier `added` event (recordings made
+    /// before membership events existed). The rest open at their `added` event.
+    pub fn initial_tabs(&self) -> Vec<String> {
+        let mut tabs = self.processes.clone();
+        let mut added_later = HashSet::<&str>::new();
+        for recorded in &self.events {
+            let process = recorded.event.process.as_str();
+            if recorded.event.kind == ProcessEventKind::Added {
+                added_later.insert(process);
+            } else if !added_later.contains(process) && !tabs.iter().any(|tab| tab == process) {
+                tabs.push(process.to_owned());
+            }
+        }
+        tabs
+    }
+}
+
+/// Appends process events to a recording file as they are consumed.
+#[derive(Debug)]
+pub struct SessionRecorder {
+    out: BufWriter<File>,
+    started_at: Instant,
+}
+
+impl SessionRecorder {
+    pub fn create(path: &Path, processes: &[String]) -> io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = json!({
+            "schema": RECORDING_SCHEMA,
+            "processes": processes,
+        });
+        writeln!(out, "{header}")?;
+        out.flush()?;
+        Ok(Self {
+            out,
+            started_at: Instant::now(),
+        })
+    }
+
+    /// Writes one event line. Lines are flushed straight away so a crashed
+    /// session still leaves a usable recording.
+    pub fn record(&mut self, event: &ProcessEvent) -> io::Result<()> {
+        let at_ms = u64::try_from(self.started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
+        writeln!(self.out, "{}", event_line(at_ms, event))?;
+        self.out.flush()
+    }
+}
+
+fn event_line(at_ms: u64, event: &ProcessEvent) -> Value {
+    let mut line = json!({
+        "at_ms": at_ms,
+        "process": event.process,
+        "kind": kind_name(&event.kind),
+        "payload": event.payload,
+    });
+    // Chunk payloads are lossy text; keep the raw bytes when they differ.
+    if let Some(chunk) = event.chunk.as_ref() {
+        if std::str::from_utf8(chunk).is_err() {
+            line["bytes"] = json!(chunk);
+        }
+    }
+    line
+}
+
+fn parse_event_line(line: &str) -> Result<RecordedEvent, String> {
+    let value = serde_json::from_str::<Value>(line).map_err(|error| error.to_string())?;
+    let field = |name: &str| value.get(name).ok_or_else(|| format!("missing `{name}`"));
+    let at_ms = field("at_ms")?
+        .as_u64()
+        .ok_or_else(|| "`at_ms` must be a number".to_owned())?;
+    let process = field("process")?
+        .as_str()
+        .ok_or_else(|| "`process` must be a string".to_owned())?
+        .to_owned();
+    let kind_raw = field("kind")?
+        .as_str()
+        .ok_or_else(|| "`kind` must be a string".to_owned())?;
+    let kind = parse_kind(kind_raw).ok_or_else(|| format!("unknown event kind `{kind_raw}`"))?;
+    let payload = field("payload")?
+        .as_str()
+        .ok_or_else(|| "`payload` must be a string".to_owned())?
+        .to_owned();
+    let chunk = match kind {
+        ProcessEventKind::StdoutChunk | ProcessEventKind::StderrChunk => match value.get("bytes") {
+            Some(bytes) => Some(
+                serde_json::from_value::<Vec<u8>>(bytes.clone())
+                    .map_err(|error| format!("invalid `bytes`: {error}"))?,
+            ),
+            None => Some(payload.as_bytes().to_vec()),
+        },
+        _ => None,
+    };
+    Ok(RecordedEvent {
+        at_ms,
+        event: ProcessEvent {
+            process,
+            kind,
+            payload,
+            chunk,
+        },
+    })
+}
+
+fn kind_name(kind: &ProcessEventKind) -> &'static str {
+    match kind {
+        ProcessEventKind::Stdout => "stdout",
+        ProcessEventKind::Stderr => "stderr",
+        ProcessEventKind::StdoutChunk => "stdout_chunk",
+        ProcessEventKind::StderrChunk => "stderr_chunk",
+        ProcessEventKind::Exit => "exit",
+        ProcessEventKind::Added => "added",
+        ProcessEventKind::Removed => "removed",
+    }
+}
+
+fn parse_kind(name: &str) -> Option<ProcessEventKind> {
+    match name {
+        "stdout" => Some(ProcessEventKind::Stdout),
+        "stderr" => Some(ProcessEventKind::Stderr),
+        "stdout_chunk" => Some(ProcessEventKind::StdoutChunk),
+        "stderr_chunk" => Some(ProcessEventKind::StderrChunk),
+        "exit" => Some(ProcessEventKind::Exit),
+        "added" => Some(ProcessEventKind::Added),
+        "removed" => Some(ProcessEventKind::Removed),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/recording_tests.rs"]
+mod tests;
@@ -2,6 +2,7 @@ use std::io::IsTerminal;
 
 use serde_json::json;
 
+use crate::testing::TestRunner;
 use crate::ui::theme::{resolve_color_enabled, Theme};
 use crate::ui::{NoticeLevel, OutputMode, PlainRenderer, Renderer};
 use crate::TaskInvocation;
@@ -86,7 +87,8 @@ pub(super) fn run_builtin_config(
                 let normalized_runner = match runner.as_deref() {
                     Some(value) => Some(normalize_test_runner_name(value).ok_or_else(|| {
                         RunnerError::TaskInvocation(format!(
-                            "invalid `--runner` value `{value}` for built-in `config` (supported: vitest, cargo-nextest, cargo-test)"
+                            "invalid `--runner` value `{value}` for built-in `config` (supported: {})",
+                            TestRunner::ALL.map(TestRunner::label).join(", ")
                         ))
                     })?),
                     None => None,
@@ -174,6 +176,21 @@ pub(super) fn run_builtin_config(
     ))?;
     renderer.text("run = \"exec ${SHELL:-/bin/zsh} -i\"")?;
     renderer.text("")?;
+    renderer.text("[tui.keys]")?;
+    renderer.text(&muted_comment(
+        color_enabled,
+        "# Optional TUI key bindings; ~/.config/effigy/tui.toml overrides them.",
+    ))?;
+    renderer.text("preset = \"vim\"  # default | vim | emacs")?;
+    renderer.text("help = [\"?\", \"f1\"]")?;
+    renderer.text("")?;
+    renderer.text("[watch]")?;
+    renderer.text(&muted_comment(
+        color_enabled,
+        "# Optional `effigy watch --owner effigy` group; each task reruns when its globs change.",
+    ))?;
+    renderer.text("map = { validate = [\"src/**\"], \"db:reset\" = [\"migrations/*.sql\"] }")?;
+    renderer.text("")?;
 
     renderer.section("Built-in Test")?;
     renderer.text("[package_manager]")?;
@@ -229,17 +246,30 @@ pub(super) fn run_builtin_config(
         color_enabled,
         "# Managed dev task configuration.",
     ))?;
+    renderer.text("description = \"Run the local dev stack\"")?;
     renderer.text("mode = \"tui\"")?;
     renderer.text("fail_on_non_zero = true")?;
     renderer.text(&muted_comment(
         color_enabled,
-        "# Concurrent launch plan with explicit start and tab ordering.",
+        "# Optional resource limits (setrlimit) inherited by each concurrent process.",
+    ))?;
+    renderer.text("limits = { memory_mb = 2048, cpu_seconds = 600, nofile = 4096 }")?;
+    renderer.text(&muted_comment(
+        color_enabled,
+        "# Optional failure and watch-rerun notifications: true (bell), channel list, or table.",
+    ))?;
+    renderer.text(
+        "notify = { via = [\"bell\", \"osc9\"], command = \"notify-send effigy \\\"$EFFIGY_NOTIFY_MESSAGE\\\"\" }",
+    )?;
+    renderer.text(&muted_comment(
+        color_enabled,
+        "# Concurrent launch plan with start/tab ordering, stop behaviour, shutdown dependencies and restart-on-change `watch` globs.",
     ))?;
     renderer.text("concurrent = [")?;
     renderer.text("  { task = \"catalog-a/api\", start = 1, tab = 3 },")?;
-    renderer.text("  { task = \"catalog-a/jobs\", start = 2, tab = 4, start_after_ms = 1200 },")?;
+    renderer.text("  { task = \"catalog-a/jobs\", start = 2, tab = 4, start_after_ms = 1200, watch = { include = [\"jobs/**\"] } },")?;
     renderer.text("  { task = \"catalog-b/dev\", start = 3, tab = 2 },")?;
-    renderer.text("  { run = \"my-other-arbitrary-process\", start = 4, tab = 1 }")?;
+    renderer.text("  { run = \"my-other-arbitrary-process\", start = 4, tab = 1, stop = { signal = \"SIGINT\", timeout_ms = 10000 }, depends_on = [\"catalog-a/api\"] }")?;
     renderer.text("]")?;
     renderer.text("")?;
     renderer.text("[tasks.dev.profiles.admin]")?;
@@ -247,9 +277,16 @@ pub(super) fn run_builtin_config(
         color_enabled,
         "# Optional profile-specific concurrent override.",
     ))?;
+    renderer.text(&muted_comment(
+        color_enabled,
+        "# Optional grid layout (g toggles at runtime); `panes = [[\"a\", \"b\"], [\"c\"]]` picks up to 4 processes.",
+    ))?;
+    renderer.text("layout = \"grid\"")?;
     renderer.text("concurrent = [")?;
     renderer.text("  { task = \"catalog-a/api\", start = 1, tab = 2 },")?;
-    renderer.text("  { run = \"my-admin-process\", start = 2, tab = 1 }")?;
+    renderer.text(
+        "  { run = \"my-admin-process --port {port.ADMIN_PORT}\", start = 2, tab = 1, ports = { ADMIN_PORT = { port = 4100, auto = true } } }",
+    )?;
     renderer.text("]")?;
     renderer.text("")?;
     renderer.text("[tasks.validate]")?;
@@ -260,6 +297,12 @@ pub(super) fn run_builtin_config(
     renderer.text(
         "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]",
     )?;
+    renderer.text(&muted_comment(
+        color_enabled,
+        "# Optional `effigy watch validate` defaults; command-line flags take precedence.",
+    ))?;
+    renderer
+        .text("watch = { include = [\"src/**\"], exclude = [\"**/*.snap\"], debounce_ms = 300 }")?;
     renderer.text("")?;
 
     let out = renderer.into_inner();
@@ -309,12 +352,24 @@ fn style_schema_comments(schema: String, color_enabled: bool) -> String {
         .join("\n")
 }
 
-fn normalize_test_runner_name(value: &str) -> Option<&'static str> {
-    match value {
-        "vitest" => Some("vitest"),
-        "nextest" | "cargo-nextest" => Some("cargo-nextest"),
-        "cargo-test" => Some("cargo-test"),
-        _ => None,
+fn normalize_test_runner_name(value: &str) -> Option<TestRunner> {
+    TestRunner::from_label(value)
+}
+
+/// `[test.runners]` example line for one runner.
+fn test_runner_schema_line(runner: TestRunner) -> String {
+    let command = match runner {
+        TestRunner::Vitest => "bun x vitest run",
+        TestRunner::Jest => "npx jest --ci",
+        TestRunner::Pytest => "python -m pytest",
+        TestRunner::Pest => "vendor/bin/pest --parallel",
+        other => other.default_command(),
+    };
+    let label = runner.label();
+    if label.contains('-') {
+        format!("\"{label}\" = \"{command}\"")
+    } else {
+        format!("{label} = \"{command}\"")
     }
 }
 
@@ -349,6 +404,15 @@ fn render_builtin_config_schema() -> String {
         "# Interactive shell command used by managed shell tabs.",
         "run = \"exec ${SHELL:-/bin/zsh} -i\"",
         "",
+        "[tui.keys]",
+        "# Optional TUI key bindings; ~/.config/effigy/tui.toml overrides them.",
+        "preset = \"vim\"  # default | vim | emacs",
+        "help = [\"?\", \"f1\"]",
+        "",
+        "[watch]",
+        "# Optional `effigy watch --owner effigy` group; each task reruns when its globs change.",
+        "map = { validate = [\"src/**\"], \"db:reset\" = [\"migrations/*.sql\"] }",
+        "",
         "[tasks]",
         "# Compact task command mappings.",
         "api = \"cargo run -p api\"",
@@ -356,26 +420,35 @@ fn render_builtin_config_schema() -> String {
         "",
         "[tasks.dev]",
         "# Managed dev task configuration.",
+        "description = \"Run the local dev stack\"",
         "mode = \"tui\"",
         "fail_on_non_zero = true",
-        "# Concurrent launch plan with explicit start and tab ordering.",
+        "# Optional resource limits (setrlimit) inherited by each concurrent process.",
+        "limits = { memory_mb = 2048, cpu_seconds = 600, nofile = 4096 }",
+        "# Optional failure and watch-rerun notifications: true (bell), channel list, or table.",
+        "notify = { via = [\"bell\", \"osc9\"], command = \"notify-send effigy \\\"$EFFIGY_NOTIFY_MESSAGE\\\"\" }",
+        "# Concurrent launch plan with start/tab ordering, stop behaviour, shutdown dependencies and restart-on-change `watch` globs.",
         "concurrent = [",
         "  { task = \"catalog-a/api\", start = 1, tab = 3 },",
-        "  { task = \"catalog-a/jobs\", start = 2, tab = 4, start_after_ms = 1200 },",
+        "  { task = \"catalog-a/jobs\", start = 2, tab = 4, start_after_ms = 1200, watch = { include = [\"jobs/**\"] } },",
         "  { task = \"catalog-b/dev\", start = 3, tab = 2 },",
-        "  { run = \"my-other-arbitrary-process\", start = 4, tab = 1 }",
+        "  { run = \"my-other-arbitrary-process\", start = 4, tab = 1, stop = { signal = \"SIGINT\", timeout_ms = 10000 }, depends_on = [\"catalog-a/api\"] }",
         "]",
         "",
         "[tasks.dev.profiles.admin]",
         "# Optional profile-specific concurrent override.",
+        "# Optional grid layout (g toggles at runtime); `panes = [[\"a\", \"b\"], [\"c\"]]` picks up to 4 processes.",
+        "layout = \"grid\"",
         "concurrent = [",
         "  { task = \"catalog-a/api\", start = 1, tab = 2 },",
-        "  { run = \"my-admin-process\", start = 2, tab = 1 }",
+        "  { run = \"my-admin-process --port {port.ADMIN_PORT}\", start = 2, tab = 1, ports = { ADMIN_PORT = { port = 4100, auto = true } } }",
         "]",
         "",
         "[tasks.validate]",
         "# Example DAG-style run sequence with explicit step ids and dependencies.",
         "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]",
+        "# Optional `effigy watch validate` defaults; command-line flags take precedence.",
+        "watch = { include = [\"src/**\"], exclude = [\"**/*.snap\"], debounce_ms = 300 }",
         "",
     ]
     .join("\n")
@@ -469,26 +542,35 @@ fn render_builtin_config_schema_target(target: &str, minimal: bool) -> Option<St
                 "",
                 "[tasks.dev]",
                 "# Managed dev task configuration.",
+                "description = \"Run the local dev stack\"",
                 "mode = \"tui\"",
                 "fail_on_non_zero = true",
-                "# Concurrent launch plan with explicit start and tab ordering.",
+                "# Optional resource limits (setrlimit) inherited by each concurrent process.",
+                "limits = { memory_mb = 2048, cpu_seconds = 600, nofile = 4096 }",
+                "# Optional failure and watch-rerun notifications: true (bell), channel list, or table.",
+                "notify = { via = [\"bell\", \"osc9\"], command = \"notify-send effigy \\\"$EFFIGY_NOTIFY_MESSAGE\\\"\" }",
+                "# Concurrent launch plan with start/tab ordering, stop behaviour, shutdown dependencies and restart-on-change `watch` globs.",
                 "concurrent = [",
                 "  { task = \"catalog-a/api\", start = 1, tab = 3 },",
-                "  { task = \"catalog-a/jobs\", start = 2, tab = 4, start_after_ms = 1200 },",
+                "  { task = \"catalog-a/jobs\", start = 2, tab = 4, start_after_ms = 1200, watch = { include = [\"jobs/**\"] } },",
                 "  { task = \"catalog-b/dev\", start = 3, tab = 2 },",
-                "  { run = \"my-other-arbitrary-process\", start = 4, tab = 1 }",
+                "  { run = \"my-other-arbitrary-process\", start = 4, tab = 1, stop = { signal = \"SIGINT\", timeout_ms = 10000 }, depends_on = [\"catalog-a/api\"] }",
                 "]",
                 "",
                 "[tasks.dev.profiles.admin]",
                 "# Optional profile-specific concurrent override.",
+                "# Optional grid layout (g toggles at runtime); `panes = [[\"a\", \"b\"], [\"c\"]]` picks up to 4 processes.",
+                "layout = \"grid\"",
                 "concurrent = [",
                 "  { task = \"catalog-a/api\", start = 1, tab = 2 },",
-                "  { run = \"my-admin-process\", start = 2, tab = 1 }",
+                "  { run = \"my-admin-process --port {port.ADMIN_PORT}\", start = 2, tab = 1, ports = { ADMIN_PORT = { port = 4100, auto = true } } }",
                 "]",
                 "",
                 "[tasks.validate]",
                 "# Example DAG-style run sequence with explicit step ids and dependencies.",
                 "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]",
+                "# Optional `effigy watch validate` defaults; command-line flags take precedence.",
+                "watch = { include = [\"src/**\"], exclude = [\"**/*.snap\"], debounce_ms = 300 }",
                 "",
             ]
             .join("\n"),
@@ -519,8 +601,8 @@ fn render_builtin_config_schema_target(target: &str, minimal: bool) -> Option<St
     }
 }
 
-fn render_builtin_config_schema_test_target(minimal: bool, runner: Option<&str>) -> String {
-    let header = match (minimal, runner) {
+fn render_builtin_config_schema_test_target(minimal: bool, runner: Option<TestRunner>) -> String {
+    let header = match (minimal, runner.map(TestRunner::label)) {
         (true, Some(name)) => {
             format!("# Minimal strict-valid effigy.toml starter (test target, runner: {name})")
         }
@@ -548,15 +630,8 @@ fn render_builtin_config_schema_test_target(minimal: bool, runner: Option<&str>)
     lines.push("[test.runners]".to_owned());
     lines.push("# Per-runner command overrides for built-in detection.".to_owned());
     match runner {
-        Some("vitest") => lines.push("vitest = \"bun x vitest run\"".to_owned()),
-        Some("cargo-nextest") => lines.push("\"cargo-nextest\" = \"cargo nextest run\"".to_owned()),
-        Some("cargo-test") => lines.push("\"cargo-test\" = \"cargo test\"".to_owned()),
-        Some(_) => {}
-        None => {
-            lines.push("vitest = \"bun x vitest run\"".to_owned());
-            lines.push("\"cargo-nextest\" = \"cargo nextest run\"".to_owned());
-            lines.push("\"cargo-test\" = \"cargo test\"".to_owned());
-        }
+        Some(runner) => lines.push(test_runner_schema_line(runner)),
+        None => lines.extend(TestRunner::ALL.map(test_runner_schema_line)),
     }
     lines.push(String::new());
     lines.join("\n")
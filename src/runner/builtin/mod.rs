@@ -10,6 +10,7 @@ mod doctor;
 mod help;
 mod init;
 mod migrate;
+mod replay;
 mod tasks;
 mod test;
 mod unlock;
@@ -56,11 +57,27 @@ pub(super) fn try_run_builtin_task(
         "tasks" => tasks::run_builtin_tasks(task, runtime_args, &target_root, false).map(Some),
         "config" => config::run_builtin_config(task, &runtime_args.passthrough),
         "help" => help::run_builtin_help(task, &runtime_args.passthrough),
-        "watch" => watch::run_builtin_watch(task, runtime_args, &target_root),
+        "watch" => {
+            watch::run_builtin_watch(task, runtime_args, &target_root, catalogs, invocation_cwd)
+        }
         "init" => init::run_builtin_init(task, &runtime_args.passthrough, &target_root),
         "migrate" => migrate::run_builtin_migrate(task, &runtime_args.passthrough, &target_root),
         "unlock" => unlock::run_builtin_unlock(task, &runtime_args.passthrough, &target_root),
-        "test" => test::try_run_builtin_test(selector, task, runtime_args, &target_root, catalogs),
+        "replay" => replay::run_builtin_replay(
+            task,
+            &runtime_args.passthrough,
+            &target_root,
+            catalogs,
+            invocation_cwd,
+        ),
+        "test" => test::try_run_builtin_test(
+            selector,
+            task,
+            runtime_args,
+            &target_root,
+            catalogs,
+            invocation_cwd,
+        ),
         _ => Ok(None),
     }
 }
@@ -69,3 +86,26 @@ pub(super) fn try_run_builtin_task(
 pub(super) fn builtin_test_max_parallel(catalogs: &[LoadedCatalog], resolved_root: &Path) -> usize {
     test::builtin_test_max_parallel(catalogs, resolved_root)
 }
+
+#[cfg(test)]
+pub(super) fn junit_report_summary(raw: &str) -> Option<(String, Vec<String>)> {
+    test::results::parse_junit_xml(raw).map(|report| (report.summary(), report.failing()))
+}
+
+#[cfg(test)]
+pub(super) fn jest_report_summary(raw: &str, root: &Path) -> Option<(String, Vec<String>)> {
+    test::results::parse_jest_json(raw, root).map(|report| (report.summary(), report.failing()))
+}
+
+#[cfg(all(test, unix))]
+pub(super) fn watch_supervised_groups() -> Vec<i32> {
+    watch::supervised_groups()
+}
+
+#[cfg(test)]
+pub(super) fn watch_matcher_watches_dir(include: &[String], exclude: &[String], dir: &str) -> bool {
+    let ignore = super::ignore::IgnoreRules::new(Path::new("."), false);
+    watch::build_matcher(include, exclude, ignore)
+        .map(|matcher| matcher.watches_dir(dir))
+        .unwrap_or(false)
+}
@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use crate::recording::SessionRecording;
+use crate::tui::{replay_multiprocess_tui, MultiProcessTuiOptions};
+use crate::TaskInvocation;
+
+use super::super::tui_config::resolve_tui_keymap;
+use super::super::{LoadedCatalog, RunnerError};
+
+#[derive(Debug)]
+struct ReplayRequest {
+    path: PathBuf,
+    /// `None` replays every event without waiting.
+    speed: Option<f64>,
+}
+
+pub(super) fn run_builtin_replay(
+    task: &TaskInvocation,
+    args: &[String],
+    target_root: &Path,
+    catalogs: &[LoadedCatalog],
+    invocation_cwd: &Path,
+) -> Result<Option<String>, RunnerError> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return Ok(Some(render_replay_help()));
+    }
+    let request = parse_replay_request(task, args)?;
+    let path = invocation_cwd.join(&request.path);
+    let recording =
+        SessionRecording::load(&path).map_err(|error| RunnerError::SessionRecordingIo {
+            path: path.clone(),
+            error,
+        })?;
+    let root_catalog = catalogs
+        .iter()
+        .find(|catalog| catalog.catalog_root == target_root);
+    replay_multiprocess_tui(
+        target_root.to_path_buf(),
+        recording,
+        request.speed,
+        MultiProcessTuiOptions {
+            keymap: resolve_tui_keymap(root_catalog)?,
+            ..MultiProcessTuiOptions::default()
+        },
+    )
+    .map_err(|error| {
+        RunnerError::Ui(format!(
+            "replay of {} failed: {error}",
+            request.path.display()
+        ))
+    })?;
+    Ok(Some(String::new()))
+}
+
+fn parse_replay_request(
+    task: &TaskInvocation,
+    args: &[String],
+) -> Result<ReplayRequest, RunnerError> {
+    let mut path = None;
+    let mut speed = Some(1.0);
+    let mut index = 0usize;
+    while index < args.len() {
+        match args[index].as_str() {
+            "--speed" => {
+                let value = args
+                    .get(index + 1)
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .filter(|value| value.is_finite() && *value > 0.0)
+                    .ok_or_else(|| {
+                        RunnerError::TaskInvocation(
+                            "`--speed` requires a positive number (for example `--speed 4`)"
+                                .to_owned(),
+                        )
+                    })?;
+                speed = Some(value);
+                index += 2;
+            }
+            "--instant" => {
+                speed = None;
+                index += 1;
+            }
+            value if !value.starts_with('-') && path.is_none() => {
+                path = Some(PathBuf::from(value));
+                index += 1;
+            }
+            _ => {
+                return Err(RunnerError::TaskInvocation(format!(
+                    "unknown argument(s) for built-in `{}`: {}",
+                    task.name,
+                    args.join(" ")
+                )));
+            }
+        }
+    }
+    let path = path.ok_or_else(|| {
+        RunnerError::TaskInvocation(
+            "`replay` requires a recording file (for example `effigy replay session.effigy-rec`)"
+                .to_owned(),
+        )
+    })?;
+    Ok(ReplayRequest { path, speed })
+}
+
+fn render_replay_help() -> String {
+    [
+        "replay Help",
+        "",
+        "Usage",
+        "effigy replay <file> [--speed <factor> | --instant]",
+        "",
+        "Recording",
+        "- effigy <managed-task> [profile] --record <file>",
+        "",
+        "Examples",
+        "- effigy dev --record session.effigy-rec",
+        "- effigy replay session.effigy-rec",
+        "- effigy replay session.effigy-rec --speed 4",
+        "- effigy replay session.effigy-rec --instant",
+    ]
+    .join("\n")
+}
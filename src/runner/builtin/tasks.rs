@@ -91,5 +91,6 @@ pub(super) fn run_builtin_tasks(
         resolve_selector,
         output_json,
         pretty_json,
+        no_ignore: runtime_args.no_ignore,
     })
 }
@@ -1,31 +1,38 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
-use std::io::IsTerminal;
+use std::io::{BufRead, BufReader, IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command as ProcessCommand;
+use std::process::{Command as ProcessCommand, Stdio};
 use std::sync::{Arc, Mutex};
 
-use crate::process_manager::ProcessSpec;
+use crate::process_manager::{ProcessLimits, ProcessSpec, ProcessStop};
 use crate::testing::{detect_test_runner_plans, TestRunner};
-use crate::tui::{run_multiprocess_tui, MultiProcessTuiOptions};
+use crate::tui::{run_multiprocess_tui, KeyMap, MultiProcessTuiOptions};
 use crate::ui::theme::resolve_color_enabled;
 use crate::ui::{KeyValue, NoticeLevel, OutputMode, PlainRenderer, Renderer};
 use crate::TaskInvocation;
 use serde_json::json;
 
+use super::super::notify::resolve_task_notifier;
+use super::super::tui_config::resolve_tui_keymap;
 use super::super::util::{normalize_builtin_test_suite, shell_quote, with_local_node_bin_path};
 use super::super::{
     LoadedCatalog, ManifestJsPackageManager, RunnerError, TaskRuntimeArgs, TaskSelector,
     DEFAULT_BUILTIN_TEST_MAX_PARALLEL,
 };
 
+pub(super) mod results;
+
+use results::{render_junit_xml, JunitSuite, TestCase, TestCaseStatus, TestReport, TestReporter};
+
 pub(super) fn try_run_builtin_test(
     selector: &TaskSelector,
     task: &TaskInvocation,
     runtime_args: &TaskRuntimeArgs,
     resolved_root: &Path,
     catalogs: &[LoadedCatalog],
+    invocation_cwd: &Path,
 ) -> Result<Option<String>, RunnerError> {
-    let (flags, mut passthrough) = extract_builtin_test_flags(&runtime_args.passthrough);
+    let (flags, mut passthrough) = extract_builtin_test_flags(&runtime_args.passthrough)?;
     let targets = resolve_builtin_test_targets(selector, resolved_root, catalogs);
     let mut runnable = targets
         .iter()
@@ -298,7 +305,36 @@ pub(super) fn try_run_builtin_test(
         .join(" ");
     let runnable = runnable
         .into_iter()
-        .map(|(name, root, command, suite)| {
+        .enumerate()
+        .map(|(index, (name, root, command, suite))| {
+            // Configured `[test.suites]` commands run exactly as written, and
+            // `[test.runners]` overrides only get reporters that read stdout.
+            let plan = targets
+                .iter()
+                .filter(|target| target.root == root && target.suite_source == "auto-detected")
+                .flat_map(|target| &target.plans)
+                .find(|plan| plan.suite == suite);
+            let reporter = plan.and_then(|plan| {
+                TestRunner::from_label(&suite)
+                    .and_then(|runner| {
+                        TestReporter::for_runner(
+                            runner,
+                            &std::env::temp_dir()
+                                .join(format!("effigy-test-{}-{index}", std::process::id())),
+                        )
+                    })
+                    .filter(|reporter| !plan.runner_override || reporter.args().is_empty())
+            });
+            let reporter_args = reporter
+                .as_ref()
+                .map(TestReporter::args)
+                .unwrap_or_default()
+                .join(" ");
+            let run_command = [command.as_str(), &reporter_args, &args_rendered]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ");
             let command = if args_rendered.is_empty() {
                 command
             } else {
@@ -309,13 +345,18 @@ pub(super) fn try_run_builtin_test(
                 runner: suite,
                 root,
                 command,
+                run_command,
+                reporter,
             }
         })
         .collect::<Vec<BuiltinTestRunnable>>();
     let max_parallel = builtin_test_max_parallel(catalogs, resolved_root);
     let should_tui = should_run_builtin_test_tui(flags.tui, runnable.len());
     let results = if should_tui {
-        run_builtin_test_targets_tui(runnable)?
+        let root_catalog = catalogs
+            .iter()
+            .find(|catalog| catalog.catalog_root == resolved_root);
+        run_builtin_test_targets_tui(runnable, resolve_tui_keymap(root_catalog)?)?
     } else {
         run_builtin_test_targets_parallel(runnable, max_parallel, flags.output_json)?
     };
@@ -330,6 +371,10 @@ pub(super) fn try_run_builtin_test(
         })
         .collect::<Vec<(String, Option<i32>)>>();
     failures.sort_by(|a, b| a.0.cmp(&b.0));
+    let junit_path = flags.junit.as_ref().map(|path| invocation_cwd.join(path));
+    if let Some(path) = junit_path.as_ref() {
+        write_builtin_test_junit(path, &results)?;
+    }
     let rendered = render_builtin_test_results(&results, flags.verbose_results)?;
     let rendered_json = if flags.output_json {
         Some(render_builtin_test_results_json(
@@ -337,6 +382,7 @@ pub(super) fn try_run_builtin_test(
             &targets,
             requested_suite.as_deref(),
             &passthrough,
+            junit_path.as_deref(),
         )?)
     } else {
         None
@@ -550,6 +596,7 @@ fn render_builtin_test_results_json(
     targets: &[BuiltinTestTarget],
     requested_suite: Option<&str>,
     passthrough: &[String],
+    junit_path: Option<&Path>,
 ) -> Result<String, RunnerError> {
     let suite_source_by_root = targets
         .iter()
@@ -575,6 +622,11 @@ fn render_builtin_test_results_json(
                 "target": result.name,
                 "suite": result.runner,
                 "code": result.code,
+                "failing_tests": result
+                    .report
+                    .as_ref()
+                    .map(TestReport::failing)
+                    .unwrap_or_default(),
             })
         })
         .collect::<Vec<serde_json::Value>>();
@@ -601,6 +653,12 @@ fn render_builtin_test_results_json(
                 "command": result.command,
                 "success": result.success,
                 "code": result.code,
+                "tests": result.report.as_ref().map(|report| json!({
+                    "passed": report.passed(),
+                    "failed": report.failed(),
+                    "skipped": report.skipped(),
+                    "failing": report.failing(),
+                })),
             })
         })
         .collect::<Vec<serde_json::Value>>();
@@ -609,40 +667,47 @@ fn render_builtin_test_results_json(
         "schema_version": 1,
         "targets": target_values,
         "failures": failures,
+        "junit": junit_path.map(|path| path.display().to_string()),
         "hint": build_builtin_test_filter_hint_payload(results, requested_suite, passthrough),
     });
     serde_json::to_string_pretty(&payload)
         .map_err(|error| RunnerError::Ui(format!("failed to encode json: {error}")))
 }
 
-fn extract_builtin_test_flags(raw_args: &[String]) -> (BuiltinTestCliFlags, Vec<String>) {
+fn extract_builtin_test_flags(
+    raw_args: &[String],
+) -> Result<(BuiltinTestCliFlags, Vec<String>), RunnerError> {
     let mut flags = BuiltinTestCliFlags {
         plan_mode: false,
         verbose_results: false,
         tui: false,
         output_json: false,
+        junit: None,
     };
-    let passthrough = raw_args
-        .iter()
-        .filter_map(|arg| {
-            if arg == "--plan" {
-                flags.plan_mode = true;
-                None
-            } else if arg == "--verbose-results" {
-                flags.verbose_results = true;
-                None
-            } else if arg == "--tui" {
-                flags.tui = true;
-                None
-            } else if arg == "--json" {
-                flags.output_json = true;
-                None
-            } else {
-                Some(arg.clone())
-            }
-        })
-        .collect::<Vec<String>>();
-    (flags, passthrough)
+    let mut passthrough = Vec::<String>::new();
+    let mut args = raw_args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--plan" {
+            flags.plan_mode = true;
+        } else if arg == "--verbose-results" {
+            flags.verbose_results = true;
+        } else if arg == "--tui" {
+            flags.tui = true;
+        } else if arg == "--json" {
+            flags.output_json = true;
+        } else if arg == "--junit" {
+            let Some(path) = args.next() else {
+                return Err(RunnerError::TaskInvocation(
+                    "`--junit` requires an output path (for example `--junit junit.xml`)"
+                        .to_owned(),
+                ));
+            };
+            flags.junit = Some(PathBuf::from(path));
+        } else {
+            passthrough.push(arg.clone());
+        }
+    }
+    Ok((flags, passthrough))
 }
 
 #[derive(Debug, Clone)]
@@ -650,6 +715,7 @@ struct BuiltinResolvedPlan {
     suite: String,
     command: String,
     evidence: Vec<String>,
+    runner_override: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -739,14 +805,18 @@ struct BuiltinTestExecResult {
     command: String,
     success: bool,
     code: Option<i32>,
+    /// Parsed machine report; `None` when the runner has no reporter or did
+    /// not write one.
+    report: Option<TestReport>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct BuiltinTestCliFlags {
     plan_mode: bool,
     verbose_results: bool,
     tui: bool,
     output_json: bool,
+    junit: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -754,7 +824,12 @@ struct BuiltinTestRunnable {
     name: String,
     runner: String,
     root: PathBuf,
+    /// Command shown to the user: the runner command plus forwarded args.
     command: String,
+    /// `command` with the reporter's arguments inserted before the forwarded
+    /// args.
+    run_command: String,
+    reporter: Option<TestReporter>,
 }
 
 fn should_run_builtin_test_tui(force_tui: bool, suite_count: usize) -> bool {
@@ -766,10 +841,14 @@ fn should_run_builtin_test_tui(force_tui: bool, suite_count: usize) -> bool {
 
 fn run_builtin_test_targets_tui(
     runnable: Vec<BuiltinTestRunnable>,
+    keymap: KeyMap,
 ) -> Result<Vec<BuiltinTestExecResult>, RunnerError> {
     if runnable.is_empty() {
         return Ok(Vec::new());
     }
+    for suite in &runnable {
+        prepare_builtin_test_reporter(suite)?;
+    }
     let tab_order = runnable
         .iter()
         .map(|suite| suite.name.clone())
@@ -778,10 +857,14 @@ fn run_builtin_test_targets_tui(
         .iter()
         .map(|suite| ProcessSpec {
             name: suite.name.clone(),
-            run: suite.command.clone(),
+            run: suite.run_command.clone(),
             cwd: suite.root.clone(),
             start_after_ms: 0,
             pty: true,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         })
         .collect::<Vec<ProcessSpec>>();
     let outcome = run_multiprocess_tui(
@@ -790,6 +873,9 @@ fn run_builtin_test_targets_tui(
         tab_order,
         MultiProcessTuiOptions {
             esc_quit_on_complete: true,
+            keymap,
+            notify: resolve_task_notifier(None)?,
+            ..MultiProcessTuiOptions::default()
         },
     )
     .map_err(|error| RunnerError::Ui(format!("builtin test tui runtime failed: {error}")))?;
@@ -805,6 +891,11 @@ fn run_builtin_test_targets_tui(
             let code = diagnostic
                 .and_then(|value| value.strip_prefix("exit="))
                 .and_then(|value| value.parse::<i32>().ok());
+            // Output went to the suite's pty tab, so stdout reporters find nothing.
+            let report = suite
+                .reporter
+                .as_ref()
+                .and_then(|reporter| reporter.collect(&suite.root, ""));
             BuiltinTestExecResult {
                 name: suite.name,
                 runner: suite.runner,
@@ -812,11 +903,24 @@ fn run_builtin_test_targets_tui(
                 command: suite.command,
                 success: diagnostic.is_none(),
                 code,
+                report,
             }
         })
         .collect::<Vec<BuiltinTestExecResult>>())
 }
 
+fn prepare_builtin_test_reporter(suite: &BuiltinTestRunnable) -> Result<(), RunnerError> {
+    let Some(reporter) = suite.reporter.as_ref() else {
+        return Ok(());
+    };
+    reporter.prepare().map_err(|error| {
+        RunnerError::TaskInvocation(format!(
+            "failed to prepare the test report for `{}`: {error}",
+            suite.name
+        ))
+    })
+}
+
 fn run_builtin_test_targets_parallel(
     runnable: Vec<BuiltinTestRunnable>,
     max_parallel: usize,
@@ -825,12 +929,8 @@ fn run_builtin_test_targets_parallel(
     if runnable.is_empty() {
         return Ok(Vec::new());
     }
-    let jobs = runnable
-        .into_iter()
-        .map(|job| (job.name, job.root, job.runner, job.command))
-        .collect::<Vec<(String, PathBuf, String, String)>>();
-    let worker_count = max_parallel.min(jobs.len()).max(1);
-    let queue = Arc::new(Mutex::new(VecDeque::from(jobs)));
+    let worker_count = max_parallel.min(runnable.len()).max(1);
+    let queue = Arc::new(Mutex::new(VecDeque::from(runnable)));
 
     std::thread::scope(|scope| -> Result<Vec<BuiltinTestExecResult>, RunnerError> {
         let mut handles = Vec::with_capacity(worker_count);
@@ -843,35 +943,47 @@ fn run_builtin_test_targets_parallel(
                         let mut queue = queue_ref.lock().expect("test queue lock poisoned");
                         queue.pop_front()
                     };
-                    let Some((name, root, runner, command)) = job else {
+                    let Some(suite) = job else {
                         break;
                     };
+                    prepare_builtin_test_reporter(&suite)?;
                     let mut process = ProcessCommand::new("sh");
-                    process.arg("-lc").arg(&command).current_dir(&root);
-                    with_local_node_bin_path(&mut process, &root);
-                    let status = if capture_output {
-                        process
-                            .output()
-                            .map_err(|error| RunnerError::TaskCommandLaunch {
-                                command: command.clone(),
-                                error,
-                            })?
-                            .status
+                    process
+                        .arg("-lc")
+                        .arg(&suite.run_command)
+                        .current_dir(&suite.root);
+                    with_local_node_bin_path(&mut process, &suite.root);
+                    let reads_stdout = suite
+                        .reporter
+                        .as_ref()
+                        .is_some_and(TestReporter::reads_stdout);
+                    let launch_error = |error| RunnerError::TaskCommandLaunch {
+                        command: suite.command.clone(),
+                        error,
+                    };
+                    let (status, stdout) = if capture_output {
+                        let output = process.output().map_err(launch_error)?;
+                        (
+                            output.status,
+                            String::from_utf8_lossy(&output.stdout).into_owned(),
+                        )
+                    } else if reads_stdout {
+                        run_builtin_test_teeing_stdout(&mut process).map_err(launch_error)?
                     } else {
-                        process
-                            .status()
-                            .map_err(|error| RunnerError::TaskCommandLaunch {
-                                command: command.clone(),
-                                error,
-                            })?
+                        (process.status().map_err(launch_error)?, String::new())
                     };
+                    let report = suite
+                        .reporter
+                        .as_ref()
+                        .and_then(|reporter| reporter.collect(&suite.root, &stdout));
                     local.push(BuiltinTestExecResult {
-                        name,
-                        runner,
-                        root,
-                        command,
+                        name: suite.name,
+                        runner: suite.runner,
+                        root: suite.root,
+                        command: suite.command,
                         success: status.success(),
                         code: status.code(),
+                        report,
                     });
                 }
                 Ok::<Vec<BuiltinTestExecResult>, RunnerError>(local)
@@ -889,6 +1001,27 @@ fn run_builtin_test_targets_parallel(
     })
 }
 
+/// Runs the process with its stdout echoed line by line to ours, returning
+/// the exit status and everything it printed.
+fn run_builtin_test_teeing_stdout(
+    process: &mut ProcessCommand,
+) -> std::io::Result<(std::process::ExitStatus, String)> {
+    let mut child = process.stdout(Stdio::piped()).spawn()?;
+    let mut captured = String::new();
+    if let Some(stdout) = child.stdout.take() {
+        let mut reader = BufReader::new(stdout);
+        let mut line = Vec::<u8>::new();
+        let mut out = std::io::stdout();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            let _ = out.write_all(&line);
+            let _ = out.flush();
+            captured.push_str(&String::from_utf8_lossy(&line));
+            line.clear();
+        }
+    }
+    Ok((child.wait()?, captured))
+}
+
 pub(super) fn builtin_test_max_parallel(catalogs: &[LoadedCatalog], resolved_root: &Path) -> usize {
     let configured = catalogs
         .iter()
@@ -918,6 +1051,7 @@ fn resolve_target_test_plans(
                     suite: suite.clone(),
                     command,
                     evidence: vec![format!("test.suites.{suite}")],
+                    runner_override: false,
                 })
                 .collect::<Vec<BuiltinResolvedPlan>>(),
             "configured".to_owned(),
@@ -929,11 +1063,16 @@ fn resolve_target_test_plans(
     (
         detect_test_runner_plans(target_root)
             .into_iter()
-            .map(|plan| apply_builtin_test_runner_config(plan, package_manager, &runner_overrides))
-            .map(|plan| BuiltinResolvedPlan {
-                suite: plan.runner.label().to_owned(),
-                command: plan.command,
-                evidence: plan.evidence,
+            .map(|plan| {
+                let runner_override = runner_overrides.contains_key(plan.runner.label());
+                let plan =
+                    apply_builtin_test_runner_config(plan, package_manager, &runner_overrides);
+                BuiltinResolvedPlan {
+                    suite: plan.runner.label().to_owned(),
+                    command: plan.command,
+                    evidence: plan.evidence,
+                    runner_override,
+                }
             })
             .collect::<Vec<BuiltinResolvedPlan>>(),
         "auto-detected".to_owned(),
@@ -1011,15 +1150,15 @@ fn apply_builtin_test_runner_config(
     package_manager: Option<ManifestJsPackageManager>,
     runner_overrides: &BTreeMap<String, String>,
 ) -> crate::testing::TestRunnerPlan {
-    if plan.runner == TestRunner::Vitest {
+    if matches!(plan.runner, TestRunner::Vitest | TestRunner::Jest) {
         if let Some(manager) = package_manager {
-            let (command, manager_label) = match manager {
-                ManifestJsPackageManager::Bun => ("bun x vitest run", "bun"),
-                ManifestJsPackageManager::Pnpm => ("pnpm exec vitest run", "pnpm"),
-                ManifestJsPackageManager::Npm => ("npx vitest run", "npm"),
-                ManifestJsPackageManager::Direct => ("vitest run", "direct"),
+            let (prefix, manager_label) = match manager {
+                ManifestJsPackageManager::Bun => ("bun x ", "bun"),
+                ManifestJsPackageManager::Pnpm => ("pnpm exec ", "pnpm"),
+                ManifestJsPackageManager::Npm => ("npx ", "npm"),
+                ManifestJsPackageManager::Direct => ("", "direct"),
             };
-            plan.command = command.to_owned();
+            plan.command = format!("{prefix}{}", plan.runner.default_command());
             plan.evidence
                 .push(format!("package_manager.js={manager_label}"));
         }
@@ -1035,6 +1174,72 @@ fn apply_builtin_test_runner_config(
     plan
 }
 
+/// Writes one JUnit XML file with a `<testsuite>` per target. Targets without
+/// a parsed report, and failed targets whose report has no failing case (for
+/// example a compile error), get a case named after their command.
+fn write_builtin_test_junit(
+    path: &Path,
+    results: &[BuiltinTestExecResult],
+) -> Result<(), RunnerError> {
+    let mut ordered = results.iter().collect::<Vec<&BuiltinTestExecResult>>();
+    ordered.sort_by(|a, b| a.name.cmp(&b.name));
+    let cases = ordered
+        .iter()
+        .map(|result| {
+            let mut cases = result
+                .report
+                .as_ref()
+                .map(|report| report.cases.clone())
+                .unwrap_or_default();
+            for case in &mut cases {
+                if case.suite.is_empty() {
+                    case.suite = result.runner.clone();
+                }
+            }
+            let failed_case = cases
+                .iter()
+                .any(|case| case.status == TestCaseStatus::Failed);
+            if cases.is_empty() || (!result.success && !failed_case) {
+                cases.push(TestCase {
+                    suite: result.runner.clone(),
+                    name: result.command.clone(),
+                    status: if result.success {
+                        TestCaseStatus::Passed
+                    } else {
+                        TestCaseStatus::Failed
+                    },
+                    time_secs: None,
+                    message: (!result.success).then(|| match result.code {
+                        Some(code) => format!("exited with code {code}"),
+                        None => "terminated by a signal".to_owned(),
+                    }),
+                });
+            }
+            cases
+        })
+        .collect::<Vec<Vec<TestCase>>>();
+    let suites = ordered
+        .iter()
+        .zip(&cases)
+        .map(|(result, cases)| JunitSuite {
+            name: &result.name,
+            cases,
+        })
+        .collect::<Vec<JunitSuite<'_>>>();
+    let write = || -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, render_junit_xml(&suites))
+    };
+    write().map_err(|error| {
+        RunnerError::TaskInvocation(format!(
+            "failed to write JUnit report `{}`: {error}",
+            path.display()
+        ))
+    })
+}
+
 fn render_builtin_test_results(
     results: &[BuiltinTestExecResult],
     verbose: bool,
@@ -1046,37 +1251,50 @@ fn render_builtin_test_results(
     renderer.section("Test Results")?;
     renderer.key_values(&[KeyValue::new("targets", results.len().to_string())])?;
     renderer.text("")?;
-    let mut ordered = results
-        .iter()
-        .map(|result| {
-            (
-                result.name.clone(),
-                result.runner.clone(),
-                result.root.display().to_string(),
-                result.command.clone(),
-                result.success,
-                result.code,
-            )
-        })
-        .collect::<Vec<(String, String, String, String, bool, Option<i32>)>>();
-    ordered.sort_by(|a, b| a.0.cmp(&b.0));
-    for (name, runner, root, command, success, code) in ordered {
-        let status = if success {
+    let mut ordered = results.iter().collect::<Vec<&BuiltinTestExecResult>>();
+    ordered.sort_by(|a, b| a.name.cmp(&b.name));
+    for result in &ordered {
+        let mut status = if result.success {
             "ok".to_owned()
         } else {
-            match code {
+            match result.code {
                 Some(value) => format!("exit={value}"),
                 None => "terminated".to_owned(),
             }
         };
+        if let Some(report) = result.report.as_ref() {
+            status = format!("{status}  {}", report.summary());
+        }
         let value = if verbose {
-            format!("{status}  runner:{runner}  root:{root}  command:{command}")
+            format!(
+                "{status}  runner:{}  root:{}  command:{}",
+                result.runner,
+                result.root.display(),
+                result.command
+            )
         } else {
             status
         };
-        renderer.key_values(&[KeyValue::new(name, value)])?;
+        renderer.key_values(&[KeyValue::new(result.name.clone(), value)])?;
     }
     renderer.text("")?;
+    let failing = ordered
+        .iter()
+        .flat_map(|result| {
+            result
+                .report
+                .as_ref()
+                .map(TestReport::failing)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|test| format!("{}: {test}", result.name))
+        })
+        .collect::<Vec<String>>();
+    if !failing.is_empty() {
+        renderer.section("Failing Tests")?;
+        renderer.bullet_list("failing", &failing)?;
+        renderer.text("")?;
+    }
     let out = renderer.into_inner();
     String::from_utf8(out)
         .map_err(|error| RunnerError::Ui(format!("invalid utf-8 in rendered output: {error}")))
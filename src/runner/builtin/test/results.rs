@@ -0,0 +1,431 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::testing::TestRunner;
+
+use super::super::super::util::shell_quote;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TestCaseStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TestCase {
+    /// Test file, module or binary the case belongs to.
+    pub(super) suite: String,
+    pub(super) name: String,
+    pub(super) status: TestCaseStatus,
+    pub(super) time_secs: Option<f64>,
+    pub(super) message: Option<String>,
+}
+
+/// Test cases parsed from a runner's machine-readable report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(in super::super) struct TestReport {
+    pub(super) cases: Vec<TestCase>,
+}
+
+impl TestReport {
+    fn count(&self, status: TestCaseStatus) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.status == status)
+            .count()
+    }
+
+    pub(super) fn passed(&self) -> usize {
+        self.count(TestCaseStatus::Passed)
+    }
+
+    pub(super) fn failed(&self) -> usize {
+        self.count(TestCaseStatus::Failed)
+    }
+
+    pub(super) fn skipped(&self) -> usize {
+        self.count(TestCaseStatus::Skipped)
+    }
+
+    pub(in super::super) fn failing(&self) -> Vec<String> {
+        self.cases
+            .iter()
+            .filter(|case| case.status == TestCaseStatus::Failed)
+            .map(|case| case.name.clone())
+            .collect()
+    }
+
+    pub(in super::super) fn summary(&self) -> String {
+        format!(
+            "{} passed, {} failed, {} skipped",
+            self.passed(),
+            self.failed(),
+            self.skipped()
+        )
+    }
+}
+
+/// How a runner is asked for a machine-readable report. File reporters write
+/// into a scratch directory that is removed once the report is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum TestReporter {
+    /// vitest (`--reporter=json`) and jest (`--json`) write jest-style JSON.
+    JestJson { runner: TestRunner, path: PathBuf },
+    /// cargo-nextest writes JUnit XML, enabled through a tool config file.
+    NextestJunit { config: PathBuf, path: PathBuf },
+    /// libtest's JSON format needs nightly, so `cargo test` is read from its
+    /// stable `test <name> ... ok` lines on stdout. This is best-effort: a
+    /// custom harness or a changed libtest format yields no report, and the
+    /// target falls back to its exit status.
+    LibtestText,
+}
+
+impl TestReporter {
+    pub(super) fn for_runner(runner: TestRunner, scratch: &Path) -> Option<Self> {
+        match runner {
+            TestRunner::Vitest | TestRunner::Jest => Some(TestReporter::JestJson {
+                runner,
+                path: scratch.join("report.json"),
+            }),
+            TestRunner::CargoNextest => Some(TestReporter::NextestJunit {
+                config: scratch.join("nextest.toml"),
+                path: scratch.join("junit.xml"),
+            }),
+            TestRunner::CargoTest => Some(TestReporter::LibtestText),
+            _ => None,
+        }
+    }
+
+    /// Arguments inserted between the runner command and the user's args.
+    pub(super) fn args(&self) -> Vec<String> {
+        match self {
+            TestReporter::JestJson {
+                runner: TestRunner::Vitest,
+                path,
+            } => vec![
+                "--reporter=default".to_owned(),
+                "--reporter=json".to_owned(),
+                format!(
+                    "--outputFile.json={}",
+                    shell_quote(&path.display().to_string())
+                ),
+            ],
+            TestReporter::JestJson { path, .. } => vec![
+                "--json".to_owned(),
+                format!("--outputFile={}", shell_quote(&path.display().to_string())),
+            ],
+            TestReporter::NextestJunit { config, .. } => vec![
+                "--tool-config-file".to_owned(),
+                shell_quote(&format!("effigy:{}", config.display())),
+            ],
+            TestReporter::LibtestText => Vec::new(),
+        }
+    }
+
+    pub(super) fn reads_stdout(&self) -> bool {
+        matches!(self, TestReporter::LibtestText)
+    }
+
+    /// Creates the scratch directory and any config the runner reads.
+    pub(super) fn prepare(&self) -> std::io::Result<()> {
+        match self {
+            TestReporter::JestJson { path, .. } => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                Ok(())
+            }
+            TestReporter::NextestJunit { config, path } => {
+                if let Some(dir) = config.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                let junit_path = toml::Value::String(path.display().to_string());
+                fs::write(
+                    config,
+                    format!("[profile.default.junit]\npath = {junit_path}\n"),
+                )
+            }
+            TestReporter::LibtestText => Ok(()),
+        }
+    }
+
+    /// Reads the report, or `None` when the runner did not produce one (for
+    /// example when a user override dropped the reporter flags).
+    pub(super) fn collect(&self, root: &Path, stdout: &str) -> Option<TestReport> {
+        match self {
+            TestReporter::JestJson { path, .. } => {
+                let raw = fs::read_to_string(path).ok();
+                remove_scratch(path);
+                parse_jest_json(&raw?, root)
+            }
+            TestReporter::NextestJunit { path, .. } => {
+                let raw = fs::read_to_string(path).ok();
+                remove_scratch(path);
+                parse_junit_xml(&raw?)
+            }
+            TestReporter::LibtestText => parse_libtest_text(stdout),
+        }
+    }
+}
+
+fn remove_scratch(path: &Path) {
+    if let Some(dir) = path.parent() {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+/// Parses vitest/jest JSON (`testResults[].assertionResults[]`). Test files
+/// that failed before running any test are reported as one failed case.
+pub(in super::super) fn parse_jest_json(raw: &str, root: &Path) -> Option<TestReport> {
+    let json = serde_json::from_str::<Value>(raw).ok()?;
+    let files = json.get("testResults")?.as_array()?;
+    let mut cases = Vec::<TestCase>::new();
+    for file in files {
+        let file_name = file.get("name").and_then(Value::as_str).unwrap_or_default();
+        let suite = Path::new(file_name)
+            .strip_prefix(root)
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| file_name.to_owned());
+        let assertions = file
+            .get("assertionResults")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        if assertions.is_empty() && file.get("status").and_then(Value::as_str) == Some("failed") {
+            cases.push(TestCase {
+                suite: suite.clone(),
+                name: suite.clone(),
+                status: TestCaseStatus::Failed,
+                time_secs: None,
+                message: file
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .filter(|message| !message.is_empty())
+                    .map(str::to_owned),
+            });
+            continue;
+        }
+        for assertion in assertions {
+            let name = assertion
+                .get("fullName")
+                .or_else(|| assertion.get("title"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .trim()
+                .to_owned();
+            let status = match assertion.get("status").and_then(Value::as_str) {
+                Some("passed") => TestCaseStatus::Passed,
+                Some("failed") => TestCaseStatus::Failed,
+                _ => TestCaseStatus::Skipped,
+            };
+            let message = assertion
+                .get("failureMessages")
+                .and_then(Value::as_array)
+                .map(|messages| {
+                    messages
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<&str>>()
+                        .join("\n")
+                })
+                .filter(|message| !message.is_empty());
+            cases.push(TestCase {
+                suite: suite.clone(),
+                name,
+                status,
+                time_secs: assertion
+                    .get("duration")
+                    .and_then(Value::as_f64)
+                    .map(|ms| ms / 1000.0),
+                message,
+            });
+        }
+    }
+    Some(TestReport { cases })
+}
+
+/// Parses the `<testcase>` elements of a JUnit XML report. A case with a
+/// `<failure>` or `<error>` child failed, one with `<skipped>` was skipped.
+pub(in super::super) fn parse_junit_xml(raw: &str) -> Option<TestReport> {
+    if !raw.contains("<testsuite") {
+        return None;
+    }
+    let mut cases = Vec::<TestCase>::new();
+    let mut rest = raw;
+    while let Some(start) = rest.find("<testcase") {
+        rest = &rest[start + "<testcase".len()..];
+        let tag_end = rest.find('>')?;
+        let attributes = &rest[..tag_end];
+        let self_closing = attributes.ends_with('/');
+        rest = &rest[tag_end + 1..];
+        let body = if self_closing {
+            ""
+        } else {
+            let end = rest.find("</testcase>")?;
+            let body = &rest[..end];
+            rest = &rest[end + "</testcase>".len()..];
+            body
+        };
+        let (status, message) = if let Some(element) = ["<failure", "<error"]
+            .iter()
+            .find_map(|tag| body.find(tag).map(|index| &body[index..]))
+        {
+            (
+                TestCaseStatus::Failed,
+                xml_attribute(
+                    &element[..element.find('>').unwrap_or(element.len())],
+                    "message",
+                ),
+            )
+        } else if body.contains("<skipped") {
+            (TestCaseStatus::Skipped, None)
+        } else {
+            (TestCaseStatus::Passed, None)
+        };
+        cases.push(TestCase {
+            suite: xml_attribute(attributes, "classname").unwrap_or_default(),
+            name: xml_attribute(attributes, "name").unwrap_or_default(),
+            status,
+            time_secs: xml_attribute(attributes, "time").and_then(|time| time.parse().ok()),
+            message,
+        });
+    }
+    Some(TestReport { cases })
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    loop {
+        let index = rest.find(name)?;
+        let preceded_by_space = rest[..index]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        let after = rest[index + name.len()..].trim_start();
+        rest = &rest[index + name.len()..];
+        let Some(after) = after.strip_prefix('=') else {
+            continue;
+        };
+        if !preceded_by_space {
+            continue;
+        }
+        let after = after.trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &after[1..];
+        let end = value.find(quote)?;
+        return Some(xml_unescape(&value[..end]));
+    }
+}
+
+fn xml_unescape(raw: &str) -> String {
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+fn xml_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Parses libtest lines such as `test tests::adds ... ok`, across every test
+/// binary and the doc tests. Cargo names the binaries on stderr, so cases
+/// carry no suite. Returns `None` when no line matches.
+pub(super) fn parse_libtest_text(stdout: &str) -> Option<TestReport> {
+    let mut cases = Vec::<TestCase>::new();
+    for line in stdout.lines() {
+        let Some((name, outcome)) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.rsplit_once(" ... "))
+        else {
+            continue;
+        };
+        let status = match outcome.trim() {
+            "ok" => TestCaseStatus::Passed,
+            "FAILED" => TestCaseStatus::Failed,
+            outcome if outcome.starts_with("ignored") => TestCaseStatus::Skipped,
+            _ => continue,
+        };
+        cases.push(TestCase {
+            suite: String::new(),
+            name: name.trim().to_owned(),
+            status,
+            time_secs: None,
+            message: None,
+        });
+    }
+    (!cases.is_empty()).then_some(TestReport { cases })
+}
+
+/// One `<testsuite>` of the merged JUnit report: a test target's cases.
+pub(super) struct JunitSuite<'a> {
+    pub(super) name: &'a str,
+    pub(super) cases: &'a [TestCase],
+}
+
+pub(super) fn render_junit_xml(suites: &[JunitSuite<'_>]) -> String {
+    let count = |cases: &[TestCase], status: TestCaseStatus| {
+        cases.iter().filter(|case| case.status == status).count()
+    };
+    let all = suites
+        .iter()
+        .flat_map(|suite| suite.cases.iter().cloned())
+        .collect::<Vec<TestCase>>();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"effigy\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+        all.len(),
+        count(&all, TestCaseStatus::Failed),
+        count(&all, TestCaseStatus::Skipped)
+    ));
+    for suite in suites {
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+            xml_escape(suite.name),
+            suite.cases.len(),
+            count(suite.cases, TestCaseStatus::Failed),
+            count(suite.cases, TestCaseStatus::Skipped)
+        ));
+        for case in suite.cases {
+            let time = case
+                .time_secs
+                .map(|secs| format!(" time=\"{secs:.3}\""))
+                .unwrap_or_default();
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\"{time}",
+                xml_escape(&case.name),
+                xml_escape(&case.suite)
+            );
+            match case.status {
+                TestCaseStatus::Passed => out.push_str(&format!("{open}/>\n")),
+                TestCaseStatus::Skipped => {
+                    out.push_str(&format!("{open}>\n      <skipped/>\n    </testcase>\n"));
+                }
+                TestCaseStatus::Failed => {
+                    let message = case.message.as_deref().unwrap_or("failed");
+                    let summary = message.lines().next().unwrap_or("failed");
+                    out.push_str(&format!(
+                        "{open}>\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                        xml_escape(summary),
+                        xml_escape(message)
+                    ));
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
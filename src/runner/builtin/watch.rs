@@ -1,20 +1,43 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::BTreeSet;
 use std::io::IsTerminal;
-use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
+use std::path::Path;
+use std::process::{Child, Command as ProcessCommand, ExitStatus, Stdio};
+use std::time::Duration;
 
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde_json::json;
-use walkdir::WalkDir;
 
+use crate::notify::{Notifier, NotifyEvent};
+use crate::process_manager::parse_stop_signal;
 use crate::ui::theme::resolve_color_enabled;
 use crate::ui::{OutputMode, PlainRenderer};
 use crate::{render_help, HelpTopic, TaskInvocation};
 
+use super::super::ignore::IgnoreRules;
 use super::super::locking::{acquire_scopes, LockScope};
-use super::super::{run_manifest_task_with_cwd, RunnerError, TaskRuntimeArgs};
+use super::super::managed::resolve_effigy_invocation_prefix;
+use super::super::manifest::{ManifestWatch, ManifestWatchGroup};
+use super::super::notify::resolve_task_notifier;
+use super::super::util::{render_changed_files, shell_quote};
+use super::super::{
+    parse_task_selector, select_catalog_and_task, LoadedCatalog, ManifestTask, RunnerError,
+    TaskRuntimeArgs, CHANGED_FILES_ENV,
+};
+
+mod backend;
+mod dashboard;
+mod group;
+mod restart;
+
+use backend::{ChangeWatcher, WatchBackend};
+use dashboard::run_watch_dashboard;
+use group::{run_watch_group, WatchGroupTask};
+use restart::{RestartPolicy, SupervisedTarget, TargetOutput, DEFAULT_KILL_TIMEOUT_MS};
 
 const DEFAULT_DEBOUNCE_MS: u64 = 400;
+/// How often a watch loop checks on a running target while it waits for
+/// changes.
+const RUN_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum WatchOwner {
@@ -22,34 +45,209 @@ enum WatchOwner {
     External,
 }
 
+/// What a change does while a run is still in progress (`--on-change`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum OnChange {
+    /// Merge the changes into one rerun after the current run finishes.
+    #[default]
+    Queue,
+    /// Stop the current run and start a fresh one with its changes and the
+    /// new ones.
+    Cancel,
+    /// Drop the changes.
+    Ignore,
+}
+
+/// What a watch reruns: a catalog or built-in task, or a shell command given
+/// with `--run`.
+#[derive(Debug, Clone)]
+enum WatchTarget {
+    Task(TaskInvocation),
+    Command(String),
+}
+
+impl WatchTarget {
+    fn label(&self) -> &str {
+        match self {
+            WatchTarget::Task(task) => &task.name,
+            WatchTarget::Command(command) => command,
+        }
+    }
+
+    /// Shell command line that runs the target as a child process. Task
+    /// targets render `{changed}` themselves from `EFFIGY_CHANGED_FILES`.
+    fn command_line(&self, output_json: bool, changes: &[String]) -> Result<String, RunnerError> {
+        match self {
+            WatchTarget::Task(task) => {
+                let mut command_line = format!(
+                    "exec {} {}",
+                    resolve_effigy_invocation_prefix()?,
+                    shell_quote(&task.name)
+                );
+                for arg in &task.args {
+                    command_line.push(' ');
+                    command_line.push_str(&shell_quote(arg));
+                }
+                if output_json {
+                    command_line.push_str(" --json");
+                }
+                Ok(command_line)
+            }
+            WatchTarget::Command(command) => {
+                Ok(command.replace("{changed}", &render_changed_files(changes)))
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct WatchRequest {
     output_json: bool,
     help: bool,
     owner: Option<WatchOwner>,
-    debounce_ms: u64,
+    /// `None` until `--debounce-ms` or the task's `watch.debounce_ms` sets it.
+    debounce_ms: Option<u64>,
     include: Vec<String>,
     exclude: Vec<String>,
     max_runs: Option<usize>,
-    target: Option<TaskInvocation>,
+    poll: bool,
+    no_ignore: bool,
+    restart: bool,
+    signal: Option<String>,
+    kill_timeout_ms: Option<u64>,
+    on_change: Option<OnChange>,
+    /// `--map <GLOB>=<TASK>` pairs, in flag order.
+    map: Vec<(String, String)>,
+    tui: bool,
+    target: Option<WatchTarget>,
 }
 
+impl WatchRequest {
+    fn debounce_ms(&self) -> u64 {
+        self.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS)
+    }
+
+    fn on_change(&self) -> OnChange {
+        self.on_change.unwrap_or_default()
+    }
+
+    /// How `--restart` and `--on-change cancel` stop a running target.
+    fn stop_policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            signal: self.signal.clone().unwrap_or_else(|| "SIGTERM".to_owned()),
+            kill_timeout: Duration::from_millis(
+                self.kill_timeout_ms.unwrap_or(DEFAULT_KILL_TIMEOUT_MS),
+            ),
+        }
+    }
+
+    /// Stop policy for runs that `--on-change cancel` may stop; `None` runs
+    /// the target as a plain child.
+    fn cancel_policy(&self) -> Option<RestartPolicy> {
+        (self.on_change() == OnChange::Cancel).then(|| self.stop_policy())
+    }
+
+    fn validate_stop_flags(&self) -> Result<(), RunnerError> {
+        if self.restart && self.on_change.is_some() {
+            return Err(RunnerError::TaskInvocation(
+                "`--on-change` does not apply to `--restart`, which always replaces the running target"
+                    .to_owned(),
+            ));
+        }
+        // The dashboard's `r` key cancels runs in every `--on-change` mode.
+        if !self.restart
+            && !self.tui
+            && self.on_change() != OnChange::Cancel
+            && (self.signal.is_some() || self.kill_timeout_ms.is_some())
+        {
+            return Err(RunnerError::TaskInvocation(
+                "`--signal` and `--kill-timeout-ms` require `--restart` or `--on-change cancel`"
+                    .to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fills in `[tasks.<name>.watch]` defaults. Flags win: `--include`
+    /// replaces the configured includes, `--exclude` adds to the configured
+    /// excludes, and `--restart` cannot be turned off by config.
+    fn apply_task_config(&mut self, config: &ManifestWatch) {
+        if self.include.is_empty() {
+            self.include = config.include.clone();
+        }
+        let cli_exclude = std::mem::take(&mut self.exclude);
+        self.exclude = config.exclude.iter().cloned().chain(cli_exclude).collect();
+        if self.debounce_ms.is_none() {
+            self.debounce_ms = config.debounce_ms.map(|value| value.get());
+        }
+        self.restart |= config.restart.unwrap_or(false);
+    }
+}
+
+/// One start of a watch target and the files that triggered it (empty for
+/// the first run).
 #[derive(Debug)]
-struct WatchMatcher {
-    include: Option<GlobSet>,
-    exclude: GlobSet,
+struct WatchRun {
+    target: String,
+    files: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct FileStamp {
-    modified: Option<SystemTime>,
-    size: u64,
+#[derive(Debug, Default)]
+struct WatchSummary {
+    targets: Vec<String>,
+    run_log: Vec<WatchRun>,
+    backend: Option<WatchBackend>,
+    restart: bool,
+    /// Restarts where the previous target ignored its stop signal and was
+    /// killed after the kill timeout.
+    forced_stops: usize,
+    /// Runs stopped by `--on-change cancel` before they finished.
+    cancelled: usize,
+    /// Backend problems met during the watch, such as falling back to
+    /// polling.
+    warnings: Vec<String>,
+}
+
+impl WatchSummary {
+    fn runs(&self) -> usize {
+        self.run_log.len()
+    }
+
+    fn record_run(&mut self, target: &str, files: Vec<String>) {
+        self.run_log.push(WatchRun {
+            target: target.to_owned(),
+            files,
+        });
+    }
+
+    /// Picks up a backend switch after a wait for changes. Returns the
+    /// warning so interactive callers can show it straight away.
+    fn note_backend(&mut self, watcher: &mut ChangeWatcher) -> Option<String> {
+        self.backend = Some(watcher.backend());
+        let warning = watcher.take_warning()?;
+        self.warnings.push(warning.clone());
+        Some(warning)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct WatchMatcher {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    /// Literal leading directories of each include glob; `None` when any
+    /// include glob can match at any depth.
+    include_dirs: Option<Vec<String>>,
+    /// Directories excluded wholesale by `<dir>/**` exclude globs.
+    exclude_dirs: GlobSet,
+    ignore: IgnoreRules,
 }
 
 pub(super) fn run_builtin_watch(
     task: &TaskInvocation,
     runtime_args: &TaskRuntimeArgs,
     target_root: &Path,
+    catalogs: &[LoadedCatalog],
+    invocation_cwd: &Path,
 ) -> Result<Option<String>, RunnerError> {
     if runtime_args.verbose_root {
         return Err(RunnerError::TaskInvocation(
@@ -57,7 +255,14 @@ pub(super) fn run_builtin_watch(
         ));
     }
 
-    let request = parse_watch_request(task, &runtime_args.passthrough)?;
+    let mut request = parse_watch_request(task, &runtime_args.passthrough)?;
+    request.no_ignore = runtime_args.no_ignore;
+    if request.no_ignore {
+        // Reruns resolve the target again, so they need the flag too.
+        if let Some(WatchTarget::Task(target)) = request.target.as_mut() {
+            target.args.push("--no-ignore".to_owned());
+        }
+    }
     if request.help {
         return render_watch_help_payload(request.output_json);
     }
@@ -78,52 +283,481 @@ pub(super) fn run_builtin_watch(
         ));
     }
 
-    let target = request.target.ok_or_else(|| {
+    let group_config = catalogs
+        .iter()
+        .filter(|catalog| catalog.catalog_root == target_root)
+        .find_map(|catalog| catalog.manifest.watch.as_ref());
+    if !request.map.is_empty()
+        || (request.target.is_none() && group_config.is_some_and(|group| !group.map.is_empty()))
+    {
+        if request.tui {
+            return Err(RunnerError::TaskInvocation(
+                "`--tui` is not supported for watch groups".to_owned(),
+            ));
+        }
+        return run_builtin_watch_group(
+            request,
+            group_config,
+            target_root,
+            catalogs,
+            invocation_cwd,
+        );
+    }
+
+    let target = request.target.take().ok_or_else(|| {
         RunnerError::TaskInvocation(
-            "watch requires a target task selector (for example `effigy watch --owner effigy test`)."
+            "watch requires a target task selector (for example `effigy watch --owner effigy test`), `--map <glob>=<task>` or a `[watch]` table."
                 .to_owned(),
         )
     })?;
-    if target.name == "watch" {
+    let target_task = match &target {
+        WatchTarget::Task(task) => {
+            if task.name == "watch" {
+                return Err(RunnerError::TaskInvocation(
+                    "watch target cannot be `watch` (nested watch loops are blocked by owner policy)."
+                        .to_owned(),
+                ));
+            }
+            resolve_watch_task(task, catalogs, invocation_cwd)
+        }
+        WatchTarget::Command(_) => None,
+    };
+    if let Some(config) = target_task.and_then(|task| task.watch.as_ref()) {
+        request.apply_task_config(config);
+    }
+    request.validate_stop_flags()?;
+    if request.tui {
+        validate_tui_flags(&request)?;
+    }
+    // `--run` targets are managed processes or ad-hoc commands; only task
+    // targets take the per-target watch lock.
+    let _watch_lock = match &target {
+        WatchTarget::Task(task) => Some(acquire_scopes(
+            target_root,
+            &[LockScope::Task(format!("watch:{}", task.name))],
+        )?),
+        WatchTarget::Command(_) => None,
+    };
+
+    let matcher = build_matcher(
+        &request.include,
+        &request.exclude,
+        IgnoreRules::new(target_root, !request.no_ignore),
+    )?;
+    let notifier = resolve_task_notifier(target_task.and_then(|task| task.notify.as_ref()))?;
+    if request.tui {
+        let summary =
+            run_watch_dashboard(&target, target_root, &matcher, &request, notifier.as_ref())?;
+        return render_watch_result_json(false, &summary);
+    }
+    if request.restart {
+        let summary =
+            run_watch_restart_loop(&target, target_root, &matcher, &request, notifier.as_ref())?;
+        return render_watch_result_json(request.output_json, &summary);
+    }
+
+    let summary = run_watch_loop(&target, target_root, &matcher, &request, notifier.as_ref())?;
+    render_watch_result_json(request.output_json, &summary)
+}
+
+/// `--tui` takes over the terminal until `q`, so it excludes the flags that
+/// bound or reshape the run output.
+fn validate_tui_flags(request: &WatchRequest) -> Result<(), RunnerError> {
+    if request.output_json || request.max_runs.is_some() {
         return Err(RunnerError::TaskInvocation(
-            "watch target cannot be `watch` (nested watch loops are blocked by owner policy)."
+            "`--tui` cannot be combined with `--json`, `--once` or `--max-runs`".to_owned(),
+        ));
+    }
+    if request.restart {
+        return Err(RunnerError::TaskInvocation(
+            "`--tui` does not support `--restart`; use `--on-change cancel` to replace running runs"
                 .to_owned(),
         ));
     }
-    let watch_scope = LockScope::Task(format!("watch:{}", target.name));
-    let _watch_lock = acquire_scopes(target_root, &[watch_scope])?;
-
-    let matcher = build_matcher(&request.include, &request.exclude)?;
-    let max_runs = request.max_runs;
-    let mut runs = 0usize;
-    run_watch_target(&target, target_root, request.output_json)?;
-    runs += 1;
-    if Some(runs) == max_runs {
-        return render_watch_result_json(request.output_json, runs);
+    if !std::io::stdout().is_terminal() {
+        return Err(RunnerError::TaskInvocation(
+            "`--tui` requires an interactive terminal".to_owned(),
+        ));
     }
+    Ok(())
+}
+
+/// Reruns the target on every change. Changes that arrive while a run is in
+/// progress follow `--on-change`: merged into one rerun after it finishes
+/// (default), dropped, or used to cancel it and start a fresh run.
+fn run_watch_loop(
+    target: &WatchTarget,
+    target_root: &Path,
+    matcher: &WatchMatcher,
+    request: &WatchRequest,
+    notifier: Option<&Notifier>,
+) -> Result<WatchSummary, RunnerError> {
+    let cancel_policy = request.cancel_policy();
+    let mut summary = WatchSummary {
+        targets: vec![target.label().to_owned()],
+        ..WatchSummary::default()
+    };
+    let mut watcher = if request.max_runs == Some(1) {
+        None
+    } else {
+        let watcher =
+            ChangeWatcher::new(target_root, matcher, request.debounce_ms(), request.poll)?;
+        summary.backend = Some(watcher.backend());
+        Some(watcher)
+    };
+    let mut running = Some(spawn_watch_target(
+        target,
+        target_root,
+        request.output_json,
+        &[],
+        cancel_policy.as_ref(),
+    )?);
+    let mut current = Vec::<String>::new();
+    let mut queued: Option<BTreeSet<String>> = None;
+    summary.record_run(target.label(), Vec::new());
 
-    let mut snapshot = collect_snapshot(target_root, &matcher)?;
     loop {
-        let _changes = wait_for_changes(target_root, &matcher, &mut snapshot, request.debounce_ms)?;
-        run_watch_target(&target, target_root, request.output_json)?;
-        runs += 1;
-        if Some(runs) == max_runs {
-            return render_watch_result_json(request.output_json, runs);
+        if let Some(run) = running.as_mut() {
+            if let Some(status) = run.try_wait()? {
+                running = None;
+                check_watch_exit(target, status)?;
+                if let (true, Some(notifier)) = (summary.runs() > 1, notifier) {
+                    notifier.send(
+                        target_root,
+                        NotifyEvent::WatchRunFinished,
+                        &format!(
+                            "watch rerun {} of `{}` finished",
+                            summary.runs() - 1,
+                            target.label()
+                        ),
+                    );
+                }
+                if Some(summary.runs()) == request.max_runs {
+                    return Ok(summary);
+                }
+            }
+        }
+        let Some(watcher) = watcher.as_mut() else {
+            std::thread::sleep(RUN_POLL_INTERVAL);
+            continue;
+        };
+        if running.is_none() {
+            let changes = match queued.take() {
+                Some(queued) => queued.into_iter().collect(),
+                None => watcher.wait_for_changes(matcher, request.debounce_ms())?,
+            };
+            summary.note_backend(watcher);
+            running = Some(spawn_watch_target(
+                target,
+                target_root,
+                request.output_json,
+                &changes,
+                cancel_policy.as_ref(),
+            )?);
+            current = changes.clone();
+            summary.record_run(target.label(), changes);
+            continue;
+        }
+
+        let Some(changes) =
+            watcher.changes_within(matcher, request.debounce_ms(), Some(RUN_POLL_INTERVAL))?
+        else {
+            continue;
+        };
+        summary.note_backend(watcher);
+        // The last run allowed by `--max-runs` always finishes.
+        if Some(summary.runs()) == request.max_runs {
+            continue;
+        }
+        match request.on_change() {
+            OnChange::Queue => queued.get_or_insert_with(BTreeSet::new).extend(changes),
+            OnChange::Cancel => {
+                if let Some(mut run) = running.take() {
+                    if run.cancel() {
+                        summary.forced_stops += 1;
+                    }
+                }
+                summary.cancelled += 1;
+                queued = Some(
+                    std::mem::take(&mut current)
+                        .into_iter()
+                        .chain(changes)
+                        .collect(),
+                );
+            }
+            OnChange::Ignore => {}
+        }
+    }
+}
+
+/// Watch group from `--map` flags or the root manifest's `[watch]` table.
+/// `--map` replaces the configured mappings; `exclude` and `debounce_ms`
+/// combine with the flags like task `watch` defaults do.
+fn run_builtin_watch_group(
+    mut request: WatchRequest,
+    config: Option<&ManifestWatchGroup>,
+    target_root: &Path,
+    catalogs: &[LoadedCatalog],
+    invocation_cwd: &Path,
+) -> Result<Option<String>, RunnerError> {
+    if request.target.is_some() {
+        return Err(RunnerError::TaskInvocation(
+            "`--map` cannot be combined with a target task or `--run`".to_owned(),
+        ));
+    }
+    if request.restart {
+        return Err(RunnerError::TaskInvocation(
+            "`--restart` is not supported for watch groups".to_owned(),
+        ));
+    }
+    request.validate_stop_flags()?;
+    if !request.include.is_empty() {
+        return Err(RunnerError::TaskInvocation(
+            "`--include` is not supported for watch groups; give each task its globs with `--map <glob>=<task>`"
+                .to_owned(),
+        ));
+    }
+    let mut mappings = std::mem::take(&mut request.map);
+    if let Some(config) = config {
+        if mappings.is_empty() {
+            mappings = config
+                .map
+                .iter()
+                .flat_map(|(task_name, globs)| {
+                    globs.iter().map(|glob| (glob.clone(), task_name.clone()))
+                })
+                .collect();
+        }
+        let cli_exclude = std::mem::take(&mut request.exclude);
+        request.exclude = config.exclude.iter().cloned().chain(cli_exclude).collect();
+        if request.debounce_ms.is_none() {
+            request.debounce_ms = config.debounce_ms.map(|value| value.get());
+        }
+    }
+
+    let mut tasks = Vec::<WatchGroupTask>::new();
+    for (glob, task_name) in mappings {
+        if task_name == "watch" {
+            return Err(RunnerError::TaskInvocation(
+                "watch group tasks cannot be `watch` (nested watch loops are blocked by owner policy)."
+                    .to_owned(),
+            ));
+        }
+        if let Some(task) = tasks
+            .iter_mut()
+            .find(|task| task.target.label() == task_name)
+        {
+            task.globs.push(glob);
+            continue;
+        }
+        let invocation = TaskInvocation {
+            name: task_name,
+            args: if request.no_ignore {
+                vec!["--no-ignore".to_owned()]
+            } else {
+                Vec::new()
+            },
+        };
+        let notify = resolve_watch_task(&invocation, catalogs, invocation_cwd)
+            .and_then(|task| task.notify.as_ref());
+        tasks.push(WatchGroupTask {
+            notifier: resolve_task_notifier(notify)?,
+            target: WatchTarget::Task(invocation),
+            globs: vec![glob],
+        });
+    }
+    if tasks.is_empty() {
+        return Err(RunnerError::TaskInvocation(
+            "the `[watch]` table has no `map` entries".to_owned(),
+        ));
+    }
+    // The group holds the watch lock of every task it drives, so a single
+    // `effigy watch <task>` for one of them fails fast.
+    let scopes = tasks
+        .iter()
+        .map(|task| LockScope::Task(format!("watch:{}", task.target.label())))
+        .collect::<Vec<LockScope>>();
+    let _watch_lock = acquire_scopes(target_root, &scopes)?;
+
+    let globs = tasks
+        .iter()
+        .flat_map(|task| task.globs.iter().cloned())
+        .collect::<Vec<String>>();
+    let matcher = build_matcher(
+        &globs,
+        &request.exclude,
+        IgnoreRules::new(target_root, !request.no_ignore),
+    )?;
+    let summary = run_watch_group(tasks, target_root, &matcher, &request)?;
+    render_watch_result_json(request.output_json, &summary)
+}
+
+/// `--restart`: keeps the target running as a supervised child and replaces
+/// it on every change. With `--max-runs`, the last start runs until the
+/// target exits on its own.
+fn run_watch_restart_loop(
+    target: &WatchTarget,
+    target_root: &Path,
+    matcher: &WatchMatcher,
+    request: &WatchRequest,
+    notifier: Option<&Notifier>,
+) -> Result<WatchSummary, RunnerError> {
+    let policy = request.stop_policy();
+    let mut summary = WatchSummary {
+        targets: vec![target.label().to_owned()],
+        restart: true,
+        ..WatchSummary::default()
+    };
+    let mut running = Some(SupervisedTarget::spawn(
+        &target.command_line(request.output_json, &[])?,
+        target.label(),
+        target_root,
+        &policy,
+        TargetOutput::for_json(request.output_json),
+        &[],
+    )?);
+    summary.record_run(target.label(), Vec::new());
+
+    if Some(summary.runs()) != request.max_runs {
+        let mut watcher =
+            ChangeWatcher::new(target_root, matcher, request.debounce_ms(), request.poll)?;
+        summary.backend = Some(watcher.backend());
+        loop {
+            let changes = watcher.wait_for_changes(matcher, request.debounce_ms())?;
+            summary.note_backend(&mut watcher);
+            // Drop the previous target before spawning so its signal
+            // forwarding is restored first.
+            if let Some(mut previous) = running.take() {
+                if previous.stop() {
+                    summary.forced_stops += 1;
+                }
+            }
+            running = Some(SupervisedTarget::spawn(
+                &target.command_line(request.output_json, &changes)?,
+                target.label(),
+                target_root,
+                &policy,
+                TargetOutput::for_json(request.output_json),
+                &changes,
+            )?);
+            summary.record_run(target.label(), changes);
+            if let Some(notifier) = notifier {
+                notifier.send(
+                    target_root,
+                    NotifyEvent::WatchRunFinished,
+                    &format!(
+                        "watch restart {} of `{}` started",
+                        summary.runs() - 1,
+                        target.label()
+                    ),
+                );
+            }
+            if Some(summary.runs()) == request.max_runs {
+                break;
+            }
         }
     }
+    if let Some(mut last) = running.take() {
+        last.wait()?;
+    }
+    Ok(summary)
 }
 
-fn run_watch_target(
+/// Manifest task behind a watch target, for its `watch` defaults and
+/// notifier. Built-in targets such as `test` have none and only use the
+/// `EFFIGY_NOTIFY*` env vars.
+fn resolve_watch_task<'a>(
     target: &TaskInvocation,
+    catalogs: &'a [LoadedCatalog],
+    invocation_cwd: &Path,
+) -> Option<&'a ManifestTask> {
+    parse_task_selector(&target.name)
+        .ok()
+        .and_then(|selector| select_catalog_and_task(&selector, catalogs, invocation_cwd).ok())
+        .map(|selection| selection.task)
+}
+
+/// A run of the watch target. Runs that `--on-change cancel` may stop are
+/// supervised in their own process group, so a cancel stops everything the
+/// run started.
+enum TargetRun {
+    Plain(Child),
+    Supervised(SupervisedTarget),
+}
+
+impl TargetRun {
+    fn try_wait(&mut self) -> Result<Option<ExitStatus>, RunnerError> {
+        match self {
+            TargetRun::Plain(child) => child.try_wait().map_err(|error| {
+                RunnerError::TaskInvocation(format!("watch failed to wait for target: {error}"))
+            }),
+            TargetRun::Supervised(target) => target.try_wait(),
+        }
+    }
+
+    /// Stops the run. Returns `true` when it ignored the stop signal and had
+    /// to be killed.
+    fn cancel(&mut self) -> bool {
+        match self {
+            TargetRun::Plain(child) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                false
+            }
+            TargetRun::Supervised(target) => target.stop(),
+        }
+    }
+}
+
+/// Starts the target with the files that triggered the run in
+/// `EFFIGY_CHANGED_FILES`.
+fn spawn_watch_target(
+    target: &WatchTarget,
     target_root: &Path,
     output_json: bool,
-) -> Result<(), RunnerError> {
-    let mut invocation = target.clone();
+    changes: &[String],
+    cancel_policy: Option<&RestartPolicy>,
+) -> Result<TargetRun, RunnerError> {
+    let command_line = target.command_line(output_json, changes)?;
+    if let Some(policy) = cancel_policy {
+        return SupervisedTarget::spawn(
+            &command_line,
+            target.label(),
+            target_root,
+            policy,
+            TargetOutput::for_json(output_json),
+            changes,
+        )
+        .map(TargetRun::Supervised);
+    }
+    let mut process = ProcessCommand::new("sh");
+    process
+        .arg("-c")
+        .arg(&command_line)
+        .current_dir(target_root)
+        .env(CHANGED_FILES_ENV, changes.join("\n"));
     if output_json {
-        invocation.args.push("--json".to_owned());
+        process.stdout(Stdio::null());
     }
-    let _ = run_manifest_task_with_cwd(&invocation, target_root.to_path_buf())?;
-    Ok(())
+    process
+        .spawn()
+        .map(TargetRun::Plain)
+        .map_err(|error| RunnerError::TaskCommandLaunch {
+            command: command_line,
+            error,
+        })
+}
+
+fn check_watch_exit(target: &WatchTarget, status: ExitStatus) -> Result<(), RunnerError> {
+    if status.success() {
+        return Ok(());
+    }
+    Err(RunnerError::TaskCommandFailure {
+        command: target.label().to_owned(),
+        code: status.code(),
+        stdout: String::new(),
+        stderr: String::new(),
+    })
 }
 
 fn parse_watch_request(
@@ -133,10 +767,18 @@ fn parse_watch_request(
     let mut output_json = false;
     let mut help = false;
     let mut owner: Option<WatchOwner> = None;
-    let mut debounce_ms = DEFAULT_DEBOUNCE_MS;
+    let mut debounce_ms: Option<u64> = None;
     let mut include = Vec::<String>::new();
     let mut exclude = Vec::<String>::new();
     let mut max_runs: Option<usize> = None;
+    let mut poll = false;
+    let mut restart = false;
+    let mut signal: Option<String> = None;
+    let mut kill_timeout_ms: Option<u64> = None;
+    let mut run_command: Option<String> = None;
+    let mut on_change: Option<OnChange> = None;
+    let mut map = Vec::<(String, String)>::new();
+    let mut tui = false;
     let mut target: Option<TaskInvocation> = None;
     let mut i = 0usize;
 
@@ -187,7 +829,7 @@ fn parse_watch_request(
                         "`--debounce-ms` must be greater than zero".to_owned(),
                     ));
                 }
-                debounce_ms = parsed;
+                debounce_ms = Some(parsed);
                 i += 2;
             }
             "--include" => {
@@ -212,6 +854,96 @@ fn parse_watch_request(
                 max_runs = Some(1);
                 i += 1;
             }
+            "--poll" => {
+                poll = true;
+                i += 1;
+            }
+            "--restart" => {
+                restart = true;
+                i += 1;
+            }
+            "--tui" => {
+                tui = true;
+                i += 1;
+            }
+            "--run" => {
+                let Some(value) = args.get(i + 1) else {
+                    return Err(RunnerError::TaskInvocation(
+                        "`--run` requires a shell command".to_owned(),
+                    ));
+                };
+                run_command = Some(value.clone());
+                i += 2;
+            }
+            "--on-change" => {
+                let Some(value) = args.get(i + 1) else {
+                    return Err(RunnerError::TaskInvocation(
+                        "`--on-change` requires a value (`queue`, `cancel` or `ignore`)".to_owned(),
+                    ));
+                };
+                on_change = Some(match value.as_str() {
+                    "queue" => OnChange::Queue,
+                    "cancel" => OnChange::Cancel,
+                    "ignore" => OnChange::Ignore,
+                    _ => {
+                        return Err(RunnerError::TaskInvocation(format!(
+                            "invalid `--on-change` value `{value}` (expected `queue`, `cancel` or `ignore`)"
+                        )));
+                    }
+                });
+                i += 2;
+            }
+            "--map" => {
+                let Some(value) = args.get(i + 1) else {
+                    return Err(RunnerError::TaskInvocation(
+                        "`--map` requires a `<glob>=<task>` value".to_owned(),
+                    ));
+                };
+                let Some((glob, task_name)) = value
+                    .rsplit_once('=')
+                    .map(|(glob, task_name)| (glob.trim(), task_name.trim()))
+                    .filter(|(glob, task_name)| !glob.is_empty() && !task_name.is_empty())
+                else {
+                    return Err(RunnerError::TaskInvocation(format!(
+                        "invalid `--map` value `{value}` (expected `<glob>=<task>`, for example `src/**/*.ts=typecheck`)"
+                    )));
+                };
+                map.push((glob.to_owned(), task_name.to_owned()));
+                i += 2;
+            }
+            "--signal" => {
+                let Some(value) = args.get(i + 1) else {
+                    return Err(RunnerError::TaskInvocation(
+                        "`--signal` requires a signal name (for example `SIGINT`)".to_owned(),
+                    ));
+                };
+                let Some(parsed) = parse_stop_signal(value) else {
+                    return Err(RunnerError::TaskInvocation(format!(
+                        "invalid `--signal` value `{value}` (expected a signal name such as `SIGTERM`, `SIGINT` or `HUP`)"
+                    )));
+                };
+                signal = Some(parsed);
+                i += 2;
+            }
+            "--kill-timeout-ms" => {
+                let Some(value) = args.get(i + 1) else {
+                    return Err(RunnerError::TaskInvocation(
+                        "`--kill-timeout-ms` requires a numeric value".to_owned(),
+                    ));
+                };
+                let parsed = value.parse::<u64>().map_err(|_| {
+                    RunnerError::TaskInvocation(format!(
+                        "invalid `--kill-timeout-ms` value `{value}` (expected a positive integer)"
+                    ))
+                })?;
+                if parsed == 0 {
+                    return Err(RunnerError::TaskInvocation(
+                        "`--kill-timeout-ms` must be greater than zero".to_owned(),
+                    ));
+                }
+                kill_timeout_ms = Some(parsed);
+                i += 2;
+            }
             "--max-runs" => {
                 let Some(value) = args.get(i + 1) else {
                     return Err(RunnerError::TaskInvocation(
@@ -242,6 +974,11 @@ fn parse_watch_request(
                     task.name, arg
                 )));
             }
+            _ if run_command.is_some() => {
+                return Err(RunnerError::TaskInvocation(format!(
+                    "watch takes either `--run <CMD>` or a target task, not both (got `{arg}`)"
+                )));
+            }
             _ => {
                 target = Some(TaskInvocation {
                     name: arg.clone(),
@@ -260,7 +997,17 @@ fn parse_watch_request(
         include,
         exclude,
         max_runs,
-        target,
+        poll,
+        no_ignore: false,
+        restart,
+        signal,
+        kill_timeout_ms,
+        on_change,
+        map,
+        tui,
+        target: run_command
+            .map(WatchTarget::Command)
+            .or(target.map(WatchTarget::Task)),
     })
 }
 
@@ -289,22 +1036,55 @@ fn render_watch_help_payload(output_json: bool) -> Result<Option<String>, Runner
     Ok(Some(rendered))
 }
 
-fn render_watch_result_json(output_json: bool, runs: usize) -> Result<Option<String>, RunnerError> {
+fn render_watch_result_json(
+    output_json: bool,
+    summary: &WatchSummary,
+) -> Result<Option<String>, RunnerError> {
     if !output_json {
-        return Ok(Some(format!("watch complete after {runs} run(s).")));
+        let mut rendered = if summary.cancelled > 0 {
+            format!(
+                "watch complete after {} run(s) ({} cancelled).",
+                summary.runs(),
+                summary.cancelled
+            )
+        } else {
+            format!("watch complete after {} run(s).", summary.runs())
+        };
+        for warning in &summary.warnings {
+            rendered.push_str(&format!("\nwarning: {warning}"));
+        }
+        return Ok(Some(rendered));
     }
     let payload = json!({
         "schema": "effigy.watch.v1",
         "schema_version": 1,
         "ok": true,
-        "runs": runs,
+        "runs": summary.runs(),
+        "targets": summary.targets,
+        "backend": summary.backend.map(WatchBackend::label),
+        "restart": summary.restart,
+        "forced_stops": summary.forced_stops,
+        "cancelled": summary.cancelled,
+        "warnings": summary.warnings,
+        "changes": summary
+            .run_log
+            .iter()
+            .enumerate()
+            .map(|(index, run)| {
+                json!({ "run": index + 1, "target": run.target, "files": run.files })
+            })
+            .collect::<Vec<serde_json::Value>>(),
     });
     serde_json::to_string_pretty(&payload)
         .map(Some)
         .map_err(|error| RunnerError::Ui(format!("failed to encode json: {error}")))
 }
 
-fn build_matcher(include: &[String], exclude: &[String]) -> Result<WatchMatcher, RunnerError> {
+pub(super) fn build_matcher(
+    include: &[String],
+    exclude: &[String],
+    ignore: IgnoreRules,
+) -> Result<WatchMatcher, RunnerError> {
     let include_set = if include.is_empty() {
         None
     } else {
@@ -312,17 +1092,57 @@ fn build_matcher(include: &[String], exclude: &[String]) -> Result<WatchMatcher,
     };
     let mut excludes = vec![
         ".git/**".to_owned(),
+        ".effigy/**".to_owned(),
         "node_modules/**".to_owned(),
         "target/**".to_owned(),
     ];
     excludes.extend(exclude.iter().cloned());
     let exclude_set = build_glob_set(&excludes, "exclude")?;
+    let excluded_dirs = excludes
+        .iter()
+        .filter_map(|pattern| pattern.strip_suffix("/**"))
+        .map(str::to_owned)
+        .collect::<Vec<String>>();
+    let exclude_dirs = build_glob_set(&excluded_dirs, "exclude")?;
+    let include_dirs = include
+        .iter()
+        .map(|pattern| literal_dir_prefix(pattern))
+        .collect::<Option<Vec<String>>>()
+        .filter(|dirs| !dirs.is_empty());
     Ok(WatchMatcher {
         include: include_set,
         exclude: exclude_set,
+        include_dirs,
+        exclude_dirs,
+        ignore,
     })
 }
 
+#[cfg(all(test, unix))]
+pub(super) fn supervised_groups() -> Vec<i32> {
+    restart::supervised_groups()
+}
+
+/// Leading directory components of `pattern` up to its first glob
+/// metacharacter, e.g. `src/app` for `src/app/**/*.ts`. `None` when the
+/// pattern starts with a wildcard and can match anywhere.
+fn literal_dir_prefix(pattern: &str) -> Option<String> {
+    let components = pattern
+        .trim_start_matches("./")
+        .split('/')
+        .collect::<Vec<&str>>();
+    let literal = components[..components.len().saturating_sub(1)]
+        .iter()
+        .take_while(|component| !component.contains(['*', '?', '[', '{']))
+        .copied()
+        .collect::<Vec<&str>>();
+    if literal.is_empty() {
+        None
+    } else {
+        Some(literal.join("/"))
+    }
+}
+
 fn build_glob_set(patterns: &[String], label: &str) -> Result<GlobSet, RunnerError> {
     let mut builder = GlobSetBuilder::new();
     for pattern in patterns {
@@ -336,115 +1156,40 @@ fn build_glob_set(patterns: &[String], label: &str) -> Result<GlobSet, RunnerErr
     })
 }
 
-fn wait_for_changes(
-    root: &Path,
-    matcher: &WatchMatcher,
-    snapshot: &mut HashMap<PathBuf, FileStamp>,
-    debounce_ms: u64,
-) -> Result<Vec<String>, RunnerError> {
-    let debounce = Duration::from_millis(debounce_ms);
-    let poll_ms = (debounce_ms / 4).clamp(50, 800);
-    let poll = Duration::from_millis(poll_ms);
-    let mut changed = BTreeSet::<String>::new();
-    loop {
-        std::thread::sleep(poll);
-        let next = collect_snapshot(root, matcher)?;
-        for rel in snapshot_diff(snapshot, &next) {
-            changed.insert(rel);
-        }
-        *snapshot = next;
-        if changed.is_empty() {
-            continue;
-        }
-        let mut quiet_deadline = std::time::Instant::now() + debounce;
-        loop {
-            std::thread::sleep(poll);
-            let next = collect_snapshot(root, matcher)?;
-            let delta = snapshot_diff(snapshot, &next);
-            *snapshot = next;
-            if delta.is_empty() {
-                if std::time::Instant::now() >= quiet_deadline {
-                    return Ok(changed.into_iter().collect());
-                }
-            } else {
-                for rel in delta {
-                    changed.insert(rel);
-                }
-                quiet_deadline = std::time::Instant::now() + debounce;
-            }
-        }
-    }
-}
-
-fn collect_snapshot(
-    root: &Path,
-    matcher: &WatchMatcher,
-) -> Result<HashMap<PathBuf, FileStamp>, RunnerError> {
-    let mut snapshot = HashMap::<PathBuf, FileStamp>::new();
-    for entry in WalkDir::new(root).follow_links(false) {
-        let entry = entry.map_err(|error| {
-            RunnerError::TaskInvocation(format!(
-                "watch scan failed under {}: {error}",
-                root.display()
-            ))
-        })?;
-        let path = entry.path();
-        let rel = path.strip_prefix(root).unwrap_or(path);
-        if rel.as_os_str().is_empty() {
-            continue;
-        }
-        let rel_for_match = normalize_for_match(rel);
-        if !matcher.matches(&rel_for_match) {
-            continue;
-        }
-        if !entry.file_type().is_file() {
-            continue;
-        }
-        let metadata = entry.metadata().map_err(|error| {
-            RunnerError::TaskInvocation(format!(
-                "watch metadata read failed for {}: {error}",
-                path.display()
-            ))
-        })?;
-        snapshot.insert(
-            rel.to_path_buf(),
-            FileStamp {
-                modified: metadata.modified().ok(),
-                size: metadata.len(),
-            },
-        );
-    }
-    Ok(snapshot)
-}
-
-fn snapshot_diff(
-    old: &HashMap<PathBuf, FileStamp>,
-    new: &HashMap<PathBuf, FileStamp>,
-) -> Vec<String> {
-    let mut changed = BTreeSet::<String>::new();
-    for (path, stamp) in new {
-        if old.get(path) != Some(stamp) {
-            changed.insert(path.to_string_lossy().replace('\\', "/"));
-        }
-    }
-    for path in old.keys() {
-        if !new.contains_key(path) {
-            changed.insert(path.to_string_lossy().replace('\\', "/"));
-        }
-    }
-    changed.into_iter().collect()
-}
-
 impl WatchMatcher {
     fn matches(&self, rel_path: &str) -> bool {
         let rel = rel_path.trim_start_matches("./");
         if self.exclude.is_match(rel) {
             return false;
         }
-        match self.include.as_ref() {
+        let included = match self.include.as_ref() {
             Some(include) => include.is_match(rel),
             None => true,
+        };
+        included && !self.ignore.is_ignored(rel, false)
+    }
+
+    /// Whether a directory can contain matching files and so needs watching.
+    pub(super) fn watches_dir(&self, rel_dir: &str) -> bool {
+        let rel = rel_dir.trim_start_matches("./");
+        if rel.is_empty() {
+            return true;
+        }
+        if self.exclude_dirs.is_match(rel) || self.ignore.is_ignored(rel, true) {
+            return false;
         }
+        let Some(include_dirs) = self.include_dirs.as_ref() else {
+            return true;
+        };
+        include_dirs.iter().any(|dir| {
+            rel == dir
+                || rel
+                    .strip_prefix(dir.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+                || dir
+                    .strip_prefix(rel)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
     }
 }
 
@@ -0,0 +1,541 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use walkdir::WalkDir;
+
+use super::super::super::ignore::IGNORE_FILES;
+use super::super::super::RunnerError;
+use super::{normalize_for_match, WatchMatcher};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum WatchBackend {
+    Inotify,
+    Poll,
+}
+
+impl WatchBackend {
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            WatchBackend::Inotify => "inotify",
+            WatchBackend::Poll => "poll",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+/// Changes seen by one wake-up of a backend. `overflowed` means the kernel
+/// dropped events, so something changed but the paths are unknown.
+#[derive(Debug, Default)]
+struct ChangeBatch {
+    paths: BTreeSet<String>,
+    overflowed: bool,
+}
+
+impl ChangeBatch {
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty() && !self.overflowed
+    }
+}
+
+/// One poll of the tree: the matching files, plus the ignore files in the
+/// scanned directories so edits to them can be noticed without re-reading
+/// them on every poll.
+struct Snapshot {
+    files: HashMap<PathBuf, FileStamp>,
+    ignore_files: HashMap<PathBuf, FileStamp>,
+}
+
+enum ChangeSource {
+    Poll {
+        snapshot: Snapshot,
+        interval: Duration,
+    },
+    #[cfg(target_os = "linux")]
+    Inotify(inotify::InotifyWatcher),
+}
+
+pub(super) struct ChangeWatcher {
+    root: PathBuf,
+    source: ChangeSource,
+    poll_interval: Duration,
+    /// Why the watcher fell back to polling, until the caller takes it.
+    warning: Option<String>,
+}
+
+impl ChangeWatcher {
+    /// Uses inotify on Linux unless `force_poll` is set; falls back to the
+    /// poller when inotify cannot be set up (for example when the
+    /// `max_user_watches` limit is reached).
+    pub(super) fn new(
+        root: &Path,
+        matcher: &WatchMatcher,
+        debounce_ms: u64,
+        force_poll: bool,
+    ) -> Result<Self, RunnerError> {
+        let poll_interval = Duration::from_millis((debounce_ms / 4).clamp(50, 800));
+        #[cfg(target_os = "linux")]
+        if !force_poll {
+            if let Ok(watcher) = inotify::InotifyWatcher::new(root, matcher) {
+                return Ok(Self {
+                    root: root.to_path_buf(),
+                    source: ChangeSource::Inotify(watcher),
+                    poll_interval,
+                    warning: None,
+                });
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = force_poll;
+        Ok(Self {
+            root: root.to_path_buf(),
+            source: ChangeSource::Poll {
+                snapshot: collect_snapshot(root, matcher)?,
+                interval: poll_interval,
+            },
+            poll_interval,
+            warning: None,
+        })
+    }
+
+    /// The reason the watcher switched from inotify to polling mid-watch,
+    /// once.
+    pub(super) fn take_warning(&mut self) -> Option<String> {
+        self.warning.take()
+    }
+
+    pub(super) fn backend(&self) -> WatchBackend {
+        match self.source {
+            ChangeSource::Poll { .. } => WatchBackend::Poll,
+            #[cfg(target_os = "linux")]
+            ChangeSource::Inotify(_) => WatchBackend::Inotify,
+        }
+    }
+
+    /// Blocks until a matching change arrives, then keeps collecting until
+    /// `debounce_ms` passes without further changes. Returns the changed
+    /// paths relative to the root; the list is empty only when the kernel
+    /// dropped events.
+    pub(super) fn wait_for_changes(
+        &mut self,
+        matcher: &WatchMatcher,
+        debounce_ms: u64,
+    ) -> Result<Vec<String>, RunnerError> {
+        loop {
+            if let Some(changed) = self.changes_within(matcher, debounce_ms, None)? {
+                return Ok(changed);
+            }
+        }
+    }
+
+    /// Like `wait_for_changes`, but returns `None` when the first wake-up
+    /// (at most `timeout`, or one poll interval) brings no change, so the
+    /// caller can check on running targets in between.
+    pub(super) fn changes_within(
+        &mut self,
+        matcher: &WatchMatcher,
+        debounce_ms: u64,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Vec<String>>, RunnerError> {
+        let batch = self.next_batch(matcher, timeout)?;
+        if batch.is_empty() {
+            return Ok(None);
+        }
+        let debounce = Duration::from_millis(debounce_ms);
+        let mut changed = batch.paths;
+        let mut quiet_deadline = Instant::now() + debounce;
+        loop {
+            let remaining = quiet_deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(Some(changed.into_iter().collect()));
+            }
+            let batch = self.next_batch(matcher, Some(remaining))?;
+            if !batch.is_empty() {
+                changed.extend(batch.paths);
+                quiet_deadline = Instant::now() + debounce;
+            }
+        }
+    }
+
+    fn next_batch(
+        &mut self,
+        matcher: &WatchMatcher,
+        timeout: Option<Duration>,
+    ) -> Result<ChangeBatch, RunnerError> {
+        match &mut self.source {
+            ChangeSource::Poll { snapshot, interval } => {
+                std::thread::sleep(timeout.map_or(*interval, |timeout| timeout.min(*interval)));
+                let mut next = collect_snapshot(&self.root, matcher)?;
+                if next.ignore_files != snapshot.ignore_files {
+                    // The scan used the old rules; scan again with the new.
+                    matcher.ignore.reload();
+                    next = collect_snapshot(&self.root, matcher)?;
+                }
+                let paths = snapshot_diff(&snapshot.files, &next.files);
+                *snapshot = next;
+                Ok(ChangeBatch {
+                    paths,
+                    overflowed: false,
+                })
+            }
+            #[cfg(target_os = "linux")]
+            ChangeSource::Inotify(watcher) => {
+                let batch = watcher.next_batch(matcher, timeout)?;
+                if let Some(error) = watcher.take_failure() {
+                    // Some directory is unwatched, so inotify can no longer
+                    // see every change; the poller can.
+                    self.warning = Some(format!(
+                        "inotify could not watch a new directory ({error}); falling back to polling"
+                    ));
+                    self.source = ChangeSource::Poll {
+                        snapshot: collect_snapshot(&self.root, matcher)?,
+                        interval: self.poll_interval,
+                    };
+                }
+                Ok(batch)
+            }
+        }
+    }
+}
+
+fn collect_snapshot(root: &Path, matcher: &WatchMatcher) -> Result<Snapshot, RunnerError> {
+    let mut snapshot = Snapshot {
+        files: HashMap::new(),
+        ignore_files: HashMap::new(),
+    };
+    let walker = WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            !entry.file_type().is_dir()
+                || matcher.watches_dir(&normalize_for_match(
+                    entry.path().strip_prefix(root).unwrap_or(entry.path()),
+                ))
+        });
+    for entry in walker {
+        let entry = entry.map_err(|error| {
+            RunnerError::TaskInvocation(format!(
+                "watch scan failed under {}: {error}",
+                root.display()
+            ))
+        })?;
+        let path = entry.path();
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let matches = matcher.matches(&normalize_for_match(rel));
+        let is_ignore_file = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| IGNORE_FILES.contains(&name));
+        if !matches && !is_ignore_file {
+            continue;
+        }
+        let metadata = entry.metadata().map_err(|error| {
+            RunnerError::TaskInvocation(format!(
+                "watch metadata read failed for {}: {error}",
+                path.display()
+            ))
+        })?;
+        let stamp = FileStamp {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        };
+        if is_ignore_file {
+            snapshot.ignore_files.insert(rel.to_path_buf(), stamp);
+        }
+        if matches {
+            snapshot.files.insert(rel.to_path_buf(), stamp);
+        }
+    }
+    Ok(snapshot)
+}
+
+fn snapshot_diff(
+    old: &HashMap<PathBuf, FileStamp>,
+    new: &HashMap<PathBuf, FileStamp>,
+) -> BTreeSet<String> {
+    let mut changed = BTreeSet::<String>::new();
+    for (path, stamp) in new {
+        if old.get(path) != Some(stamp) {
+            changed.insert(normalize_for_match(path));
+        }
+    }
+    for path in old.keys() {
+        if !new.contains_key(path) {
+            changed.insert(normalize_for_match(path));
+        }
+    }
+    changed
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::collections::{BTreeSet, HashMap, HashSet};
+    use std::os::fd::AsFd;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use nix::errno::Errno;
+    use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
+    use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+    use walkdir::WalkDir;
+
+    use super::super::super::super::ignore::IGNORE_FILES;
+    use super::super::super::super::RunnerError;
+    use super::super::{normalize_for_match, WatchMatcher};
+    use super::ChangeBatch;
+
+    /// One inotify watch per directory that can hold matching files;
+    /// directories pruned by the matcher are never registered.
+    pub(super) struct InotifyWatcher {
+        root: PathBuf,
+        inotify: Inotify,
+        dirs: HashMap<WatchDescriptor, String>,
+        /// Set when a directory created after startup could not be watched
+        /// (for example `ENOSPC` once `max_user_watches` is reached).
+        failure: Option<Errno>,
+    }
+
+    impl InotifyWatcher {
+        pub(super) fn new(root: &Path, matcher: &WatchMatcher) -> Result<Self, Errno> {
+            let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+            let mut watcher = Self {
+                root: root.to_path_buf(),
+                inotify,
+                dirs: HashMap::new(),
+                failure: None,
+            };
+            watcher.register_tree("", matcher, None)?;
+            Ok(watcher)
+        }
+
+        fn watch_mask() -> AddWatchFlags {
+            AddWatchFlags::IN_CREATE
+                | AddWatchFlags::IN_DELETE
+                | AddWatchFlags::IN_MODIFY
+                | AddWatchFlags::IN_CLOSE_WRITE
+                | AddWatchFlags::IN_ATTRIB
+                | AddWatchFlags::IN_MOVED_FROM
+                | AddWatchFlags::IN_MOVED_TO
+                | AddWatchFlags::IN_ONLYDIR
+                | AddWatchFlags::IN_DONT_FOLLOW
+        }
+
+        /// Watches `rel_dir` and every unpruned directory below it. Files
+        /// already inside are added to `found` so a directory created (or
+        /// moved in) together with its contents still reports them.
+        fn register_tree(
+            &mut self,
+            rel_dir: &str,
+            matcher: &WatchMatcher,
+            mut found: Option<&mut BTreeSet<String>>,
+        ) -> Result<(), Errno> {
+            let start = if rel_dir.is_empty() {
+                self.root.clone()
+            } else {
+                self.root.join(rel_dir)
+            };
+            let root = self.root.clone();
+            let walker = WalkDir::new(&start)
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|entry| {
+                    !entry.file_type().is_dir()
+                        || matcher.watches_dir(&relative(&root, entry.path()))
+                });
+            for entry in walker.flatten() {
+                let rel = relative(&self.root, entry.path());
+                if entry.file_type().is_dir() {
+                    self.watch_dir(entry.path(), rel)?;
+                } else if let Some(found) = found.as_deref_mut() {
+                    if matcher.matches(&rel) {
+                        found.insert(rel);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn watch_dir(&mut self, path: &Path, rel: String) -> Result<(), Errno> {
+            match self.inotify.add_watch(path, Self::watch_mask()) {
+                Ok(wd) => {
+                    self.dirs.insert(wd, rel);
+                    Ok(())
+                }
+                // Directory vanished between the walk and the watch.
+                Err(Errno::ENOENT) | Err(Errno::ENOTDIR) => Ok(()),
+                Err(error) => Err(error),
+            }
+        }
+
+        /// Brings the watches in line with reloaded ignore rules: directories
+        /// that are now ignored are dropped and directories that are no
+        /// longer ignored are watched, with their matching files added to
+        /// `found`.
+        fn rescan(
+            &mut self,
+            matcher: &WatchMatcher,
+            found: &mut BTreeSet<String>,
+        ) -> Result<(), Errno> {
+            let pruned = self
+                .dirs
+                .iter()
+                .filter(|(_, dir)| !dir.is_empty() && !matcher.watches_dir(dir))
+                .map(|(wd, _)| *wd)
+                .collect::<Vec<WatchDescriptor>>();
+            for wd in pruned {
+                self.dirs.remove(&wd);
+                let _ = self.inotify.rm_watch(wd);
+            }
+            let watched = self.dirs.values().cloned().collect::<HashSet<String>>();
+            let root = self.root.clone();
+            let walker = WalkDir::new(&root)
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|entry| {
+                    !entry.file_type().is_dir()
+                        || matcher.watches_dir(&relative(&root, entry.path()))
+                });
+            for entry in walker.flatten() {
+                let rel = relative(&root, entry.path());
+                if entry.file_type().is_dir() {
+                    if !watched.contains(&rel) {
+                        self.watch_dir(entry.path(), rel)?;
+                    }
+                    continue;
+                }
+                let parent = rel.rsplit_once('/').map_or("", |(dir, _)| dir);
+                if !watched.contains(parent) && matcher.matches(&rel) {
+                    found.insert(rel);
+                }
+            }
+            Ok(())
+        }
+
+        pub(super) fn take_failure(&mut self) -> Option<Errno> {
+            self.failure.take()
+        }
+
+        fn forget_tree(&mut self, rel_dir: &str) {
+            let nested = format!("{rel_dir}/");
+            let stale = self
+                .dirs
+                .iter()
+                .filter(|(_, dir)| dir.as_str() == rel_dir || dir.starts_with(&nested))
+                .map(|(wd, _)| *wd)
+                .collect::<Vec<WatchDescriptor>>();
+            for wd in stale {
+                self.dirs.remove(&wd);
+                let _ = self.inotify.rm_watch(wd);
+            }
+        }
+
+        pub(super) fn next_batch(
+            &mut self,
+            matcher: &WatchMatcher,
+            timeout: Option<Duration>,
+        ) -> Result<ChangeBatch, RunnerError> {
+            let timeout = match timeout {
+                Some(timeout) => PollTimeout::try_from(timeout).unwrap_or(PollTimeout::MAX),
+                None => PollTimeout::NONE,
+            };
+            let mut fds = [PollFd::new(self.inotify.as_fd(), PollFlags::POLLIN)];
+            match poll(&mut fds, timeout) {
+                Ok(0) | Err(Errno::EINTR) => return Ok(ChangeBatch::default()),
+                Ok(_) => {}
+                Err(error) => return Err(watch_error(error)),
+            }
+
+            let mut batch = ChangeBatch::default();
+            let mut ignore_changed = false;
+            loop {
+                let events = match self.inotify.read_events() {
+                    Ok(events) => events,
+                    Err(Errno::EAGAIN) => break,
+                    Err(error) => return Err(watch_error(error)),
+                };
+                for event in events {
+                    if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+                        batch.overflowed = true;
+                        continue;
+                    }
+                    if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+                        self.dirs.remove(&event.wd);
+                        continue;
+                    }
+                    let (Some(dir), Some(name)) = (self.dirs.get(&event.wd), event.name) else {
+                        continue;
+                    };
+                    let name = name.to_string_lossy();
+                    let rel = if dir.is_empty() {
+                        name.into_owned()
+                    } else {
+                        format!("{dir}/{name}")
+                    };
+                    if event.mask.contains(AddWatchFlags::IN_ISDIR) {
+                        if event
+                            .mask
+                            .intersects(AddWatchFlags::IN_DELETE | AddWatchFlags::IN_MOVED_FROM)
+                        {
+                            self.forget_tree(&rel);
+                        }
+                        if event
+                            .mask
+                            .intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO)
+                            && matcher.watches_dir(&rel)
+                        {
+                            if let Err(error) =
+                                self.register_tree(&rel, matcher, Some(&mut batch.paths))
+                            {
+                                self.fail(error, &mut batch);
+                            }
+                        }
+                        continue;
+                    }
+                    if rel
+                        .rsplit('/')
+                        .next()
+                        .is_some_and(|file| IGNORE_FILES.contains(&file))
+                    {
+                        matcher.ignore.reload();
+                        ignore_changed = true;
+                    }
+                    if matcher.matches(&rel) {
+                        batch.paths.insert(rel);
+                    }
+                }
+            }
+            if ignore_changed {
+                if let Err(error) = self.rescan(matcher, &mut batch.paths) {
+                    self.fail(error, &mut batch);
+                }
+            }
+            Ok(batch)
+        }
+
+        /// Records a watch that could not be added. Changes under the
+        /// unwatched directory are unknown, so the batch counts as overflowed.
+        fn fail(&mut self, error: Errno, batch: &mut ChangeBatch) {
+            self.failure.get_or_insert(error);
+            batch.overflowed = true;
+        }
+    }
+
+    fn relative(root: &Path, path: &Path) -> String {
+        normalize_for_match(path.strip_prefix(root).unwrap_or(path))
+    }
+
+    fn watch_error(error: Errno) -> RunnerError {
+        RunnerError::TaskInvocation(format!("watch event read failed: {error}"))
+    }
+}
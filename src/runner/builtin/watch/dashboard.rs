@@ -0,0 +1,209 @@
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use crate::notify::{Notifier, NotifyEvent};
+use crate::tui::core::LogEntryKind;
+use crate::tui::{WatchDashboard, WatchDashboardCommand, WatchDashboardTerminal, WatchRunStatus};
+
+use super::super::super::RunnerError;
+use super::backend::ChangeWatcher;
+use super::restart::{SupervisedTarget, TargetOutput};
+use super::{OnChange, WatchMatcher, WatchRequest, WatchSummary, WatchTarget, RUN_POLL_INTERVAL};
+
+/// One line of target output, tagged with the run that printed it so late
+/// lines of a cancelled run do not leak into the next one.
+type OutputLine = (usize, LogEntryKind, String);
+
+/// `effigy watch --tui`: reruns the target like the plain loop, but shows
+/// the run history and the current run's output in a dashboard. A failed run
+/// is recorded and the watch keeps going. `r` cancels the running run (if
+/// any) and starts a fresh one, `p` pauses reacting to changes (changes made
+/// while paused are dropped), `c` clears the history, and `q` stops the
+/// running run and leaves.
+pub(super) fn run_watch_dashboard(
+    target: &WatchTarget,
+    target_root: &Path,
+    matcher: &WatchMatcher,
+    request: &WatchRequest,
+    notifier: Option<&Notifier>,
+) -> Result<WatchSummary, RunnerError> {
+    let policy = request.stop_policy();
+    let mut watcher =
+        ChangeWatcher::new(target_root, matcher, request.debounce_ms(), request.poll)?;
+    let mut summary = WatchSummary {
+        targets: vec![target.label().to_owned()],
+        backend: Some(watcher.backend()),
+        ..WatchSummary::default()
+    };
+    let mut dashboard = WatchDashboard::new(target.label(), watcher.backend().label());
+    let mut terminal = WatchDashboardTerminal::enter().map_err(ui_error)?;
+    let (sender, output) = mpsc::channel::<OutputLine>();
+
+    let mut running: Option<SupervisedTarget> = None;
+    let mut current = Vec::<String>::new();
+    let mut queued: Option<BTreeSet<String>> = Some(BTreeSet::new());
+    loop {
+        drain_output(&output, summary.runs(), &mut dashboard);
+        if let Some(run) = running.as_mut() {
+            if let Some(status) = run.try_wait()? {
+                running = None;
+                drain_output(&output, summary.runs(), &mut dashboard);
+                let passed = status.success();
+                dashboard.run_finished(if passed {
+                    WatchRunStatus::Passed
+                } else {
+                    WatchRunStatus::Failed(status.code())
+                });
+                if let (true, Some(notifier)) = (summary.runs() > 1, notifier) {
+                    notifier.send(
+                        target_root,
+                        NotifyEvent::WatchRunFinished,
+                        &format!(
+                            "watch rerun {} of `{}` {}",
+                            summary.runs() - 1,
+                            target.label(),
+                            if passed { "finished" } else { "failed" }
+                        ),
+                    );
+                }
+            }
+        }
+        if running.is_none() {
+            if let Some(changes) = queued.take() {
+                let changes = changes.into_iter().collect::<Vec<String>>();
+                let mut run = SupervisedTarget::spawn(
+                    &target.command_line(false, &changes)?,
+                    target.label(),
+                    target_root,
+                    &policy,
+                    TargetOutput::Piped,
+                    &changes,
+                )?;
+                summary.record_run(target.label(), changes.clone());
+                forward_output(&mut run, summary.runs(), &sender);
+                dashboard.run_started(changes.clone());
+                current = changes;
+                running = Some(run);
+            }
+        }
+
+        terminal.draw(&dashboard).map_err(ui_error)?;
+        match terminal.next_command(Duration::ZERO).map_err(ui_error)? {
+            Some(WatchDashboardCommand::Quit) => {
+                if let Some(mut run) = running.take() {
+                    if run.stop() {
+                        summary.forced_stops += 1;
+                    }
+                    summary.cancelled += 1;
+                }
+                return Ok(summary);
+            }
+            Some(WatchDashboardCommand::Rerun) => {
+                if let Some(mut run) = running.take() {
+                    if run.stop() {
+                        summary.forced_stops += 1;
+                    }
+                    summary.cancelled += 1;
+                    dashboard.run_finished(WatchRunStatus::Cancelled);
+                }
+                queued
+                    .get_or_insert_with(BTreeSet::new)
+                    .extend(std::mem::take(&mut current));
+                continue;
+            }
+            Some(WatchDashboardCommand::TogglePause) => dashboard.toggle_pause(),
+            Some(WatchDashboardCommand::Clear) => dashboard.clear(),
+            None => {}
+        }
+
+        let Some(changes) =
+            watcher.changes_within(matcher, request.debounce_ms(), Some(RUN_POLL_INTERVAL))?
+        else {
+            continue;
+        };
+        if let Some(warning) = summary.note_backend(&mut watcher) {
+            dashboard.set_backend(watcher.backend().label());
+            dashboard.ingest_output(
+                LogEntryKind::Stderr,
+                &format!("[effigy] warning: {warning}"),
+            );
+        }
+        if dashboard.paused() {
+            continue;
+        }
+        let Some(run) = running.as_mut() else {
+            queued.get_or_insert_with(BTreeSet::new).extend(changes);
+            continue;
+        };
+        match request.on_change() {
+            OnChange::Queue => queued.get_or_insert_with(BTreeSet::new).extend(changes),
+            OnChange::Cancel => {
+                if run.stop() {
+                    summary.forced_stops += 1;
+                }
+                running = None;
+                summary.cancelled += 1;
+                dashboard.run_finished(WatchRunStatus::Cancelled);
+                queued = Some(
+                    std::mem::take(&mut current)
+                        .into_iter()
+                        .chain(changes)
+                        .collect(),
+                );
+            }
+            OnChange::Ignore => {}
+        }
+    }
+}
+
+fn drain_output(output: &Receiver<OutputLine>, run: usize, dashboard: &mut WatchDashboard) {
+    while let Ok((line_run, kind, line)) = output.try_recv() {
+        if line_run == run {
+            dashboard.ingest_output(kind, &line);
+        }
+    }
+}
+
+/// Reads the run's piped stdout and stderr line by line on background
+/// threads; the threads end when the run's pipes close.
+fn forward_output(run: &mut SupervisedTarget, number: usize, sender: &Sender<OutputLine>) {
+    let (stdout, stderr) = run.take_output();
+    if let Some(stdout) = stdout {
+        spawn_line_reader(stdout, number, LogEntryKind::Stdout, sender.clone());
+    }
+    if let Some(stderr) = stderr {
+        spawn_line_reader(stderr, number, LogEntryKind::Stderr, sender.clone());
+    }
+}
+
+fn spawn_line_reader<R: Read + Send + 'static>(
+    source: R,
+    number: usize,
+    kind: LogEntryKind,
+    sender: Sender<OutputLine>,
+) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buffer);
+                    let line = line.trim_end_matches(['\n', '\r']).to_owned();
+                    if sender.send((number, kind.clone(), line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn ui_error(error: std::io::Error) -> RunnerError {
+    RunnerError::Ui(format!("watch dashboard failed: {error}"))
+}
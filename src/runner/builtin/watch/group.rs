@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use globset::GlobSet;
+
+use crate::notify::{Notifier, NotifyEvent};
+
+use super::super::super::RunnerError;
+use super::backend::ChangeWatcher;
+use super::restart::RestartPolicy;
+use super::{
+    build_glob_set, check_watch_exit, spawn_watch_target, OnChange, TargetRun, WatchMatcher,
+    WatchRequest, WatchSummary, WatchTarget, RUN_POLL_INTERVAL,
+};
+
+/// One task of a watch group, with the globs of all its mappings.
+#[derive(Debug)]
+pub(super) struct WatchGroupTask {
+    pub(super) target: WatchTarget,
+    pub(super) globs: Vec<String>,
+    pub(super) notifier: Option<Notifier>,
+}
+
+/// A group task's routing globs and run state. Changes for a task are
+/// merged into one queued run until it can start.
+struct GroupSlot {
+    task: WatchGroupTask,
+    globs: GlobSet,
+    running: Option<TargetRun>,
+    /// Changes behind the running run, requeued when it is cancelled.
+    current: Vec<String>,
+    queued: Option<BTreeSet<String>>,
+    runs: usize,
+}
+
+impl GroupSlot {
+    /// The changes that concern this task, or `None` when none do. An empty
+    /// change list (dropped kernel events) concerns every task.
+    fn route(&self, changes: &[String]) -> Option<Vec<String>> {
+        if changes.is_empty() {
+            return Some(Vec::new());
+        }
+        let routed = changes
+            .iter()
+            .filter(|path| self.globs.is_match(path.as_str()))
+            .cloned()
+            .collect::<Vec<String>>();
+        (!routed.is_empty()).then_some(routed)
+    }
+
+    fn start(
+        &mut self,
+        target_root: &Path,
+        output_json: bool,
+        cancel_policy: Option<&RestartPolicy>,
+        summary: &mut WatchSummary,
+    ) -> Result<(), RunnerError> {
+        let changes = self
+            .queued
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<String>>();
+        self.running = Some(spawn_watch_target(
+            &self.task.target,
+            target_root,
+            output_json,
+            &changes,
+            cancel_policy,
+        )?);
+        self.current = changes.clone();
+        self.runs += 1;
+        summary.record_run(self.task.target.label(), changes);
+        Ok(())
+    }
+
+    /// Reaps the running task if it has exited. Returns `None` while it is
+    /// still running (or when nothing runs).
+    fn try_finish(&mut self) -> Option<Result<(), RunnerError>> {
+        let status = match self.running.as_mut()?.try_wait() {
+            Ok(Some(status)) => status,
+            Ok(None) => return None,
+            Err(error) => return Some(Err(error)),
+        };
+        self.running = None;
+        Some(check_watch_exit(&self.task.target, status))
+    }
+}
+
+/// Runs every group task once, then reruns each task when files matching
+/// its globs change. Task runs take the workspace lock, so the group runs
+/// one task at a time: changes for a busy or waiting task are merged into
+/// its queued run, and queued tasks start in turn. `--on-change` applies to
+/// the running task only. A failed run stops the
+/// group, like a failed single-target rerun. `--max-runs` counts runs across
+/// the group and never cuts the first pass short.
+pub(super) fn run_watch_group(
+    tasks: Vec<WatchGroupTask>,
+    target_root: &Path,
+    matcher: &WatchMatcher,
+    request: &WatchRequest,
+) -> Result<WatchSummary, RunnerError> {
+    let mut slots = tasks
+        .into_iter()
+        .map(|task| {
+            Ok(GroupSlot {
+                globs: build_glob_set(&task.globs, "map")?,
+                task,
+                running: None,
+                current: Vec::new(),
+                queued: Some(BTreeSet::new()),
+                runs: 0,
+            })
+        })
+        .collect::<Result<Vec<GroupSlot>, RunnerError>>()?;
+    let mut summary = WatchSummary {
+        targets: slots
+            .iter()
+            .map(|slot| slot.task.target.label().to_owned())
+            .collect(),
+        ..WatchSummary::default()
+    };
+    let mut watcher = if request
+        .max_runs
+        .is_some_and(|max_runs| max_runs <= slots.len())
+    {
+        None
+    } else {
+        let watcher =
+            ChangeWatcher::new(target_root, matcher, request.debounce_ms(), request.poll)?;
+        summary.backend = Some(watcher.backend());
+        Some(watcher)
+    };
+
+    let cancel_policy = request.cancel_policy();
+    let mut failure: Option<RunnerError> = None;
+    let mut next = 0usize;
+    loop {
+        for slot in &mut slots {
+            match slot.try_finish() {
+                Some(Err(error)) => {
+                    failure.get_or_insert(error);
+                }
+                Some(Ok(())) if slot.runs > 1 => {
+                    if let Some(notifier) = slot.task.notifier.as_ref() {
+                        notifier.send(
+                            target_root,
+                            NotifyEvent::WatchRunFinished,
+                            &format!(
+                                "watch rerun {} of `{}` finished",
+                                slot.runs - 1,
+                                slot.task.target.label()
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let running = slots.iter().any(|slot| slot.running.is_some());
+        let done = failure.is_some()
+            || (slots.iter().all(|slot| slot.runs > 0)
+                && request
+                    .max_runs
+                    .is_some_and(|max_runs| summary.runs() >= max_runs));
+        if done {
+            if !running {
+                return match failure {
+                    Some(error) => Err(error),
+                    None => Ok(summary),
+                };
+            }
+            std::thread::sleep(RUN_POLL_INTERVAL);
+            continue;
+        }
+        if !running {
+            let count = slots.len();
+            if let Some(index) = (0..count)
+                .map(|offset| (next + offset) % count)
+                .find(|index| slots[*index].queued.is_some())
+            {
+                slots[index].start(
+                    target_root,
+                    request.output_json,
+                    cancel_policy.as_ref(),
+                    &mut summary,
+                )?;
+                next = index + 1;
+                continue;
+            }
+        }
+
+        let Some(watcher) = watcher.as_mut() else {
+            std::thread::sleep(RUN_POLL_INTERVAL);
+            continue;
+        };
+        let timeout = running.then_some(RUN_POLL_INTERVAL);
+        let Some(changes) = watcher.changes_within(matcher, request.debounce_ms(), timeout)? else {
+            continue;
+        };
+        summary.note_backend(watcher);
+        let last_run = request
+            .max_runs
+            .is_some_and(|max_runs| summary.runs() >= max_runs);
+        for slot in &mut slots {
+            let Some(routed) = slot.route(&changes) else {
+                continue;
+            };
+            let Some(run) = slot.running.as_mut() else {
+                slot.queued.get_or_insert_with(BTreeSet::new).extend(routed);
+                continue;
+            };
+            match request.on_change() {
+                OnChange::Queue => slot.queued.get_or_insert_with(BTreeSet::new).extend(routed),
+                // The last run allowed by `--max-runs` always finishes.
+                OnChange::Cancel if !last_run => {
+                    if run.cancel() {
+                        summary.forced_stops += 1;
+                    }
+                    slot.running = None;
+                    summary.cancelled += 1;
+                    slot.queued = Some(
+                        std::mem::take(&mut slot.current)
+                            .into_iter()
+                            .chain(routed)
+                            .collect(),
+                    );
+                }
+                OnChange::Cancel | OnChange::Ignore => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::path::Path;
+use std::process::{Child, ChildStderr, ChildStdout, Command as ProcessCommand, ExitStatus, Stdio};
+#[cfg(unix)]
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+use nix::sys::prctl::set_child_subreaper;
+#[cfg(unix)]
+use nix::sys::signal::{kill, Signal};
+#[cfg(unix)]
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+#[cfg(unix)]
+use nix::unistd::{setpgid, Pid};
+#[cfg(unix)]
+use signal_hook::iterator::Signals;
+#[cfg(unix)]
+use signal_hook::low_level::emulate_default_handler;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+use super::super::super::{RunnerError, CHANGED_FILES_ENV};
+
+pub(super) const DEFAULT_KILL_TIMEOUT_MS: u64 = 5000;
+
+/// Targets alive in this process (a watch group can run several at once),
+/// and the process group and stop signal of each one that has started.
+/// Signals and the child subreaper flag are process-wide, so they are set up
+/// for the first target and the subreaper flag is cleared after the last.
+#[cfg(unix)]
+struct Supervision {
+    targets: usize,
+    groups: Vec<(Pid, Signal)>,
+}
+
+#[cfg(unix)]
+static SUPERVISION: Mutex<Supervision> = Mutex::new(Supervision {
+    targets: 0,
+    groups: Vec::new(),
+});
+#[cfg(unix)]
+const FORWARDED_SIGNALS: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct RestartPolicy {
+    /// Normalized signal name, e.g. `SIGTERM`.
+    pub(super) signal: String,
+    pub(super) kill_timeout: Duration,
+}
+
+/// Where a supervised target's output goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TargetOutput {
+    Inherit,
+    /// `--json` keeps stdout for the watch payload.
+    QuietStdout,
+    /// The dashboard reads stdout and stderr through pipes.
+    Piped,
+}
+
+impl TargetOutput {
+    pub(super) fn for_json(output_json: bool) -> Self {
+        if output_json {
+            Self::QuietStdout
+        } else {
+            Self::Inherit
+        }
+    }
+}
+
+/// The watch target running as a child in a separate process group, so a
+/// restart stops the whole tree it started.
+pub(super) struct SupervisedTarget {
+    child: Child,
+    policy: RestartPolicy,
+}
+
+impl SupervisedTarget {
+    pub(super) fn spawn(
+        command_line: &str,
+        label: &str,
+        target_root: &Path,
+        policy: &RestartPolicy,
+        output: TargetOutput,
+        changes: &[String],
+    ) -> Result<Self, RunnerError> {
+        let mut process = ProcessCommand::new("sh");
+        process
+            .arg("-c")
+            .arg(command_line)
+            .current_dir(target_root)
+            .env(CHANGED_FILES_ENV, changes.join("\n"))
+            .stdin(Stdio::null());
+        match output {
+            TargetOutput::Inherit => {}
+            TargetOutput::QuietStdout => {
+                process.stdout(Stdio::null());
+            }
+            TargetOutput::Piped => {
+                process.stdout(Stdio::piped()).stderr(Stdio::piped());
+            }
+        }
+        #[cfg(unix)]
+        unsafe {
+            process.pre_exec(|| {
+                setpgid(Pid::from_raw(0), Pid::from_raw(0)).map_err(std::io::Error::from)
+            });
+        }
+        #[cfg(unix)]
+        begin_supervision();
+        let child = match process.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                #[cfg(unix)]
+                end_supervision(None);
+                return Err(RunnerError::TaskInvocation(format!(
+                    "watch failed to start `{label}`: {error}"
+                )));
+            }
+        };
+        #[cfg(unix)]
+        supervise_group(&child, policy);
+        Ok(Self {
+            child,
+            policy: policy.clone(),
+        })
+    }
+
+    /// The piped stdout and stderr of a target spawned with
+    /// [`TargetOutput::Piped`]; `None` once taken or when not piped.
+    pub(super) fn take_output(&mut self) -> (Option<ChildStdout>, Option<ChildStderr>) {
+        (self.child.stdout.take(), self.child.stderr.take())
+    }
+
+    /// Sends the stop signal to the target's process group, waits up to the
+    /// kill timeout for the group to exit, then kills it. Returns `true` when
+    /// the target had to be killed.
+    pub(super) fn stop(&mut self) -> bool {
+        if self.group_exited() {
+            return false;
+        }
+        #[cfg(unix)]
+        {
+            let signal = self
+                .policy
+                .signal
+                .parse::<Signal>()
+                .unwrap_or(Signal::SIGTERM);
+            self.signal_group(signal);
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = self.child.kill();
+        }
+        let deadline = Instant::now() + self.policy.kill_timeout;
+        while Instant::now() < deadline {
+            if self.group_exited() {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(25));
+        }
+        #[cfg(unix)]
+        self.signal_group(Signal::SIGKILL);
+        let _ = self.child.kill();
+        while !self.group_exited() {
+            std::thread::sleep(Duration::from_millis(25));
+        }
+        true
+    }
+
+    /// The target's exit status once the direct child has exited; also
+    /// reaps adopted descendants that have exited by then.
+    pub(super) fn try_wait(&mut self) -> Result<Option<ExitStatus>, RunnerError> {
+        let status = self.child.try_wait().map_err(|error| {
+            RunnerError::TaskInvocation(format!("watch failed to wait for target: {error}"))
+        })?;
+        if status.is_some() {
+            let _ = self.group_exited();
+        }
+        Ok(status)
+    }
+
+    /// Waits for the target to finish on its own.
+    pub(super) fn wait(&mut self) -> Result<(), RunnerError> {
+        self.child.wait().map(|_| ()).map_err(|error| {
+            RunnerError::TaskInvocation(format!("watch failed to wait for target: {error}"))
+        })?;
+        while !self.group_exited() {
+            std::thread::sleep(Duration::from_millis(25));
+        }
+        Ok(())
+    }
+
+    /// Whether the direct child and every adopted descendant still in its
+    /// process group have exited. Reaps them as a side effect.
+    fn group_exited(&mut self) -> bool {
+        if self.child.try_wait().ok().flatten().is_none() {
+            return false;
+        }
+        #[cfg(unix)]
+        {
+            let group = Pid::from_raw(-(self.child.id() as i32));
+            loop {
+                match waitpid(group, Some(WaitPidFlag::WNOHANG)) {
+                    Ok(WaitStatus::StillAlive) => return false,
+                    Ok(_) => continue,
+                    Err(_) => return true,
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        true
+    }
+
+    #[cfg(unix)]
+    fn signal_group(&self, signal: Signal) {
+        let pid = self.child.id() as i32;
+        if pid > 0 {
+            let _ = kill(Pid::from_raw(-pid), signal);
+        }
+    }
+}
+
+impl Drop for SupervisedTarget {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        end_supervision(Some(Pid::from_raw(self.child.id() as i32)));
+    }
+}
+
+/// Counts a target about to be spawned. The first one makes effigy a child
+/// subreaper, so a restart can wait for (and reap) the target's orphaned
+/// descendants and not just the direct child, and starts signal forwarding.
+#[cfg(unix)]
+fn begin_supervision() {
+    let mut supervision = SUPERVISION.lock().expect("supervision lock");
+    if supervision.targets == 0 {
+        #[cfg(target_os = "linux")]
+        let _ = set_child_subreaper(true);
+        start_signal_forwarding();
+    }
+    supervision.targets += 1;
+}
+
+#[cfg(unix)]
+fn supervise_group(child: &Child, policy: &RestartPolicy) {
+    let signal = policy.signal.parse::<Signal>().unwrap_or(Signal::SIGTERM);
+    SUPERVISION
+        .lock()
+        .expect("supervision lock")
+        .groups
+        .push((Pid::from_raw(child.id() as i32), signal));
+}
+
+/// Forgets a target (`None` when it never started). After the last one
+/// effigy stops being a child subreaper.
+#[cfg(unix)]
+fn end_supervision(group: Option<Pid>) {
+    let mut supervision = SUPERVISION.lock().expect("supervision lock");
+    if let Some(group) = group {
+        supervision.groups.retain(|(pid, _)| *pid != group);
+    }
+    supervision.targets = supervision.targets.saturating_sub(1);
+    if supervision.targets == 0 {
+        #[cfg(target_os = "linux")]
+        let _ = set_child_subreaper(false);
+    }
+}
+
+/// Targets live in their own process groups, so Ctrl-C in the terminal no
+/// longer reaches them. A dedicated thread receives SIGINT, SIGTERM and
+/// SIGHUP sent to effigy, sends each running target's group its stop signal
+/// and then exits with the original signal. The thread stays for the rest of
+/// the process; with no target running it only does the default action.
+#[cfg(unix)]
+fn start_signal_forwarding() {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        let Ok(mut signals) = Signals::new(FORWARDED_SIGNALS) else {
+            return;
+        };
+        let _ = std::thread::Builder::new()
+            .name("effigy-watch-signals".to_owned())
+            .spawn(move || {
+                for signal in signals.forever() {
+                    let groups = SUPERVISION.lock().expect("supervision lock").groups.clone();
+                    for (group, stop_signal) in groups {
+                        let _ = kill(Pid::from_raw(-group.as_raw()), stop_signal);
+                    }
+                    let _ = emulate_default_handler(signal);
+                }
+            });
+    });
+}
+
+/// Process groups that signals are currently forwarded to.
+#[cfg(all(test, unix))]
+pub(super) fn supervised_groups() -> Vec<i32> {
+    SUPERVISION
+        .lock()
+        .expect("supervision lock")
+        .groups
+        .iter()
+        .map(|(group, _)| group.as_raw())
+        .collect()
+}
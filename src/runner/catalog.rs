@@ -2,13 +2,23 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 
+use super::ignore::IgnoreRules;
 use super::{
     CatalogSelectionMode, LoadedCatalog, RunnerError, TaskManifest, TaskSelection, TaskSelector,
     TASK_MANIFEST_FILE,
 };
 
 pub(super) fn discover_catalogs(workspace_root: &Path) -> Result<Vec<LoadedCatalog>, RunnerError> {
-    let manifest_paths = discover_manifest_paths(workspace_root)?;
+    discover_catalogs_with_ignore(workspace_root, true)
+}
+
+/// Like [`discover_catalogs`]; `respect_ignore = false` also scans directories
+/// excluded by `.gitignore`, `.ignore` and `.effigyignore` (`--no-ignore`).
+pub(super) fn discover_catalogs_with_ignore(
+    workspace_root: &Path,
+    respect_ignore: bool,
+) -> Result<Vec<LoadedCatalog>, RunnerError> {
+    let manifest_paths = discover_manifest_paths_with_ignore(workspace_root, respect_ignore)?;
     if manifest_paths.is_empty() {
         return Err(RunnerError::TaskCatalogsMissing {
             root: workspace_root.to_path_buf(),
@@ -62,6 +72,14 @@ pub(super) fn discover_catalogs(workspace_root: &Path) -> Result<Vec<LoadedCatal
 }
 
 pub(super) fn discover_manifest_paths(workspace_root: &Path) -> Result<Vec<PathBuf>, RunnerError> {
+    discover_manifest_paths_with_ignore(workspace_root, true)
+}
+
+fn discover_manifest_paths_with_ignore(
+    workspace_root: &Path,
+    respect_ignore: bool,
+) -> Result<Vec<PathBuf>, RunnerError> {
+    let ignore = IgnoreRules::new(workspace_root, respect_ignore);
     let mut pending: Vec<PathBuf> = vec![workspace_root.to_path_buf()];
     let mut visited_dirs: HashSet<PathBuf> = HashSet::new();
     let mut manifests_by_catalog: HashMap<PathBuf, PathBuf> = HashMap::new();
@@ -95,8 +113,13 @@ pub(super) fn discover_manifest_paths(workspace_root: &Path) -> Result<Vec<PathB
                     && fs::metadata(&path)
                         .map(|meta| meta.is_dir())
                         .unwrap_or(false));
+            let rel = path
+                .strip_prefix(workspace_root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
             if is_dir {
-                if should_skip_dir(&path) {
+                if should_skip_dir(&path) || ignore.is_ignored(&rel, true) {
                     continue;
                 }
                 pending.push(path);
@@ -108,7 +131,10 @@ pub(super) fn discover_manifest_paths(workspace_root: &Path) -> Result<Vec<PathB
                     && fs::metadata(&path)
                         .map(|meta| meta.is_file())
                         .unwrap_or(false));
-            if is_file && path.file_name().and_then(|n| n.to_str()) == Some(TASK_MANIFEST_FILE) {
+            if is_file
+                && path.file_name().and_then(|n| n.to_str()) == Some(TASK_MANIFEST_FILE)
+                && !ignore.is_ignored(&rel, false)
+            {
                 let catalog_root = path.parent().map(Path::to_path_buf).unwrap_or_default();
                 manifests_by_catalog.insert(catalog_root, path);
                 continue;
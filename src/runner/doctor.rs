@@ -7,6 +7,7 @@ use serde_json::json;
 use toml::Value;
 
 use crate::resolver::resolve_target_root;
+use crate::tui::{KeyMap, KEY_PRESETS};
 use crate::ui::theme::resolve_color_enabled;
 use crate::ui::{
     KeyValue, NoticeLevel, OutputMode, PlainRenderer, Renderer, SummaryCounts, TableSpec,
@@ -18,15 +19,17 @@ use super::catalog::{
 };
 use super::deferral::{select_deferral, should_attempt_deferral};
 use super::execute::run_manifest_task_with_cwd;
+use super::managed::port_is_free;
 use super::util::{parse_task_reference_invocation, parse_task_selector};
 use super::{
     CatalogSelectionMode, LoadedCatalog, ManifestJsPackageManager, ManifestManagedConcurrentEntry,
     ManifestManagedRun, ManifestManagedRunStep, RunnerError, TaskManifest,
 };
 
-const CHECK_IDS: [&str; 9] = [
+const CHECK_IDS: [&str; 10] = [
     "workspace.root-resolution",
     "environment.tools.required",
+    "environment.ports",
     "manifest.parse",
     "manifest.schema.unsupported_key",
     "manifest.schema.unsupported_value",
@@ -180,6 +183,7 @@ pub(super) fn run_doctor(args: DoctorArgs) -> Result<String, RunnerError> {
         &mut findings,
         &mut statuses,
     );
+    check_managed_ports(&parsed_catalogs, &mut findings, &mut statuses);
     check_task_references(&parsed_catalogs, &mut findings, &mut statuses);
     check_health_task(
         &resolved.resolved_root,
@@ -785,6 +789,96 @@ fn check_environment_tools(
     }
 }
 
+fn check_managed_ports(
+    catalogs: &[LoadedCatalog],
+    findings: &mut Vec<DoctorFinding>,
+    statuses: &mut HashMap<String, DoctorSeverity>,
+) {
+    for catalog in catalogs {
+        for (task_name, task) in &catalog.manifest.tasks {
+            let mut profiles = Vec::<(&str, &[ManifestManagedConcurrentEntry])>::new();
+            if !task.concurrent.is_empty() {
+                profiles.push(("default", task.concurrent.as_slice()));
+            }
+            for (profile_name, profile) in &task.profiles {
+                if let Some(entries) = profile.concurrent_entries() {
+                    profiles.push((profile_name.as_str(), entries));
+                }
+            }
+            for (profile_name, entries) in profiles {
+                let mut declared = HashMap::<u16, String>::new();
+                for (index, entry) in entries.iter().enumerate() {
+                    let process = entry
+                        .name
+                        .clone()
+                        .or_else(|| entry.task.clone())
+                        .unwrap_or_else(|| format!("process-{}", index + 1));
+                    for (name, port) in &entry.ports {
+                        let location = format!(
+                            "{}/{task_name} profile `{profile_name}` process `{process}`",
+                            catalog.alias
+                        );
+                        if let Some(owner) = declared.insert(port.port, process.clone()) {
+                            add_finding(
+                                findings,
+                                statuses,
+                                DoctorFinding {
+                                    check_id: "environment.ports".to_owned(),
+                                    severity: DoctorSeverity::Error,
+                                    evidence: format!(
+                                        "{location} declares `{name}` = {} which is also declared by process `{owner}`",
+                                        port.port
+                                    ),
+                                    remediation: "Give each process in a profile a distinct port."
+                                        .to_owned(),
+                                    fixable: false,
+                                },
+                            );
+                            continue;
+                        }
+                        let (severity, state, remediation) = if port_is_free(port.port) {
+                            (
+                                DoctorSeverity::Info,
+                                "free",
+                                "No action required.".to_owned(),
+                            )
+                        } else if port.auto {
+                            (
+                                DoctorSeverity::Info,
+                                "in use; the next free port will be picked",
+                                "No action required.".to_owned(),
+                            )
+                        } else {
+                            (
+                                DoctorSeverity::Warning,
+                                "in use",
+                                format!(
+                                    "Stop the process listening on {} or set `{name} = {{ port = {}, auto = true }}`.",
+                                    port.port, port.port
+                                ),
+                            )
+                        };
+                        add_finding(
+                            findings,
+                            statuses,
+                            DoctorFinding {
+                                check_id: "environment.ports".to_owned(),
+                                severity,
+                                evidence: format!(
+                                    "{location} declares `{name}` = {} ({state})",
+                                    port.port
+                                ),
+                                remediation,
+                                fixable: false,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
 fn collect_required_tools_from_manifest<'a>(
     manifest: &'a TaskManifest,
     required: &mut HashSet<&'a str>,
@@ -1463,6 +1557,8 @@ fn validate_manifest_schema(
         "test",
         "package_manager",
         "shell",
+        "tui",
+        "watch",
         "tasks",
     ];
     for key in table.keys() {
@@ -1487,6 +1583,22 @@ fn validate_manifest_schema(
     if let Some(shell) = table.get("shell") {
         validate_known_table(manifest_path, "shell", shell, &["run"], findings, statuses);
     }
+    if let Some(watch) = table.get("watch") {
+        validate_known_table(
+            manifest_path,
+            "watch",
+            watch,
+            &["map", "exclude", "debounce_ms"],
+            findings,
+            statuses,
+        );
+    }
+    if let Some(tui) = table.get("tui") {
+        validate_known_table(manifest_path, "tui", tui, &["keys"], findings, statuses);
+        if let Some(keys) = tui.get("keys") {
+            validate_tui_keys(manifest_path, keys, findings, statuses);
+        }
+    }
 
     if let Some(package_manager) = table.get("package_manager") {
         validate_known_table(
@@ -1759,7 +1871,18 @@ fn validate_manifest_schema(
             for key in task_table.keys() {
                 if !matches!(
                     key.as_str(),
-                    "run" | "mode" | "fail_on_non_zero" | "shell" | "concurrent" | "profiles"
+                    "description"
+                        | "run"
+                        | "mode"
+                        | "fail_on_non_zero"
+                        | "shell"
+                        | "concurrent"
+                        | "profiles"
+                        | "limits"
+                        | "layout"
+                        | "panes"
+                        | "notify"
+                        | "watch"
                 ) {
                     push_unsupported_key(
                         manifest_path,
@@ -1770,6 +1893,18 @@ fn validate_manifest_schema(
                 }
             }
 
+            if let Some(description) = task_table.get("description") {
+                if !description.is_str() {
+                    push_unsupported_value(
+                        manifest_path,
+                        &format!("tasks.{task_name}.description"),
+                        value_type(description),
+                        "expected string",
+                        findings,
+                        statuses,
+                    );
+                }
+            }
             if let Some(mode) = task_table.get("mode") {
                 if let Some(raw) = mode.as_str() {
                     if raw != "tui" {
@@ -1805,6 +1940,32 @@ fn validate_manifest_schema(
                     );
                 }
             }
+            if let Some(limits) = task_table.get("limits") {
+                validate_limits_table(
+                    manifest_path,
+                    &format!("tasks.{task_name}.limits"),
+                    limits,
+                    findings,
+                    statuses,
+                );
+            }
+            if let Some(watch) = task_table.get("watch") {
+                validate_known_table(
+                    manifest_path,
+                    &format!("tasks.{task_name}.watch"),
+                    watch,
+                    &["include", "exclude", "debounce_ms", "restart"],
+                    findings,
+                    statuses,
+                );
+            }
+            validate_layout_keys(
+                manifest_path,
+                &format!("tasks.{task_name}"),
+                task_table,
+                findings,
+                statuses,
+            );
             if let Some(concurrent) = task_table.get("concurrent") {
                 validate_concurrent_array(
                     manifest_path,
@@ -1819,7 +1980,7 @@ fn validate_manifest_schema(
                     for (profile_name, profile_value) in profile_table {
                         if let Some(profile_inner) = profile_value.as_table() {
                             for key in profile_inner.keys() {
-                                if key != "concurrent" {
+                                if !matches!(key.as_str(), "concurrent" | "layout" | "panes") {
                                     push_unsupported_key(
                                         manifest_path,
                                         &format!("tasks.{task_name}.profiles.{profile_name}.{key}"),
@@ -1828,6 +1989,13 @@ fn validate_manifest_schema(
                                     );
                                 }
                             }
+                            validate_layout_keys(
+                                manifest_path,
+                                &format!("tasks.{task_name}.profiles.{profile_name}"),
+                                profile_inner,
+                                findings,
+                                statuses,
+                            );
                             if let Some(concurrent) = profile_inner.get("concurrent") {
                                 validate_concurrent_array(
                                     manifest_path,
@@ -1930,7 +2098,17 @@ fn validate_concurrent_array(
         for key in table.keys() {
             if !matches!(
                 key.as_str(),
-                "name" | "task" | "run" | "start" | "tab" | "start_after_ms"
+                "name"
+                    | "task"
+                    | "run"
+                    | "start"
+                    | "tab"
+                    | "start_after_ms"
+                    | "ports"
+                    | "limits"
+                    | "stop"
+                    | "depends_on"
+                    | "watch"
             ) {
                 push_unsupported_key(
                     manifest_path,
@@ -1940,6 +2118,163 @@ fn validate_concurrent_array(
                 );
             }
         }
+        if let Some(limits) = table.get("limits") {
+            validate_limits_table(
+                manifest_path,
+                &format!("{path}[{index}].limits"),
+                limits,
+                findings,
+                statuses,
+            );
+        }
+        if let Some(stop) = table.get("stop") {
+            validate_known_table(
+                manifest_path,
+                &format!("{path}[{index}].stop"),
+                stop,
+                &["signal", "timeout_ms", "run"],
+                findings,
+                statuses,
+            );
+        }
+        if let Some(watch) = table.get("watch") {
+            validate_known_table(
+                manifest_path,
+                &format!("{path}[{index}].watch"),
+                watch,
+                &["include", "exclude", "debounce_ms"],
+                findings,
+                statuses,
+            );
+        }
+    }
+}
+
+fn validate_tui_keys(
+    manifest_path: &Path,
+    keys: &Value,
+    findings: &mut Vec<DoctorFinding>,
+    statuses: &mut HashMap<String, DoctorSeverity>,
+) {
+    let Some(table) = keys.as_table() else {
+        push_unsupported_value(
+            manifest_path,
+            "tui.keys",
+            value_type(keys),
+            "expected table of action = key or [keys]",
+            findings,
+            statuses,
+        );
+        return;
+    };
+    let mut preset = None;
+    let mut overrides = Vec::new();
+    for (action, value) in table {
+        let parsed = match value {
+            Value::String(key) => Some(vec![key.clone()]),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_owned))
+                .collect::<Option<Vec<String>>>(),
+            _ => None,
+        };
+        let Some(parsed) = parsed else {
+            push_unsupported_value(
+                manifest_path,
+                &format!("tui.keys.{action}"),
+                value_type(value),
+                "expected key string or array of key strings",
+                findings,
+                statuses,
+            );
+            return;
+        };
+        if action == "preset" {
+            preset = parsed.into_iter().next();
+        } else {
+            overrides.push((action.clone(), parsed));
+        }
+    }
+    if let Err(detail) = KeyMap::from_config(preset.as_deref(), &overrides) {
+        push_unsupported_value(
+            manifest_path,
+            "tui.keys",
+            &detail,
+            &format!("presets: {}", KEY_PRESETS.join(", ")),
+            findings,
+            statuses,
+        );
+    }
+}
+
+fn validate_layout_keys(
+    manifest_path: &Path,
+    path: &str,
+    table: &toml::map::Map<String, Value>,
+    findings: &mut Vec<DoctorFinding>,
+    statuses: &mut HashMap<String, DoctorSeverity>,
+) {
+    if let Some(layout) = table.get("layout") {
+        if !matches!(layout.as_str(), Some("tabs" | "grid")) {
+            push_unsupported_value(
+                manifest_path,
+                &format!("{path}.layout"),
+                &layout.to_string(),
+                "expected `tabs` or `grid`",
+                findings,
+                statuses,
+            );
+        }
+    }
+    if let Some(panes) = table.get("panes") {
+        let valid = panes.as_array().is_some_and(|rows| {
+            rows.iter().all(|row| {
+                row.as_array()
+                    .is_some_and(|names| names.iter().all(Value::is_str))
+            })
+        });
+        if !valid {
+            push_unsupported_value(
+                manifest_path,
+                &format!("{path}.panes"),
+                value_type(panes),
+                "expected array of process-name arrays",
+                findings,
+                statuses,
+            );
+        }
+    }
+}
+
+fn validate_limits_table(
+    manifest_path: &Path,
+    path: &str,
+    value: &Value,
+    findings: &mut Vec<DoctorFinding>,
+    statuses: &mut HashMap<String, DoctorSeverity>,
+) {
+    validate_known_table(
+        manifest_path,
+        path,
+        value,
+        &["memory_mb", "cpu_seconds", "nofile"],
+        findings,
+        statuses,
+    );
+    let Some(table) = value.as_table() else {
+        return;
+    };
+    for (key, limit) in table {
+        if limit.as_integer().is_some_and(|raw| raw <= 0) || !limit.is_integer() {
+            push_unsupported_value(
+                manifest_path,
+                &format!("{path}.{key}"),
+                &limit.to_string(),
+                "expected positive integer",
+                findings,
+                statuses,
+            );
+        }
     }
 }
 
@@ -4,19 +4,27 @@ use std::process::Command as ProcessCommand;
 
 use serde_json::json;
 
+use crate::notify::NotifyEvent;
+use crate::process_manager::{
+    apply_process_limits, children_peak_rss_bytes, describe_limit_breach, describe_limit_crash,
+};
 use crate::resolver::resolve_target_root;
 use crate::TaskInvocation;
 
-use super::catalog::select_catalog_and_task;
+use super::catalog::{discover_catalogs_with_ignore, select_catalog_and_task};
 use super::deferral::{run_deferred_request, select_deferral, should_attempt_deferral};
 use super::locking::{acquire_scopes, LockScope};
-use super::managed::{render_task_run_spec, resolve_managed_task_plan, run_or_render_managed_task};
+use super::managed::{
+    attach_palette_entries, render_task_run_spec, resolve_managed_task_plan,
+    run_or_render_managed_task,
+};
+use super::notify::resolve_task_notifier;
 use super::render::render_task_resolution_trace;
 use super::util::{
     parse_task_runtime_args, parse_task_selector, shell_quote, with_local_node_bin_path,
 };
 use super::{
-    discover_catalogs, try_run_builtin_task, LoadedCatalog, ManifestManagedRun, ManifestTask,
+    try_run_builtin_task, LoadedCatalog, ManifestManagedRun, ManifestResourceLimits, ManifestTask,
     RunnerError,
 };
 
@@ -53,18 +61,20 @@ pub(super) fn run_manifest_task_with_cwd(
     let invocation_cwd = fs::canonicalize(&cwd).unwrap_or_else(|_| cwd.clone());
     let runtime_args = parse_task_runtime_args(&task.args)?;
     let (passthrough_without_json, output_json) = strip_task_json_flag(&runtime_args.passthrough);
-    let runtime_args_for_execution = super::TaskRuntimeArgs {
+    let mut runtime_args_for_execution = super::TaskRuntimeArgs {
         repo_override: runtime_args.repo_override.clone(),
         verbose_root: runtime_args.verbose_root,
+        no_ignore: runtime_args.no_ignore,
         passthrough: passthrough_without_json,
     };
     let resolved = resolve_target_root(cwd, runtime_args.repo_override.clone())?;
     let selector = parse_task_selector(&task.name)?;
-    let catalogs = match discover_catalogs(&resolved.resolved_root) {
-        Ok(catalogs) => catalogs,
-        Err(RunnerError::TaskCatalogsMissing { .. }) => Vec::new(),
-        Err(error) => return Err(error),
-    };
+    let catalogs =
+        match discover_catalogs_with_ignore(&resolved.resolved_root, !runtime_args.no_ignore) {
+            Ok(catalogs) => catalogs,
+            Err(RunnerError::TaskCatalogsMissing { .. }) => Vec::new(),
+            Err(error) => return Err(error),
+        };
     let selection = match select_catalog_and_task(&selector, &catalogs, &invocation_cwd) {
         Ok(selection) => selection,
         Err(error) => {
@@ -103,7 +113,13 @@ pub(super) fn run_manifest_task_with_cwd(
     };
 
     let repo_for_task = selection.catalog.catalog_root.clone();
-    if let Some(plan) = resolve_managed_task_plan(
+    let record = if selection.task.mode.is_some() {
+        take_task_record_flag(&mut runtime_args_for_execution.passthrough)?
+            .map(|path| invocation_cwd.join(path))
+    } else {
+        None
+    };
+    if let Some(mut plan) = resolve_managed_task_plan(
         &selector,
         selection.catalog,
         selection.task,
@@ -111,6 +127,15 @@ pub(super) fn run_manifest_task_with_cwd(
         &catalogs,
         &selection.catalog.catalog_root,
     )? {
+        plan.record = record;
+        attach_palette_entries(
+            &mut plan,
+            &selector,
+            selection.catalog,
+            selection.task,
+            &catalogs,
+            &selection.catalog.catalog_root,
+        );
         let _lock_guards = acquire_scopes(
             &resolved.resolved_root,
             &[
@@ -162,9 +187,26 @@ pub(super) fn run_manifest_task_with_cwd(
         ],
     )?;
 
+    let notifier = resolve_task_notifier(selection.task.notify.as_ref())?;
+    let notify_failure = |code: Option<i32>| {
+        if let Some(notifier) = notifier.as_ref() {
+            let exit = code.map_or_else(|| "signal".to_owned(), |code| format!("exit={code}"));
+            notifier.send(
+                &repo_for_task,
+                NotifyEvent::TaskFailed,
+                &format!("task `{}` failed ({exit})", selector.task_name),
+            );
+        }
+    };
+    let limits = selection
+        .task
+        .limits
+        .map(ManifestResourceLimits::to_process_limits)
+        .unwrap_or_default();
     let mut process = ProcessCommand::new("sh");
     process.arg("-lc").arg(&command).current_dir(&repo_for_task);
     with_local_node_bin_path(&mut process, &repo_for_task);
+    apply_process_limits(&mut process, limits);
     if output_json {
         let output = process
             .output()
@@ -186,6 +228,7 @@ pub(super) fn run_manifest_task_with_cwd(
         if output.status.success() {
             return Ok(rendered);
         }
+        notify_failure(output.status.code());
         return Err(RunnerError::CommandJsonFailure { rendered });
     }
 
@@ -210,6 +253,13 @@ pub(super) fn run_manifest_task_with_cwd(
         return Ok(String::new());
     }
 
+    notify_failure(status.code());
+    if let Some(detail) = describe_limit_breach(status, limits, children_peak_rss_bytes()) {
+        return Err(RunnerError::TaskResourceLimitExceeded { command, detail });
+    }
+    if let Some(detail) = describe_limit_crash(status, limits) {
+        return Err(RunnerError::TaskCommandCrashed { command, detail });
+    }
     Err(RunnerError::TaskCommandFailure {
         command,
         code: status.code(),
@@ -237,6 +287,25 @@ fn strip_task_json_flag(args: &[String]) -> (Vec<String>, bool) {
     (stripped, json_mode)
 }
 
+/// Removes `--record <file>` (before any `--`) from managed task arguments.
+fn take_task_record_flag(args: &mut Vec<String>) -> Result<Option<PathBuf>, RunnerError> {
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    let Some(index) = args[..end].iter().position(|arg| arg == "--record") else {
+        return Ok(None);
+    };
+    let Some(path) = args.get(index + 1).filter(|value| value.as_str() != "--") else {
+        return Err(RunnerError::TaskInvocation(
+            "task argument --record requires a file path".to_owned(),
+        ));
+    };
+    let path = PathBuf::from(path);
+    args.drain(index..=index + 1);
+    Ok(Some(path))
+}
+
 fn render_task_command_json(
     task_name: &str,
     selector: &super::TaskSelector,
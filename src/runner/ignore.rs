@@ -0,0 +1,111 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ::ignore::Match;
+
+/// Ignore files read in every scanned directory. Later files take precedence,
+/// so `.effigyignore` can re-include (`!pattern`) what `.gitignore` excludes.
+pub(super) const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".effigyignore"];
+
+/// Gitignore rules for a workspace, loaded lazily per directory as scans
+/// descend. Matching follows git's own rules via the `ignore` crate; this
+/// type only decides which directories' files apply. Paths are relative to
+/// the workspace root and use `/`.
+#[derive(Debug)]
+pub(super) struct IgnoreRules {
+    root: PathBuf,
+    enabled: bool,
+    loaded: RefCell<HashMap<String, Gitignore>>,
+}
+
+impl IgnoreRules {
+    pub(super) fn new(root: &Path, enabled: bool) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            enabled,
+            loaded: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Drops cached rules so edited ignore files are re-read on the next
+    /// check.
+    pub(super) fn reload(&self) {
+        self.loaded.borrow_mut().clear();
+    }
+
+    /// Whether `rel_path` (or any directory above it) is ignored. As in git,
+    /// a path under an ignored directory cannot be re-included.
+    pub(super) fn is_ignored(&self, rel_path: &str, is_dir: bool) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let rel = rel_path.trim_start_matches("./").trim_end_matches('/');
+        if rel.is_empty() {
+            return false;
+        }
+        let mut ancestor_end = 0usize;
+        while let Some(offset) = rel[ancestor_end..].find('/') {
+            let ancestor = &rel[..ancestor_end + offset];
+            if self.matches_rules(ancestor, true) {
+                return true;
+            }
+            ancestor_end += offset + 1;
+        }
+        self.matches_rules(rel, is_dir)
+    }
+
+    /// Checks the ignore files of every directory from the root down to the
+    /// parent of `rel`; a match in a deeper directory overrides shallower
+    /// ones, as in git.
+    fn matches_rules(&self, rel: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        let mut end = 0usize;
+        loop {
+            let dir = &rel[..end];
+            let below = if end == 0 { rel } else { &rel[end + 1..] };
+            let decision = self.with_rules(dir, |rules| match rules.matched(below, is_dir) {
+                Match::Ignore(_) => Some(true),
+                Match::Whitelist(_) => Some(false),
+                Match::None => None,
+            });
+            if let Some(decision) = decision {
+                ignored = decision;
+            }
+            match next_dir_end(rel, end) {
+                Some(next) => end = next,
+                None => break,
+            }
+        }
+        ignored
+    }
+
+    fn with_rules<T>(&self, rel_dir: &str, apply: impl FnOnce(&Gitignore) -> T) -> T {
+        let mut loaded = self.loaded.borrow_mut();
+        let rules = loaded
+            .entry(rel_dir.to_owned())
+            .or_insert_with(|| load_dir_rules(&self.root.join(rel_dir)));
+        apply(rules)
+    }
+}
+
+/// End offset of the next directory prefix of `rel` after the one ending at
+/// `end` (0 is the root), or `None` once only the file name is left.
+fn next_dir_end(rel: &str, end: usize) -> Option<usize> {
+    let start = if end == 0 { 0 } else { end + 1 };
+    rel[start..].find('/').map(|offset| start + offset)
+}
+
+/// Reads the ignore files of one directory. Lines git would reject (and
+/// unreadable files) are skipped, matching git's own leniency.
+fn load_dir_rules(dir: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(dir);
+    for name in IGNORE_FILES {
+        let path = dir.join(name);
+        if path.is_file() {
+            let _ = builder.add(path);
+        }
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
@@ -1,21 +1,34 @@
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::IsTerminal;
+use std::net::{Ipv4Addr, TcpListener};
+use std::num::NonZeroU64;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crate::process_manager::{ProcessEventKind, ProcessSpec, ProcessSupervisor};
-use crate::tui::{run_multiprocess_tui, MultiProcessTuiOptions};
+use crate::notify::NotifyEvent;
+use crate::process_manager::{
+    parse_stop_signal, ProcessEventKind, ProcessLimits, ProcessSpec, ProcessStop, ProcessSupervisor,
+};
+use crate::tui::{run_multiprocess_tui, MultiProcessTuiOptions, PaletteProfile, MAX_GRID_PANES};
 use crate::ui::theme::resolve_color_enabled;
 use crate::ui::{
     KeyValue, NoticeLevel, OutputMode, PlainRenderer, Renderer, SummaryCounts, TableSpec,
 };
 
 use super::catalog::select_catalog_and_task;
-use super::util::{parse_task_reference_invocation, render_task_selector, shell_quote};
+use super::execute::catalog_task_label;
+use super::manifest::ManifestWatch;
+use super::notify::resolve_task_notifier;
+use super::tui_config::{user_config_dir, TuiKeyConfig};
+use super::util::{
+    changed_files_from_env, parse_task_reference_invocation, render_changed_files,
+    render_task_selector, shell_quote,
+};
 use super::{
-    LoadedCatalog, ManagedProcessSpec, ManagedTaskPlan, ManifestManagedConcurrentEntry,
-    ManifestManagedRun, ManifestManagedRunStep, ManifestTask, RunnerError, TaskRuntimeArgs,
-    TaskSelector, BUILTIN_TASKS, DEFAULT_MANAGED_SHELL_RUN,
+    LoadedCatalog, ManagedPaletteProfile, ManagedProcessSpec, ManagedTaskPlan,
+    ManifestManagedConcurrentEntry, ManifestManagedRun, ManifestManagedRunStep,
+    ManifestResourceLimits, ManifestTask, RunnerError, TaskRuntimeArgs, TaskSelector,
+    BUILTIN_TASKS, DEFAULT_MANAGED_SHELL_RUN,
 };
 
 pub(super) fn resolve_managed_task_plan(
@@ -73,6 +86,7 @@ pub(super) fn resolve_managed_task_plan(
 #[derive(Debug)]
 struct ConcurrentResolvedProcess {
     spec: ManagedProcessSpec,
+    watch: Option<ManifestWatch>,
     start_rank: usize,
     tab_rank: usize,
     index: usize,
@@ -96,6 +110,8 @@ fn resolve_managed_concurrent_task_plan(
     }
 
     let mut used_names = HashSet::<String>::new();
+    let mut claimed_ports = HashMap::<u16, String>::new();
+    let mut port_owners = HashMap::<String, String>::new();
     let mut resolved = Vec::<ConcurrentResolvedProcess>::with_capacity(entries.len());
     for (index, entry) in entries.iter().enumerate() {
         let ordinal = index + 1;
@@ -139,6 +155,15 @@ fn resolve_managed_concurrent_task_plan(
                 });
             }
         };
+        let ports = resolve_concurrent_ports(
+            &selector.task_name,
+            &process_name,
+            entry,
+            &mut claimed_ports,
+            &mut port_owners,
+        )?;
+        let stop = resolve_process_stop(&selector.task_name, &process_name, entry)?;
+        let watch = resolve_process_watch(&selector.task_name, &process_name, entry)?;
         let start_rank = entry.start.unwrap_or(ordinal);
         let tab_rank = entry.tab.unwrap_or(start_rank);
         resolved.push(ConcurrentResolvedProcess {
@@ -147,13 +172,40 @@ fn resolve_managed_concurrent_task_plan(
                 run,
                 cwd,
                 start_after_ms: entry.start_after_ms.unwrap_or(0),
+                ports,
+                limits: entry
+                    .limits
+                    .unwrap_or_default()
+                    .overlay(task.limits)
+                    .to_process_limits(),
+                stop,
+                depends_on: entry.depends_on.clone(),
             },
+            watch,
             start_rank,
             tab_rank,
             index,
         });
     }
 
+    let profile_ports = resolved
+        .iter()
+        .flat_map(|entry| entry.spec.ports.iter().cloned())
+        .collect::<Vec<(String, u16)>>();
+    for entry in &mut resolved {
+        entry.spec.run =
+            render_port_placeholders(&entry.spec.run, &profile_ports).map_err(|name| {
+                RunnerError::TaskManagedProcessInvalidDefinition {
+                    task: selector.task_name.clone(),
+                    process: entry.spec.name.clone(),
+                    detail: format!("`{{port.{name}}}` does not match a declared `ports` entry"),
+                }
+            })?;
+        if let Some(watch) = entry.watch.as_ref() {
+            entry.spec.run = render_watched_process_run(&entry.spec.run, watch, &entry.spec.stop)?;
+        }
+    }
+
     resolved.sort_by(|a, b| {
         a.start_rank
             .cmp(&b.start_rank)
@@ -185,8 +237,13 @@ fn resolve_managed_concurrent_task_plan(
             run: shell_run,
             cwd: task_scope_cwd.to_path_buf(),
             start_after_ms: 0,
+            ports: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         });
     }
+    validate_shutdown_dependencies(&selector.task_name, &processes)?;
 
     let mut tab_entries = resolved
         .iter()
@@ -207,16 +264,442 @@ fn resolve_managed_concurrent_task_plan(
         }
     }
 
+    let (grid_layout, panes) =
+        resolve_profile_layout(&selector.task_name, task, profile_name, &tab_order)?;
+    let keys = TuiKeyConfig::from_catalog(Some(catalog))?;
+    let notify = resolve_task_notifier(task.notify.as_ref())?;
+
     Ok(ManagedTaskPlan {
         mode: "tui".to_owned(),
         profile: profile_name.to_owned(),
         processes,
         tab_order,
+        grid_layout,
+        panes,
+        keys,
         fail_on_non_zero: task.fail_on_non_zero.unwrap_or(true),
+        notify,
+        record: None,
+        palette_tasks: Vec::new(),
+        palette_profiles: Vec::new(),
         passthrough: passthrough.iter().skip(1).cloned().collect(),
     })
 }
 
+/// Fills in what the TUI command palette can start: every catalog task with a
+/// `run` command, and the managed task's other profiles. Entries that fail to
+/// resolve are left out instead of failing the session.
+pub(super) fn attach_palette_entries(
+    plan: &mut ManagedTaskPlan,
+    selector: &TaskSelector,
+    catalog: &LoadedCatalog,
+    task: &ManifestTask,
+    catalogs: &[LoadedCatalog],
+    task_scope_cwd: &Path,
+) {
+    for candidate_catalog in catalogs {
+        for (name, candidate) in &candidate_catalog.manifest.tasks {
+            let (None, Some(run)) = (candidate.mode.as_ref(), candidate.run.as_ref()) else {
+                continue;
+            };
+            let Ok(run) = render_task_run_spec(
+                name,
+                run,
+                "",
+                &candidate_catalog.catalog_root,
+                catalogs,
+                &candidate_catalog.catalog_root,
+                0,
+            ) else {
+                continue;
+            };
+            plan.palette_tasks.push(ManagedProcessSpec {
+                name: catalog_task_label(candidate_catalog, name),
+                run,
+                cwd: candidate_catalog.catalog_root.clone(),
+                start_after_ms: 0,
+                ports: Vec::new(),
+                limits: candidate
+                    .limits
+                    .map(ManifestResourceLimits::to_process_limits)
+                    .unwrap_or_default(),
+                stop: ProcessStop::default(),
+                depends_on: Vec::new(),
+            });
+        }
+    }
+    plan.palette_tasks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for profile_name in available_concurrent_profiles(task) {
+        if profile_name == plan.profile {
+            continue;
+        }
+        let Some(entries) = concurrent_entries_for_profile(task, &profile_name) else {
+            continue;
+        };
+        let Ok(resolved) = resolve_managed_concurrent_task_plan(
+            selector,
+            catalog,
+            task,
+            &profile_name,
+            entries,
+            std::slice::from_ref(&profile_name),
+            catalogs,
+            task_scope_cwd,
+        ) else {
+            continue;
+        };
+        plan.palette_profiles.push(ManagedPaletteProfile {
+            name: profile_name,
+            processes: resolved.processes,
+            tab_order: resolved.tab_order,
+            panes: resolved.panes,
+        });
+    }
+}
+
+fn resolve_profile_layout(
+    task_name: &str,
+    task: &ManifestTask,
+    profile_name: &str,
+    tab_order: &[String],
+) -> Result<(bool, Vec<Vec<String>>), RunnerError> {
+    let profile = task
+        .profiles
+        .get(profile_name)
+        .filter(|profile| profile.concurrent_entries().is_some());
+    let layout = profile
+        .and_then(|profile| profile.layout.as_deref())
+        .or(task.layout.as_deref());
+    let panes = profile
+        .map(|profile| profile.panes.as_slice())
+        .filter(|panes| !panes.is_empty())
+        .unwrap_or(task.panes.as_slice());
+    let invalid = |detail: String| RunnerError::TaskManagedProfileLayoutInvalid {
+        task: task_name.to_owned(),
+        profile: profile_name.to_owned(),
+        detail,
+    };
+
+    let grid_layout = match layout {
+        None | Some("tabs") => false,
+        Some("grid") => true,
+        Some(other) => {
+            return Err(invalid(format!(
+                "unsupported layout `{other}`; expected `tabs` or `grid`"
+            )));
+        }
+    };
+
+    if panes.is_empty() {
+        return Ok((grid_layout, Vec::new()));
+    }
+
+    let mut seen = HashSet::<&str>::new();
+    for row in panes {
+        if row.is_empty() {
+            return Err(invalid("`panes` rows must not be empty".to_owned()));
+        }
+        for name in row {
+            if !tab_order.iter().any(|process| process == name) {
+                return Err(invalid(format!(
+                    "pane `{name}` does not match a process in this profile (available: {})",
+                    tab_order.join(", ")
+                )));
+            }
+            if !seen.insert(name.as_str()) {
+                return Err(invalid(format!("pane `{name}` is listed more than once")));
+            }
+        }
+    }
+    if seen.len() > MAX_GRID_PANES {
+        return Err(invalid(format!(
+            "`panes` lists {} processes; at most {MAX_GRID_PANES} are supported",
+            seen.len()
+        )));
+    }
+    Ok((grid_layout, panes.to_vec()))
+}
+
+fn resolve_process_stop(
+    task_name: &str,
+    process_name: &str,
+    entry: &ManifestManagedConcurrentEntry,
+) -> Result<ProcessStop, RunnerError> {
+    let Some(stop) = entry.stop.as_ref() else {
+        return Ok(ProcessStop::default());
+    };
+    let invalid = |detail: String| RunnerError::TaskManagedProcessInvalidDefinition {
+        task: task_name.to_owned(),
+        process: process_name.to_owned(),
+        detail,
+    };
+    let signal = match stop.signal.as_deref() {
+        Some(raw) => Some(
+            parse_stop_signal(raw)
+                .ok_or_else(|| invalid(format!("`stop.signal` `{raw}` is not a known signal")))?,
+        ),
+        None => None,
+    };
+    let run = match stop.run.as_deref().map(str::trim) {
+        Some("") => return Err(invalid("`stop.run` must not be empty".to_owned())),
+        other => other.map(ToOwned::to_owned),
+    };
+    Ok(ProcessStop {
+        signal,
+        timeout_ms: stop.timeout_ms.map(NonZeroU64::get),
+        run,
+    })
+}
+
+fn resolve_process_watch(
+    task_name: &str,
+    process_name: &str,
+    entry: &ManifestManagedConcurrentEntry,
+) -> Result<Option<ManifestWatch>, RunnerError> {
+    let Some(watch) = entry.watch.as_ref() else {
+        return Ok(None);
+    };
+    if watch.restart == Some(false) {
+        return Err(RunnerError::TaskManagedProcessInvalidDefinition {
+            task: task_name.to_owned(),
+            process: process_name.to_owned(),
+            detail: "`watch` on a concurrent entry always restarts the process; remove `restart = false`".to_owned(),
+        });
+    }
+    Ok(Some(watch.clone()))
+}
+
+/// Wraps a watched process in `effigy watch --restart --run <CMD>`, which
+/// restarts the command on change and forwards the process's stop signal to
+/// it at shutdown.
+fn render_watched_process_run(
+    run: &str,
+    watch: &ManifestWatch,
+    stop: &ProcessStop,
+) -> Result<String, RunnerError> {
+    let mut command_line = format!(
+        "exec {} watch --owner effigy --restart",
+        resolve_effigy_invocation_prefix()?
+    );
+    for glob in &watch.include {
+        command_line.push_str(&format!(" --include {}", shell_quote(glob)));
+    }
+    for glob in &watch.exclude {
+        command_line.push_str(&format!(" --exclude {}", shell_quote(glob)));
+    }
+    if let Some(debounce_ms) = watch.debounce_ms {
+        command_line.push_str(&format!(" --debounce-ms {debounce_ms}"));
+    }
+    if let Some(signal) = stop.signal.as_deref() {
+        command_line.push_str(&format!(" --signal {signal}"));
+    }
+    if let Some(timeout_ms) = stop.timeout_ms {
+        command_line.push_str(&format!(" --kill-timeout-ms {timeout_ms}"));
+    }
+    command_line.push_str(&format!(" --run {}", shell_quote(run)));
+    Ok(command_line)
+}
+
+/// Checks that `depends_on` names other processes in the profile and that the
+/// shutdown order has no cycle.
+fn validate_shutdown_dependencies(
+    task_name: &str,
+    processes: &[ManagedProcessSpec],
+) -> Result<(), RunnerError> {
+    let names = processes
+        .iter()
+        .map(|process| process.name.as_str())
+        .collect::<HashSet<&str>>();
+    for process in processes {
+        for dependency in &process.depends_on {
+            let detail = if dependency == &process.name {
+                "`depends_on` must not name the process itself".to_owned()
+            } else if !names.contains(dependency.as_str()) {
+                format!("`depends_on` names unknown process `{dependency}`")
+            } else {
+                continue;
+            };
+            return Err(RunnerError::TaskManagedProcessInvalidDefinition {
+                task: task_name.to_owned(),
+                process: process.name.clone(),
+                detail,
+            });
+        }
+    }
+
+    let mut remaining = processes.iter().collect::<Vec<&ManagedProcessSpec>>();
+    while !remaining.is_empty() {
+        let before = remaining.len();
+        let blocked = remaining
+            .iter()
+            .flat_map(|process| process.depends_on.iter().map(String::as_str))
+            .collect::<HashSet<&str>>();
+        remaining.retain(|process| blocked.contains(process.name.as_str()));
+        if remaining.len() == before {
+            let cycle = remaining
+                .iter()
+                .map(|process| process.name.as_str())
+                .collect::<Vec<&str>>();
+            return Err(RunnerError::TaskManagedProcessInvalidDefinition {
+                task: task_name.to_owned(),
+                process: cycle[0].to_owned(),
+                detail: format!(
+                    "`depends_on` has a cycle involving {}",
+                    cycle
+                        .iter()
+                        .map(|name| format!("`{name}`"))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn resolve_concurrent_ports(
+    task_name: &str,
+    process_name: &str,
+    entry: &ManifestManagedConcurrentEntry,
+    claimed_ports: &mut HashMap<u16, String>,
+    port_owners: &mut HashMap<String, String>,
+) -> Result<Vec<(String, u16)>, RunnerError> {
+    let mut ports = Vec::<(String, u16)>::with_capacity(entry.ports.len());
+    for (name, declared) in &entry.ports {
+        if !is_valid_port_name(name) {
+            return Err(RunnerError::TaskManagedProcessInvalidDefinition {
+                task: task_name.to_owned(),
+                process: process_name.to_owned(),
+                detail: format!(
+                    "port name `{name}` must use letters, digits, and `_` and must not start with a digit"
+                ),
+            });
+        }
+        if let Some(owner) = port_owners.insert(name.clone(), process_name.to_owned()) {
+            return Err(RunnerError::TaskManagedProcessInvalidDefinition {
+                task: task_name.to_owned(),
+                process: process_name.to_owned(),
+                detail: format!("port name `{name}` is already declared by process `{owner}`"),
+            });
+        }
+        if declared.port == 0 {
+            return Err(RunnerError::TaskManagedProcessInvalidDefinition {
+                task: task_name.to_owned(),
+                process: process_name.to_owned(),
+                detail: format!("port `{name}` must be between 1 and 65535"),
+            });
+        }
+        let scan_limit = if declared.auto {
+            MANAGED_PORT_SCAN_LIMIT
+        } else {
+            1
+        };
+        let mut chosen = None::<u16>;
+        let mut last_detail = String::new();
+        for offset in 0..scan_limit {
+            let Some(candidate) = declared.port.checked_add(offset) else {
+                break;
+            };
+            if let Some(owner) = claimed_ports.get(&candidate) {
+                last_detail = format!("already assigned to process `{owner}` in this profile");
+                continue;
+            }
+            if !port_is_free(candidate) {
+                last_detail = "already in use on this machine".to_owned();
+                continue;
+            }
+            chosen = Some(candidate);
+            break;
+        }
+        let Some(port) = chosen else {
+            return Err(RunnerError::TaskManagedPortUnavailable {
+                task: task_name.to_owned(),
+                process: process_name.to_owned(),
+                name: name.clone(),
+                port: declared.port,
+                detail: if declared.auto {
+                    format!("no free port found in the next {MANAGED_PORT_SCAN_LIMIT} ports")
+                } else {
+                    format!("{last_detail}; set `{name} = {{ port = {}, auto = true }}` to pick the next free port", declared.port)
+                },
+            });
+        };
+        claimed_ports.insert(port, process_name.to_owned());
+        ports.push((name.clone(), port));
+    }
+    Ok(ports)
+}
+
+fn is_valid_port_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+pub(super) fn port_is_free(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+        && TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).is_ok()
+}
+
+fn render_port_placeholders(run: &str, ports: &[(String, u16)]) -> Result<String, String> {
+    let mut rendered = String::with_capacity(run.len());
+    let mut rest = run;
+    while let Some(start) = rest.find("{port.") {
+        let after = &rest[start + "{port.".len()..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        let Some((_, port)) = ports.iter().find(|(candidate, _)| candidate == name) else {
+            return Err(name.to_owned());
+        };
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(&port.to_string());
+        rest = &after[end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+fn managed_port_env(processes: &[ManagedProcessSpec]) -> Vec<(String, String)> {
+    processes
+        .iter()
+        .flat_map(|process| process.ports.iter())
+        .map(|(name, port)| (name.clone(), port.to_string()))
+        .collect()
+}
+
+fn managed_process_ports(processes: &[ManagedProcessSpec]) -> HashMap<String, Vec<(String, u16)>> {
+    processes
+        .iter()
+        .filter(|process| !process.ports.is_empty())
+        .map(|process| (process.name.clone(), process.ports.clone()))
+        .collect()
+}
+
+fn managed_process_specs(
+    processes: Vec<ManagedProcessSpec>,
+    port_env: &[(String, String)],
+) -> Vec<ProcessSpec> {
+    processes
+        .into_iter()
+        .map(|process| ProcessSpec {
+            name: process.name,
+            run: process.run,
+            cwd: process.cwd,
+            start_after_ms: process.start_after_ms,
+            pty: true,
+            env: port_env.to_vec(),
+            limits: process.limits,
+            stop: process.stop,
+            depends_on: process.depends_on,
+        })
+        .collect()
+}
+
 fn concurrent_entries_for_profile<'a>(
     task: &'a ManifestTask,
     profile_name: &str,
@@ -343,11 +826,10 @@ pub(super) fn render_task_run_spec(
             "task `{task_name}` run expansion exceeded maximum nested task references (12)"
         )));
     }
-    let repo_rendered = shell_quote(&repo_root.display().to_string());
     match run {
-        ManifestManagedRun::Command(command) => Ok(command
-            .replace("{repo}", &repo_rendered)
-            .replace("{args}", args_rendered)),
+        ManifestManagedRun::Command(command) => {
+            Ok(render_run_tokens(command, repo_root, args_rendered))
+        }
         ManifestManagedRun::Sequence(steps) => {
             if steps.is_empty() {
                 return Err(RunnerError::TaskInvocation(format!(
@@ -392,6 +874,7 @@ pub(super) fn render_task_run_spec(
 }
 
 const DEFAULT_DAG_MAX_PARALLEL: usize = 4;
+const MANAGED_PORT_SCAN_LIMIT: u16 = 100;
 
 #[derive(Clone, Copy)]
 struct RunStepPolicy {
@@ -718,6 +1201,21 @@ fn render_policy_wrapped_command(command: &str, policy: RunStepPolicy) -> String
     format!("sh -lc {}", shell_quote(&lines.join("\n")))
 }
 
+/// Replaces `{repo}`, `{args}` and `{changed}` (files from the watch rerun
+/// that started this task) in a run command.
+fn render_run_tokens(command: &str, repo_root: &Path, args_rendered: &str) -> String {
+    let mut rendered = command
+        .replace("{repo}", &shell_quote(&repo_root.display().to_string()))
+        .replace("{args}", args_rendered);
+    if rendered.contains("{changed}") {
+        rendered = rendered.replace(
+            "{changed}",
+            &render_changed_files(&changed_files_from_env()),
+        );
+    }
+    rendered
+}
+
 fn resolve_task_run_step(
     task_name: &str,
     step: &ManifestManagedRunStep,
@@ -743,19 +1241,11 @@ fn resolve_task_run_step(
                     depth,
                 )
             } else {
-                let repo_rendered = shell_quote(&repo_root.display().to_string());
-                Ok(command
-                    .replace("{repo}", &repo_rendered)
-                    .replace("{args}", args_rendered))
+                Ok(render_run_tokens(command, repo_root, args_rendered))
             }
         }
         ManifestManagedRunStep::Step(step) => match (&step.run, &step.task) {
-            (Some(run), None) => {
-                let repo_rendered = shell_quote(&repo_root.display().to_string());
-                Ok(run
-                    .replace("{repo}", &repo_rendered)
-                    .replace("{args}", args_rendered))
-            }
+            (Some(run), None) => Ok(render_run_tokens(run, repo_root, args_rendered)),
             (None, Some(task_ref)) => resolve_task_reference_step(
                 task_name,
                 task_ref,
@@ -864,7 +1354,7 @@ fn render_builtin_task_reference_invocation(
     }
 }
 
-fn resolve_effigy_invocation_prefix() -> Result<String, RunnerError> {
+pub(super) fn resolve_effigy_invocation_prefix() -> Result<String, RunnerError> {
     if let Ok(explicit) = std::env::var("EFFIGY_EXECUTABLE") {
         let trimmed = explicit.trim();
         if !trimmed.is_empty() {
@@ -904,6 +1394,7 @@ fn render_managed_task_plan(
         KeyValue::new("manifest", manifest_path.display().to_string()),
         KeyValue::new("processes", plan.processes.len().to_string()),
         KeyValue::new("tab-order", plan.tab_order.join(", ")),
+        KeyValue::new("layout", render_plan_layout(plan.grid_layout, &plan.panes)),
         KeyValue::new(
             "fail-on-non-zero",
             if plan.fail_on_non_zero {
@@ -932,6 +1423,8 @@ fn render_managed_task_plan(
                 process.cwd.display().to_string(),
                 process.run,
                 process.start_after_ms.to_string(),
+                render_process_ports(&process.ports),
+                render_process_limits(process.limits),
             ]
         })
         .collect::<Vec<Vec<String>>>();
@@ -941,6 +1434,8 @@ fn render_managed_task_plan(
             "cwd".to_owned(),
             "run".to_owned(),
             "start-after-ms".to_owned(),
+            "ports".to_owned(),
+            "limits".to_owned(),
         ],
         rows,
     ))?;
@@ -959,6 +1454,47 @@ fn render_managed_task_plan(
         .map_err(|error| RunnerError::Ui(format!("invalid utf-8 in rendered output: {error}")))
 }
 
+fn render_plan_layout(grid_layout: bool, panes: &[Vec<String>]) -> String {
+    let label = if grid_layout { "grid" } else { "tabs" };
+    if panes.is_empty() {
+        return label.to_owned();
+    }
+    let rendered = panes
+        .iter()
+        .map(|row| row.join(", "))
+        .collect::<Vec<String>>()
+        .join(" | ");
+    format!("{label} (panes: {rendered})")
+}
+
+fn render_process_ports(ports: &[(String, u16)]) -> String {
+    if ports.is_empty() {
+        return "-".to_owned();
+    }
+    ports
+        .iter()
+        .map(|(name, port)| format!("{name}={port}"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub(super) fn render_process_limits(limits: ProcessLimits) -> String {
+    let mut parts = Vec::<String>::new();
+    if let Some(memory_mb) = limits.memory_mb {
+        parts.push(format!("memory={memory_mb}MB"));
+    }
+    if let Some(cpu_seconds) = limits.cpu_seconds {
+        parts.push(format!("cpu={cpu_seconds}s"));
+    }
+    if let Some(nofile) = limits.nofile {
+        parts.push(format!("nofile={nofile}"));
+    }
+    if parts.is_empty() {
+        return "-".to_owned();
+    }
+    parts.join(", ")
+}
+
 pub(super) fn run_or_render_managed_task(
     task_name: &str,
     repo_root: &Path,
@@ -995,25 +1531,50 @@ fn run_managed_task_tui(
     let ManagedTaskPlan {
         processes,
         tab_order,
+        grid_layout,
+        panes,
+        keys,
         fail_on_non_zero,
+        notify,
+        record,
         profile,
+        palette_tasks,
+        palette_profiles,
         ..
     } = plan;
-    let specs = processes
+    let keymap = keys.resolve(user_config_dir().as_deref())?;
+    let port_env = managed_port_env(&processes);
+    let process_ports = managed_process_ports(&processes);
+    let specs = managed_process_specs(processes, &port_env);
+    let palette_tasks = managed_process_specs(palette_tasks, &port_env);
+    let palette_profiles = palette_profiles
         .into_iter()
-        .map(|process| ProcessSpec {
-            name: process.name,
-            run: process.run,
-            cwd: process.cwd,
-            start_after_ms: process.start_after_ms,
-            pty: true,
+        .map(|profile| PaletteProfile {
+            process_ports: managed_process_ports(&profile.processes),
+            processes: managed_process_specs(
+                profile.processes.clone(),
+                &managed_port_env(&profile.processes),
+            ),
+            name: profile.name,
+            tab_order: profile.tab_order,
+            panes: profile.panes,
         })
-        .collect::<Vec<ProcessSpec>>();
+        .collect::<Vec<PaletteProfile>>();
     let outcome = run_multiprocess_tui(
         repo_root.to_path_buf(),
         specs,
         tab_order,
-        MultiProcessTuiOptions::default(),
+        MultiProcessTuiOptions {
+            process_ports,
+            panes,
+            start_in_grid: grid_layout,
+            keymap,
+            notify,
+            record,
+            palette_tasks,
+            palette_profiles,
+            ..MultiProcessTuiOptions::default()
+        },
     )
     .map_err(|error| {
         RunnerError::Ui(format!(
@@ -1035,6 +1596,7 @@ fn run_managed_task_runtime(
     repo_root: &Path,
     plan: ManagedTaskPlan,
 ) -> Result<String, RunnerError> {
+    let port_env = managed_port_env(&plan.processes);
     let specs = plan
         .processes
         .iter()
@@ -1043,11 +1605,27 @@ fn run_managed_task_runtime(
             run: process.run.clone(),
             cwd: process.cwd.clone(),
             start_after_ms: process.start_after_ms,
-            pty: true,
+            pty: false,
+            env: port_env.clone(),
+            limits: process.limits,
+            stop: process.stop.clone(),
+            depends_on: process.depends_on.clone(),
         })
         .collect::<Vec<ProcessSpec>>();
     let expected = specs.len();
+    let process_names = specs
+        .iter()
+        .map(|spec| spec.name.clone())
+        .collect::<Vec<String>>();
     let supervisor = ProcessSupervisor::spawn(repo_root.to_path_buf(), specs)?;
+    if let Some(path) = plan.record.as_ref() {
+        supervisor
+            .record_to(path, &process_names)
+            .map_err(|error| RunnerError::SessionRecordingIo {
+                path: path.clone(),
+                error,
+            })?;
+    }
 
     let color_enabled =
         resolve_color_enabled(OutputMode::from_env(), std::io::stdout().is_terminal());
@@ -1089,10 +1667,20 @@ fn run_managed_task_runtime(
                 ProcessEventKind::Stderr => {
                     renderer.text(&format!("[{} stderr] {}", event.process, event.payload))?;
                 }
-                ProcessEventKind::StdoutChunk | ProcessEventKind::StderrChunk => {}
+                ProcessEventKind::StdoutChunk
+                | ProcessEventKind::StderrChunk
+                | ProcessEventKind::Added
+                | ProcessEventKind::Removed => {}
                 ProcessEventKind::Exit => {
                     exit_count += 1;
                     if event.payload != "exit=0" {
+                        if let Some(notifier) = plan.notify.as_ref() {
+                            notifier.send(
+                                repo_root,
+                                NotifyEvent::ProcessFailed,
+                                &format!("process `{}` exited ({})", event.process, event.payload),
+                            );
+                        }
                         non_zero_exits.push((event.process.clone(), event.payload.clone()));
                     }
                     renderer.notice(
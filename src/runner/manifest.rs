@@ -1,9 +1,13 @@
 use std::collections::BTreeMap;
 use std::fmt;
+use std::num::NonZeroU64;
 
 use indexmap::IndexMap;
 use serde::de::{self, SeqAccess, Visitor};
 
+use crate::notify::NotifyChannel;
+use crate::process_manager::ProcessLimits;
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(super) struct TaskManifest {
@@ -17,6 +21,10 @@ pub(super) struct TaskManifest {
     pub(super) package_manager: Option<ManifestPackageManagerConfig>,
     #[serde(default)]
     pub(super) shell: Option<ManifestShellConfig>,
+    #[serde(default)]
+    pub(super) tui: Option<ManifestTuiConfig>,
+    #[serde(default)]
+    pub(super) watch: Option<ManifestWatchGroup>,
     #[serde(default, deserialize_with = "deserialize_tasks")]
     pub(super) tasks: BTreeMap<String, ManifestTask>,
 }
@@ -28,6 +36,80 @@ pub(super) struct ManifestShellConfig {
     pub(super) run: Option<String>,
 }
 
+/// `[tui]` table, shared by the manifest and the user-level `tui.toml`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ManifestTuiConfig {
+    #[serde(default)]
+    pub(super) keys: IndexMap<String, ManifestKeyList>,
+}
+
+impl ManifestTuiConfig {
+    pub(super) fn key_preset(&self) -> Option<&str> {
+        self.keys
+            .get("preset")
+            .and_then(|keys| keys.0.first())
+            .map(String::as_str)
+    }
+
+    pub(super) fn key_overrides(&self) -> Vec<(String, Vec<String>)> {
+        self.keys
+            .iter()
+            .filter(|(action, _)| action.as_str() != "preset")
+            .map(|(action, keys)| (action.clone(), keys.0.clone()))
+            .collect()
+    }
+}
+
+/// User-level `tui.toml` under the effigy config directory.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UserTuiConfigFile {
+    #[serde(default)]
+    pub(super) tui: Option<ManifestTuiConfig>,
+}
+
+/// One key (`"ctrl+g"`) or a list of keys (`["j", "down"]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ManifestKeyList(pub(super) Vec<String>);
+
+impl<'de> serde::Deserialize<'de> for ManifestKeyList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct KeyListVisitor;
+
+        impl<'de> Visitor<'de> for KeyListVisitor {
+            type Value = ManifestKeyList;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("key string or array of key strings")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ManifestKeyList(vec![value.to_owned()]))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut keys = Vec::new();
+                while let Some(key) = seq.next_element::<String>()? {
+                    keys.push(key);
+                }
+                Ok(ManifestKeyList(keys))
+            }
+        }
+
+        deserializer.deserialize_any(KeyListVisitor)
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(super) struct ManifestTestConfig {
@@ -187,6 +269,8 @@ impl<'de> serde::Deserialize<'de> for ManifestTestSuite {
 #[derive(Debug, serde::Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub(super) struct ManifestTask {
+    #[serde(default)]
+    pub(super) description: Option<String>,
     #[serde(default)]
     pub(super) run: Option<ManifestManagedRun>,
     #[serde(default)]
@@ -199,13 +283,135 @@ pub(super) struct ManifestTask {
     pub(super) concurrent: Vec<ManifestManagedConcurrentEntry>,
     #[serde(default)]
     pub(super) profiles: IndexMap<String, ManifestManagedProfile>,
+    #[serde(default)]
+    pub(super) limits: Option<ManifestResourceLimits>,
+    #[serde(default)]
+    pub(super) layout: Option<String>,
+    #[serde(default)]
+    pub(super) panes: Vec<Vec<String>>,
+    #[serde(default)]
+    pub(super) notify: Option<ManifestNotify>,
+    #[serde(default)]
+    pub(super) watch: Option<ManifestWatch>,
+}
+
+/// `notify = true`, `notify = ["bell", "osc9"]` or
+/// `notify = { via = ["osc777"], command = "..." }`. `false` mutes the task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ManifestNotify {
+    pub(super) enabled: bool,
+    pub(super) via: Vec<NotifyChannel>,
+    pub(super) command: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestNotifyTable {
+    #[serde(default)]
+    via: Vec<NotifyChannel>,
+    #[serde(default)]
+    command: Option<String>,
+}
+
+impl<'de> serde::Deserialize<'de> for ManifestNotify {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct NotifyVisitor;
+
+        impl<'de> Visitor<'de> for NotifyVisitor {
+            type Value = ManifestNotify;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str(
+                    "bool, array of channels, or `{ via = [...], command = \"...\" }` table",
+                )
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ManifestNotify {
+                    enabled: value,
+                    via: if value {
+                        vec![NotifyChannel::Bell]
+                    } else {
+                        Vec::new()
+                    },
+                    command: None,
+                })
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let via = <Vec<NotifyChannel> as serde::Deserialize>::deserialize(
+                    de::value::SeqAccessDeserializer::new(seq),
+                )?;
+                Ok(ManifestNotify {
+                    enabled: true,
+                    via,
+                    command: None,
+                })
+            }
+
+            fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let table = <ManifestNotifyTable as serde::Deserialize>::deserialize(
+                    de::value::MapAccessDeserializer::new(map),
+                )?;
+                Ok(ManifestNotify {
+                    enabled: true,
+                    via: table.via,
+                    command: table.command,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(NotifyVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ManifestResourceLimits {
+    #[serde(default)]
+    pub(super) memory_mb: Option<NonZeroU64>,
+    #[serde(default)]
+    pub(super) cpu_seconds: Option<NonZeroU64>,
+    #[serde(default)]
+    pub(super) nofile: Option<NonZeroU64>,
+}
+
+impl ManifestResourceLimits {
+    pub(super) fn overlay(self, base: Option<ManifestResourceLimits>) -> Self {
+        let base = base.unwrap_or_default();
+        Self {
+            memory_mb: self.memory_mb.or(base.memory_mb),
+            cpu_seconds: self.cpu_seconds.or(base.cpu_seconds),
+            nofile: self.nofile.or(base.nofile),
+        }
+    }
+
+    pub(super) fn to_process_limits(self) -> ProcessLimits {
+        ProcessLimits {
+            memory_mb: self.memory_mb.map(NonZeroU64::get),
+            cpu_seconds: self.cpu_seconds.map(NonZeroU64::get),
+            nofile: self.nofile.map(NonZeroU64::get),
+        }
+    }
 }
 
 #[derive(Debug)]
 enum ManifestTaskDefinition {
     Run(String),
     RunSequence(Vec<ManifestManagedRunStep>),
-    Full(ManifestTask),
+    Full(Box<ManifestTask>),
 }
 
 impl ManifestTaskDefinition {
@@ -219,7 +425,7 @@ impl ManifestTaskDefinition {
                 run: Some(ManifestManagedRun::Sequence(sequence)),
                 ..ManifestTask::default()
             },
-            ManifestTaskDefinition::Full(task) => task,
+            ManifestTaskDefinition::Full(task) => *task,
         }
     }
 }
@@ -269,7 +475,7 @@ impl<'de> serde::Deserialize<'de> for ManifestTaskDefinition {
                 let task = <ManifestTask as serde::Deserialize>::deserialize(
                     de::value::MapAccessDeserializer::new(map),
                 )?;
-                Ok(ManifestTaskDefinition::Full(task))
+                Ok(ManifestTaskDefinition::Full(Box::new(task)))
             }
         }
 
@@ -292,6 +498,120 @@ pub(super) struct ManifestManagedConcurrentEntry {
     pub(super) tab: Option<usize>,
     #[serde(default)]
     pub(super) start_after_ms: Option<u64>,
+    #[serde(default)]
+    pub(super) ports: IndexMap<String, ManifestManagedPort>,
+    #[serde(default)]
+    pub(super) limits: Option<ManifestResourceLimits>,
+    #[serde(default)]
+    pub(super) stop: Option<ManifestProcessStop>,
+    #[serde(default)]
+    pub(super) depends_on: Vec<String>,
+    #[serde(default)]
+    pub(super) watch: Option<ManifestWatch>,
+}
+
+/// `[tasks.<name>.watch]` defaults for `effigy watch <name>`, and
+/// `watch = {...}` on concurrent entries to restart a process on change.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ManifestWatch {
+    #[serde(default)]
+    pub(super) include: Vec<String>,
+    #[serde(default)]
+    pub(super) exclude: Vec<String>,
+    #[serde(default)]
+    pub(super) debounce_ms: Option<NonZeroU64>,
+    #[serde(default)]
+    pub(super) restart: Option<bool>,
+}
+
+/// Root `[watch]` table: the watch group run by `effigy watch --owner effigy`
+/// without a target. Each `map` key is a task selector that reruns when a
+/// file matching one of its globs changes.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ManifestWatchGroup {
+    #[serde(default)]
+    pub(super) map: IndexMap<String, Vec<String>>,
+    #[serde(default)]
+    pub(super) exclude: Vec<String>,
+    #[serde(default)]
+    pub(super) debounce_ms: Option<NonZeroU64>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ManifestProcessStop {
+    #[serde(default)]
+    pub(super) signal: Option<String>,
+    #[serde(default)]
+    pub(super) timeout_ms: Option<NonZeroU64>,
+    #[serde(default)]
+    pub(super) run: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ManifestManagedPort {
+    pub(super) port: u16,
+    pub(super) auto: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestManagedPortTable {
+    port: u16,
+    #[serde(default)]
+    auto: bool,
+}
+
+impl<'de> serde::Deserialize<'de> for ManifestManagedPort {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ManagedPortVisitor;
+
+        impl<'de> Visitor<'de> for ManagedPortVisitor {
+            type Value = ManifestManagedPort;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("port number or `{ port = <number>, auto = <bool> }` table")
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let port = u16::try_from(value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))?;
+                Ok(ManifestManagedPort { port, auto: false })
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let port = u16::try_from(value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))?;
+                Ok(ManifestManagedPort { port, auto: false })
+            }
+
+            fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let table = <ManifestManagedPortTable as serde::Deserialize>::deserialize(
+                    de::value::MapAccessDeserializer::new(map),
+                )?;
+                Ok(ManifestManagedPort {
+                    port: table.port,
+                    auto: table.auto,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(ManagedPortVisitor)
+    }
 }
 
 #[derive(Debug)]
@@ -418,6 +738,10 @@ impl<'de> serde::Deserialize<'de> for ManifestManagedRunStep {
 pub(super) struct ManifestManagedProfile {
     #[serde(default)]
     pub(super) concurrent: Vec<ManifestManagedConcurrentEntry>,
+    #[serde(default)]
+    pub(super) layout: Option<String>,
+    #[serde(default)]
+    pub(super) panes: Vec<Vec<String>>,
 }
 
 impl ManifestManagedProfile {
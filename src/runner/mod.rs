@@ -3,11 +3,13 @@ use std::path::{Path, PathBuf};
 
 use serde_json::json;
 
+use crate::notify::NOTIFY_ENV;
 use crate::process_manager::ProcessManagerError;
 use crate::resolver::{resolve_target_root, ResolveError};
 use crate::tasks::TaskError;
 use crate::ui::theme::{resolve_color_enabled, Theme};
 use crate::ui::{KeyValue, NoticeLevel, OutputMode, PlainRenderer, Renderer};
+#[cfg(test)]
 use crate::TaskInvocation;
 use crate::{Command, DoctorArgs, TasksArgs};
 
@@ -16,27 +18,34 @@ mod catalog;
 mod deferral;
 mod doctor;
 mod execute;
+mod ignore;
 mod locking;
 mod managed;
 mod manifest;
 mod model;
+mod notify;
+mod picker;
 mod render;
+mod tui_config;
 mod util;
 
 use builtin::try_run_builtin_task;
-use catalog::{discover_catalogs, select_catalog_and_task};
+use catalog::{discover_catalogs, discover_catalogs_with_ignore, select_catalog_and_task};
 use execute::{catalog_task_label, run_manifest_task, task_run_preview};
 use manifest::{
     ManifestJsPackageManager, ManifestManagedConcurrentEntry, ManifestManagedRun,
-    ManifestManagedRunStep, ManifestTask, TaskManifest,
+    ManifestManagedRunStep, ManifestResourceLimits, ManifestTask, TaskManifest,
 };
 use model::{
-    CatalogSelectionMode, DeferredCommand, LoadedCatalog, ManagedProcessSpec, ManagedTaskPlan,
-    TaskRuntimeArgs, TaskSelection, TaskSelector, BUILTIN_TASKS, DEFAULT_BUILTIN_TEST_MAX_PARALLEL,
-    DEFAULT_MANAGED_SHELL_RUN, DEFER_DEPTH_ENV, IMPLICIT_ROOT_DEFER_TEMPLATE, TASK_MANIFEST_FILE,
+    CatalogSelectionMode, DeferredCommand, LoadedCatalog, ManagedPaletteProfile,
+    ManagedProcessSpec, ManagedTaskPlan, TaskRuntimeArgs, TaskSelection, TaskSelector,
+    BUILTIN_TASKS, CHANGED_FILES_ENV, DEFAULT_BUILTIN_TEST_MAX_PARALLEL, DEFAULT_MANAGED_SHELL_RUN,
+    DEFER_DEPTH_ENV, IMPLICIT_ROOT_DEFER_TEMPLATE, TASK_MANIFEST_FILE,
 };
 use util::{parse_task_reference_invocation, parse_task_runtime_args, parse_task_selector};
 
+pub use picker::{pick_task, TaskPickerOutcome};
+
 #[derive(Debug)]
 struct ManagedProfileDisplayRow {
     task: String,
@@ -93,6 +102,14 @@ pub enum RunnerError {
         command: String,
         error: std::io::Error,
     },
+    TaskResourceLimitExceeded {
+        command: String,
+        detail: String,
+    },
+    TaskCommandCrashed {
+        command: String,
+        detail: String,
+    },
     TaskCommandFailure {
         command: String,
         code: Option<i32>,
@@ -142,12 +159,35 @@ pub enum RunnerError {
         profile: String,
         detail: String,
     },
+    TaskManagedProfileLayoutInvalid {
+        task: String,
+        profile: String,
+        detail: String,
+    },
+    TuiKeyBindingsInvalid {
+        path: PathBuf,
+        detail: String,
+    },
+    NotifyEnvInvalid {
+        detail: String,
+    },
+    SessionRecordingIo {
+        path: PathBuf,
+        error: std::io::Error,
+    },
     TaskManagedTaskReferenceInvalid {
         task: String,
         process: String,
         reference: String,
         detail: String,
     },
+    TaskManagedPortUnavailable {
+        task: String,
+        process: String,
+        name: String,
+        port: u16,
+        detail: String,
+    },
     TaskManagedNonZeroExit {
         task: String,
         profile: String,
@@ -224,6 +264,12 @@ impl std::fmt::Display for RunnerError {
             RunnerError::TaskCommandLaunch { command, error } => {
                 write!(f, "failed to launch task command `{command}`: {error}")
             }
+            RunnerError::TaskResourceLimitExceeded { command, detail } => {
+                write!(f, "task command `{command}` was stopped: {detail}")
+            }
+            RunnerError::TaskCommandCrashed { command, detail } => {
+                write!(f, "task command `{command}` {detail}")
+            }
             RunnerError::TaskCommandFailure {
                 command,
                 code,
@@ -307,6 +353,23 @@ impl std::fmt::Display for RunnerError {
                 f,
                 "managed task `{task}` profile `{profile}` tab order is invalid: {detail}"
             ),
+            RunnerError::TaskManagedProfileLayoutInvalid {
+                task,
+                profile,
+                detail,
+            } => write!(
+                f,
+                "managed task `{task}` profile `{profile}` layout is invalid: {detail}"
+            ),
+            RunnerError::TuiKeyBindingsInvalid { path, detail } => {
+                write!(f, "invalid `[tui.keys]` in {}: {detail}", path.display())
+            }
+            RunnerError::NotifyEnvInvalid { detail } => {
+                write!(f, "invalid `{NOTIFY_ENV}`: {detail}")
+            }
+            RunnerError::SessionRecordingIo { path, error } => {
+                write!(f, "session recording {}: {error}", path.display())
+            }
             RunnerError::TaskManagedTaskReferenceInvalid {
                 task,
                 process,
@@ -316,6 +379,16 @@ impl std::fmt::Display for RunnerError {
                 f,
                 "managed task `{task}` process `{process}` task ref `{reference}` is invalid: {detail}"
             ),
+            RunnerError::TaskManagedPortUnavailable {
+                task,
+                process,
+                name,
+                port,
+                detail,
+            } => write!(
+                f,
+                "managed task `{task}` process `{process}` port `{name}` ({port}) is unavailable: {detail}"
+            ),
             RunnerError::TaskManagedNonZeroExit {
                 task,
                 profile,
@@ -434,7 +507,7 @@ pub fn run_doctor(args: DoctorArgs) -> Result<String, RunnerError> {
 pub fn run_tasks(args: TasksArgs) -> Result<String, RunnerError> {
     let cwd = std::env::current_dir().map_err(RunnerError::Cwd)?;
     let resolved = resolve_target_root(cwd, args.repo_override)?;
-    let catalogs = match discover_catalogs(&resolved.resolved_root) {
+    let catalogs = match discover_catalogs_with_ignore(&resolved.resolved_root, !args.no_ignore) {
         Ok(catalogs) => catalogs,
         Err(RunnerError::TaskCatalogsMissing { .. }) => Vec::new(),
         Err(error) => return Err(error),
@@ -1056,6 +1129,7 @@ fn render_probe_lock_scopes(probe: &serde_json::Value) -> String {
     rendered.join(", ")
 }
 
+#[cfg(test)]
 fn run_manifest_task_with_cwd(task: &TaskInvocation, cwd: PathBuf) -> Result<String, RunnerError> {
     execute::run_manifest_task_with_cwd(task, cwd)
 }
@@ -1065,6 +1139,34 @@ fn builtin_test_max_parallel(catalogs: &[LoadedCatalog], resolved_root: &std::pa
     builtin::builtin_test_max_parallel(catalogs, resolved_root)
 }
 
+#[cfg(test)]
+fn junit_report_summary(raw: &str) -> Option<(String, Vec<String>)> {
+    builtin::junit_report_summary(raw)
+}
+
+#[cfg(test)]
+fn jest_report_summary(raw: &str, root: &std::path::Path) -> Option<(String, Vec<String>)> {
+    builtin::jest_report_summary(raw, root)
+}
+
+#[cfg(test)]
+fn catalog_tui_keymap(
+    catalog: &LoadedCatalog,
+    user_config_dir: Option<&std::path::Path>,
+) -> Result<crate::tui::KeyMap, RunnerError> {
+    tui_config::TuiKeyConfig::from_catalog(Some(catalog))?.resolve(user_config_dir)
+}
+
+#[cfg(all(test, unix))]
+fn watch_supervised_groups() -> Vec<i32> {
+    builtin::watch_supervised_groups()
+}
+
+#[cfg(test)]
+fn watch_matcher_watches_dir(include: &[String], exclude: &[String], dir: &str) -> bool {
+    builtin::watch_matcher_watches_dir(include, exclude, dir)
+}
+
 #[cfg(test)]
 #[path = "../tests/runner_tests.rs"]
 mod tests;
@@ -1,6 +1,10 @@
 use std::path::PathBuf;
 
+use crate::notify::Notifier;
+use crate::process_manager::{ProcessLimits, ProcessStop};
+
 use super::manifest::{ManifestTask, TaskManifest};
+use super::tui_config::TuiKeyConfig;
 
 #[derive(Debug)]
 pub(super) struct LoadedCatalog {
@@ -44,6 +48,8 @@ pub(super) struct DeferredCommand {
 pub(super) struct TaskRuntimeArgs {
     pub(super) repo_override: Option<PathBuf>,
     pub(super) verbose_root: bool,
+    /// `--no-ignore`: also discover catalogs under ignored directories.
+    pub(super) no_ignore: bool,
     pub(super) passthrough: Vec<String>,
 }
 
@@ -53,6 +59,10 @@ pub(super) struct ManagedProcessSpec {
     pub(super) run: String,
     pub(super) cwd: PathBuf,
     pub(super) start_after_ms: u64,
+    pub(super) ports: Vec<(String, u16)>,
+    pub(super) limits: ProcessLimits,
+    pub(super) stop: ProcessStop,
+    pub(super) depends_on: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -61,17 +71,37 @@ pub(super) struct ManagedTaskPlan {
     pub(super) profile: String,
     pub(super) processes: Vec<ManagedProcessSpec>,
     pub(super) tab_order: Vec<String>,
+    pub(super) grid_layout: bool,
+    pub(super) panes: Vec<Vec<String>>,
+    pub(super) keys: TuiKeyConfig,
     pub(super) fail_on_non_zero: bool,
+    pub(super) notify: Option<Notifier>,
+    /// `--record <file>` target for the session's process events.
+    pub(super) record: Option<PathBuf>,
+    /// Catalog tasks the TUI command palette can start as new tabs.
+    pub(super) palette_tasks: Vec<ManagedProcessSpec>,
+    /// The task's other profiles, for switching from the command palette.
+    pub(super) palette_profiles: Vec<ManagedPaletteProfile>,
     pub(super) passthrough: Vec<String>,
 }
 
+#[derive(Debug)]
+pub(super) struct ManagedPaletteProfile {
+    pub(super) name: String,
+    pub(super) processes: Vec<ManagedProcessSpec>,
+    pub(super) tab_order: Vec<String>,
+    pub(super) panes: Vec<Vec<String>>,
+}
+
 pub(super) const TASK_MANIFEST_FILE: &str = "effigy.toml";
 pub(super) const DEFER_DEPTH_ENV: &str = "EFFIGY_DEFER_DEPTH";
+/// Newline-separated files that triggered an `effigy watch` rerun.
+pub(super) const CHANGED_FILES_ENV: &str = "EFFIGY_CHANGED_FILES";
 pub(super) const IMPLICIT_ROOT_DEFER_TEMPLATE: &str =
     "composer global exec effigy -- {request} {args}";
 pub(super) const DEFAULT_BUILTIN_TEST_MAX_PARALLEL: usize = 3;
 pub(super) const DEFAULT_MANAGED_SHELL_RUN: &str = "exec ${SHELL:-/bin/zsh} -i";
-pub(super) const BUILTIN_TASKS: [(&str, &str); 9] = [
+pub(super) const BUILTIN_TASKS: [(&str, &str); 10] = [
     ("help", "Show general help (same as --help)"),
     (
         "config",
@@ -102,4 +132,8 @@ pub(super) const BUILTIN_TASKS: [(&str, &str); 9] = [
         "unlock",
         "Manually clear lock scopes (`workspace`, `task:*`, `profile:*/*`)",
     ),
+    (
+        "replay",
+        "Replay a managed TUI session recorded with `--record <file>`",
+    ),
 ];
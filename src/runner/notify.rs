@@ -0,0 +1,44 @@
+use crate::notify::{parse_channels, Notifier, NotifyChannel, NOTIFY_COMMAND_ENV, NOTIFY_ENV};
+
+use super::manifest::ManifestNotify;
+use super::RunnerError;
+
+/// Resolves where a task's notifications go. `EFFIGY_NOTIFY=0` mutes every
+/// task. Otherwise the task's `notify` wins over the `EFFIGY_NOTIFY` channels,
+/// and `EFFIGY_NOTIFY_COMMAND` is used when the task sets no command.
+pub(super) fn resolve_task_notifier(
+    task_notify: Option<&ManifestNotify>,
+) -> Result<Option<Notifier>, RunnerError> {
+    let env_value = std::env::var(NOTIFY_ENV).ok();
+    let env_command = std::env::var(NOTIFY_COMMAND_ENV)
+        .ok()
+        .filter(|command| !command.trim().is_empty());
+    resolve_notifier(task_notify, env_value.as_deref(), env_command)
+}
+
+fn resolve_notifier(
+    task_notify: Option<&ManifestNotify>,
+    env_value: Option<&str>,
+    env_command: Option<String>,
+) -> Result<Option<Notifier>, RunnerError> {
+    let env_channels = match env_value.map(|value| value.trim().to_ascii_lowercase()) {
+        None => Vec::new(),
+        Some(value) if matches!(value.as_str(), "0" | "false" | "off") => return Ok(None),
+        Some(value) if matches!(value.as_str(), "1" | "true" | "on") => vec![NotifyChannel::Bell],
+        Some(value) => {
+            parse_channels(&value).map_err(|detail| RunnerError::NotifyEnvInvalid { detail })?
+        }
+    };
+    let notifier = match task_notify {
+        Some(notify) if !notify.enabled => return Ok(None),
+        Some(notify) => Notifier {
+            channels: notify.via.clone(),
+            command: notify.command.clone().or(env_command),
+        },
+        None => Notifier {
+            channels: env_channels,
+            command: env_command,
+        },
+    };
+    Ok((!notifier.is_empty()).then_some(notifier))
+}
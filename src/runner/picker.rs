@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resolver::resolve_target_root;
+use crate::tui::{run_task_picker, PickerEntry};
+use crate::TaskInvocation;
+
+use super::util::split_task_reference_words;
+use super::{catalog_task_label, discover_catalogs, task_run_preview, LoadedCatalog, RunnerError};
+
+const HISTORY_FILE: &str = ".effigy/history.json";
+const HISTORY_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskPickerOutcome {
+    /// No catalogs (or no tasks) to pick from; callers fall back to help.
+    Unavailable,
+    Cancelled,
+    Selected(TaskInvocation),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TaskHistory {
+    #[serde(default)]
+    recent: Vec<String>,
+}
+
+/// Lists every catalog task in an interactive picker, prompts for passthrough
+/// args and records the choice in `.effigy/history.json`.
+pub fn pick_task() -> Result<TaskPickerOutcome, RunnerError> {
+    let cwd = std::env::current_dir().map_err(RunnerError::Cwd)?;
+    let resolved = resolve_target_root(cwd, None)?;
+    let catalogs = match discover_catalogs(&resolved.resolved_root) {
+        Ok(catalogs) => catalogs,
+        Err(RunnerError::TaskCatalogsMissing { .. }) => return Ok(TaskPickerOutcome::Unavailable),
+        Err(error) => return Err(error),
+    };
+    let history = load_task_history(&resolved.resolved_root);
+    let entries = picker_entries(&catalogs, &history);
+    if entries.is_empty() {
+        return Ok(TaskPickerOutcome::Unavailable);
+    }
+
+    let Some(selection) = run_task_picker(entries)
+        .map_err(|error| RunnerError::Ui(format!("task picker failed: {error}")))?
+    else {
+        return Ok(TaskPickerOutcome::Cancelled);
+    };
+    let args = split_task_reference_words(&selection.args)?;
+    record_task_history(&resolved.resolved_root, &selection.task);
+    Ok(TaskPickerOutcome::Selected(TaskInvocation {
+        name: selection.task,
+        args,
+    }))
+}
+
+/// Catalog tasks in `effigy tasks` order, with tasks from `history` moved to
+/// the front in most-recent-first order.
+pub(super) fn picker_entries(catalogs: &[LoadedCatalog], history: &[String]) -> Vec<PickerEntry> {
+    let mut ordered_catalogs = catalogs.iter().collect::<Vec<&LoadedCatalog>>();
+    ordered_catalogs.sort_by(|a, b| {
+        a.depth
+            .cmp(&b.depth)
+            .then_with(|| a.alias.cmp(&b.alias))
+            .then_with(|| a.manifest_path.cmp(&b.manifest_path))
+    });
+    let mut entries = ordered_catalogs
+        .iter()
+        .flat_map(|catalog| {
+            catalog
+                .manifest
+                .tasks
+                .iter()
+                .map(|(name, task)| PickerEntry {
+                    task: catalog_task_label(catalog, name),
+                    description: task.description.clone(),
+                    run: task_run_preview(task),
+                    recent: false,
+                })
+        })
+        .collect::<Vec<PickerEntry>>();
+    for entry in &mut entries {
+        entry.recent = history.contains(&entry.task);
+    }
+    entries.sort_by_key(|entry| {
+        history
+            .iter()
+            .position(|task| task == &entry.task)
+            .unwrap_or(usize::MAX)
+    });
+    entries
+}
+
+fn history_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(HISTORY_FILE)
+}
+
+/// Recently picked task labels, most recent first. A missing or unreadable
+/// history file is treated as empty.
+pub(super) fn load_task_history(workspace_root: &Path) -> Vec<String> {
+    fs::read_to_string(history_path(workspace_root))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<TaskHistory>(&raw).ok())
+        .map(|history| history.recent)
+        .unwrap_or_default()
+}
+
+/// Moves `task` to the front of the history, keeping at most
+/// `HISTORY_LIMIT` entries. History is a convenience, so write failures are
+/// ignored.
+pub(super) fn record_task_history(workspace_root: &Path, task: &str) {
+    let mut recent = load_task_history(workspace_root);
+    recent.retain(|existing| existing != task);
+    recent.insert(0, task.to_owned());
+    recent.truncate(HISTORY_LIMIT);
+    let path = history_path(workspace_root);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(encoded) = serde_json::to_string_pretty(&TaskHistory { recent }) {
+        let _ = fs::write(path, format!("{encoded}\n"));
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::tui::KeyMap;
+
+use super::manifest::{ManifestTuiConfig, UserTuiConfigFile};
+use super::{LoadedCatalog, RunnerError};
+
+const USER_TUI_CONFIG_FILE: &str = "tui.toml";
+
+/// The catalog manifest's `[tui.keys]`, checked when a plan is resolved. The
+/// user's `tui.toml` is only read by [`TuiKeyConfig::resolve`], right before a
+/// TUI launches, so plans do not depend on the machine's config directory.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TuiKeyConfig {
+    manifest: Option<(PathBuf, ManifestTuiConfig)>,
+}
+
+impl TuiKeyConfig {
+    pub(super) fn from_catalog(catalog: Option<&LoadedCatalog>) -> Result<Self, RunnerError> {
+        let Some((path, tui)) = catalog.and_then(|catalog| {
+            catalog
+                .manifest
+                .tui
+                .as_ref()
+                .map(|tui| (catalog.manifest_path.as_path(), tui))
+        }) else {
+            return Ok(Self::default());
+        };
+        build_keymap(path, None, Some(tui))?;
+        Ok(Self {
+            manifest: Some((path.to_path_buf(), tui.clone())),
+        })
+    }
+
+    /// Merges `tui.toml` from `user_config_dir` over the manifest keys. User
+    /// keys win, and a user `preset` replaces the manifest's.
+    pub(super) fn resolve(&self, user_config_dir: Option<&Path>) -> Result<KeyMap, RunnerError> {
+        let manifest = self
+            .manifest
+            .as_ref()
+            .map(|(path, tui)| (path.as_path(), tui));
+        let Some(user_path) = user_config_dir
+            .map(|dir| dir.join(USER_TUI_CONFIG_FILE))
+            .filter(|path| path.is_file())
+        else {
+            return match manifest {
+                Some((path, tui)) => build_keymap(path, None, Some(tui)),
+                None => Ok(KeyMap::default()),
+            };
+        };
+        let user = load_user_tui_config(&user_path)?;
+        build_keymap(&user_path, user.tui.as_ref(), manifest.map(|(_, tui)| tui))
+    }
+}
+
+/// Resolves the key bindings for a TUI that is about to launch.
+pub(super) fn resolve_tui_keymap(catalog: Option<&LoadedCatalog>) -> Result<KeyMap, RunnerError> {
+    TuiKeyConfig::from_catalog(catalog)?.resolve(user_config_dir().as_deref())
+}
+
+fn build_keymap(
+    error_path: &Path,
+    user: Option<&ManifestTuiConfig>,
+    manifest: Option<&ManifestTuiConfig>,
+) -> Result<KeyMap, RunnerError> {
+    let preset = user
+        .and_then(ManifestTuiConfig::key_preset)
+        .or_else(|| manifest.and_then(ManifestTuiConfig::key_preset));
+    let overrides = manifest
+        .into_iter()
+        .chain(user)
+        .flat_map(ManifestTuiConfig::key_overrides)
+        .collect::<Vec<(String, Vec<String>)>>();
+    KeyMap::from_config(preset, &overrides).map_err(|detail| RunnerError::TuiKeyBindingsInvalid {
+        path: error_path.to_path_buf(),
+        detail,
+    })
+}
+
+fn load_user_tui_config(path: &Path) -> Result<UserTuiConfigFile, RunnerError> {
+    let source = fs::read_to_string(path).map_err(|error| RunnerError::TaskManifestRead {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    toml::from_str(&source).map_err(|error| RunnerError::TaskManifestParse {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// `$XDG_CONFIG_HOME/effigy`, falling back to `~/.config/effigy`.
+pub(super) fn user_config_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|value| !value.is_empty())
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    Some(config_home.join("effigy"))
+}
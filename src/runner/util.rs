@@ -1,20 +1,18 @@
 use std::path::{Path, PathBuf};
 use std::process::Command as ProcessCommand;
 
-use super::{RunnerError, TaskRuntimeArgs, TaskSelector};
+use crate::testing::TestRunner;
+
+use super::{RunnerError, TaskRuntimeArgs, TaskSelector, CHANGED_FILES_ENV};
 
 pub(super) fn normalize_builtin_test_suite(raw: &str) -> Option<&'static str> {
-    match raw {
-        "vitest" => Some("vitest"),
-        "nextest" | "cargo-nextest" => Some("cargo-nextest"),
-        "cargo-test" => Some("cargo-test"),
-        _ => None,
-    }
+    TestRunner::from_label(raw).map(TestRunner::label)
 }
 
 pub(super) fn parse_task_runtime_args(args: &[String]) -> Result<TaskRuntimeArgs, RunnerError> {
     let mut repo: Option<PathBuf> = None;
     let mut verbose_root = false;
+    let mut no_ignore = false;
     let mut passthrough: Vec<String> = Vec::new();
     let mut i = 0usize;
     while i < args.len() {
@@ -34,12 +32,18 @@ pub(super) fn parse_task_runtime_args(args: &[String]) -> Result<TaskRuntimeArgs
             i += 1;
             continue;
         }
+        if arg == "--no-ignore" {
+            no_ignore = true;
+            i += 1;
+            continue;
+        }
         passthrough.push(arg.clone());
         i += 1;
     }
     Ok(TaskRuntimeArgs {
         repo_override: repo,
         verbose_root,
+        no_ignore,
         passthrough,
     })
 }
@@ -91,7 +95,7 @@ pub(super) fn render_task_selector(selector: &TaskSelector) -> String {
         .unwrap_or_else(|| selector.task_name.clone())
 }
 
-fn split_task_reference_words(raw: &str) -> Result<Vec<String>, RunnerError> {
+pub(super) fn split_task_reference_words(raw: &str) -> Result<Vec<String>, RunnerError> {
     let mut out = Vec::<String>::new();
     let mut current = String::new();
     let mut token_started = false;
@@ -182,6 +186,25 @@ pub(super) fn with_local_node_bin_path(process: &mut ProcessCommand, cwd: &Path)
     process.env("PATH", merged);
 }
 
+/// Files passed down by the `effigy watch` rerun that started this process.
+pub(super) fn changed_files_from_env() -> Vec<String> {
+    std::env::var(CHANGED_FILES_ENV)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// `{changed}` interpolation: each changed file shell-quoted, space-separated.
+pub(super) fn render_changed_files(files: &[String]) -> String {
+    files
+        .iter()
+        .map(|file| shell_quote(file))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 pub(super) fn shell_quote(raw: &str) -> String {
     if raw.is_empty() {
         return "''".to_owned();
@@ -10,19 +10,93 @@ const VITEST_CONFIG_FILES: &[&str] = &[
     "vitest.config.cjs",
 ];
 
+const JEST_CONFIG_FILES: &[&str] = &[
+    "jest.config.ts",
+    "jest.config.mts",
+    "jest.config.js",
+    "jest.config.mjs",
+    "jest.config.cjs",
+    "jest.config.json",
+];
+
+const PHPUNIT_CONFIG_FILES: &[&str] = &["phpunit.xml", "phpunit.xml.dist"];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TestRunner {
     Vitest,
+    Jest,
+    BunTest,
     CargoNextest,
     CargoTest,
+    Pytest,
+    GoTest,
+    Pest,
+    Phpunit,
 }
 
 impl TestRunner {
+    /// Every runner, in detection priority order.
+    pub const ALL: [TestRunner; 9] = [
+        TestRunner::Vitest,
+        TestRunner::Jest,
+        TestRunner::BunTest,
+        TestRunner::CargoNextest,
+        TestRunner::CargoTest,
+        TestRunner::Pytest,
+        TestRunner::GoTest,
+        TestRunner::Pest,
+        TestRunner::Phpunit,
+    ];
+
     pub fn label(self) -> &'static str {
         match self {
             TestRunner::Vitest => "vitest",
+            TestRunner::Jest => "jest",
+            TestRunner::BunTest => "bun-test",
             TestRunner::CargoNextest => "cargo-nextest",
             TestRunner::CargoTest => "cargo-test",
+            TestRunner::Pytest => "pytest",
+            TestRunner::GoTest => "go-test",
+            TestRunner::Pest => "pest",
+            TestRunner::Phpunit => "phpunit",
+        }
+    }
+
+    /// Parses a runner label or one of its aliases (`nextest`).
+    pub fn from_label(raw: &str) -> Option<Self> {
+        if raw == "nextest" {
+            return Some(TestRunner::CargoNextest);
+        }
+        TestRunner::ALL
+            .into_iter()
+            .find(|runner| runner.label() == raw)
+    }
+
+    /// Command used when no `[test.runners]` override or package manager
+    /// applies.
+    pub fn default_command(self) -> &'static str {
+        match self {
+            TestRunner::Vitest => "vitest run",
+            TestRunner::Jest => "jest",
+            TestRunner::BunTest => "bun test",
+            TestRunner::CargoNextest => "cargo nextest run",
+            TestRunner::CargoTest => "cargo test",
+            TestRunner::Pytest => "pytest",
+            TestRunner::GoTest => "go test ./...",
+            TestRunner::Pest => "vendor/bin/pest",
+            TestRunner::Phpunit => "vendor/bin/phpunit",
+        }
+    }
+
+    /// Runners of one ecosystem test the same sources, so a target runs only
+    /// the first detected runner of each.
+    fn ecosystem(self) -> &'static str {
+        match self {
+            TestRunner::Vitest | TestRunner::Jest | TestRunner::BunTest => "js",
+            TestRunner::CargoNextest | TestRunner::CargoTest => "rust",
+            TestRunner::Pytest => "python",
+            TestRunner::GoTest => "go",
+            TestRunner::Pest | TestRunner::Phpunit => "php",
         }
     }
 }
@@ -48,168 +122,263 @@ pub struct TestRunnerDetection {
     pub candidates: Vec<TestRunnerCandidate>,
 }
 
+/// Evidence-based detection for one runner: the evidence lines when the
+/// runner applies to a repo root, otherwise why it was rejected.
+struct RunnerDetector {
+    runner: TestRunner,
+    detect: fn(&Path) -> Result<Vec<String>, String>,
+}
+
+/// Detectors in priority order; see [`TestRunner::ALL`].
+const RUNNER_DETECTORS: &[RunnerDetector] = &[
+    RunnerDetector {
+        runner: TestRunner::Vitest,
+        detect: detect_vitest,
+    },
+    RunnerDetector {
+        runner: TestRunner::Jest,
+        detect: detect_jest,
+    },
+    RunnerDetector {
+        runner: TestRunner::BunTest,
+        detect: detect_bun_test,
+    },
+    RunnerDetector {
+        runner: TestRunner::CargoNextest,
+        detect: detect_cargo_nextest,
+    },
+    RunnerDetector {
+        runner: TestRunner::CargoTest,
+        detect: detect_cargo_test,
+    },
+    RunnerDetector {
+        runner: TestRunner::Pytest,
+        detect: detect_pytest,
+    },
+    RunnerDetector {
+        runner: TestRunner::GoTest,
+        detect: detect_go_test,
+    },
+    RunnerDetector {
+        runner: TestRunner::Pest,
+        detect: detect_pest,
+    },
+    RunnerDetector {
+        runner: TestRunner::Phpunit,
+        detect: detect_phpunit,
+    },
+];
+
 pub fn detect_test_runner(repo_root: &Path) -> Option<TestRunnerPlan> {
     detect_test_runner_detailed(repo_root).selected
 }
 
+/// The first detected runner of each ecosystem, in priority order.
 pub fn detect_test_runner_plans(repo_root: &Path) -> Vec<TestRunnerPlan> {
-    let (vitest_plan, _) = detect_vitest(repo_root);
-    let (nextest_plan, _, fallback_plan, _) = detect_rust(repo_root);
-    let mut plans = Vec::<TestRunnerPlan>::new();
-    if let Some(plan) = vitest_plan {
-        plans.push(plan);
-    }
-    if let Some(plan) = nextest_plan.or(fallback_plan) {
-        plans.push(plan);
-    }
-    plans
+    let mut ecosystems = Vec::<&'static str>::new();
+    detect_all(repo_root)
+        .into_iter()
+        .filter_map(|(plan, _)| plan)
+        .filter(|plan| {
+            let ecosystem = plan.runner.ecosystem();
+            if ecosystems.contains(&ecosystem) {
+                return false;
+            }
+            ecosystems.push(ecosystem);
+            true
+        })
+        .collect()
 }
 
 pub fn detect_test_runner_detailed(repo_root: &Path) -> TestRunnerDetection {
-    let (vitest_plan, vitest_candidate) = detect_vitest(repo_root);
-    let (nextest_plan, nextest_candidate, fallback_plan, fallback_candidate) =
-        detect_rust(repo_root);
-    let selected = vitest_plan.or(nextest_plan).or(fallback_plan);
+    let detections = detect_all(repo_root);
+    let selected = detections.iter().find_map(|(plan, _)| plan.clone());
     TestRunnerDetection {
         selected,
-        candidates: vec![vitest_candidate, nextest_candidate, fallback_candidate],
+        candidates: detections
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .collect(),
+    }
+}
+
+fn detect_all(repo_root: &Path) -> Vec<(Option<TestRunnerPlan>, TestRunnerCandidate)> {
+    RUNNER_DETECTORS
+        .iter()
+        .map(|detector| {
+            let command = detector.runner.default_command().to_owned();
+            match (detector.detect)(repo_root) {
+                Ok(evidence) => (
+                    Some(TestRunnerPlan {
+                        runner: detector.runner,
+                        command: command.clone(),
+                        evidence: evidence.clone(),
+                    }),
+                    TestRunnerCandidate {
+                        runner: detector.runner,
+                        command,
+                        available: true,
+                        reason: evidence.join("; "),
+                    },
+                ),
+                Err(reason) => (
+                    None,
+                    TestRunnerCandidate {
+                        runner: detector.runner,
+                        command,
+                        available: false,
+                        reason,
+                    },
+                ),
+            }
+        })
+        .collect()
+}
+
+fn detect_vitest(repo_root: &Path) -> Result<Vec<String>, String> {
+    let mut evidence = Vec::<String>::new();
+    if read_package_json(repo_root).is_some_and(|json| package_json_mentions(&json, "vitest")) {
+        evidence.push("package.json includes vitest dependency/script evidence".to_owned());
+    }
+    evidence.extend(found_files(repo_root, VITEST_CONFIG_FILES));
+    if repo_root.join("node_modules/.bin/vitest").is_file() {
+        evidence.push("found local `node_modules/.bin/vitest` executable".to_owned());
     }
+    non_empty(evidence, "no package/config/bin vitest markers found")
 }
 
-fn detect_vitest(repo_root: &Path) -> (Option<TestRunnerPlan>, TestRunnerCandidate) {
+fn detect_jest(repo_root: &Path) -> Result<Vec<String>, String> {
     let mut evidence = Vec::<String>::new();
-    let package_json = repo_root.join("package.json");
-    if let Ok(raw) = fs::read_to_string(&package_json) {
-        if package_json_mentions_vitest(&raw) {
-            evidence.push("package.json includes vitest dependency/script evidence".to_owned());
+    if let Some(json) = read_package_json(repo_root) {
+        if package_json_mentions(&json, "jest") {
+            evidence.push("package.json includes jest dependency/script evidence".to_owned());
         }
+        if json.get("jest").is_some() {
+            evidence.push("package.json has a `jest` config key".to_owned());
+        }
+    }
+    evidence.extend(found_files(repo_root, JEST_CONFIG_FILES));
+    if repo_root.join("node_modules/.bin/jest").is_file() {
+        evidence.push("found local `node_modules/.bin/jest` executable".to_owned());
     }
+    non_empty(evidence, "no package/config/bin jest markers found")
+}
 
-    for filename in VITEST_CONFIG_FILES {
-        if repo_root.join(filename).is_file() {
-            evidence.push(format!("found `{filename}`"));
+fn detect_bun_test(repo_root: &Path) -> Result<Vec<String>, String> {
+    let mut evidence = Vec::<String>::new();
+    if read_package_json(repo_root).is_some_and(|json| scripts_contain(&json, "bun test")) {
+        evidence.push("package.json script runs `bun test`".to_owned());
+    }
+    if fs::read_to_string(repo_root.join("bunfig.toml"))
+        .is_ok_and(|raw| raw.lines().any(|line| line.trim() == "[test]"))
+    {
+        evidence.push("`bunfig.toml` has a `[test]` section".to_owned());
+    }
+    non_empty(
+        evidence,
+        "no `bun test` script or `bunfig.toml` [test] section found",
+    )
+}
+
+fn detect_cargo_nextest(repo_root: &Path) -> Result<Vec<String>, String> {
+    if !repo_root.join("Cargo.toml").is_file() {
+        return Err("Cargo.toml not found".to_owned());
+    }
+    if !command_on_path("cargo-nextest") {
+        return Err("Cargo.toml present but `cargo-nextest` is not on PATH".to_owned());
+    }
+    Ok(vec![
+        "found `Cargo.toml`".to_owned(),
+        "found `cargo-nextest` on PATH".to_owned(),
+    ])
+}
+
+fn detect_cargo_test(repo_root: &Path) -> Result<Vec<String>, String> {
+    if !repo_root.join("Cargo.toml").is_file() {
+        return Err("Cargo.toml not found".to_owned());
+    }
+    let fallback = if command_on_path("cargo-nextest") {
+        "fallback if `cargo nextest run` is unavailable"
+    } else {
+        "`cargo-nextest` not found on PATH; falling back to `cargo test`"
+    };
+    Ok(vec!["found `Cargo.toml`".to_owned(), fallback.to_owned()])
+}
+
+fn detect_pytest(repo_root: &Path) -> Result<Vec<String>, String> {
+    let mut evidence = found_files(repo_root, &["pytest.ini", "conftest.py"]);
+    for (filename, section) in [
+        ("pyproject.toml", "[tool.pytest.ini_options]"),
+        ("setup.cfg", "[tool:pytest]"),
+        ("tox.ini", "[pytest]"),
+    ] {
+        if fs::read_to_string(repo_root.join(filename))
+            .is_ok_and(|raw| raw.lines().any(|line| line.trim() == section))
+        {
+            evidence.push(format!("`{filename}` has a `{section}` section"));
         }
     }
+    non_empty(evidence, "no pytest config or conftest.py markers found")
+}
 
-    if repo_root.join("node_modules/.bin/vitest").is_file() {
-        evidence.push("found local `node_modules/.bin/vitest` executable".to_owned());
+fn detect_go_test(repo_root: &Path) -> Result<Vec<String>, String> {
+    non_empty(found_files(repo_root, &["go.mod"]), "go.mod not found")
+}
+
+fn detect_pest(repo_root: &Path) -> Result<Vec<String>, String> {
+    let mut evidence = Vec::<String>::new();
+    if read_composer_json(repo_root).is_some_and(|json| composer_requires(&json, "pestphp/pest")) {
+        evidence.push("composer.json requires `pestphp/pest`".to_owned());
+    }
+    evidence.extend(found_files(repo_root, &["tests/Pest.php"]));
+    if repo_root.join("vendor/bin/pest").is_file() {
+        evidence.push("found local `vendor/bin/pest` executable".to_owned());
     }
+    non_empty(evidence, "no composer/config/bin pest markers found")
+}
 
+fn detect_phpunit(repo_root: &Path) -> Result<Vec<String>, String> {
+    let mut evidence = Vec::<String>::new();
+    if read_composer_json(repo_root).is_some_and(|json| composer_requires(&json, "phpunit/phpunit"))
+    {
+        evidence.push("composer.json requires `phpunit/phpunit`".to_owned());
+    }
+    evidence.extend(found_files(repo_root, PHPUNIT_CONFIG_FILES));
+    if repo_root.join("vendor/bin/phpunit").is_file() {
+        evidence.push("found local `vendor/bin/phpunit` executable".to_owned());
+    }
+    non_empty(evidence, "no composer/config/bin phpunit markers found")
+}
+
+fn non_empty(evidence: Vec<String>, reason: &str) -> Result<Vec<String>, String> {
     if evidence.is_empty() {
-        return (
-            None,
-            TestRunnerCandidate {
-                runner: TestRunner::Vitest,
-                command: "vitest run".to_owned(),
-                available: false,
-                reason: "no package/config/bin vitest markers found".to_owned(),
-            },
-        );
-    }
-
-    (
-        Some(TestRunnerPlan {
-            runner: TestRunner::Vitest,
-            command: "vitest run".to_owned(),
-            evidence: evidence.clone(),
-        }),
-        TestRunnerCandidate {
-            runner: TestRunner::Vitest,
-            command: "vitest run".to_owned(),
-            available: true,
-            reason: evidence.join("; "),
-        },
-    )
+        return Err(reason.to_owned());
+    }
+    Ok(evidence)
 }
 
-fn detect_rust(
-    repo_root: &Path,
-) -> (
-    Option<TestRunnerPlan>,
-    TestRunnerCandidate,
-    Option<TestRunnerPlan>,
-    TestRunnerCandidate,
-) {
-    let cargo_toml = repo_root.join("Cargo.toml");
-    if !cargo_toml.is_file() {
-        return (
-            None,
-            TestRunnerCandidate {
-                runner: TestRunner::CargoNextest,
-                command: "cargo nextest run".to_owned(),
-                available: false,
-                reason: "Cargo.toml not found".to_owned(),
-            },
-            None,
-            TestRunnerCandidate {
-                runner: TestRunner::CargoTest,
-                command: "cargo test".to_owned(),
-                available: false,
-                reason: "Cargo.toml not found".to_owned(),
-            },
-        );
-    }
-    let mut evidence = vec!["found `Cargo.toml`".to_owned()];
-    if command_on_path("cargo-nextest") {
-        evidence.push("found `cargo-nextest` on PATH".to_owned());
-        return (
-            Some(TestRunnerPlan {
-                runner: TestRunner::CargoNextest,
-                command: "cargo nextest run".to_owned(),
-                evidence: evidence.clone(),
-            }),
-            TestRunnerCandidate {
-                runner: TestRunner::CargoNextest,
-                command: "cargo nextest run".to_owned(),
-                available: true,
-                reason: evidence.join("; "),
-            },
-            Some(TestRunnerPlan {
-                runner: TestRunner::CargoTest,
-                command: "cargo test".to_owned(),
-                evidence: vec![
-                    "found `Cargo.toml`".to_owned(),
-                    "fallback if `cargo nextest run` is unavailable".to_owned(),
-                ],
-            }),
-            TestRunnerCandidate {
-                runner: TestRunner::CargoTest,
-                command: "cargo test".to_owned(),
-                available: true,
-                reason: "fallback Rust runner".to_owned(),
-            },
-        );
-    }
-    evidence.push("`cargo-nextest` not found on PATH; falling back to `cargo test`".to_owned());
-    (
-        None,
-        TestRunnerCandidate {
-            runner: TestRunner::CargoNextest,
-            command: "cargo nextest run".to_owned(),
-            available: false,
-            reason: "Cargo.toml present but `cargo-nextest` is not on PATH".to_owned(),
-        },
-        Some(TestRunnerPlan {
-            runner: TestRunner::CargoTest,
-            command: "cargo test".to_owned(),
-            evidence: evidence.clone(),
-        }),
-        TestRunnerCandidate {
-            runner: TestRunner::CargoTest,
-            command: "cargo test".to_owned(),
-            available: true,
-            reason: evidence.join("; "),
-        },
-    )
+fn found_files(repo_root: &Path, filenames: &[&str]) -> Vec<String> {
+    filenames
+        .iter()
+        .filter(|filename| repo_root.join(filename).is_file())
+        .map(|filename| format!("found `{filename}`"))
+        .collect()
 }
 
-fn package_json_mentions_vitest(raw: &str) -> bool {
-    let Ok(json) = serde_json::from_str::<Value>(raw) else {
-        return false;
-    };
-    dependency_contains(&json, "dependencies", "vitest")
-        || dependency_contains(&json, "devDependencies", "vitest")
-        || scripts_contain_vitest(&json)
+fn read_package_json(repo_root: &Path) -> Option<Value> {
+    let raw = fs::read_to_string(repo_root.join("package.json")).ok()?;
+    serde_json::from_str::<Value>(&raw).ok()
+}
+
+fn read_composer_json(repo_root: &Path) -> Option<Value> {
+    let raw = fs::read_to_string(repo_root.join("composer.json")).ok()?;
+    serde_json::from_str::<Value>(&raw).ok()
+}
+
+fn package_json_mentions(json: &Value, name: &str) -> bool {
+    dependency_contains(json, "dependencies", name)
+        || dependency_contains(json, "devDependencies", name)
+        || scripts_contain(json, name)
 }
 
 fn dependency_contains(json: &Value, field: &str, name: &str) -> bool {
@@ -218,17 +387,22 @@ fn dependency_contains(json: &Value, field: &str, name: &str) -> bool {
         .is_some_and(|deps| deps.contains_key(name))
 }
 
-fn scripts_contain_vitest(json: &Value) -> bool {
+fn scripts_contain(json: &Value, needle: &str) -> bool {
     json.get("scripts")
         .and_then(Value::as_object)
         .is_some_and(|scripts| {
             scripts
                 .values()
                 .filter_map(Value::as_str)
-                .any(|script| script.contains("vitest"))
+                .any(|script| script.contains(needle))
         })
 }
 
+fn composer_requires(json: &Value, package: &str) -> bool {
+    dependency_contains(json, "require", package)
+        || dependency_contains(json, "require-dev", package)
+}
+
 fn command_on_path(command: &str) -> bool {
     std::env::var_os("PATH")
         .map(|value| std::env::split_paths(&value).collect::<Vec<PathBuf>>())
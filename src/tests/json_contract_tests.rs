@@ -29,6 +29,7 @@ fn tasks_json_contract_has_versioned_top_level_shape() {
             resolve_selector: None,
             output_json: true,
             pretty_json: true,
+            no_ignore: false,
         })
     })
     .expect("run tasks json");
@@ -51,6 +52,7 @@ fn tasks_filtered_json_contract_has_versioned_shape_and_filter_fields() {
             resolve_selector: None,
             output_json: true,
             pretty_json: true,
+            no_ignore: false,
         })
     })
     .expect("run filtered tasks json");
@@ -82,6 +84,7 @@ fn tasks_json_contract_with_resolve_has_diagnostics_and_probe_fields() {
             resolve_selector: Some("farmyard/api".to_owned()),
             output_json: true,
             pretty_json: true,
+            no_ignore: false,
         })
     })
     .expect("run tasks json resolve");
@@ -114,6 +117,7 @@ fn tasks_filtered_json_contract_with_resolve_has_diagnostics_and_probe_fields()
             resolve_selector: Some("farmyard/build".to_owned()),
             output_json: true,
             pretty_json: true,
+            no_ignore: false,
         })
     })
     .expect("run filtered tasks json resolve");
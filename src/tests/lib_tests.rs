@@ -212,6 +212,7 @@ fn command_requests_json_checks_task_or_global_mode() {
         resolve_selector: None,
         output_json: true,
         pretty_json: true,
+        no_ignore: false,
     });
     assert!(command_requests_json(&cmd_tasks, false));
 
@@ -233,6 +234,7 @@ fn apply_global_json_flag_sets_non_task_command_json_mode() {
         resolve_selector: None,
         output_json: false,
         pretty_json: true,
+        no_ignore: false,
     });
     let doctor_cmd = Command::Doctor(DoctorArgs {
         repo_override: None,
@@ -272,6 +274,7 @@ fn parse_tasks_with_filters() {
             resolve_selector: None,
             output_json: false,
             pretty_json: true,
+            no_ignore: false,
         })
     );
 }
@@ -288,6 +291,7 @@ fn parse_tasks_supports_json_flag() {
             resolve_selector: None,
             output_json: true,
             pretty_json: true,
+            no_ignore: false,
         })
     );
 }
@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn parse_channels_accepts_lists_and_rejects_unknown_names() {
+    assert_eq!(
+        parse_channels("bell, OSC9,bell"),
+        Ok(vec![NotifyChannel::Bell, NotifyChannel::Osc9])
+    );
+    assert_eq!(parse_channels(""), Ok(Vec::new()));
+    let err = parse_channels("bell,toast").expect_err("unknown channel");
+    assert!(err.contains("`toast`"), "{err}");
+}
+
+#[test]
+fn write_terminal_emits_bell_and_osc_sequences() {
+    let notifier = Notifier {
+        channels: vec![
+            NotifyChannel::Bell,
+            NotifyChannel::Osc9,
+            NotifyChannel::Osc777,
+        ],
+        command: None,
+    };
+    let mut out = Vec::new();
+    notifier
+        .write_terminal(&mut out, "api exited\u{1b} (exit=1)")
+        .expect("write");
+    assert_eq!(
+        String::from_utf8(out).expect("utf8"),
+        "\u{7}\u{1b}]9;effigy: api exited (exit=1)\u{7}\u{1b}]777;notify;effigy;api exited (exit=1)\u{7}"
+    );
+}
@@ -0,0 +1,101 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::*;
+
+fn chunk_event(bytes: &[u8]) -> ProcessEvent {
+    ProcessEvent {
+        process: "api".to_owned(),
+        kind: ProcessEventKind::StdoutChunk,
+        payload: String::from_utf8_lossy(bytes).into_owned(),
+        chunk: Some(bytes.to_vec()),
+    }
+}
+
+#[test]
+fn recorder_round_trips_text_and_raw_chunks() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock")
+        .as_nanos();
+    let path = std::env::temp_dir()
+        .join(format!("effigy-recording-{nanos}"))
+        .join("session.effigy-rec");
+    let events = vec![
+        chunk_event(b"\x1b[32mready\x1b[0m\r\n"),
+        chunk_event(&[0xe2, 0x94]),
+        ProcessEvent {
+            process: "api".to_owned(),
+            kind: ProcessEventKind::Exit,
+            payload: "exit=1".to_owned(),
+            chunk: None,
+        },
+    ];
+    let mut recorder =
+        SessionRecorder::create(&path, &["api".to_owned(), "web".to_owned()]).expect("create");
+    for event in &events {
+        recorder.record(event).expect("record");
+    }
+
+    let recording = SessionRecording::load(&path).expect("load");
+    assert_eq!(
+        recording.processes,
+        vec!["api".to_owned(), "web".to_owned()]
+    );
+    assert_eq!(
+        recording
+            .events
+            .into_iter()
+            .map(|recorded| recorded.event)
+            .collect::<Vec<ProcessEvent>>(),
+        events
+    );
+    let _ = fs::remove_dir_all(path.parent().expect("parent"));
+}
+
+#[test]
+fn parse_rejects_unknown_schema_and_bad_events() {
+    assert_eq!(
+        SessionRecording::parse(r#"{"schema":"other"}"#),
+        Err(format!(
+            "line 1: expected schema `{RECORDING_SCHEMA}` in the header"
+        ))
+    );
+    let source = format!(
+        "{{\"schema\":\"{RECORDING_SCHEMA}\",\"processes\":[\"api\"]}}\n{{\"at_ms\":5,\"process\":\"api\",\"kind\":\"beep\",\"payload\":\"\"}}\n"
+    );
+    assert_eq!(
+        SessionRecording::parse(&source),
+        Err("line 2: unknown event kind `beep`".to_owned())
+    );
+}
+
+#[test]
+fn initial_tabs_leave_added_processes_to_their_added_event() {
+    let source = format!(
+        "{{\"schema\":\"{RECORDING_SCHEMA}\",\"processes\":[\"api\"]}}\n\
+{{\"at_ms\":1,\"process\":\"lint\",\"kind\":\"added\",\"payload\":\"\"}}\n\
+{{\"at_ms\":2,\"process\":\"lint\",\"kind\":\"stdout\",\"payload\":\"ok\"}}\n\
+{{\"at_ms\":3,\"process\":\"lint\",\"kind\":\"removed\",\"payload\":\"\"}}\n"
+    );
+    let recording = SessionRecording::parse(&source).expect("parse");
+    assert_eq!(recording.events[0].event.kind, ProcessEventKind::Added);
+    assert_eq!(recording.events[2].event.kind, ProcessEventKind::Removed);
+    assert_eq!(recording.initial_tabs(), vec!["api".to_owned()]);
+
+    let legacy = SessionRecording {
+        processes: vec!["api".to_owned()],
+        events: vec![RecordedEvent {
+            at_ms: 0,
+            event: ProcessEvent {
+                process: "web".to_owned(),
+                kind: ProcessEventKind::Stdout,
+                payload: "ready".to_owned(),
+                chunk: None,
+            },
+        }],
+    };
+    assert_eq!(
+        legacy.initial_tabs(),
+        vec!["api".to_owned(), "web".to_owned()]
+    );
+}
@@ -1,13 +1,19 @@
+use super::ignore::IgnoreRules;
+use super::picker::{load_task_history, picker_entries, record_task_history};
+#[cfg(unix)]
+use super::watch_supervised_groups;
 use super::{
-    builtin_test_max_parallel, discover_catalogs, parse_task_runtime_args, parse_task_selector,
-    run_doctor, run_manifest_task_with_cwd, run_tasks, RunnerError, TaskRuntimeArgs,
+    builtin_test_max_parallel, catalog_tui_keymap, discover_catalogs, jest_report_summary,
+    junit_report_summary, parse_task_runtime_args, parse_task_selector, run_doctor,
+    run_manifest_task_with_cwd, run_tasks, watch_matcher_watches_dir, RunnerError, TaskRuntimeArgs,
 };
+use crate::tui::KeyMap;
 use crate::{DoctorArgs, TaskInvocation, TasksArgs};
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::symlink;
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -18,6 +24,7 @@ fn parse_task_runtime_args_extracts_repo_verbose_and_passthrough() {
         "--repo".to_owned(),
         "/tmp/x".to_owned(),
         "--verbose-root".to_owned(),
+        "--no-ignore".to_owned(),
         "--flag".to_owned(),
         "abc".to_owned(),
     ];
@@ -27,6 +34,7 @@ fn parse_task_runtime_args_extracts_repo_verbose_and_passthrough() {
         TaskRuntimeArgs {
             repo_override: Some(PathBuf::from("/tmp/x")),
             verbose_root: true,
+            no_ignore: true,
             passthrough: vec!["--flag".to_owned(), "abc".to_owned()],
         }
     );
@@ -353,6 +361,7 @@ fn run_manifest_task_builtin_init_creates_scaffold_when_missing() {
         resolve_selector: None,
         output_json: false,
         pretty_json: true,
+        no_ignore: false,
     })
     .expect("generated scaffold should parse and list tasks");
     assert!(listed.contains("ping"));
@@ -731,617 +740,967 @@ fn run_manifest_task_builtin_watch_once_executes_target_task() {
     assert!(marker.exists(), "watch --once should execute the target");
 }
 
-#[test]
-fn run_manifest_task_builtin_watch_rejects_concurrent_watch_owner_for_same_target() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("builtin-watch-lock-conflict");
+fn run_watch_with_background_edits(name: &str, extra_args: &[&str]) -> serde_json::Value {
+    run_watch_with_background_edits_in(&temp_workspace(name), "printf built", extra_args)
+}
+
+fn run_watch_with_background_edits_in(
+    root: &Path,
+    build_run: &str,
+    extra_args: &[&str],
+) -> serde_json::Value {
+    let src = root.join("src");
+    fs::create_dir_all(&src).expect("mkdir src");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.build]\nrun = \"sleep 2\"\n",
+        &format!(
+            "[tasks.build]\nrun = \"{}\"\n",
+            build_run.replace('\\', "\\\\").replace('"', "\\\"")
+        ),
     );
 
-    let root_for_thread = root.clone();
-    let join = thread::spawn(move || {
-        run_manifest_task_with_cwd(
-            &TaskInvocation {
-                name: "watch".to_owned(),
-                args: vec![
-                    "--owner".to_owned(),
-                    "effigy".to_owned(),
-                    "--once".to_owned(),
-                    "build".to_owned(),
-                ],
-            },
-            root_for_thread,
-        )
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer_stop = stop.clone();
+    let writer_src = src.clone();
+    let writer = thread::spawn(move || {
+        let mut tick = 0usize;
+        while !writer_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(150));
+            tick += 1;
+            // New directories must be picked up by the event backend too.
+            let nested = writer_src.join(format!("nested-{tick}"));
+            let _ = fs::create_dir_all(&nested);
+            let _ = fs::write(nested.join("app.ts"), format!("tick {tick}"));
+        }
     });
 
-    let watch_lock = root.join(".effigy/locks/task-watch-build.lock");
-    let started = Instant::now();
-    while !watch_lock.exists() {
-        assert!(
-            started.elapsed() < Duration::from_secs(2),
-            "watch lock was not created in time"
-        );
-        thread::sleep(Duration::from_millis(20));
-    }
+    let mut args = vec![
+        "--owner".to_owned(),
+        "effigy".to_owned(),
+        "--debounce-ms".to_owned(),
+        "100".to_owned(),
+        "--include".to_owned(),
+        "src/**".to_owned(),
+        "--max-runs".to_owned(),
+        "2".to_owned(),
+        "--json".to_owned(),
+    ];
+    args.extend(extra_args.iter().map(|arg| (*arg).to_owned()));
+    args.push("build".to_owned());
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "watch".to_owned(),
+            args,
+        },
+        root.to_path_buf(),
+    );
+    stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    writer.join().expect("join writer");
+    serde_json::from_str(&out.expect("watch should rerun after edits")).expect("parse json")
+}
+
+fn run_watch_un_ignoring_directory_mid_watch(name: &str, extra_args: &[&str]) -> serde_json::Value {
+    let root = temp_workspace(name);
+    let generated = root.join("src/generated");
+    fs::create_dir_all(&generated).expect("mkdir");
+    fs::write(generated.join("old.ts"), "old").expect("write old");
+    fs::write(root.join(".gitignore"), "generated/\n").expect("write gitignore");
+    write_manifest(&root.join("effigy.toml"), "[tasks.build]\nrun = \"true\"\n");
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer_stop = stop.clone();
+    let writer_root = root.clone();
+    let writer = thread::spawn(move || {
+        let mut tick = 0usize;
+        while !writer_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(150));
+            tick += 1;
+            match tick {
+                2 => {
+                    let _ = fs::write(writer_root.join(".gitignore"), "");
+                }
+                6.. => {
+                    let _ = fs::write(
+                        writer_root.join("src/generated/api.ts"),
+                        format!("tick {tick}"),
+                    );
+                }
+                _ => {}
+            }
+            // Keeps the watch from hanging if the directory is never watched.
+            if tick > 40 {
+                let _ = fs::write(writer_root.join("src/app.ts"), format!("tick {tick}"));
+            }
+        }
+    });
 
-    let err = run_manifest_task_with_cwd(
+    let mut args = vec![
+        "--owner".to_owned(),
+        "effigy".to_owned(),
+        "--debounce-ms".to_owned(),
+        "100".to_owned(),
+        "--include".to_owned(),
+        "src/**".to_owned(),
+        "--max-runs".to_owned(),
+        "3".to_owned(),
+        "--json".to_owned(),
+    ];
+    args.extend(extra_args.iter().map(|arg| (*arg).to_owned()));
+    args.push("build".to_owned());
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "watch".to_owned(),
-            args: vec![
-                "--owner".to_owned(),
-                "effigy".to_owned(),
-                "--once".to_owned(),
-                "build".to_owned(),
-            ],
+            args,
         },
         root.clone(),
-    )
-    .expect_err("second watch owner should conflict on watch scope lock");
+    );
+    stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    writer.join().expect("join writer");
+    serde_json::from_str(&out.expect("watch should rerun after edits")).expect("parse json")
+}
 
-    match err {
-        RunnerError::TaskLockConflict {
-            scope, remediation, ..
-        } => {
-            assert_eq!(scope, "task:watch:build");
-            assert!(remediation.contains("effigy unlock task:watch:build"));
-        }
-        other => panic!("unexpected error: {other}"),
+#[test]
+fn run_manifest_task_builtin_watch_registers_directories_un_ignored_mid_watch() {
+    let parsed = run_watch_un_ignoring_directory_mid_watch("builtin-watch-unignore", &[]);
+    if parsed["backend"] == "inotify" {
+        assert_eq!(
+            parsed["changes"][1]["files"],
+            serde_json::json!(["src/generated/old.ts"])
+        );
     }
-
-    let first = join.join().expect("thread join");
-    first.expect("first watch should complete");
+    assert_eq!(
+        parsed["changes"][2]["files"],
+        serde_json::json!(["src/generated/api.ts"])
+    );
+    assert_eq!(parsed["warnings"], serde_json::json!([]));
 }
 
 #[test]
-fn run_manifest_task_builtin_init_help_renders_topic() {
-    let root = temp_workspace("builtin-init-help");
-    write_manifest(&root.join("effigy.toml"), "");
-
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "init".to_owned(),
-            args: vec!["--help".to_owned()],
-        },
-        root,
-    )
-    .expect("run init --help");
-    assert!(out.contains("init Help"));
-    assert!(out.contains("effigy init [--dry-run] [--force] [--json]"));
+fn run_manifest_task_builtin_watch_poll_rescans_after_ignore_file_edits() {
+    // The root `.gitignore` is outside `--include`, so the poller has to
+    // notice the edit without it being a watched file.
+    let parsed =
+        run_watch_un_ignoring_directory_mid_watch("builtin-watch-unignore-poll", &["--poll"]);
+    assert_eq!(parsed["backend"], "poll");
+    assert_eq!(
+        parsed["changes"][1]["files"],
+        serde_json::json!(["src/generated/old.ts"])
+    );
+    assert_eq!(
+        parsed["changes"][2]["files"],
+        serde_json::json!(["src/generated/api.ts"])
+    );
 }
 
 #[test]
-fn run_manifest_task_builtin_migrate_help_json_uses_help_schema() {
-    let root = temp_workspace("builtin-migrate-help-json");
-    write_manifest(&root.join("effigy.toml"), "");
+fn watch_matcher_prunes_excluded_and_out_of_scope_directories() {
+    let globs = |patterns: &[&str]| {
+        patterns
+            .iter()
+            .map(|pattern| (*pattern).to_owned())
+            .collect::<Vec<String>>()
+    };
+    let none = globs(&[]);
+    assert!(watch_matcher_watches_dir(&none, &none, ""));
+    assert!(watch_matcher_watches_dir(&none, &none, "src/app"));
+    assert!(!watch_matcher_watches_dir(&none, &none, "node_modules"));
+    assert!(!watch_matcher_watches_dir(&none, &none, ".git"));
+    assert!(!watch_matcher_watches_dir(
+        &none,
+        &globs(&["dist/**"]),
+        "dist"
+    ));
+    // File-level excludes never prune whole directories.
+    assert!(watch_matcher_watches_dir(
+        &none,
+        &globs(&["**/*.snap"]),
+        "src"
+    ));
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "migrate".to_owned(),
-            args: vec!["--help".to_owned(), "--json".to_owned()],
-        },
-        root,
-    )
-    .expect("run migrate --help --json");
-    assert!(out.contains("\"schema\": \"effigy.help.v1\""));
-    assert!(out.contains("\"topic\": \"migrate\""));
+    let include = globs(&["packages/api/src/**/*.ts", "schema/*.graphql"]);
+    assert!(watch_matcher_watches_dir(&include, &none, "packages"));
+    assert!(watch_matcher_watches_dir(
+        &include,
+        &none,
+        "packages/api/src/routes"
+    ));
+    assert!(watch_matcher_watches_dir(&include, &none, "schema"));
+    assert!(!watch_matcher_watches_dir(&include, &none, "packages/web"));
+    assert!(!watch_matcher_watches_dir(&include, &none, "docs"));
+    assert!(!watch_matcher_watches_dir(&include, &none, "schemas"));
+    assert!(watch_matcher_watches_dir(
+        &globs(&["**/*.ts"]),
+        &none,
+        "docs"
+    ));
 }
 
+#[cfg(target_os = "linux")]
 #[test]
-fn run_manifest_task_verbose_root_includes_resolution_trace() {
-    let _guard = test_lock().lock().expect("lock");
-    let _env = EnvGuard::set_many(&[("EFFIGY_COLOR", None), ("NO_COLOR", None)]);
-    let root = temp_workspace("verbose-trace");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.ping]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[tasks.ping]\nrun = \"printf farmyard\"\n",
-    );
-
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "farmyard/ping".to_owned(),
-            args: vec!["--verbose-root".to_owned()],
-        },
-        root,
-    )
-    .expect("run");
+fn run_manifest_task_builtin_watch_reruns_on_inotify_events() {
+    let parsed = run_watch_with_background_edits("builtin-watch-inotify", &[]);
+    assert_eq!(parsed["runs"], 2);
+    assert_eq!(parsed["backend"], "inotify");
+}
 
-    assert!(out.contains("Task Resolution"));
-    assert!(out.contains("catalog-alias: farmyard"));
-    assert!(out.contains("farmyard"));
+#[test]
+fn run_manifest_task_builtin_watch_poll_flag_uses_rescanning_backend() {
+    let parsed = run_watch_with_background_edits("builtin-watch-poll", &["--poll"]);
+    assert_eq!(parsed["runs"], 2);
+    assert_eq!(parsed["backend"], "poll");
 }
 
 #[test]
-fn run_manifest_task_includes_local_node_modules_bin_in_path() {
-    let root = temp_workspace("local-node-bin-path");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.local]\nrun = \"local-tool\"\n",
+fn run_manifest_task_builtin_watch_passes_changed_files_to_rerun() {
+    let root = temp_workspace("builtin-watch-changed");
+    let parsed = run_watch_with_background_edits_in(
+        &root,
+        "printf '%s|' {changed} >> changed.log; printf '%s\\n' \"$EFFIGY_CHANGED_FILES\" >> env.log",
+        &["--poll"],
     );
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let tool = local_bin.join("local-tool");
-    fs::write(&tool, "#!/bin/sh\nexit 0\n").expect("write local tool");
-    let mut perms = fs::metadata(&tool).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&tool, perms).expect("chmod");
-
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "local".to_owned(),
-            args: Vec::new(),
-        },
-        root,
-    )
-    .expect("run local tool");
+    assert_eq!(parsed["runs"], 2);
+    assert_eq!(parsed["changes"][0]["run"], 1);
+    assert_eq!(parsed["changes"][0]["files"], serde_json::json!([]));
+    let rerun_files = parsed["changes"][1]["files"]
+        .as_array()
+        .expect("rerun files")
+        .iter()
+        .filter_map(|file| file.as_str())
+        .collect::<Vec<&str>>();
+    assert!(!rerun_files.is_empty());
+    assert!(rerun_files
+        .iter()
+        .all(|file| file.starts_with("src/nested-")));
 
-    assert_eq!(out, "");
+    let changed_log = fs::read_to_string(root.join("changed.log")).expect("read changed log");
+    let env_log = fs::read_to_string(root.join("env.log")).expect("read env log");
+    for file in &rerun_files {
+        assert!(changed_log.contains(&format!("{file}|")), "{changed_log}");
+        assert!(env_log.lines().any(|line| line == *file), "{env_log}");
+    }
 }
 
+#[cfg(unix)]
 #[test]
-fn run_manifest_task_run_array_supports_task_reference_steps() {
-    let root = temp_workspace("run-array-task-refs");
+fn run_manifest_task_builtin_watch_restart_stops_and_respawns_target() {
+    let root = temp_workspace("builtin-watch-restart");
+    let src = root.join("src");
+    fs::create_dir_all(&src).expect("mkdir src");
+    let log = root.join("restart.log");
+    // The first start runs until stopped; the second exits so the bounded
+    // watch can finish. stderr is dropped because the shell's "Terminated"
+    // report would hit a closed pipe once the `--json` child is gone.
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.lint]
-run = "printf lint-ok"
-
-[tasks.validate]
-run = [{ task = "lint" }, "printf validate-ok"]
-"#,
-    );
+        "[tasks.serve]\nrun = \"exec 2>/dev/null; if [ -f started ]; then echo start >> restart.log; exit 0; fi; touch started; trap 'echo term >> restart.log; exit 0' TERM; echo start >> restart.log; while true; do sleep 0.1; done\"\n",
+    );
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer_stop = stop.clone();
+    let writer_src = src.clone();
+    let writer_log = log.clone();
+    let writer = thread::spawn(move || {
+        let mut tick = 0usize;
+        while !writer_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(150));
+            if !writer_log.exists() {
+                continue;
+            }
+            tick += 1;
+            let _ = fs::write(writer_src.join("app.ts"), format!("tick {tick}"));
+        }
+    });
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: vec!["--verbose-root".to_owned()],
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--debounce-ms".to_owned(),
+                "100".to_owned(),
+                "--include".to_owned(),
+                "src/**".to_owned(),
+                "--restart".to_owned(),
+                "--max-runs".to_owned(),
+                "2".to_owned(),
+                "--json".to_owned(),
+                "serve".to_owned(),
+            ],
         },
         root,
-    )
-    .expect("run");
-
-    assert!(out.contains("printf lint-ok"));
-    assert!(out.contains("printf validate-ok"));
+    );
+    stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    writer.join().expect("join writer");
+    let parsed: serde_json::Value =
+        serde_json::from_str(&out.expect("watch --restart should respawn after edits"))
+            .expect("parse json");
+    assert_eq!(parsed["runs"], 2);
+    assert_eq!(parsed["restart"], true);
+    assert_eq!(parsed["forced_stops"], 0);
+    assert_eq!(
+        fs::read_to_string(&log).expect("read restart log"),
+        "start\nterm\nstart\n"
+    );
 }
 
 #[test]
-fn run_manifest_task_run_array_accepts_dag_metadata() {
-    let root = temp_workspace("run-array-dag-metadata");
+fn run_manifest_task_builtin_watch_applies_task_watch_config() {
+    let root = temp_workspace("builtin-watch-task-config");
+    let marker = root.join("watch-config.log");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "lint", run = "printf lint-ok" },
-  { id = "build", run = "printf build-ok", depends_on = ["lint"] },
-  { run = "printf validate-ok" }
-]
-"#,
+        &format!(
+            "[tasks.serve]\nrun = \"printf served > '{}'\"\nwatch = {{ include = [\"src/**\"], debounce_ms = 150, restart = true }}\n",
+            marker.display()
+        ),
     );
 
+    // `--signal` is only valid because the task config turns on restart.
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: vec!["--verbose-root".to_owned()],
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--signal".to_owned(),
+                "INT".to_owned(),
+                "--once".to_owned(),
+                "--json".to_owned(),
+                "serve".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run");
+    .expect("watch should pick up task watch config");
 
-    assert!(out.contains("printf lint-ok"));
-    assert!(out.contains("printf build-ok"));
-    assert!(out.contains("printf validate-ok"));
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    assert_eq!(parsed["restart"], true);
+    assert_eq!(parsed["runs"], 1);
+    assert!(marker.exists(), "restart target should have run");
 }
 
 #[test]
-fn run_manifest_task_run_array_rejects_depends_on_without_id() {
-    let root = temp_workspace("run-array-depends-on-without-id");
+fn run_manifest_task_builtin_watch_signal_requires_restart() {
+    let root = temp_workspace("builtin-watch-signal-no-restart");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "lint", run = "printf lint-ok" },
-  { run = "printf build-ok", depends_on = ["lint"] }
-]
-"#,
+        "[tasks.serve]\nrun = \"printf served\"\n",
     );
 
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--signal".to_owned(),
+                "SIGINT".to_owned(),
+                "--once".to_owned(),
+                "serve".to_owned(),
+            ],
         },
         root,
     )
-    .expect_err("expected depends_on without id error");
+    .expect_err("expected --signal without --restart to fail");
 
     match err {
         RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("defines `depends_on` but is missing a non-empty `id`"));
+            assert!(message.contains("require `--restart`"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_manifest_task_run_array_rejects_missing_dependency_step() {
-    let root = temp_workspace("run-array-missing-dependency-step");
+fn run_manifest_task_builtin_watch_on_change_validates_flags() {
+    let root = temp_workspace("builtin-watch-on-change-validate");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "build", run = "printf build-ok", depends_on = ["lint"] }
-]
-"#,
+        "[tasks.serve]\nrun = \"printf served\"\n",
     );
-
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
-        },
-        root,
-    )
-    .expect_err("expected missing dependency error");
-
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("depends on missing step `lint`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
-}
+    let cases = [
+        (
+            vec!["--on-change", "restart"],
+            "invalid `--on-change` value",
+        ),
+        (
+            vec!["--on-change", "cancel", "--restart"],
+            "does not apply to `--restart`",
+        ),
+        (
+            vec!["--on-change", "queue", "--signal", "SIGINT"],
+            "require `--restart` or `--on-change cancel`",
+        ),
+    ];
+    for (args, expected) in cases {
+        let mut watch_args = vec!["--owner".to_owned(), "effigy".to_owned()];
+        watch_args.extend(args.iter().map(|arg| (*arg).to_owned()));
+        watch_args.extend(["--once".to_owned(), "serve".to_owned()]);
+        let err = run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "watch".to_owned(),
+                args: watch_args,
+            },
+            root.clone(),
+        )
+        .expect_err("invalid --on-change flags should fail");
+        match err {
+            RunnerError::TaskInvocation(message) => {
+                assert!(message.contains(expected), "{args:?}: {message}");
+            }
+            other => panic!("unexpected error for {args:?}: {other}"),
+        }
+    }
+}
 
 #[test]
-fn run_manifest_task_run_array_rejects_duplicate_step_ids() {
-    let root = temp_workspace("run-array-duplicate-step-ids");
+fn run_manifest_task_builtin_watch_tui_validates_flags() {
+    let root = temp_workspace("builtin-watch-tui-validate");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "lint", run = "printf lint-ok" },
-  { id = "lint", run = "printf lint-again" }
-]
-"#,
+        "[tasks.serve]\nrun = \"printf served\"\n",
     );
-
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
-        },
-        root,
-    )
-    .expect_err("expected duplicate id error");
-
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("duplicate step id `lint`"));
+    let cases = [
+        (
+            vec!["--tui", "--once", "serve"],
+            "cannot be combined with `--json`, `--once` or `--max-runs`",
+        ),
+        (
+            vec!["--tui", "--restart", "serve"],
+            "does not support `--restart`",
+        ),
+        (
+            vec!["--tui", "--map", "src/**=serve"],
+            "not supported for watch groups",
+        ),
+    ];
+    for (args, expected) in cases {
+        let mut watch_args = vec!["--owner".to_owned(), "effigy".to_owned()];
+        watch_args.extend(args.iter().map(|arg| (*arg).to_owned()));
+        let err = run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "watch".to_owned(),
+                args: watch_args,
+            },
+            root.clone(),
+        )
+        .expect_err("invalid --tui flags should fail");
+        match err {
+            RunnerError::TaskInvocation(message) => {
+                assert!(message.contains(expected), "{args:?}: {message}");
+            }
+            other => panic!("unexpected error for {args:?}: {other}"),
         }
-        other => panic!("unexpected error: {other}"),
     }
 }
 
+#[cfg(unix)]
 #[test]
-fn run_manifest_task_run_array_rejects_dependency_cycles() {
-    let root = temp_workspace("run-array-dependency-cycles");
+fn run_manifest_task_builtin_watch_on_change_cancel_replaces_running_run() {
+    let root = temp_workspace("builtin-watch-on-change-cancel");
+    let src = root.join("src");
+    fs::create_dir_all(&src).expect("mkdir src");
+    let log = root.join("runs.log");
+    // Runs block until `src/stop` exists, so only a cancel can replace them.
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "lint", run = "printf lint-ok", depends_on = ["build"] },
-  { id = "build", run = "printf build-ok", depends_on = ["lint"] }
-]
-"#,
-    );
+        "[tasks.check]\nrun = \"echo start >> runs.log; if [ -f src/stop ]; then exit 0; fi; sleep 30\"\n",
+    );
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer_stop = stop.clone();
+    let writer_src = src.clone();
+    let writer_log = log.clone();
+    let writer = thread::spawn(move || {
+        let mut edited_for = 0usize;
+        while !writer_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(100));
+            let starts = fs::read_to_string(&writer_log)
+                .map(|log| log.lines().count())
+                .unwrap_or(0);
+            if starts == edited_for {
+                continue;
+            }
+            edited_for = starts;
+            let file = if starts == 1 { "app.ts" } else { "stop" };
+            let _ = fs::write(writer_src.join(file), "edit");
+        }
+    });
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--debounce-ms".to_owned(),
+                "100".to_owned(),
+                "--include".to_owned(),
+                "src/**".to_owned(),
+                "--on-change".to_owned(),
+                "cancel".to_owned(),
+                "--max-runs".to_owned(),
+                "3".to_owned(),
+                "--json".to_owned(),
+                "check".to_owned(),
+            ],
         },
         root,
-    )
-    .expect_err("expected cycle error");
-
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("contains dependency cycle"));
-            assert!(
-                message.contains("build -> lint -> build")
-                    || message.contains("lint -> build -> lint")
-            );
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    );
+    stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    writer.join().expect("join writer");
+    let parsed: serde_json::Value =
+        serde_json::from_str(&out.expect("cancelled runs should be replaced")).expect("parse json");
+    assert_eq!(parsed["runs"], 3);
+    assert_eq!(parsed["cancelled"], 2);
+    assert_eq!(
+        parsed["changes"][1]["files"],
+        serde_json::json!(["src/app.ts"])
+    );
+    // The cancelled run's files carry over into the run that replaces it.
+    assert_eq!(
+        parsed["changes"][2]["files"],
+        serde_json::json!(["src/app.ts", "src/stop"])
+    );
 }
 
 #[test]
-fn run_manifest_task_run_array_rejects_self_dependency_cycle() {
-    let root = temp_workspace("run-array-self-dependency-cycle");
+fn run_manifest_task_builtin_watch_rejects_concurrent_watch_owner_for_same_target() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("builtin-watch-lock-conflict");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "lint", run = "printf lint-ok", depends_on = ["lint"] }
-]
-"#,
+        "[tasks.build]\nrun = \"sleep 2\"\n",
     );
 
+    let root_for_thread = root.clone();
+    let join = thread::spawn(move || {
+        run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "watch".to_owned(),
+                args: vec![
+                    "--owner".to_owned(),
+                    "effigy".to_owned(),
+                    "--once".to_owned(),
+                    "build".to_owned(),
+                ],
+            },
+            root_for_thread,
+        )
+    });
+
+    let watch_lock = root.join(".effigy/locks/task-watch-build.lock");
+    let started = Instant::now();
+    while !watch_lock.exists() {
+        assert!(
+            started.elapsed() < Duration::from_secs(2),
+            "watch lock was not created in time"
+        );
+        thread::sleep(Duration::from_millis(20));
+    }
+
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--once".to_owned(),
+                "build".to_owned(),
+            ],
         },
-        root,
+        root.clone(),
     )
-    .expect_err("expected self cycle error");
+    .expect_err("second watch owner should conflict on watch scope lock");
 
     match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("cannot depend on itself"));
+        RunnerError::TaskLockConflict {
+            scope, remediation, ..
+        } => {
+            assert_eq!(scope, "task:watch:build");
+            assert!(remediation.contains("effigy unlock task:watch:build"));
         }
         other => panic!("unexpected error: {other}"),
     }
+
+    let first = join.join().expect("thread join");
+    first.expect("first watch should complete");
 }
 
 #[test]
-fn run_manifest_task_run_array_executes_ready_steps_in_parallel() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("run-array-parallel-ready-steps");
-    let marker = root.join("parallel-ready.log");
-    let _env = EnvGuard::set_many(&[("EFFIGY_DAG_MAX_PARALLEL", Some("2".to_owned()))]);
+fn run_manifest_task_builtin_watch_group_routes_changes_to_mapped_tasks() {
+    let root = temp_workspace("builtin-watch-group");
+    let src = root.join("src");
+    fs::create_dir_all(&src).expect("mkdir src");
+    fs::create_dir_all(root.join("schema")).expect("mkdir schema");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[tasks.validate]
-run = [
-  {{ id = "seed", run = "printf seed > \"{}\"" }},
-  {{ id = "a", run = "sh -lc 'sleep 0.7; printf a >> \"{}\"'", depends_on = ["seed"] }},
-  {{ id = "b", run = "sh -lc 'sleep 0.7; printf b >> \"{}\"'", depends_on = ["seed"] }}
-]
-"#,
-            marker.display(),
-            marker.display(),
-            marker.display()
-        ),
-    );
+        "[watch]\ndebounce_ms = 100\nmap = { typecheck = [\"src/**/*.ts\"], codegen = [\"schema/*.graphql\"] }\n\n[tasks.typecheck]\nrun = \"echo typecheck >> runs.log\"\n\n[tasks.codegen]\nrun = \"echo codegen >> runs.log\"\n",
+    );
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer_stop = stop.clone();
+    let writer_src = src.clone();
+    let writer = thread::spawn(move || {
+        let mut tick = 0usize;
+        while !writer_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(150));
+            tick += 1;
+            let _ = fs::write(writer_src.join("app.ts"), format!("tick {tick}"));
+        }
+    });
 
-    let start = Instant::now();
-    run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--poll".to_owned(),
+                "--max-runs".to_owned(),
+                "3".to_owned(),
+                "--json".to_owned(),
+            ],
         },
         root.clone(),
-    )
-    .expect("run");
-    let elapsed = start.elapsed();
-
-    let body = fs::read_to_string(marker).expect("read marker");
-    assert!(body.contains('a'));
-    assert!(body.contains('b'));
-    assert!(
-        elapsed < Duration::from_millis(1200),
-        "expected parallel schedule, elapsed={elapsed:?}"
+    );
+    stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    writer.join().expect("join writer");
+    let parsed: serde_json::Value =
+        serde_json::from_str(&out.expect("watch group should rerun after edits"))
+            .expect("parse json");
+    assert_eq!(parsed["runs"], 3);
+    assert_eq!(
+        parsed["targets"],
+        serde_json::json!(["typecheck", "codegen"])
+    );
+    assert_eq!(parsed["changes"][0]["target"], "typecheck");
+    assert_eq!(parsed["changes"][1]["target"], "codegen");
+    assert_eq!(parsed["changes"][2]["target"], "typecheck");
+    assert_eq!(
+        parsed["changes"][2]["files"],
+        serde_json::json!(["src/app.ts"])
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("runs.log")).expect("read runs log"),
+        "typecheck\ncodegen\ntypecheck\n"
     );
 }
 
+#[cfg(unix)]
 #[test]
-fn run_manifest_task_run_array_honors_parallel_cap() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("run-array-parallel-cap");
-    let marker = root.join("parallel-cap.log");
-    let _env = EnvGuard::set_many(&[("EFFIGY_DAG_MAX_PARALLEL", Some("1".to_owned()))]);
+fn run_manifest_task_builtin_watch_group_forgets_each_cancelable_target_it_stops() {
+    let root = temp_workspace("builtin-watch-group-supervision");
+    let src = root.join("src");
+    fs::create_dir_all(&src).expect("mkdir src");
+    fs::create_dir_all(root.join("schema")).expect("mkdir schema");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[tasks.validate]
-run = [
-  {{ id = "seed", run = "printf seed > \"{}\"" }},
-  {{ id = "a", run = "sh -lc 'sleep 0.7; printf a >> \"{}\"'", depends_on = ["seed"] }},
-  {{ id = "b", run = "sh -lc 'sleep 0.7; printf b >> \"{}\"'", depends_on = ["seed"] }}
-]
-"#,
-            marker.display(),
-            marker.display(),
-            marker.display()
-        ),
-    );
+        "[watch]\ndebounce_ms = 100\nmap = { typecheck = [\"src/**/*.ts\"], codegen = [\"schema/*.graphql\"] }\n\n[tasks.typecheck]\nrun = \"echo $$ >> pids.log; sleep 0.3\"\n\n[tasks.codegen]\nrun = \"echo $$ >> pids.log; sleep 0.3\"\n",
+    );
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer_stop = stop.clone();
+    let writer_src = src.clone();
+    let writer = thread::spawn(move || {
+        let mut tick = 0usize;
+        while !writer_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(150));
+            tick += 1;
+            let _ = fs::write(writer_src.join("app.ts"), format!("tick {tick}"));
+        }
+    });
 
-    let start = Instant::now();
-    run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--on-change".to_owned(),
+                "cancel".to_owned(),
+                "--max-runs".to_owned(),
+                "3".to_owned(),
+                "--json".to_owned(),
+            ],
         },
         root.clone(),
-    )
-    .expect("run");
-    let elapsed = start.elapsed();
-
-    let body = fs::read_to_string(marker).expect("read marker");
-    assert!(body.contains('a'));
-    assert!(body.contains('b'));
+    );
+    stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    writer.join().expect("join writer");
+    out.expect("watch group should rerun after edits");
+
+    let pids = fs::read_to_string(root.join("pids.log"))
+        .expect("read pids")
+        .lines()
+        .map(|line| line.trim().parse::<i32>().expect("pid"))
+        .collect::<Vec<i32>>();
+    assert!(pids.len() >= 2, "expected both targets to start: {pids:?}");
+    let supervised = watch_supervised_groups();
     assert!(
-        elapsed >= Duration::from_millis(1200),
-        "expected capped schedule, elapsed={elapsed:?}"
+        pids.iter().all(|pid| !supervised.contains(pid)),
+        "stopped targets are still supervised: {supervised:?}"
     );
 }
 
 #[test]
-fn run_manifest_task_run_array_retries_failing_step() {
-    let root = temp_workspace("run-array-retry-step");
-    let marker = root.join("retry.marker");
-    let out_file = root.join("retry.out");
+fn run_manifest_task_builtin_watch_group_holds_watch_lock_of_each_task() {
+    let root = temp_workspace("builtin-watch-group-lock");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[tasks.validate]
-run = [
-  {{ id = "flaky", run = "sh -lc 'if [ -f \"{}\" ]; then printf ok > \"{}\"; exit 0; else touch \"{}\"; exit 7; fi'", retry = 1, retry_delay_ms = 10 }}
-]
-"#,
-            marker.display(),
-            out_file.display(),
-            marker.display()
-        ),
+        "[tasks.build]\nrun = \"sleep 2\"\n\n[tasks.lint]\nrun = \"true\"\n",
     );
 
-    let out = run_manifest_task_with_cwd(
+    let root_for_thread = root.clone();
+    let join = thread::spawn(move || {
+        run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "watch".to_owned(),
+                args: vec![
+                    "--owner".to_owned(),
+                    "effigy".to_owned(),
+                    "--map".to_owned(),
+                    "src/**=build".to_owned(),
+                    "--map".to_owned(),
+                    "docs/**=lint".to_owned(),
+                    "--once".to_owned(),
+                ],
+            },
+            root_for_thread,
+        )
+    });
+
+    let watch_lock = root.join(".effigy/locks/task-watch-lint.lock");
+    let started = Instant::now();
+    while !watch_lock.exists() {
+        assert!(
+            started.elapsed() < Duration::from_secs(2),
+            "watch group lock was not created in time"
+        );
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "watch".to_owned(),
+            args: vec![
+                "--owner".to_owned(),
+                "effigy".to_owned(),
+                "--once".to_owned(),
+                "lint".to_owned(),
+            ],
+        },
+        root.clone(),
+    )
+    .expect_err("single-target watch should conflict with the group");
+
+    match err {
+        RunnerError::TaskLockConflict { scope, .. } => {
+            assert_eq!(scope, "task:watch:lint");
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+
+    let first = join.join().expect("thread join");
+    let out = first.expect("watch group should complete");
+    assert!(out.contains("watch complete after 2 run(s)."));
+}
+
+#[test]
+fn run_manifest_task_builtin_watch_map_validates_flags() {
+    let root = temp_workspace("builtin-watch-map-validate");
+    write_manifest(&root.join("effigy.toml"), "[tasks.build]\nrun = \"true\"\n");
+    let cases = [
+        (vec!["--map", "src/**"], "expected `<glob>=<task>`"),
+        (
+            vec!["--map", "src/**=build", "build"],
+            "cannot be combined with a target task",
+        ),
+        (
+            vec!["--map", "src/**=build", "--restart"],
+            "not supported for watch groups",
+        ),
+        (
+            vec!["--map", "src/**=build", "--include", "docs/**"],
+            "`--include` is not supported for watch groups",
+        ),
+    ];
+    for (args, expected) in cases {
+        let mut watch_args = vec!["--owner".to_owned(), "effigy".to_owned()];
+        watch_args.extend(args.iter().map(|arg| (*arg).to_owned()));
+        let err = run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "watch".to_owned(),
+                args: watch_args,
+            },
+            root.clone(),
+        )
+        .expect_err("invalid watch group flags should fail");
+        match err {
+            RunnerError::TaskInvocation(message) => {
+                assert!(message.contains(expected), "{args:?}: {message}");
+            }
+            other => panic!("unexpected error for {args:?}: {other}"),
+        }
+    }
+}
+
+#[test]
+fn run_manifest_task_builtin_init_help_renders_topic() {
+    let root = temp_workspace("builtin-init-help");
+    write_manifest(&root.join("effigy.toml"), "");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "init".to_owned(),
+            args: vec!["--help".to_owned()],
         },
         root,
     )
-    .expect("retry should recover");
-    assert_eq!(out, "");
-    let body = fs::read_to_string(out_file).expect("read retry output");
-    assert_eq!(body, "ok");
+    .expect("run init --help");
+    assert!(out.contains("init Help"));
+    assert!(out.contains("effigy init [--dry-run] [--force] [--json]"));
 }
 
 #[test]
-fn run_manifest_task_run_array_enforces_timeout_ms() {
-    let root = temp_workspace("run-array-timeout-step");
+fn run_manifest_task_builtin_migrate_help_json_uses_help_schema() {
+    let root = temp_workspace("builtin-migrate-help-json");
+    write_manifest(&root.join("effigy.toml"), "");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "migrate".to_owned(),
+            args: vec!["--help".to_owned(), "--json".to_owned()],
+        },
+        root,
+    )
+    .expect("run migrate --help --json");
+    assert!(out.contains("\"schema\": \"effigy.help.v1\""));
+    assert!(out.contains("\"topic\": \"migrate\""));
+}
+
+#[test]
+fn run_manifest_task_verbose_root_includes_resolution_trace() {
+    let _guard = test_lock().lock().expect("lock");
+    let _env = EnvGuard::set_many(&[("EFFIGY_COLOR", None), ("NO_COLOR", None)]);
+    let root = temp_workspace("verbose-trace");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.validate]
-run = [
-  { id = "slow", run = "sleep 1", timeout_ms = 100 }
-]
-"#,
+        "[tasks.ping]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[tasks.ping]\nrun = \"printf farmyard\"\n",
     );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
-            args: Vec::new(),
+            name: "farmyard/ping".to_owned(),
+            args: vec!["--verbose-root".to_owned()],
         },
         root,
     )
-    .expect_err("timeout should fail");
+    .expect("run");
 
-    match err {
-        RunnerError::TaskCommandFailure { code, .. } => {
-            assert_eq!(code, Some(124));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("Task Resolution"));
+    assert!(out.contains("catalog-alias: farmyard"));
+    assert!(out.contains("farmyard"));
 }
 
 #[test]
-fn run_manifest_task_run_array_fail_fast_false_allows_other_ready_steps() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("run-array-fail-fast-false");
-    let marker = root.join("fail-fast-false.out");
+fn run_manifest_task_includes_local_node_modules_bin_in_path() {
+    let root = temp_workspace("local-node-bin-path");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[tasks.validate]
-run = [
-  {{ id = "seed", run = "printf seed > \"{}\"" }},
-  {{ id = "bad", run = "sh -lc 'sleep 0.1; exit 9'", depends_on = ["seed"], fail_fast = false }},
-  {{ id = "good", run = "printf good >> \"{}\"", depends_on = ["seed"] }}
-]
-"#,
-            marker.display(),
-            marker.display()
-        ),
+        "[tasks.local]\nrun = \"local-tool\"\n",
     );
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let tool = local_bin.join("local-tool");
+    fs::write(&tool, "#!/bin/sh\nexit 0\n").expect("write local tool");
+    let mut perms = fs::metadata(&tool).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&tool, perms).expect("chmod");
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "validate".to_owned(),
+            name: "local".to_owned(),
             args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect_err("overall command should fail");
+    .expect("run local tool");
 
-    match err {
-        RunnerError::TaskCommandFailure { .. } => {}
-        other => panic!("unexpected error: {other}"),
-    }
-    let body = fs::read_to_string(marker).expect("read marker");
-    assert!(body.contains("good"));
+    assert_eq!(out, "");
 }
 
 #[test]
-fn run_manifest_task_run_array_task_reference_supports_inline_args() {
-    let root = temp_workspace("run-array-task-ref-inline-args");
-    let marker = root.join("task-ref-inline-args.log");
+fn run_manifest_task_run_array_supports_task_reference_steps() {
+    let root = temp_workspace("run-array-task-refs");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[tasks.capture]
-run = "sh -lc 'printf %s \"$1\" > \"{}\"' sh {{args}}"
+        r#"[tasks.lint]
+run = "printf lint-ok"
 
 [tasks.validate]
-run = [{{ task = "capture hello-world" }}]
+run = [{ task = "lint" }, "printf validate-ok"]
 "#,
-            marker.display()
-        ),
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "validate".to_owned(),
-            args: Vec::new(),
+            args: vec!["--verbose-root".to_owned()],
         },
         root,
     )
     .expect("run");
 
-    assert_eq!(out, "");
-    let body = fs::read_to_string(&marker).expect("read marker");
-    assert_eq!(body, "hello-world");
+    assert!(out.contains("printf lint-ok"));
+    assert!(out.contains("printf validate-ok"));
 }
 
 #[test]
-fn run_manifest_task_run_array_task_reference_supports_quoted_inline_args() {
-    let root = temp_workspace("run-array-task-ref-quoted-inline-args");
-    let marker = root.join("task-ref-quoted-inline-args.log");
+fn run_manifest_task_run_array_accepts_dag_metadata() {
+    let root = temp_workspace("run-array-dag-metadata");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[tasks.capture]
-run = "sh -lc 'printf \"%s|%s\" \"$1\" \"$2\" > \"{}\"' sh {{args}}"
-
-[tasks.validate]
-run = [{{ task = 'capture alpha "two words"' }}]
+        r#"[tasks.validate]
+run = [
+  { id = "lint", run = "printf lint-ok" },
+  { id = "build", run = "printf build-ok", depends_on = ["lint"] },
+  { run = "printf validate-ok" }
+]
 "#,
-            marker.display()
-        ),
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "validate".to_owned(),
-            args: Vec::new(),
+            args: vec!["--verbose-root".to_owned()],
         },
         root,
     )
     .expect("run");
 
-    assert_eq!(out, "");
-    let body = fs::read_to_string(&marker).expect("read marker");
-    assert_eq!(body, "alpha|two words");
+    assert!(out.contains("printf lint-ok"));
+    assert!(out.contains("printf build-ok"));
+    assert!(out.contains("printf validate-ok"));
 }
 
 #[test]
-fn run_manifest_task_run_array_task_reference_rejects_unterminated_quoted_args() {
-    let root = temp_workspace("run-array-task-ref-unterminated-quote");
+fn run_manifest_task_run_array_rejects_depends_on_without_id() {
+    let root = temp_workspace("run-array-depends-on-without-id");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.validate]
-run = [{ task = 'test "unterminated' }]
+run = [
+  { id = "lint", run = "printf lint-ok" },
+  { run = "printf build-ok", depends_on = ["lint"] }
+]
 "#,
     );
 
@@ -1352,24 +1711,25 @@ run = [{ task = 'test "unterminated' }]
         },
         root,
     )
-    .expect_err("expected unterminated quote error");
+    .expect_err("expected depends_on without id error");
 
     match err {
         RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("run step task ref"));
-            assert!(message.contains("unterminated quote"));
+            assert!(message.contains("defines `depends_on` but is missing a non-empty `id`"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_manifest_task_run_array_task_reference_rejects_trailing_escape() {
-    let root = temp_workspace("run-array-task-ref-trailing-escape");
+fn run_manifest_task_run_array_rejects_missing_dependency_step() {
+    let root = temp_workspace("run-array-missing-dependency-step");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.validate]
-run = [{ task = "test vitest \\" }]
+run = [
+  { id = "build", run = "printf build-ok", depends_on = ["lint"] }
+]
 "#,
     );
 
@@ -1380,451 +1740,818 @@ run = [{ task = "test vitest \\" }]
         },
         root,
     )
-    .expect_err("expected trailing escape error");
+    .expect_err("expected missing dependency error");
 
     match err {
         RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("run step task ref"));
-            assert!(message.contains("trailing escape"));
+            assert!(message.contains("depends on missing step `lint`"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_manifest_task_run_array_supports_builtin_test_task_reference_steps() {
-    let root = temp_workspace("run-array-builtin-test-task-ref");
-    let marker = root.join("builtin-test-called.log");
+fn run_manifest_task_run_array_rejects_duplicate_step_ids() {
+    let root = temp_workspace("run-array-duplicate-step-ids");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[test.suites]
-unit = "sh -lc 'printf called > \"{}\"'"
-
-[tasks.validate]
-run = [{{ task = "test" }}, "printf validate-ok"]
-"#,
-            marker.display()
-        ),
+        r#"[tasks.validate]
+run = [
+  { id = "lint", run = "printf lint-ok" },
+  { id = "lint", run = "printf lint-again" }
+]
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "validate".to_owned(),
-            args: vec!["--verbose-root".to_owned()],
+            args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect("run");
+    .expect_err("expected duplicate id error");
 
-    assert!(out.contains("validate-ok"));
-    assert!(marker.exists(), "built-in test task ref should execute");
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("duplicate step id `lint`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_run_array_supports_builtin_test_task_reference_with_inline_suite_arg() {
-    let root = temp_workspace("run-array-builtin-test-task-ref-inline-suite");
-    let marker = root.join("builtin-test-called.log");
+fn run_manifest_task_run_array_rejects_dependency_cycles() {
+    let root = temp_workspace("run-array-dependency-cycles");
     write_manifest(
         &root.join("effigy.toml"),
-        &format!(
-            r#"[test.suites]
-vitest = "sh -lc 'printf called > \"{}\"'"
-
-[tasks.validate]
-run = [{{ task = "test vitest" }}, "printf validate-ok"]
+        r#"[tasks.validate]
+run = [
+  { id = "lint", run = "printf lint-ok", depends_on = ["build"] },
+  { id = "build", run = "printf build-ok", depends_on = ["lint"] }
+]
 "#,
-            marker.display()
-        ),
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "validate".to_owned(),
-            args: vec!["--verbose-root".to_owned()],
+            args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect("run");
+    .expect_err("expected cycle error");
 
-    assert!(out.contains("validate-ok"));
-    assert!(
-        marker.exists(),
-        "built-in test task ref with suite arg should execute"
-    );
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("contains dependency cycle"));
+            assert!(
+                message.contains("build -> lint -> build")
+                    || message.contains("lint -> build -> lint")
+            );
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_run_array_supports_prefixed_builtin_test_task_reference_steps() {
-    let root = temp_workspace("run-array-prefixed-builtin-test-task-ref");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    let marker = farmyard.join("builtin-test-called.log");
+fn run_manifest_task_run_array_rejects_self_dependency_cycle() {
+    let root = temp_workspace("run-array-self-dependency-cycle");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.validate]
-run = [{ task = "farmyard/test" }, "printf validate-ok"]
+run = [
+  { id = "lint", run = "printf lint-ok", depends_on = ["lint"] }
+]
 "#,
     );
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect_err("expected self cycle error");
+
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("cannot depend on itself"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_run_array_executes_ready_steps_in_parallel() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("run-array-parallel-ready-steps");
+    let marker = root.join("parallel-ready.log");
+    let _env = EnvGuard::set_many(&[("EFFIGY_DAG_MAX_PARALLEL", Some("2".to_owned()))]);
     write_manifest(
-        &farmyard.join("effigy.toml"),
+        &root.join("effigy.toml"),
         &format!(
-            r#"[catalog]
-alias = "farmyard"
-[test.suites]
-unit = "sh -lc 'printf called > \"{}\"'"
+            r#"[tasks.validate]
+run = [
+  {{ id = "seed", run = "printf seed > \"{}\"" }},
+  {{ id = "a", run = "sh -lc 'sleep 0.7; printf a >> \"{}\"'", depends_on = ["seed"] }},
+  {{ id = "b", run = "sh -lc 'sleep 0.7; printf b >> \"{}\"'", depends_on = ["seed"] }}
+]
 "#,
+            marker.display(),
+            marker.display(),
             marker.display()
         ),
     );
 
-    let out = run_manifest_task_with_cwd(
+    let start = Instant::now();
+    run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "validate".to_owned(),
-            args: vec!["--verbose-root".to_owned()],
+            args: Vec::new(),
         },
         root.clone(),
     )
     .expect("run");
+    let elapsed = start.elapsed();
 
-    assert!(out.contains("validate-ok"));
+    let body = fs::read_to_string(marker).expect("read marker");
+    assert!(body.contains('a'));
+    assert!(body.contains('b'));
     assert!(
-        marker.exists(),
-        "prefixed built-in test task ref should execute"
+        elapsed < Duration::from_millis(1200),
+        "expected parallel schedule, elapsed={elapsed:?}"
     );
 }
 
 #[test]
-fn run_tasks_lists_catalogs_and_tasks() {
-    let root = temp_workspace("list-tasks");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir");
+fn run_manifest_task_run_array_honors_parallel_cap() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("run-array-parallel-cap");
+    let marker = root.join("parallel-cap.log");
+    let _env = EnvGuard::set_many(&[("EFFIGY_DAG_MAX_PARALLEL", Some("1".to_owned()))]);
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.dev]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
+        &format!(
+            r#"[tasks.validate]
+run = [
+  {{ id = "seed", run = "printf seed > \"{}\"" }},
+  {{ id = "a", run = "sh -lc 'sleep 0.7; printf a >> \"{}\"'", depends_on = ["seed"] }},
+  {{ id = "b", run = "sh -lc 'sleep 0.7; printf b >> \"{}\"'", depends_on = ["seed"] }}
+]
+"#,
+            marker.display(),
+            marker.display(),
+            marker.display()
+        ),
     );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: None,
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
+    let start = Instant::now();
+    run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root.clone(),
+    )
+    .expect("run");
+    let elapsed = start.elapsed();
 
-    assert!(out.contains("root"));
-    assert!(out.contains("farmyard"));
-    assert!(out.contains("reset-db"));
+    let body = fs::read_to_string(marker).expect("read marker");
+    assert!(body.contains('a'));
+    assert!(body.contains('b'));
+    assert!(
+        elapsed >= Duration::from_millis(1200),
+        "expected capped schedule, elapsed={elapsed:?}"
+    );
 }
 
 #[test]
-fn run_tasks_supports_compact_task_definitions() {
-    let root = temp_workspace("compact-tasks");
+fn run_manifest_task_run_array_retries_failing_step() {
+    let root = temp_workspace("run-array-retry-step");
+    let marker = root.join("retry.marker");
+    let out_file = root.join("retry.out");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks]
-api = "printf api"
-jobs = "printf jobs"
+        &format!(
+            r#"[tasks.validate]
+run = [
+  {{ id = "flaky", run = "sh -lc 'if [ -f \"{}\" ]; then printf ok > \"{}\"; exit 0; else touch \"{}\"; exit 7; fi'", retry = 1, retry_delay_ms = 10 }}
+]
 "#,
+            marker.display(),
+            out_file.display(),
+            marker.display()
+        ),
     );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: None,
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
-
-    assert!(out.contains("api"));
-    assert!(out.contains("jobs"));
-    assert!(out.contains("printf api"));
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect("retry should recover");
+    assert_eq!(out, "");
+    let body = fs::read_to_string(out_file).expect("read retry output");
+    assert_eq!(body, "ok");
 }
 
 #[test]
-fn run_tasks_supports_mixed_compact_and_table_task_definitions() {
-    let root = temp_workspace("mixed-compact-and-table");
+fn run_manifest_task_run_array_enforces_timeout_ms() {
+    let root = temp_workspace("run-array-timeout-step");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks]
-api = "printf api"
-
-[tasks.dev]
-run = "printf dev"
+        r#"[tasks.validate]
+run = [
+  { id = "slow", run = "sleep 1", timeout_ms = 100 }
+]
 "#,
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "api".to_owned(),
+            name: "validate".to_owned(),
             args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect("run compact task");
-    assert_eq!(out, "");
+    .expect_err("timeout should fail");
 
-    let tasks = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: None,
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
-    assert!(tasks.contains("api"));
-    assert!(tasks.contains("dev"));
+    match err {
+        RunnerError::TaskCommandFailure { code, .. } => {
+            assert_eq!(code, Some(124));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_tasks_supports_compact_sequence_task_definitions() {
-    let root = temp_workspace("compact-sequence-tasks");
+fn run_manifest_task_run_array_fail_fast_false_allows_other_ready_steps() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("run-array-fail-fast-false");
+    let marker = root.join("fail-fast-false.out");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks]
-drop-db = "printf drop-db"
-migrate-db = "printf migrate-db"
-reset-db = [{ task = "drop-db" }, { task = "migrate-db" }]
+        &format!(
+            r#"[tasks.validate]
+run = [
+  {{ id = "seed", run = "printf seed > \"{}\"" }},
+  {{ id = "bad", run = "sh -lc 'sleep 0.1; exit 9'", depends_on = ["seed"], fail_fast = false }},
+  {{ id = "good", run = "printf good >> \"{}\"", depends_on = ["seed"] }}
+]
 "#,
+            marker.display(),
+            marker.display()
+        ),
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "reset-db".to_owned(),
+            name: "validate".to_owned(),
             args: Vec::new(),
         },
         root.clone(),
     )
-    .expect("run compact sequence task");
-    assert_eq!(out, "");
+    .expect_err("overall command should fail");
 
-    let tasks = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: Some("reset-db".to_owned()),
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
-    assert!(tasks.contains("reset-db"));
-    assert!(tasks.contains("<sequence:2>"));
+    match err {
+        RunnerError::TaskCommandFailure { .. } => {}
+        other => panic!("unexpected error: {other}"),
+    }
+    let body = fs::read_to_string(marker).expect("read marker");
+    assert!(body.contains("good"));
 }
 
 #[test]
-fn run_tasks_with_task_filter_reports_only_matches() {
-    let root = temp_workspace("task-filter");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir");
+fn run_manifest_task_run_array_task_reference_supports_inline_args() {
+    let root = temp_workspace("run-array-task-ref-inline-args");
+    let marker = root.join("task-ref-inline-args.log");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.dev]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
+        &format!(
+            r#"[tasks.capture]
+run = "sh -lc 'printf %s \"$1\" > \"{}\"' sh {{args}}"
+
+[tasks.validate]
+run = [{{ task = "capture hello-world" }}]
+"#,
+            marker.display()
+        ),
     );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: Some("reset-db".to_owned()),
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect("run");
 
-    assert!(out.contains("Task Matches: reset-db"));
-    assert!(out.contains("farmyard"));
-    assert!(out.contains("reset-db"));
-    assert!(!out.contains("root      │ reset-db"));
+    assert_eq!(out, "");
+    let body = fs::read_to_string(&marker).expect("read marker");
+    assert_eq!(body, "hello-world");
 }
 
 #[test]
-fn run_tasks_with_test_filter_shows_catalog_fallback_note() {
-    let root = temp_workspace("task-filter-test-note");
-
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: Some("test".to_owned()),
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
-
-    assert!(out.contains("Task Matches: test"));
-    assert!(out.contains("Built-in Task Matches"));
-    assert!(out.contains("built-in fallback supports `<catalog>/test`"));
-}
+fn run_manifest_task_run_array_task_reference_supports_quoted_inline_args() {
+    let root = temp_workspace("run-array-task-ref-quoted-inline-args");
+    let marker = root.join("task-ref-quoted-inline-args.log");
+    write_manifest(
+        &root.join("effigy.toml"),
+        &format!(
+            r#"[tasks.capture]
+run = "sh -lc 'printf \"%s|%s\" \"$1\" \"$2\" > \"{}\"' sh {{args}}"
 
-#[test]
-fn run_tasks_without_catalogs_still_lists_builtin_tasks() {
-    let root = temp_workspace("builtins-only");
+[tasks.validate]
+run = [{{ task = 'capture alpha "two words"' }}]
+"#,
+            marker.display()
+        ),
+    );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: None,
-            resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect("run");
 
-    assert!(out.contains("Tasks"));
-    assert!(out.contains("help"));
-    assert!(out.contains("doctor"));
-    assert!(out.contains("test"));
-    assert!(out.contains("<catalog>/test fallback"));
-    assert!(out.contains("tasks"));
+    assert_eq!(out, "");
+    let body = fs::read_to_string(&marker).expect("read marker");
+    assert_eq!(body, "alpha|two words");
 }
 
 #[test]
-fn run_tasks_json_renders_machine_readable_payload() {
-    let root = temp_workspace("tasks-json");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir");
+fn run_manifest_task_run_array_task_reference_rejects_unterminated_quoted_args() {
+    let root = temp_workspace("run-array-task-ref-unterminated-quote");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.dev]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
+        r#"[tasks.validate]
+run = [{ task = 'test "unterminated' }]
+"#,
     );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: None,
-            resolve_selector: None,
-            output_json: true,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks json");
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect_err("expected unterminated quote error");
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    assert_eq!(parsed["catalog_count"], 2);
-    assert!(parsed["catalog_tasks"].is_array());
-    assert!(parsed["managed_profiles"].is_array());
-    assert!(parsed["builtin_tasks"].is_array());
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("run step task ref"));
+            assert!(message.contains("unterminated quote"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_tasks_json_filter_includes_builtin_matches_and_notes() {
-    let root = temp_workspace("tasks-json-filter");
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: Some("test".to_owned()),
-            resolve_selector: None,
-            output_json: true,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks json filter");
+fn run_manifest_task_run_array_task_reference_rejects_trailing_escape() {
+    let root = temp_workspace("run-array-task-ref-trailing-escape");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.validate]
+run = [{ task = "test vitest \\" }]
+"#,
+    );
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    assert_eq!(parsed["filter"], "test");
-    assert!(parsed["builtin_matches"].is_array());
-    assert!(parsed["managed_profile_matches"].is_array());
-    assert!(parsed["notes"]
-        .as_array()
-        .is_some_and(|items| !items.is_empty()));
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect_err("expected trailing escape error");
+
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("run step task ref"));
+            assert!(message.contains("trailing escape"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_tasks_lists_managed_profiles_for_tui_tasks() {
-    let root = temp_workspace("tasks-managed-profiles-list");
+fn run_manifest_task_run_array_supports_builtin_test_task_reference_steps() {
+    let root = temp_workspace("run-array-builtin-test-task-ref");
+    let marker = root.join("builtin-test-called.log");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ run = "printf api" }]
+        &format!(
+            r#"[test.suites]
+unit = "sh -lc 'printf called > \"{}\"'"
 
-[tasks.dev.profiles.admin]
-concurrent = [{ run = "printf api" }]
+[tasks.validate]
+run = [{{ task = "test" }}, "printf validate-ok"]
 "#,
+            marker.display()
+        ),
     );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: vec!["--verbose-root".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("run");
+
+    assert!(out.contains("validate-ok"));
+    assert!(marker.exists(), "built-in test task ref should execute");
+}
+
+#[test]
+fn run_manifest_task_run_array_supports_builtin_test_task_reference_with_inline_suite_arg() {
+    let root = temp_workspace("run-array-builtin-test-task-ref-inline-suite");
+    let marker = root.join("builtin-test-called.log");
+    write_manifest(
+        &root.join("effigy.toml"),
+        &format!(
+            r#"[test.suites]
+vitest = "sh -lc 'printf called > \"{}\"'"
+
+[tasks.validate]
+run = [{{ task = "test vitest" }}, "printf validate-ok"]
+"#,
+            marker.display()
+        ),
+    );
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: vec!["--verbose-root".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("run");
+
+    assert!(out.contains("validate-ok"));
+    assert!(
+        marker.exists(),
+        "built-in test task ref with suite arg should execute"
+    );
+}
+
+#[test]
+fn run_manifest_task_run_array_supports_prefixed_builtin_test_task_reference_steps() {
+    let root = temp_workspace("run-array-prefixed-builtin-test-task-ref");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    let marker = farmyard.join("builtin-test-called.log");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.validate]
+run = [{ task = "farmyard/test" }, "printf validate-ok"]
+"#,
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        &format!(
+            r#"[catalog]
+alias = "farmyard"
+[test.suites]
+unit = "sh -lc 'printf called > \"{}\"'"
+"#,
+            marker.display()
+        ),
+    );
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "validate".to_owned(),
+            args: vec!["--verbose-root".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("run");
+
+    assert!(out.contains("validate-ok"));
+    assert!(
+        marker.exists(),
+        "prefixed built-in test task ref should execute"
+    );
+}
+
+#[test]
+fn ignore_rules_follow_gitignore_semantics_across_ignore_files() {
+    let root = temp_workspace("ignore-rules");
+    fs::create_dir_all(root.join("packages/web")).expect("mkdir");
+    fs::write(
+        root.join(".gitignore"),
+        "# build output\ndist/\n/coverage\n*.log\n.turbo\nvendor/\n",
+    )
+    .expect("write gitignore");
+    fs::write(root.join(".ignore"), "tmp-*\n").expect("write ignore");
+    fs::write(
+        root.join(".effigyignore"),
+        "!keep.log\nfixtures/generated/\n",
+    )
+    .expect("write effigyignore");
+    fs::write(root.join("packages/web/.gitignore"), "/out\n!debug.log\n")
+        .expect("write nested gitignore");
+    let rules = IgnoreRules::new(&root, true);
+
+    assert!(rules.is_ignored("dist", true));
+    assert!(rules.is_ignored("packages/web/dist/app.js", false));
+    assert!(
+        !rules.is_ignored("dist", false),
+        "`dist/` only matches directories"
+    );
+    assert!(rules.is_ignored("coverage/lcov.info", false));
+    assert!(
+        !rules.is_ignored("packages/web/coverage", true),
+        "`/coverage` is anchored"
+    );
+    assert!(rules.is_ignored("server.log", false));
+    assert!(rules.is_ignored("packages/web/.turbo/cache", false));
+    assert!(rules.is_ignored("tmp-scratch", false));
+    assert!(
+        !rules.is_ignored("keep.log", false),
+        ".effigyignore re-includes"
+    );
+    assert!(rules.is_ignored("fixtures/generated/a.json", false));
+    assert!(!rules.is_ignored("fixtures/static/a.json", false));
+    assert!(rules.is_ignored("packages/web/out/index.html", false));
+    assert!(!rules.is_ignored("packages/web/debug.log", false));
+    assert!(!rules.is_ignored("packages/web/src/main.ts", false));
+    assert!(
+        rules.is_ignored("vendor/lib/keep.log", false),
+        "files under an ignored directory cannot be re-included"
+    );
+    assert!(!IgnoreRules::new(&root, false).is_ignored("dist", true));
+}
+
+#[test]
+fn ignore_rules_agree_with_git_check_ignore() {
+    let root = temp_workspace("ignore-rules-git");
+    fs::create_dir_all(root.join("sub")).expect("mkdir");
+    fs::write(
+        root.join(".gitignore"),
+        [
+            "*.py[co]",
+            "\\#notes",
+            "\\!important",
+            "*.log",
+            "!keep.log",
+            "/build",
+            "docs/*.md",
+            "cache/",
+            "**/fixtures",
+            "logs/**",
+            "a/**/b",
+            "vendor/",
+            "!vendor/keep.txt",
+            "pkg/*",
+            "!pkg/keep.txt",
+            "tmp?",
+            "",
+        ]
+        .join("\n"),
+    )
+    .expect("write gitignore");
+    fs::write(root.join("sub/.gitignore"), "!debug.log\n/only-here\n")
+        .expect("write nested gitignore");
+    let rules = IgnoreRules::new(&root, true);
+
+    // Expected values are what `git check-ignore --no-index` reports for the
+    // same files.
+    let cases: &[(&str, bool, bool)] = &[
+        // character classes
+        ("a.pyc", false, true),
+        ("a.pyo", false, true),
+        ("a.pyd", false, false),
+        // escaped `#` and `!`
+        ("#notes", false, true),
+        ("!important", false, true),
+        ("important", false, false),
+        // negation
+        ("server.log", false, true),
+        ("keep.log", false, false),
+        // anchoring
+        ("build/x", false, true),
+        ("sub/build/x", false, false),
+        ("docs/a.md", false, true),
+        ("x/docs/a.md", false, false),
+        ("docs/deep/a.md", false, false),
+        // directory-only rules
+        ("cache", true, true),
+        ("cache", false, false),
+        ("cache/x", false, true),
+        // `**`
+        ("fixtures/x", false, true),
+        ("src/fixtures/y", false, true),
+        ("logs/x", false, true),
+        ("logs/deep/y", false, true),
+        ("a/b", false, true),
+        ("a/x/b", false, true),
+        ("a/x/y/b", false, true),
+        // `?`
+        ("tmp1", false, true),
+        ("tmp12", false, false),
+        // re-including under an excluded parent
+        ("vendor/keep.txt", false, true),
+        ("pkg/x.txt", false, true),
+        ("pkg/keep.txt", false, false),
+        // nested ignore files
+        ("sub/debug.log", false, false),
+        ("sub/other.log", false, true),
+        ("sub/only-here", false, true),
+        ("only-here", false, false),
+    ];
+    for (path, is_dir, expected) in cases {
+        assert_eq!(
+            rules.is_ignored(path, *is_dir),
+            *expected,
+            "{path} (dir: {is_dir})"
+        );
+    }
+}
+
+#[test]
+fn run_tasks_skips_ignored_catalogs_unless_no_ignore() {
+    let root = temp_workspace("tasks-ignored-catalogs");
+    for dir in ["app", "dist/web", "scratch"] {
+        fs::create_dir_all(root.join(dir)).expect("mkdir");
+        write_manifest(
+            &root.join(dir).join("effigy.toml"),
+            "[tasks.build]\nrun = \"printf ok\"\n",
+        );
+    }
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    fs::write(root.join(".gitignore"), "dist/\n").expect("write gitignore");
+    fs::write(root.join(".effigyignore"), "scratch\n").expect("write effigyignore");
+
+    let catalog_roots = |no_ignore: bool| {
+        let out = run_tasks(TasksArgs {
+            repo_override: Some(root.clone()),
             task_name: None,
             resolve_selector: None,
-            output_json: false,
-            pretty_json: true,
+            output_json: true,
+            pretty_json: false,
+            no_ignore,
         })
-    })
-    .expect("run tasks");
+        .expect("run tasks");
+        let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+        let mut roots = parsed["catalogs"]
+            .as_array()
+            .expect("catalogs")
+            .iter()
+            .map(|catalog| {
+                PathBuf::from(catalog["root"].as_str().expect("root"))
+                    .strip_prefix(&root)
+                    .expect("under root")
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<String>>();
+        roots.sort();
+        roots
+    };
 
-    assert!(out.contains("Tasks"));
-    assert!(out.contains("dev admin"));
-    assert!(out.contains("<managed:tui profile:admin>"));
-    assert!(!out.contains("dev default"));
+    assert_eq!(catalog_roots(false), vec!["".to_owned(), "app".to_owned()]);
+    assert_eq!(
+        catalog_roots(true),
+        vec![
+            "".to_owned(),
+            "app".to_owned(),
+            "dist/web".to_owned(),
+            "scratch".to_owned()
+        ]
+    );
 }
 
 #[test]
-fn run_tasks_filter_lists_managed_profiles_for_matching_task() {
-    let root = temp_workspace("tasks-managed-profiles-filter");
+fn run_manifest_task_resolves_ignored_catalogs_only_with_no_ignore() {
+    let root = temp_workspace("task-ignored-catalog");
+    fs::create_dir_all(root.join("generated/tools")).expect("mkdir");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ run = "printf api" }]
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &root.join("generated/tools/effigy.toml"),
+        "[catalog]\nalias = \"tools\"\n[tasks.build]\nrun = \"printf ok\"\n",
+    );
+    fs::write(root.join(".gitignore"), "generated/\n").expect("write gitignore");
 
-[tasks.dev.profiles.front]
-concurrent = [{ run = "printf api" }]
-"#,
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "tools/build".to_owned(),
+            args: Vec::new(),
+        },
+        root.clone(),
+    )
+    .expect_err("ignored catalog should not resolve by default");
+    assert!(
+        matches!(err, RunnerError::TaskCatalogPrefixNotFound { .. }),
+        "{err}"
+    );
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "tools/build".to_owned(),
+            args: vec!["--no-ignore".to_owned()],
+        },
+        root,
+    )
+    .expect("--no-ignore should resolve the ignored catalog");
+    assert_eq!(out, "");
+}
+
+#[test]
+fn run_tasks_lists_catalogs_and_tasks() {
+    let root = temp_workspace("list-tasks");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
     );
 
     let out = with_cwd(&root, || {
         run_tasks(TasksArgs {
             repo_override: None,
-            task_name: Some("dev".to_owned()),
+            task_name: None,
             resolve_selector: None,
             output_json: false,
             pretty_json: true,
+            no_ignore: false,
         })
     })
-    .expect("run tasks --task dev");
+    .expect("run tasks");
 
-    assert!(out.contains("Task Matches: dev"));
-    assert!(out.contains("dev front"));
-    assert!(!out.contains("dev default"));
+    assert!(out.contains("root"));
+    assert!(out.contains("farmyard"));
+    assert!(out.contains("reset-db"));
 }
 
 #[test]
-fn run_tasks_json_lists_managed_profiles_with_invocation_labels() {
-    let root = temp_workspace("tasks-managed-profiles-json-list");
+fn picker_entries_list_recent_tasks_first_with_descriptions() {
+    let root = temp_workspace("picker-entries");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ run = "printf api" }]
+        "[tasks]\nbuild = \"cargo build\"\n\n[tasks.dev]\ndescription = \"Run the dev stack\"\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
+    );
+    let catalogs = discover_catalogs(&root).expect("discover");
 
-[tasks.dev.profiles.admin]
-concurrent = [{ run = "printf api" }]
+    assert!(load_task_history(&root).is_empty());
+    record_task_history(&root, "dev");
+    record_task_history(&root, "farmyard/reset-db");
+    record_task_history(&root, "dev");
+    assert_eq!(
+        load_task_history(&root),
+        vec!["dev".to_owned(), "farmyard/reset-db".to_owned()]
+    );
+
+    let entries = picker_entries(&catalogs, &load_task_history(&root));
+    let order = entries
+        .iter()
+        .map(|entry| (entry.task.as_str(), entry.recent))
+        .collect::<Vec<(&str, bool)>>();
+    assert_eq!(
+        order,
+        vec![("dev", true), ("farmyard/reset-db", true), ("build", false)]
+    );
+    assert_eq!(entries[0].description.as_deref(), Some("Run the dev stack"));
+    assert_eq!(entries[0].run, "printf root");
+    assert_eq!(entries[2].run, "cargo build");
+}
+
+#[test]
+fn run_tasks_supports_compact_task_definitions() {
+    let root = temp_workspace("compact-tasks");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks]
+api = "printf api"
+jobs = "printf jobs"
 "#,
     );
 
@@ -1833,262 +2560,567 @@ concurrent = [{ run = "printf api" }]
             repo_override: None,
             task_name: None,
             resolve_selector: None,
-            output_json: true,
+            output_json: false,
             pretty_json: true,
+            no_ignore: false,
         })
     })
-    .expect("run tasks --json");
+    .expect("run tasks");
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    let tasks = parsed["managed_profiles"]
-        .as_array()
-        .expect("managed_profiles array")
-        .iter()
-        .filter_map(|row| row["task"].as_str())
-        .collect::<Vec<&str>>();
-    assert!(tasks.contains(&"dev admin"));
-    assert!(!tasks.contains(&"dev default"));
+    assert!(out.contains("api"));
+    assert!(out.contains("jobs"));
+    assert!(out.contains("printf api"));
 }
 
 #[test]
-fn run_tasks_json_filter_lists_managed_profiles_with_invocation_labels() {
-    let root = temp_workspace("tasks-managed-profiles-json-filter");
+fn run_tasks_supports_mixed_compact_and_table_task_definitions() {
+    let root = temp_workspace("mixed-compact-and-table");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ run = "printf api" }]
+        r#"[tasks]
+api = "printf api"
 
-[tasks.dev.profiles.front]
-concurrent = [{ run = "printf api" }]
+[tasks.dev]
+run = "printf dev"
 "#,
     );
 
-    let out = with_cwd(&root, || {
-        run_tasks(TasksArgs {
-            repo_override: None,
-            task_name: Some("dev".to_owned()),
-            resolve_selector: None,
-            output_json: true,
-            pretty_json: true,
-        })
-    })
-    .expect("run tasks --json --task dev");
-
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    let tasks = parsed["managed_profile_matches"]
-        .as_array()
-        .expect("managed_profile_matches array")
-        .iter()
-        .filter_map(|row| row["task"].as_str())
-        .collect::<Vec<&str>>();
-    assert!(tasks.contains(&"dev front"));
-    assert!(!tasks.contains(&"dev default"));
-}
-
-#[test]
-fn run_manifest_task_builtin_test_plan_renders_detection_summary() {
-    let root = temp_workspace("builtin-test-plan");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
-
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned()],
-        },
-        root,
-    )
-    .expect("run test --plan");
-
-    assert!(out.contains("Test Plan"));
-    assert!(out.contains("targets:"));
-    assert!(out.contains("runtime:"));
-    assert!(out.contains("text"));
-    assert!(out.contains("Target: root"));
-    assert!(out.contains("runner:"));
-    assert!(out.contains("available-suites:"));
-    assert!(out.contains("suite-source: auto-detected"));
-    assert!(out.contains("vitest"));
-    assert!(out.contains("fallback-chain"));
-}
-
-#[test]
-fn run_manifest_task_builtin_test_plan_json_has_versioned_schema() {
-    let root = temp_workspace("builtin-test-plan-json-schema");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
-
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned(), "--json".to_owned()],
+            name: "api".to_owned(),
+            args: Vec::new(),
         },
-        root,
+        root.clone(),
     )
-    .expect("run test --plan --json");
+    .expect("run compact task");
+    assert_eq!(out, "");
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    assert_eq!(parsed["schema"], "effigy.test.plan.v1");
-    assert_eq!(parsed["schema_version"], 1);
-    assert!(parsed["targets"].is_array());
-    assert_eq!(parsed["recovery"], serde_json::Value::Null);
+    let tasks = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: None,
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks");
+    assert!(tasks.contains("api"));
+    assert!(tasks.contains("dev"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_marks_configured_suite_source() {
-    let root = temp_workspace("builtin-test-plan-configured-suite-source");
+fn run_tasks_supports_compact_sequence_task_definitions() {
+    let root = temp_workspace("compact-sequence-tasks");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[test.suites]
-unit = "pnpm exec vitest run"
+        r#"[tasks]
+drop-db = "printf drop-db"
+migrate-db = "printf migrate-db"
+reset-db = [{ task = "drop-db" }, { task = "migrate-db" }]
 "#,
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned()],
+            name: "reset-db".to_owned(),
+            args: Vec::new(),
         },
-        root,
+        root.clone(),
     )
-    .expect("run configured suite test --plan");
+    .expect("run compact sequence task");
+    assert_eq!(out, "");
 
-    assert!(out.contains("Test Plan"));
-    assert!(out.contains("available-suites: unit"));
-    assert!(out.contains("suite-source: configured"));
-    assert!(out.contains("test.suites.unit"));
+    let tasks = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: Some("reset-db".to_owned()),
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks");
+    assert!(tasks.contains("reset-db"));
+    assert!(tasks.contains("<sequence:2>"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_mixed_workspace_reports_configured_and_auto_detected_sources(
-) {
-    let root = temp_workspace("builtin-test-plan-mixed-suite-sources");
+fn run_tasks_with_task_filter_reports_only_matches() {
+    let root = temp_workspace("task-filter");
     let farmyard = root.join("farmyard");
-    let dairy = root.join("dairy");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
-
+    fs::create_dir_all(&farmyard).expect("mkdir");
     write_manifest(
         &root.join("effigy.toml"),
         "[tasks.dev]\nrun = \"printf root\"\n",
     );
     write_manifest(
         &farmyard.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
-[test.suites]
-unit = "pnpm exec vitest run"
-"#,
-    );
-    write_manifest(
-        &dairy.join("effigy.toml"),
-        r#"[catalog]
-alias = "dairy"
-"#,
+        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
     );
-    fs::write(
-        dairy.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write dairy package");
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned()],
-        },
-        root,
-    )
-    .expect("run mixed-source test --plan");
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: Some("reset-db".to_owned()),
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks");
 
-    assert!(out.contains("Target Summary"));
-    assert!(out.contains("farmyard: source=configured suites=unit"));
-    assert!(out.contains("dairy: source=auto-detected suites=vitest"));
-    assert!(out.contains("Target: farmyard"));
-    assert!(out.contains("available-suites: unit"));
-    assert!(out.contains("suite-source: configured"));
-    assert!(out.contains("test.suites.unit"));
-    assert!(out.contains("Target: dairy"));
-    assert!(out.contains("suite-source: auto-detected"));
-    assert!(out.contains("vitest"));
+    assert!(out.contains("Task Matches: reset-db"));
+    assert!(out.contains("farmyard"));
+    assert!(out.contains("reset-db"));
+    assert!(!out.contains("root      │ reset-db"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_uses_configured_suites_as_source_of_truth() {
-    let root = temp_workspace("builtin-test-configured-suites-source-of-truth");
-    let configured_marker = root.join("configured-suite.log");
-    let vitest_marker = root.join("vitest-suite.log");
-    let manifest = format!(
-        r#"[test.suites]
-unit = "sh -lc 'printf configured > \"{}\"'"
-"#,
-        configured_marker.display()
-    );
-    write_manifest(&root.join("effigy.toml"), &manifest);
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
-    )
-    .expect("write package");
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
-    fs::write(
-        &vitest,
-        format!(
-            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            vitest_marker.display()
-        ),
-    )
-    .expect("write vitest");
-    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&vitest, perms).expect("chmod");
+fn run_tasks_with_test_filter_shows_catalog_fallback_note() {
+    let root = temp_workspace("task-filter-test-note");
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--verbose-results".to_owned()],
-        },
-        root.clone(),
-    )
-    .expect("run configured suite test");
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: Some("test".to_owned()),
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks");
 
-    assert!(out.contains("Test Results"));
-    assert!(out.contains("runner:unit"));
-    assert!(configured_marker.exists(), "configured suite should run");
-    assert!(
-        !vitest_marker.exists(),
-        "auto-detected vitest should not run"
-    );
+    assert!(out.contains("Task Matches: test"));
+    assert!(out.contains("Built-in Task Matches"));
+    assert!(out.contains("built-in fallback supports `<catalog>/test`"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_with_configured_multi_suite_requires_explicit_suite() {
-    let root = temp_workspace("builtin-test-configured-multi-suite-ambiguous");
+fn run_tasks_without_catalogs_still_lists_builtin_tasks() {
+    let root = temp_workspace("builtins-only");
+
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: None,
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks");
+
+    assert!(out.contains("Tasks"));
+    assert!(out.contains("help"));
+    assert!(out.contains("doctor"));
+    assert!(out.contains("test"));
+    assert!(out.contains("<catalog>/test fallback"));
+    assert!(out.contains("tasks"));
+}
+
+#[test]
+fn run_tasks_json_renders_machine_readable_payload() {
+    let root = temp_workspace("tasks-json");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[tasks.reset-db]\nrun = \"printf farmyard\"\n",
+    );
+
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: None,
+            resolve_selector: None,
+            output_json: true,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks json");
+
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    assert_eq!(parsed["catalog_count"], 2);
+    assert!(parsed["catalog_tasks"].is_array());
+    assert!(parsed["managed_profiles"].is_array());
+    assert!(parsed["builtin_tasks"].is_array());
+}
+
+#[test]
+fn run_tasks_json_filter_includes_builtin_matches_and_notes() {
+    let root = temp_workspace("tasks-json-filter");
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: Some("test".to_owned()),
+            resolve_selector: None,
+            output_json: true,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks json filter");
+
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    assert_eq!(parsed["filter"], "test");
+    assert!(parsed["builtin_matches"].is_array());
+    assert!(parsed["managed_profile_matches"].is_array());
+    assert!(parsed["notes"]
+        .as_array()
+        .is_some_and(|items| !items.is_empty()));
+}
+
+#[test]
+fn run_tasks_lists_managed_profiles_for_tui_tasks() {
+    let root = temp_workspace("tasks-managed-profiles-list");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ run = "printf api" }]
+
+[tasks.dev.profiles.admin]
+concurrent = [{ run = "printf api" }]
+"#,
+    );
+
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: None,
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks");
+
+    assert!(out.contains("Tasks"));
+    assert!(out.contains("dev admin"));
+    assert!(out.contains("<managed:tui profile:admin>"));
+    assert!(!out.contains("dev default"));
+}
+
+#[test]
+fn run_tasks_filter_lists_managed_profiles_for_matching_task() {
+    let root = temp_workspace("tasks-managed-profiles-filter");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ run = "printf api" }]
+
+[tasks.dev.profiles.front]
+concurrent = [{ run = "printf api" }]
+"#,
+    );
+
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: Some("dev".to_owned()),
+            resolve_selector: None,
+            output_json: false,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks --task dev");
+
+    assert!(out.contains("Task Matches: dev"));
+    assert!(out.contains("dev front"));
+    assert!(!out.contains("dev default"));
+}
+
+#[test]
+fn run_tasks_json_lists_managed_profiles_with_invocation_labels() {
+    let root = temp_workspace("tasks-managed-profiles-json-list");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ run = "printf api" }]
+
+[tasks.dev.profiles.admin]
+concurrent = [{ run = "printf api" }]
+"#,
+    );
+
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: None,
+            resolve_selector: None,
+            output_json: true,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks --json");
+
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    let tasks = parsed["managed_profiles"]
+        .as_array()
+        .expect("managed_profiles array")
+        .iter()
+        .filter_map(|row| row["task"].as_str())
+        .collect::<Vec<&str>>();
+    assert!(tasks.contains(&"dev admin"));
+    assert!(!tasks.contains(&"dev default"));
+}
+
+#[test]
+fn run_tasks_json_filter_lists_managed_profiles_with_invocation_labels() {
+    let root = temp_workspace("tasks-managed-profiles-json-filter");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ run = "printf api" }]
+
+[tasks.dev.profiles.front]
+concurrent = [{ run = "printf api" }]
+"#,
+    );
+
+    let out = with_cwd(&root, || {
+        run_tasks(TasksArgs {
+            repo_override: None,
+            task_name: Some("dev".to_owned()),
+            resolve_selector: None,
+            output_json: true,
+            pretty_json: true,
+            no_ignore: false,
+        })
+    })
+    .expect("run tasks --json --task dev");
+
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    let tasks = parsed["managed_profile_matches"]
+        .as_array()
+        .expect("managed_profile_matches array")
+        .iter()
+        .filter_map(|row| row["task"].as_str())
+        .collect::<Vec<&str>>();
+    assert!(tasks.contains(&"dev front"));
+    assert!(!tasks.contains(&"dev default"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_renders_detection_summary() {
+    let root = temp_workspace("builtin-test-plan");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned()],
+        },
+        root,
+    )
+    .expect("run test --plan");
+
+    assert!(out.contains("Test Plan"));
+    assert!(out.contains("targets:"));
+    assert!(out.contains("runtime:"));
+    assert!(out.contains("text"));
+    assert!(out.contains("Target: root"));
+    assert!(out.contains("runner:"));
+    assert!(out.contains("available-suites:"));
+    assert!(out.contains("suite-source: auto-detected"));
+    assert!(out.contains("vitest"));
+    assert!(out.contains("fallback-chain"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_json_has_versioned_schema() {
+    let root = temp_workspace("builtin-test-plan-json-schema");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned(), "--json".to_owned()],
+        },
+        root,
+    )
+    .expect("run test --plan --json");
+
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    assert_eq!(parsed["schema"], "effigy.test.plan.v1");
+    assert_eq!(parsed["schema_version"], 1);
+    assert!(parsed["targets"].is_array());
+    assert_eq!(parsed["recovery"], serde_json::Value::Null);
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_marks_configured_suite_source() {
+    let root = temp_workspace("builtin-test-plan-configured-suite-source");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[test.suites]
+unit = "pnpm exec vitest run"
+"#,
+    );
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned()],
+        },
+        root,
+    )
+    .expect("run configured suite test --plan");
+
+    assert!(out.contains("Test Plan"));
+    assert!(out.contains("available-suites: unit"));
+    assert!(out.contains("suite-source: configured"));
+    assert!(out.contains("test.suites.unit"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_mixed_workspace_reports_configured_and_auto_detected_sources(
+) {
+    let root = temp_workspace("builtin-test-plan-mixed-suite-sources");
+    let farmyard = root.join("farmyard");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
+
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        r#"[catalog]
+alias = "farmyard"
+[test.suites]
+unit = "pnpm exec vitest run"
+"#,
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        r#"[catalog]
+alias = "dairy"
+"#,
+    );
+    fs::write(
+        dairy.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write dairy package");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned()],
+        },
+        root,
+    )
+    .expect("run mixed-source test --plan");
+
+    assert!(out.contains("Target Summary"));
+    assert!(out.contains("farmyard: source=configured suites=unit"));
+    assert!(out.contains("dairy: source=auto-detected suites=vitest"));
+    assert!(out.contains("Target: farmyard"));
+    assert!(out.contains("available-suites: unit"));
+    assert!(out.contains("suite-source: configured"));
+    assert!(out.contains("test.suites.unit"));
+    assert!(out.contains("Target: dairy"));
+    assert!(out.contains("suite-source: auto-detected"));
+    assert!(out.contains("vitest"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_uses_configured_suites_as_source_of_truth() {
+    let root = temp_workspace("builtin-test-configured-suites-source-of-truth");
+    let configured_marker = root.join("configured-suite.log");
+    let vitest_marker = root.join("vitest-suite.log");
+    let manifest = format!(
+        r#"[test.suites]
+unit = "sh -lc 'printf configured > \"{}\"'"
+"#,
+        configured_marker.display()
+    );
+    write_manifest(&root.join("effigy.toml"), &manifest);
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    fs::write(
+        &vitest,
+        format!(
+            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
+            vitest_marker.display()
+        ),
+    )
+    .expect("write vitest");
+    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&vitest, perms).expect("chmod");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--verbose-results".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("run configured suite test");
+
+    assert!(out.contains("Test Results"));
+    assert!(out.contains("runner:unit"));
+    assert!(configured_marker.exists(), "configured suite should run");
+    assert!(
+        !vitest_marker.exists(),
+        "auto-detected vitest should not run"
+    );
+}
+
+#[test]
+fn run_manifest_task_builtin_test_with_configured_multi_suite_requires_explicit_suite() {
+    let root = temp_workspace("builtin-test-configured-multi-suite-ambiguous");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[test.suites]
@@ -2118,2943 +3150,3644 @@ integration = "true"
 }
 
 #[test]
-fn run_manifest_task_builtin_test_supports_configured_custom_suite_selector() {
-    let root = temp_workspace("builtin-test-configured-custom-suite-selector");
-    let unit_marker = root.join("unit-suite.log");
-    let integration_marker = root.join("integration-suite.log");
-    let manifest = format!(
-        r#"[test.suites]
-unit = "sh -lc 'printf unit > \"{}\"'"
-integration = "sh -lc 'printf integration > \"{}\"'"
-"#,
-        unit_marker.display(),
-        integration_marker.display()
-    );
-    write_manifest(&root.join("effigy.toml"), &manifest);
+fn run_manifest_task_builtin_test_supports_configured_custom_suite_selector() {
+    let root = temp_workspace("builtin-test-configured-custom-suite-selector");
+    let unit_marker = root.join("unit-suite.log");
+    let integration_marker = root.join("integration-suite.log");
+    let manifest = format!(
+        r#"[test.suites]
+unit = "sh -lc 'printf unit > \"{}\"'"
+integration = "sh -lc 'printf integration > \"{}\"'"
+"#,
+        unit_marker.display(),
+        integration_marker.display()
+    );
+    write_manifest(&root.join("effigy.toml"), &manifest);
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["unit".to_owned()],
+        },
+        root,
+    )
+    .expect("configured custom suite selector should run");
+
+    assert!(out.contains("Test Results"));
+    assert!(unit_marker.exists(), "selected suite should run");
+    assert!(
+        !integration_marker.exists(),
+        "non-selected suite should not run"
+    );
+}
+
+#[test]
+fn run_manifest_task_builtin_test_executes_local_vitest() {
+    let root = temp_workspace("builtin-test-exec-vitest");
+    fs::write(
+        root.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write package");
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    let marker = root.join("vitest-called.log");
+    fs::write(
+        &vitest,
+        format!(
+            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
+            marker.display()
+        ),
+    )
+    .expect("write vitest");
+    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&vitest, perms).expect("chmod");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--run".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("run builtin test");
+
+    assert!(out.contains("Test Results"));
+    assert!(out.contains("targets:"));
+    assert!(out.contains("root"));
+    assert!(!out.contains("runner:vitest"));
+    assert!(!out.contains("command:"));
+    assert!(marker.exists(), "vitest stub should be invoked");
+}
+
+#[test]
+fn run_manifest_task_builtin_test_json_suppresses_child_process_output() {
+    let root = temp_workspace("builtin-test-json-suppresses-child-output");
+    fs::write(
+        root.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write package");
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    fs::write(
+        &vitest,
+        "#!/bin/sh\nprintf noisy-stdout\nprintf noisy-stderr >&2\nexit 0\n",
+    )
+    .expect("write vitest");
+    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&vitest, perms).expect("chmod");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--json".to_owned(), "--run".to_owned()],
+        },
+        root,
+    )
+    .expect("run builtin test --json");
+
+    assert!(
+        !out.contains("noisy-stdout"),
+        "child stdout leaked into json output"
+    );
+    assert!(
+        !out.contains("noisy-stderr"),
+        "child stderr leaked into json output"
+    );
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    assert_eq!(parsed["schema"], "effigy.test.results.v1");
+}
+
+#[test]
+fn run_manifest_task_builtin_test_executes_js_and_rust_suites_in_same_repo() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("builtin-test-multi-context");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+    fs::create_dir_all(root.join("src")).expect("mkdir src");
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn ok() -> bool { true }\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn smoke() {\n        assert!(super::ok());\n    }\n}\n",
+    )
+    .expect("write lib");
+
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    let vitest_marker = root.join("vitest-called.log");
+    fs::write(
+        &vitest,
+        format!(
+            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
+            vitest_marker.display()
+        ),
+    )
+    .expect("write vitest");
+    let mut vitest_perms = fs::metadata(&vitest).expect("stat").permissions();
+    vitest_perms.set_mode(0o755);
+    fs::set_permissions(&vitest, vitest_perms).expect("chmod");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: Vec::new(),
+        },
+        root.clone(),
+    )
+    .expect("run builtin multi-context test");
+
+    assert!(out.contains("Test Results"));
+    assert!(out.contains("root/vitest"));
+    assert!(out.contains("root/cargo-"));
+    assert!(vitest_marker.exists(), "vitest suite should run");
+}
+
+#[test]
+fn run_manifest_task_builtin_test_with_named_args_errors_when_multi_suite_is_ambiguous() {
+    let root = temp_workspace("builtin-test-multi-suite-ambiguous");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["user-service".to_owned()],
+        },
+        root,
+    )
+    .expect_err("expected ambiguity error");
+
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("ambiguous"));
+            assert!(message.contains("vitest"));
+            assert!(message.contains("cargo-"));
+            assert!(message.contains("Try one of:"));
+            assert!(message.contains("Use `effigy test --plan <args>`"));
+            assert!(message.contains("effigy test vitest user-service"));
+            assert!(message.contains("effigy test cargo-"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_with_named_args_in_multi_suite_returns_recovery_output() {
+    let root = temp_workspace("builtin-test-multi-suite-plan-recovery");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned(), "user-service".to_owned()],
+        },
+        root,
+    )
+    .expect("plan should return recovery output");
+
+    assert!(out.contains("Test Plan"));
+    assert!(out.contains("runtime: plan-recovery"));
+    assert!(out.contains("available-suites:"));
+    assert!(out.contains("ambiguous"));
+    assert!(out.contains("Try one of:"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_json_recovery_has_versioned_schema() {
+    let root = temp_workspace("builtin-test-plan-json-recovery-schema");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "test".to_owned(),
-            args: vec!["unit".to_owned()],
+            args: vec![
+                "--plan".to_owned(),
+                "--json".to_owned(),
+                "user-service".to_owned(),
+            ],
         },
         root,
     )
-    .expect("configured custom suite selector should run");
+    .expect("plan recovery should return json");
 
-    assert!(out.contains("Test Results"));
-    assert!(unit_marker.exists(), "selected suite should run");
-    assert!(
-        !integration_marker.exists(),
-        "non-selected suite should not run"
-    );
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
+    assert_eq!(parsed["schema"], "effigy.test.plan.v1");
+    assert_eq!(parsed["schema_version"], 1);
+    assert_eq!(parsed["runtime"], "plan-recovery");
+    assert!(parsed["recovery"].is_object());
 }
 
 #[test]
-fn run_manifest_task_builtin_test_executes_local_vitest() {
-    let root = temp_workspace("builtin-test-exec-vitest");
+fn run_manifest_task_builtin_test_supports_positional_suite_selector() {
+    let root = temp_workspace("builtin-test-suite-selector");
     fs::write(
         root.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
     )
     .expect("write package");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+
     let local_bin = root.join("node_modules/.bin");
     fs::create_dir_all(&local_bin).expect("mkdir local bin");
     let vitest = local_bin.join("vitest");
-    let marker = root.join("vitest-called.log");
+    let vitest_marker = root.join("vitest-called.log");
     fs::write(
         &vitest,
         format!(
             "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            marker.display()
+            vitest_marker.display()
         ),
     )
     .expect("write vitest");
-    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&vitest, perms).expect("chmod");
+    let mut vitest_perms = fs::metadata(&vitest).expect("stat").permissions();
+    vitest_perms.set_mode(0o755);
+    fs::set_permissions(&vitest, vitest_perms).expect("chmod");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "test".to_owned(),
-            args: vec!["--run".to_owned()],
+            args: vec!["vitest".to_owned(), "user-service".to_owned()],
         },
         root.clone(),
     )
-    .expect("run builtin test");
+    .expect("run builtin suite-selected test");
 
     assert!(out.contains("Test Results"));
-    assert!(out.contains("targets:"));
-    assert!(out.contains("root"));
-    assert!(!out.contains("runner:vitest"));
-    assert!(!out.contains("command:"));
-    assert!(marker.exists(), "vitest stub should be invoked");
+    assert!(out.contains("root/vitest"));
+    assert!(!out.contains("root/cargo-"));
+    assert!(vitest_marker.exists(), "vitest suite should run");
 }
 
 #[test]
-fn run_manifest_task_builtin_test_json_suppresses_child_process_output() {
-    let root = temp_workspace("builtin-test-json-suppresses-child-output");
+fn run_manifest_task_builtin_test_plan_mistyped_suite_returns_recovery_output() {
+    let root = temp_workspace("builtin-test-plan-mistyped-suite-recovery");
     fs::write(
         root.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
     )
     .expect("write package");
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
     fs::write(
-        &vitest,
-        "#!/bin/sh\nprintf noisy-stdout\nprintf noisy-stderr >&2\nexit 0\n",
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
     )
-    .expect("write vitest");
-    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&vitest, perms).expect("chmod");
+    .expect("write cargo toml");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "test".to_owned(),
-            args: vec!["--json".to_owned(), "--run".to_owned()],
+            args: vec![
+                "--plan".to_owned(),
+                "viteest".to_owned(),
+                "user-service".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run builtin test --json");
+    .expect("plan should return typo recovery output");
+
+    assert!(out.contains("Test Plan"));
+    assert!(out.contains("runtime: plan-recovery"));
+    assert!(out.contains("Did you mean `vitest`?"));
+    assert!(out.contains("Try: effigy test vitest user-service"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_errors_for_unavailable_positional_suite_selector() {
+    let root = temp_workspace("builtin-test-suite-selector-unavailable");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["nextest".to_owned()],
+        },
+        root,
+    )
+    .expect_err("suite should be unavailable");
+
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("not available"));
+            assert!(message.contains("nextest"));
+            assert!(message.contains("vitest"));
+            assert!(message.contains("Try one of:"));
+            assert!(message.contains("Use `effigy test --plan <args>`"));
+            assert!(message.contains("effigy test vitest"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_builtin_test_mistyped_suite_suggests_nearest_runner() {
+    let root = temp_workspace("builtin-test-mistyped-suite-suggestion");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "scripts": {
+    "test": "vitest run"
+  }
+}"#,
+    )
+    .expect("write package");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["viteest".to_owned(), "user-service".to_owned()],
+        },
+        root,
+    )
+    .expect_err("expected mistyped suite error");
+
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("runner `viteest` is not available"));
+            assert!(message.contains("Did you mean `vitest`?"));
+            assert!(message.contains("Try: effigy test vitest user-service"));
+            assert!(message.contains("Use `effigy test --plan <args>`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_explicit_test_task_overrides_builtin_auto_detection() {
+    let root = temp_workspace("builtin-test-explicit-override");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.test]\nrun = \"printf explicit > explicit-test.log\"\n",
+    );
+    fs::write(
+        root.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write package");
 
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: Vec::new(),
+        },
+        root.clone(),
+    )
+    .expect("run explicit test task");
+
+    assert_eq!(out, "");
     assert!(
-        !out.contains("noisy-stdout"),
-        "child stdout leaked into json output"
+        root.join("explicit-test.log").exists(),
+        "explicit task should run before builtin test detection"
+    );
+}
+
+#[test]
+fn run_manifest_task_builtin_test_falls_through_to_deferral_when_no_detection_matches() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("builtin-test-defers");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[defer]\nrun = \"test {request} = 'test' && test {args} = '--watch'\"\n",
+    );
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--watch".to_owned()],
+        },
+        root,
+    )
+    .expect("builtin test should defer when detection is unavailable");
+
+    assert_eq!(out, "");
+}
+
+#[test]
+fn run_manifest_task_builtin_test_fans_out_across_catalog_roots() {
+    let root = temp_workspace("builtin-test-fanout");
+    let farmyard = root.join("farmyard");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.dev]\nrun = \"printf root\"\n",
     );
-    assert!(
-        !out.contains("noisy-stderr"),
-        "child stderr leaked into json output"
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.ping]\nrun = \"printf ok\"\n",
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        "[catalog]\nalias = \"dairy\"\n[tasks.ping]\nrun = \"printf ok\"\n",
     );
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    assert_eq!(parsed["schema"], "effigy.test.results.v1");
-}
 
-#[test]
-fn run_manifest_task_builtin_test_executes_js_and_rust_suites_in_same_repo() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("builtin-test-multi-context");
     fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
+        farmyard.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
     )
-    .expect("write package");
+    .expect("write farmyard package");
     fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+        dairy.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
     )
-    .expect("write cargo toml");
-    fs::create_dir_all(root.join("src")).expect("mkdir src");
+    .expect("write dairy package");
+
+    let farmyard_bin = farmyard.join("node_modules/.bin");
+    fs::create_dir_all(&farmyard_bin).expect("mkdir farmyard bin");
+    let dairy_bin = dairy.join("node_modules/.bin");
+    fs::create_dir_all(&dairy_bin).expect("mkdir dairy bin");
+    let farmyard_marker = farmyard.join("vitest-called.log");
+    let dairy_marker = dairy.join("vitest-called.log");
+
+    let farmyard_vitest = farmyard_bin.join("vitest");
     fs::write(
-        root.join("src/lib.rs"),
-        "pub fn ok() -> bool { true }\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn smoke() {\n        assert!(super::ok());\n    }\n}\n",
+        &farmyard_vitest,
+        format!(
+            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
+            farmyard_marker.display()
+        ),
     )
-    .expect("write lib");
+    .expect("write farmyard vitest");
+    let mut farmyard_perms = fs::metadata(&farmyard_vitest).expect("stat").permissions();
+    farmyard_perms.set_mode(0o755);
+    fs::set_permissions(&farmyard_vitest, farmyard_perms).expect("chmod");
 
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
-    let vitest_marker = root.join("vitest-called.log");
+    let dairy_vitest = dairy_bin.join("vitest");
     fs::write(
-        &vitest,
+        &dairy_vitest,
         format!(
             "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            vitest_marker.display()
+            dairy_marker.display()
         ),
     )
-    .expect("write vitest");
-    let mut vitest_perms = fs::metadata(&vitest).expect("stat").permissions();
-    vitest_perms.set_mode(0o755);
-    fs::set_permissions(&vitest, vitest_perms).expect("chmod");
+    .expect("write dairy vitest");
+    let mut dairy_perms = fs::metadata(&dairy_vitest).expect("stat").permissions();
+    dairy_perms.set_mode(0o755);
+    fs::set_permissions(&dairy_vitest, dairy_perms).expect("chmod");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "test".to_owned(),
             args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect("run builtin multi-context test");
+    .expect("builtin test fanout");
 
     assert!(out.contains("Test Results"));
-    assert!(out.contains("root/vitest"));
-    assert!(out.contains("root/cargo-"));
-    assert!(vitest_marker.exists(), "vitest suite should run");
+    assert!(out.contains("targets:"));
+    assert!(out.contains("dairy"));
+    assert!(out.contains("farmyard"));
+    assert!(!out.contains("runner:vitest"));
+    assert!(!out.contains("command:"));
+    assert!(farmyard_marker.exists(), "farmyard vitest should run");
+    assert!(dairy_marker.exists(), "dairy vitest should run");
 }
 
 #[test]
-fn run_manifest_task_builtin_test_with_named_args_errors_when_multi_suite_is_ambiguous() {
-    let root = temp_workspace("builtin-test-multi-suite-ambiguous");
+fn run_manifest_task_prefixed_builtin_test_targets_catalog_root_only() {
+    let root = temp_workspace("builtin-test-prefixed-catalog");
+    let farmyard = root.join("farmyard");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.ping]\nrun = \"printf ok\"\n",
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        "[catalog]\nalias = \"dairy\"\n[tasks.ping]\nrun = \"printf ok\"\n",
+    );
+
     fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
+        farmyard.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
     )
-    .expect("write package");
+    .expect("write farmyard package");
     fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
-    )
-    .expect("write cargo toml");
-
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["user-service".to_owned()],
-        },
-        root,
+        dairy.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
     )
-    .expect_err("expected ambiguity error");
+    .expect("write dairy package");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("ambiguous"));
-            assert!(message.contains("vitest"));
-            assert!(message.contains("cargo-"));
-            assert!(message.contains("Try one of:"));
-            assert!(message.contains("Use `effigy test --plan <args>`"));
-            assert!(message.contains("effigy test vitest user-service"));
-            assert!(message.contains("effigy test cargo-"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
-}
+    let farmyard_bin = farmyard.join("node_modules/.bin");
+    fs::create_dir_all(&farmyard_bin).expect("mkdir farmyard bin");
+    let dairy_bin = dairy.join("node_modules/.bin");
+    fs::create_dir_all(&dairy_bin).expect("mkdir dairy bin");
+    let farmyard_marker = farmyard.join("vitest-called.log");
+    let dairy_marker = dairy.join("vitest-called.log");
 
-#[test]
-fn run_manifest_task_builtin_test_plan_with_named_args_in_multi_suite_returns_recovery_output() {
-    let root = temp_workspace("builtin-test-multi-suite-plan-recovery");
+    let farmyard_vitest = farmyard_bin.join("vitest");
     fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
+        &farmyard_vitest,
+        format!(
+            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
+            farmyard_marker.display()
+        ),
     )
-    .expect("write package");
+    .expect("write farmyard vitest");
+    let mut farmyard_perms = fs::metadata(&farmyard_vitest).expect("stat").permissions();
+    farmyard_perms.set_mode(0o755);
+    fs::set_permissions(&farmyard_vitest, farmyard_perms).expect("chmod");
+
+    let dairy_vitest = dairy_bin.join("vitest");
     fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
+        &dairy_vitest,
+        format!(
+            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
+            dairy_marker.display()
+        ),
     )
-    .expect("write cargo toml");
+    .expect("write dairy vitest");
+    let mut dairy_perms = fs::metadata(&dairy_vitest).expect("stat").permissions();
+    dairy_perms.set_mode(0o755);
+    fs::set_permissions(&dairy_vitest, dairy_perms).expect("chmod");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned(), "user-service".to_owned()],
+            name: "farmyard/test".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect("plan should return recovery output");
+    .expect("prefixed builtin test");
 
-    assert!(out.contains("Test Plan"));
-    assert!(out.contains("runtime: plan-recovery"));
-    assert!(out.contains("available-suites:"));
-    assert!(out.contains("ambiguous"));
-    assert!(out.contains("Try one of:"));
+    assert!(out.contains("Test Results"));
+    assert!(out.contains("farmyard"));
+    assert!(!out.contains("dairy"));
+    assert!(farmyard_marker.exists(), "farmyard vitest should run");
+    assert!(!dairy_marker.exists(), "dairy vitest should not run");
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_json_recovery_has_versioned_schema() {
-    let root = temp_workspace("builtin-test-plan-json-recovery-schema");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
-    )
-    .expect("write package");
-    fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
-    )
-    .expect("write cargo toml");
+fn run_manifest_task_prefixed_builtin_tasks_targets_catalog_root_only() {
+    let root = temp_workspace("builtin-tasks-prefixed-catalog");
+    let farmyard = root.join("farmyard");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
+
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.root-only]
+run = "printf root"
+"#,
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        r#"[catalog]
+alias = "farmyard"
+[tasks.api]
+run = "printf farmyard-api"
+"#,
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        r#"[catalog]
+alias = "dairy"
+[tasks.admin]
+run = "printf dairy-admin"
+"#,
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec![
-                "--plan".to_owned(),
-                "--json".to_owned(),
-                "user-service".to_owned(),
-            ],
+            name: "farmyard/tasks".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect("plan recovery should return json");
+    .expect("prefixed builtin tasks");
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("parse json");
-    assert_eq!(parsed["schema"], "effigy.test.plan.v1");
-    assert_eq!(parsed["schema_version"], 1);
-    assert_eq!(parsed["runtime"], "plan-recovery");
-    assert!(parsed["recovery"].is_object());
+    assert!(out.contains("Catalogs"));
+    assert!(out.contains("count: 1"));
+    assert!(out.contains("api"));
+    assert!(!out.contains("admin"));
+    assert!(!out.contains("root-only"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_supports_positional_suite_selector() {
-    let root = temp_workspace("builtin-test-suite-selector");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
-    )
-    .expect("write package");
-    fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
-    )
-    .expect("write cargo toml");
+fn run_manifest_task_relative_prefixed_builtin_tasks_target_catalog_root_only() {
+    let root = temp_workspace("builtin-tasks-relative-prefixed-catalog");
+    let dairy = root.join("dairy");
+    let froyo = root.join("froyo");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
+    fs::create_dir_all(&froyo).expect("mkdir froyo");
 
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
-    let vitest_marker = root.join("vitest-called.log");
-    fs::write(
-        &vitest,
-        format!(
-            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            vitest_marker.display()
-        ),
-    )
-    .expect("write vitest");
-    let mut vitest_perms = fs::metadata(&vitest).expect("stat").permissions();
-    vitest_perms.set_mode(0o755);
-    fs::set_permissions(&vitest, vitest_perms).expect("chmod");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.root-only]
+run = "printf root"
+"#,
+    );
+    write_manifest(
+        &froyo.join("effigy.toml"),
+        r#"[catalog]
+alias = "froyo"
+[tasks.validate]
+run = "printf froyo-validate"
+"#,
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["vitest".to_owned(), "user-service".to_owned()],
+            name: "../froyo/tasks".to_owned(),
+            args: Vec::new(),
         },
-        root.clone(),
+        dairy,
     )
-    .expect("run builtin suite-selected test");
+    .expect("relative prefixed builtin tasks");
 
-    assert!(out.contains("Test Results"));
-    assert!(out.contains("root/vitest"));
-    assert!(!out.contains("root/cargo-"));
-    assert!(vitest_marker.exists(), "vitest suite should run");
+    assert!(out.contains("Catalogs"));
+    assert!(out.contains("count: 1"));
+    assert!(out.contains("validate"));
+    assert!(!out.contains("root-only"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_mistyped_suite_returns_recovery_output() {
-    let root = temp_workspace("builtin-test-plan-mistyped-suite-recovery");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
-    )
-    .expect("write package");
-    fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
-    )
-    .expect("write cargo toml");
+fn run_manifest_task_builtin_catalogs_renders_diagnostics_and_resolution_probe() {
+    let root = temp_workspace("builtin-catalogs");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.root]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.api]\nrun = \"printf api\"\n",
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec![
-                "--plan".to_owned(),
-                "viteest".to_owned(),
-                "user-service".to_owned(),
-            ],
+            name: "catalogs".to_owned(),
+            args: vec!["--resolve".to_owned(), "farmyard/api".to_owned()],
         },
         root,
     )
-    .expect("plan should return typo recovery output");
+    .expect("builtin catalogs");
 
-    assert!(out.contains("Test Plan"));
-    assert!(out.contains("runtime: plan-recovery"));
-    assert!(out.contains("Did you mean `vitest`?"));
-    assert!(out.contains("Try: effigy test vitest user-service"));
+    assert!(out.contains("Resolution: farmyard/api"));
+    assert!(out.contains("catalog: farmyard"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_errors_for_unavailable_positional_suite_selector() {
-    let root = temp_workspace("builtin-test-suite-selector-unavailable");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
-    )
-    .expect("write package");
+fn run_manifest_task_builtin_catalogs_resolve_supports_managed_profile_invocation() {
+    let root = temp_workspace("builtin-catalogs-resolve-managed-profile");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ run = "printf default-ok" }]
 
-    let err = run_manifest_task_with_cwd(
+[tasks.dev.profiles.front]
+concurrent = [{ run = "printf front-ok" }]
+"#,
+    );
+
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["nextest".to_owned()],
+            name: "catalogs".to_owned(),
+            args: vec!["--resolve".to_owned(), "dev front".to_owned()],
         },
         root,
     )
-    .expect_err("suite should be unavailable");
+    .expect("builtin catalogs managed profile resolve");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("not available"));
-            assert!(message.contains("nextest"));
-            assert!(message.contains("vitest"));
-            assert!(message.contains("Try one of:"));
-            assert!(message.contains("Use `effigy test --plan <args>`"));
-            assert!(message.contains("effigy test vitest"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("Resolution: dev front"));
+    assert!(out.contains("status: ok"));
+    assert!(out.contains("catalog: root"));
+    assert!(out.contains("task: dev"));
+    assert!(out.contains("managed profile `front` resolved via invocation `dev front`"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_mistyped_suite_suggests_nearest_runner() {
-    let root = temp_workspace("builtin-test-mistyped-suite-suggestion");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "scripts": {
-    "test": "vitest run"
-  }
-}"#,
-    )
-    .expect("write package");
-    fs::write(
-        root.join("Cargo.toml"),
-        "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n",
-    )
-    .expect("write cargo toml");
+fn run_manifest_task_builtin_catalogs_json_renders_probe_payload() {
+    let root = temp_workspace("builtin-catalogs-json");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
 
-    let err = run_manifest_task_with_cwd(
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.root]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.api]\nrun = \"printf api\"\n",
+    );
+
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["viteest".to_owned(), "user-service".to_owned()],
+            name: "catalogs".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--resolve".to_owned(),
+                "farmyard/api".to_owned(),
+            ],
         },
         root,
     )
-    .expect_err("expected mistyped suite error");
+    .expect("builtin catalogs json");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("runner `viteest` is not available"));
-            assert!(message.contains("Did you mean `vitest`?"));
-            assert!(message.contains("Try: effigy test vitest user-service"));
-            assert!(message.contains("Use `effigy test --plan <args>`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
+    assert_eq!(parsed["schema"], "effigy.tasks.v1");
+    assert_eq!(parsed["schema_version"], 1);
+    assert!(parsed["catalogs"].is_array());
+    assert_eq!(parsed["resolve"]["status"], "ok");
+    assert_eq!(parsed["resolve"]["catalog"], "farmyard");
+    assert_eq!(parsed["resolve"]["task"], "api");
+    assert!(parsed["precedence"].is_array());
 }
 
 #[test]
-fn run_manifest_task_explicit_test_task_overrides_builtin_auto_detection() {
-    let root = temp_workspace("builtin-test-explicit-override");
+fn run_manifest_task_builtin_catalogs_json_resolve_supports_managed_profile_invocation() {
+    let root = temp_workspace("builtin-catalogs-json-resolve-managed-profile");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.test]\nrun = \"printf explicit > explicit-test.log\"\n",
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ run = "printf default-ok" }]
+
+[tasks.dev.profiles.front]
+concurrent = [{ run = "printf front-ok" }]
+"#,
     );
-    fs::write(
-        root.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write package");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: Vec::new(),
+            name: "catalogs".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--resolve".to_owned(),
+                "dev front".to_owned(),
+            ],
         },
-        root.clone(),
+        root,
     )
-    .expect("run explicit test task");
+    .expect("builtin catalogs json managed profile resolve");
 
-    assert_eq!(out, "");
-    assert!(
-        root.join("explicit-test.log").exists(),
-        "explicit task should run before builtin test detection"
-    );
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
+    assert_eq!(parsed["resolve"]["selector"], "dev front");
+    assert_eq!(parsed["resolve"]["status"], "ok");
+    assert_eq!(parsed["resolve"]["catalog"], "root");
+    assert_eq!(parsed["resolve"]["task"], "dev");
+    let evidence = parsed["resolve"]["evidence"]
+        .as_array()
+        .expect("resolve evidence array")
+        .iter()
+        .filter_map(|line| line.as_str())
+        .collect::<Vec<&str>>();
+    assert!(evidence
+        .iter()
+        .any(|line| line.contains("managed profile `front` resolved via invocation `dev front`")));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_falls_through_to_deferral_when_no_detection_matches() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("builtin-test-defers");
+fn run_manifest_task_builtin_catalogs_json_reports_resolution_errors() {
+    let root = temp_workspace("builtin-catalogs-json-error");
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"test {request} = 'test' && test {args} = '--watch'\"\n",
+        "[tasks.root]\nrun = \"printf root\"\n",
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--watch".to_owned()],
+            name: "catalogs".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--resolve".to_owned(),
+                "farmyard/api".to_owned(),
+            ],
         },
         root,
     )
-    .expect("builtin test should defer when detection is unavailable");
+    .expect("builtin catalogs json error");
 
-    assert_eq!(out, "");
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
+    assert_eq!(parsed["schema"], "effigy.tasks.v1");
+    assert_eq!(parsed["schema_version"], 1);
+    assert_eq!(parsed["resolve"]["status"], "error");
+    assert_eq!(parsed["resolve"]["catalog"], serde_json::Value::Null);
+    assert!(parsed["resolve"]["error"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("prefix `farmyard` not found")));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_fans_out_across_catalog_roots() {
-    let root = temp_workspace("builtin-test-fanout");
+fn run_manifest_task_builtin_catalogs_json_compact_output_has_no_newlines() {
+    let root = temp_workspace("builtin-catalogs-json-compact");
     let farmyard = root.join("farmyard");
-    let dairy = root.join("dairy");
     fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.dev]\nrun = \"printf root\"\n",
-    );
     write_manifest(
         &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.ping]\nrun = \"printf ok\"\n",
-    );
-    write_manifest(
-        &dairy.join("effigy.toml"),
-        "[catalog]\nalias = \"dairy\"\n[tasks.ping]\nrun = \"printf ok\"\n",
+        "[catalog]\nalias = \"farmyard\"\n[tasks.api]\nrun = \"printf api\"\n",
     );
 
-    fs::write(
-        farmyard.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write farmyard package");
-    fs::write(
-        dairy.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write dairy package");
-
-    let farmyard_bin = farmyard.join("node_modules/.bin");
-    fs::create_dir_all(&farmyard_bin).expect("mkdir farmyard bin");
-    let dairy_bin = dairy.join("node_modules/.bin");
-    fs::create_dir_all(&dairy_bin).expect("mkdir dairy bin");
-    let farmyard_marker = farmyard.join("vitest-called.log");
-    let dairy_marker = dairy.join("vitest-called.log");
-
-    let farmyard_vitest = farmyard_bin.join("vitest");
-    fs::write(
-        &farmyard_vitest,
-        format!(
-            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            farmyard_marker.display()
-        ),
-    )
-    .expect("write farmyard vitest");
-    let mut farmyard_perms = fs::metadata(&farmyard_vitest).expect("stat").permissions();
-    farmyard_perms.set_mode(0o755);
-    fs::set_permissions(&farmyard_vitest, farmyard_perms).expect("chmod");
-
-    let dairy_vitest = dairy_bin.join("vitest");
-    fs::write(
-        &dairy_vitest,
-        format!(
-            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            dairy_marker.display()
-        ),
-    )
-    .expect("write dairy vitest");
-    let mut dairy_perms = fs::metadata(&dairy_vitest).expect("stat").permissions();
-    dairy_perms.set_mode(0o755);
-    fs::set_permissions(&dairy_vitest, dairy_perms).expect("chmod");
-
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: Vec::new(),
+            name: "catalogs".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--pretty".to_owned(),
+                "false".to_owned(),
+                "--resolve".to_owned(),
+                "farmyard/api".to_owned(),
+            ],
         },
         root,
     )
-    .expect("builtin test fanout");
+    .expect("builtin catalogs compact json");
 
-    assert!(out.contains("Test Results"));
-    assert!(out.contains("targets:"));
-    assert!(out.contains("dairy"));
-    assert!(out.contains("farmyard"));
-    assert!(!out.contains("runner:vitest"));
-    assert!(!out.contains("command:"));
-    assert!(farmyard_marker.exists(), "farmyard vitest should run");
-    assert!(dairy_marker.exists(), "dairy vitest should run");
+    assert!(!out.contains('\n'));
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
+    assert_eq!(parsed["resolve"]["status"], "ok");
 }
 
 #[test]
-fn run_manifest_task_prefixed_builtin_test_targets_catalog_root_only() {
-    let root = temp_workspace("builtin-test-prefixed-catalog");
-    let farmyard = root.join("farmyard");
-    let dairy = root.join("dairy");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
+fn run_manifest_task_builtin_catalogs_pretty_requires_json() {
+    let root = temp_workspace("builtin-catalogs-pretty-requires-json");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.dev]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.ping]\nrun = \"printf ok\"\n",
-    );
-    write_manifest(
-        &dairy.join("effigy.toml"),
-        "[catalog]\nalias = \"dairy\"\n[tasks.ping]\nrun = \"printf ok\"\n",
+        "[tasks.root]\nrun = \"printf root\"\n",
     );
 
-    fs::write(
-        farmyard.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write farmyard package");
-    fs::write(
-        dairy.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "catalogs".to_owned(),
+            args: vec!["--pretty".to_owned(), "false".to_owned()],
+        },
+        root,
     )
-    .expect("write dairy package");
-
-    let farmyard_bin = farmyard.join("node_modules/.bin");
-    fs::create_dir_all(&farmyard_bin).expect("mkdir farmyard bin");
-    let dairy_bin = dairy.join("node_modules/.bin");
-    fs::create_dir_all(&dairy_bin).expect("mkdir dairy bin");
-    let farmyard_marker = farmyard.join("vitest-called.log");
-    let dairy_marker = dairy.join("vitest-called.log");
+    .expect_err("expected --pretty requires --json");
 
-    let farmyard_vitest = farmyard_bin.join("vitest");
-    fs::write(
-        &farmyard_vitest,
-        format!(
-            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            farmyard_marker.display()
-        ),
-    )
-    .expect("write farmyard vitest");
-    let mut farmyard_perms = fs::metadata(&farmyard_vitest).expect("stat").permissions();
-    farmyard_perms.set_mode(0o755);
-    fs::set_permissions(&farmyard_vitest, farmyard_perms).expect("chmod");
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--pretty` is only supported together with `--json`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
 
-    let dairy_vitest = dairy_bin.join("vitest");
-    fs::write(
-        &dairy_vitest,
-        format!(
-            "#!/bin/sh\nprintf called > \"{}\"\nexit 0\n",
-            dairy_marker.display()
-        ),
-    )
-    .expect("write dairy vitest");
-    let mut dairy_perms = fs::metadata(&dairy_vitest).expect("stat").permissions();
-    dairy_perms.set_mode(0o755);
-    fs::set_permissions(&dairy_vitest, dairy_perms).expect("chmod");
+#[test]
+fn run_manifest_task_builtin_catalogs_rejects_invalid_pretty_value() {
+    let root = temp_workspace("builtin-catalogs-invalid-pretty");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.root]\nrun = \"printf root\"\n",
+    );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "farmyard/test".to_owned(),
-            args: Vec::new(),
+            name: "catalogs".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--pretty".to_owned(),
+                "nope".to_owned(),
+            ],
         },
         root,
     )
-    .expect("prefixed builtin test");
+    .expect_err("expected invalid --pretty value");
 
-    assert!(out.contains("Test Results"));
-    assert!(out.contains("farmyard"));
-    assert!(!out.contains("dairy"));
-    assert!(farmyard_marker.exists(), "farmyard vitest should run");
-    assert!(!dairy_marker.exists(), "dairy vitest should not run");
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("value `nope` is invalid"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_prefixed_builtin_tasks_targets_catalog_root_only() {
-    let root = temp_workspace("builtin-tasks-prefixed-catalog");
+fn run_manifest_task_prefixed_builtin_help_is_supported() {
+    let root = temp_workspace("builtin-help-prefixed-catalog");
     let farmyard = root.join("farmyard");
-    let dairy = root.join("dairy");
     fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
-
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[tasks.root-only]
-run = "printf root"
-"#,
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
-[tasks.api]
-run = "printf farmyard-api"
-"#,
-    );
     write_manifest(
-        &dairy.join("effigy.toml"),
-        r#"[catalog]
-alias = "dairy"
-[tasks.admin]
-run = "printf dairy-admin"
+        &farmyard.join("effigy.toml"),
+        r#"[catalog]
+alias = "farmyard"
 "#,
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "farmyard/tasks".to_owned(),
+            name: "farmyard/help".to_owned(),
             args: Vec::new(),
         },
         root,
     )
-    .expect("prefixed builtin tasks");
+    .expect("prefixed builtin help");
 
-    assert!(out.contains("Catalogs"));
-    assert!(out.contains("count: 1"));
-    assert!(out.contains("api"));
-    assert!(!out.contains("admin"));
-    assert!(!out.contains("root-only"));
+    assert!(out.contains("Commands"));
+    assert!(out.contains("effigy help"));
 }
 
 #[test]
-fn run_manifest_task_relative_prefixed_builtin_tasks_target_catalog_root_only() {
-    let root = temp_workspace("builtin-tasks-relative-prefixed-catalog");
-    let dairy = root.join("dairy");
-    let froyo = root.join("froyo");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
-    fs::create_dir_all(&froyo).expect("mkdir froyo");
-
+fn run_tasks_rejects_legacy_builtin_config_group() {
+    let root = temp_workspace("reject-legacy-builtin-group");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.root-only]
-run = "printf root"
+        r#"[builtin.test]
+max_parallel = 2
 "#,
     );
+
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root.clone()),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
+
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `builtin`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_tasks_rejects_unknown_test_config_field() {
+    let root = temp_workspace("reject-unknown-test-field");
     write_manifest(
-        &froyo.join("effigy.toml"),
-        r#"[catalog]
-alias = "froyo"
-[tasks.validate]
-run = "printf froyo-validate"
+        &root.join("effigy.toml"),
+        r#"[test]
+max_parallels = 2
 "#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "../froyo/tasks".to_owned(),
-            args: Vec::new(),
-        },
-        dairy,
-    )
-    .expect("relative prefixed builtin tasks");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
-    assert!(out.contains("Catalogs"));
-    assert!(out.contains("count: 1"));
-    assert!(out.contains("validate"));
-    assert!(!out.contains("root-only"));
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `max_parallels`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_renders_diagnostics_and_resolution_probe() {
-    let root = temp_workspace("builtin-catalogs");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-
+fn run_tasks_rejects_unknown_package_manager_field() {
+    let root = temp_workspace("reject-unknown-package-manager-field");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.api]\nrun = \"printf api\"\n",
+        r#"[package_manager]
+jss = "pnpm"
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec!["--resolve".to_owned(), "farmyard/api".to_owned()],
-        },
-        root,
-    )
-    .expect("builtin catalogs");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
-    assert!(out.contains("Resolution: farmyard/api"));
-    assert!(out.contains("catalog: farmyard"));
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `jss`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_resolve_supports_managed_profile_invocation() {
-    let root = temp_workspace("builtin-catalogs-resolve-managed-profile");
+fn run_tasks_rejects_unknown_test_runner_override_field() {
+    let root = temp_workspace("reject-unknown-test-runner-override-field");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ run = "printf default-ok" }]
-
-[tasks.dev.profiles.front]
-concurrent = [{ run = "printf front-ok" }]
+        r#"[test.runners.vitest]
+cmd = "vitest run"
 "#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec!["--resolve".to_owned(), "dev front".to_owned()],
-        },
-        root,
-    )
-    .expect("builtin catalogs managed profile resolve");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
-    assert!(out.contains("Resolution: dev front"));
-    assert!(out.contains("status: ok"));
-    assert!(out.contains("catalog: root"));
-    assert!(out.contains("task: dev"));
-    assert!(out.contains("managed profile `front` resolved via invocation `dev front`"));
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `cmd`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_json_renders_probe_payload() {
-    let root = temp_workspace("builtin-catalogs-json");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-
+fn run_tasks_rejects_unknown_task_field() {
+    let root = temp_workspace("reject-unknown-task-field");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.api]\nrun = \"printf api\"\n",
+        r#"[tasks.dev]
+run = "printf dev"
+fial_on_non_zero = true
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec![
-                "--json".to_owned(),
-                "--resolve".to_owned(),
-                "farmyard/api".to_owned(),
-            ],
-        },
-        root,
-    )
-    .expect("builtin catalogs json");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
-    assert_eq!(parsed["schema"], "effigy.tasks.v1");
-    assert_eq!(parsed["schema_version"], 1);
-    assert!(parsed["catalogs"].is_array());
-    assert_eq!(parsed["resolve"]["status"], "ok");
-    assert_eq!(parsed["resolve"]["catalog"], "farmyard");
-    assert_eq!(parsed["resolve"]["task"], "api");
-    assert!(parsed["precedence"].is_array());
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error
+                .to_string()
+                .contains("unknown field `fial_on_non_zero`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_json_resolve_supports_managed_profile_invocation() {
-    let root = temp_workspace("builtin-catalogs-json-resolve-managed-profile");
+fn run_tasks_rejects_unknown_process_field() {
+    let root = temp_workspace("reject-unknown-process-field");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
-concurrent = [{ run = "printf default-ok" }]
-
-[tasks.dev.profiles.front]
-concurrent = [{ run = "printf front-ok" }]
+concurrent = [{ run = "printf api", tas = "api" }]
 "#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec![
-                "--json".to_owned(),
-                "--resolve".to_owned(),
-                "dev front".to_owned(),
-            ],
-        },
-        root,
-    )
-    .expect("builtin catalogs json managed profile resolve");
-
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
-    assert_eq!(parsed["resolve"]["selector"], "dev front");
-    assert_eq!(parsed["resolve"]["status"], "ok");
-    assert_eq!(parsed["resolve"]["catalog"], "root");
-    assert_eq!(parsed["resolve"]["task"], "dev");
-    let evidence = parsed["resolve"]["evidence"]
-        .as_array()
-        .expect("resolve evidence array")
-        .iter()
-        .filter_map(|line| line.as_str())
-        .collect::<Vec<&str>>();
-    assert!(evidence
-        .iter()
-        .any(|line| line.contains("managed profile `front` resolved via invocation `dev front`")));
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
+
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `tas`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_json_reports_resolution_errors() {
-    let root = temp_workspace("builtin-catalogs-json-error");
+fn run_tasks_rejects_legacy_managed_processes_block() {
+    let root = temp_workspace("reject-legacy-managed-processes-block");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
+        r#"[tasks.dev]
+mode = "tui"
+
+[tasks.dev.processes.api]
+run = "printf api"
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec![
-                "--json".to_owned(),
-                "--resolve".to_owned(),
-                "farmyard/api".to_owned(),
-            ],
-        },
-        root,
-    )
-    .expect("builtin catalogs json error");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
-    assert_eq!(parsed["schema"], "effigy.tasks.v1");
-    assert_eq!(parsed["schema_version"], 1);
-    assert_eq!(parsed["resolve"]["status"], "error");
-    assert_eq!(parsed["resolve"]["catalog"], serde_json::Value::Null);
-    assert!(parsed["resolve"]["error"]
-        .as_str()
-        .is_some_and(|msg| msg.contains("prefix `farmyard` not found")));
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `processes`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_json_compact_output_has_no_newlines() {
-    let root = temp_workspace("builtin-catalogs-json-compact");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+fn run_tasks_rejects_legacy_managed_profile_list_entry() {
+    let root = temp_workspace("reject-legacy-managed-profile-list-entry");
     write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.api]\nrun = \"printf api\"\n",
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+
+[tasks.dev.profiles]
+default = ["farmyard/api"]
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec![
-                "--json".to_owned(),
-                "--pretty".to_owned(),
-                "false".to_owned(),
-                "--resolve".to_owned(),
-                "farmyard/api".to_owned(),
-            ],
-        },
-        root,
-    )
-    .expect("builtin catalogs compact json");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
-    assert!(!out.contains('\n'));
-    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json parse");
-    assert_eq!(parsed["resolve"]["status"], "ok");
+    match err {
+        RunnerError::TaskManifestParse { error, .. } => {
+            let rendered = error.to_string();
+            assert!(rendered.contains("invalid type"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_pretty_requires_json() {
-    let root = temp_workspace("builtin-catalogs-pretty-requires-json");
+fn run_tasks_rejects_unknown_run_step_field() {
+    let root = temp_workspace("reject-unknown-run-step-field");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
+        r#"[tasks.reset-db]
+run = [
+  { run = "echo one", rnu = "echo two" }
+]
+"#,
     );
 
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec!["--pretty".to_owned(), "false".to_owned()],
-        },
-        root,
-    )
-    .expect_err("expected --pretty requires --json");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
     match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--pretty` is only supported together with `--json`"));
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `rnu`"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_manifest_task_builtin_catalogs_rejects_invalid_pretty_value() {
-    let root = temp_workspace("builtin-catalogs-invalid-pretty");
+fn run_tasks_rejects_unknown_catalog_field() {
+    let root = temp_workspace("reject-unknown-catalog-field");
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
+        r#"[catalog]
+alias = "farmyard"
+aliass = "dup"
+"#,
     );
 
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "catalogs".to_owned(),
-            args: vec![
-                "--json".to_owned(),
-                "--pretty".to_owned(),
-                "nope".to_owned(),
-            ],
-        },
-        root,
-    )
-    .expect_err("expected invalid --pretty value");
+    let err = run_tasks(TasksArgs {
+        repo_override: Some(root),
+        task_name: None,
+        resolve_selector: None,
+        output_json: false,
+        pretty_json: true,
+        no_ignore: false,
+    })
+    .expect_err("expected manifest parse failure");
 
     match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("value `nope` is invalid"));
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(error.to_string().contains("unknown field `aliass`"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_manifest_task_prefixed_builtin_help_is_supported() {
-    let root = temp_workspace("builtin-help-prefixed-catalog");
+fn run_doctor_executes_discovered_health_task() {
+    let root = temp_workspace("doctor-health-delegation");
     let farmyard = root.join("farmyard");
     fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+
     write_manifest(
         &farmyard.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
-"#,
+        "[catalog]\nalias = \"farmyard\"\n[tasks.health]\nrun = \"printf farmyard-health-ok\"\n",
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "farmyard/help".to_owned(),
+            name: "doctor".to_owned(),
             args: Vec::new(),
         },
         root,
     )
-    .expect("prefixed builtin help");
+    .expect("doctor run");
 
-    assert!(out.contains("Commands"));
-    assert!(out.contains("effigy help"));
+    assert!(out.contains("health.task.discovery"));
+    assert!(out.contains("health.task.execute"));
+    assert!(out.contains("health task executed successfully"));
 }
 
 #[test]
-fn run_tasks_rejects_legacy_builtin_config_group() {
-    let root = temp_workspace("reject-legacy-builtin-group");
+fn run_doctor_reports_error_when_health_task_fails() {
+    let root = temp_workspace("doctor-health-failure");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[builtin.test]
-max_parallel = 2
-"#,
+        "[tasks.health]\nrun = \"sh -lc 'printf health-failed; exit 3'\"\n",
     );
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root.clone()),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "doctor".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect_err("doctor should fail when health task fails");
 
     match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `builtin`"));
+        RunnerError::DoctorNonZero { rendered, .. } => {
+            assert!(rendered.contains("health.task.execute"));
+            assert!(rendered.contains("health task execution failed"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_tasks_rejects_unknown_test_config_field() {
-    let root = temp_workspace("reject-unknown-test-field");
+fn run_doctor_fix_scaffolds_health_task_when_missing() {
+    let root = temp_workspace("doctor-fix-scaffold-health");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[test]
-max_parallels = 2
-"#,
+        "[tasks.build]\nrun = \"printf ok\"\n",
     );
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "doctor".to_owned(),
+            args: vec!["--fix".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("doctor --fix");
+
+    let manifest = fs::read_to_string(root.join("effigy.toml")).expect("read manifest");
+    assert!(manifest.contains("health = \"printf health-check-placeholder\""));
+    assert!(out.contains("Fix Actions"));
+    assert!(out.contains("manifest.health_task_scaffold"));
+    assert!(out.contains("applied"));
+}
+
+#[test]
+fn run_doctor_fix_reports_skipped_when_manifest_invalid() {
+    let root = temp_workspace("doctor-fix-invalid-manifest");
+    fs::write(root.join("effigy.toml"), "[tasks\nbad = true\n").expect("write bad manifest");
+
+    let err = with_cwd(&root, || {
+        run_doctor(DoctorArgs {
+            repo_override: None,
+            output_json: false,
+            fix: true,
+            verbose: false,
+            explain: None,
+        })
     })
-    .expect_err("expected manifest parse failure");
+    .expect_err("doctor should still fail");
 
     match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `max_parallels`"));
+        RunnerError::DoctorNonZero { rendered, .. } => {
+            assert!(rendered.contains("Fix Actions"));
+            assert!(rendered.contains("manifest.health_task_scaffold"));
+            assert!(rendered.contains("skipped"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_tasks_rejects_unknown_package_manager_field() {
-    let root = temp_workspace("reject-unknown-package-manager-field");
+fn run_manifest_task_builtin_test_failure_keeps_rendered_results_summary() {
+    let root = temp_workspace("builtin-test-fanout-failure-summary");
+    let farmyard = root.join("farmyard");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[package_manager]
-jss = "pnpm"
-"#,
+        "[tasks.dev]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.ping]\nrun = \"printf ok\"\n",
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        "[catalog]\nalias = \"dairy\"\n[tasks.ping]\nrun = \"printf ok\"\n",
     );
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    fs::write(
+        farmyard.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write farmyard package");
+    fs::write(
+        dairy.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write dairy package");
+
+    let farmyard_bin = farmyard.join("node_modules/.bin");
+    fs::create_dir_all(&farmyard_bin).expect("mkdir farmyard bin");
+    let dairy_bin = dairy.join("node_modules/.bin");
+    fs::create_dir_all(&dairy_bin).expect("mkdir dairy bin");
+
+    let farmyard_vitest = farmyard_bin.join("vitest");
+    fs::write(&farmyard_vitest, "#!/bin/sh\nexit 1\n").expect("write farmyard vitest");
+    let mut farmyard_perms = fs::metadata(&farmyard_vitest).expect("stat").permissions();
+    farmyard_perms.set_mode(0o755);
+    fs::set_permissions(&farmyard_vitest, farmyard_perms).expect("chmod");
+
+    let dairy_vitest = dairy_bin.join("vitest");
+    fs::write(&dairy_vitest, "#!/bin/sh\nexit 0\n").expect("write dairy vitest");
+    let mut dairy_perms = fs::metadata(&dairy_vitest).expect("stat").permissions();
+    dairy_perms.set_mode(0o755);
+    fs::set_permissions(&dairy_vitest, dairy_perms).expect("chmod");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: Vec::new(),
+        },
+        root,
+    )
+    .expect_err("builtin test fanout should fail");
 
     match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `jss`"));
+        RunnerError::BuiltinTestNonZero { failures, rendered } => {
+            assert_eq!(failures, vec![("farmyard".to_owned(), Some(1))]);
+            assert!(rendered.contains("Test Results"));
+            assert!(rendered.contains("dairy"));
+            assert!(rendered.contains("ok"));
+            assert!(rendered.contains("farmyard"));
+            assert!(rendered.contains("exit=1"));
+            assert!(!rendered.contains("runner:vitest"));
+            assert!(!rendered.contains("command:"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_tasks_rejects_unknown_test_runner_override_field() {
-    let root = temp_workspace("reject-unknown-test-runner-override-field");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[test.runners.vitest]
-cmd = "vitest run"
-"#,
-    );
+fn run_manifest_task_builtin_test_failure_with_suite_filter_shows_no_match_hint() {
+    let root = temp_workspace("builtin-test-filtered-failure-hint");
+    fs::write(
+        root.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write package");
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    fs::write(&vitest, "#!/bin/sh\nexit 1\n").expect("write vitest");
+    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&vitest, perms).expect("chmod");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["vitest".to_owned(), "user-service".to_owned()],
+        },
+        root,
+    )
+    .expect_err("filtered suite run should fail");
 
     match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `cmd`"));
+        RunnerError::BuiltinTestNonZero { rendered, .. } => {
+            assert!(rendered.contains("Hint"));
+            assert!(rendered.contains("often means no tests matched"));
+            assert!(rendered.contains("vitest run 'user-service'"));
+            assert!(rendered.contains("Try again without the filter"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_tasks_rejects_unknown_task_field() {
-    let root = temp_workspace("reject-unknown-task-field");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[tasks.dev]
-run = "printf dev"
-fial_on_non_zero = true
-"#,
-    );
+fn run_manifest_task_builtin_test_verbose_results_include_runner_root_and_command() {
+    let root = temp_workspace("builtin-test-verbose-results");
+    fs::write(
+        root.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write package");
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    fs::write(&vitest, "#!/bin/sh\nexit 0\n").expect("write vitest");
+    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&vitest, perms).expect("chmod");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--verbose-results".to_owned(), "--run".to_owned()],
+        },
+        root,
+    )
+    .expect("run builtin test");
 
-    match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error
-                .to_string()
-                .contains("unknown field `fial_on_non_zero`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("Test Results"));
+    assert!(out.contains("runner:vitest"));
+    assert!(out.contains("root:"));
+    assert!(out.contains("command:vitest run '--run'"));
 }
 
 #[test]
-fn run_tasks_rejects_unknown_process_field() {
-    let root = temp_workspace("reject-unknown-process-field");
+fn run_manifest_task_builtin_test_tui_flag_falls_back_to_text_when_non_interactive() {
+    let root = temp_workspace("builtin-test-tui-fallback");
+    fs::write(
+        root.join("package.json"),
+        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    )
+    .expect("write package");
+    let local_bin = root.join("node_modules/.bin");
+    fs::create_dir_all(&local_bin).expect("mkdir local bin");
+    let vitest = local_bin.join("vitest");
+    fs::write(&vitest, "#!/bin/sh\nexit 0\n").expect("write vitest");
+    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&vitest, perms).expect("chmod");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--tui".to_owned()],
+        },
+        root,
+    )
+    .expect("run builtin test with tui flag");
+
+    assert!(out.contains("Test Results"));
+    assert!(out.contains("root"));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_plan_respects_configured_package_manager() {
+    let root = temp_workspace("builtin-test-plan-package-manager");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ run = "printf api", tas = "api" }]
+        r#"[package_manager]
+js = "pnpm"
 "#,
     );
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned()],
+        },
+        root,
+    )
+    .expect("run test --plan");
 
-    match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `tas`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("pnpm exec vitest run"));
+    assert!(out.contains("package_manager.js=pnpm"));
 }
 
 #[test]
-fn run_tasks_rejects_legacy_managed_processes_block() {
-    let root = temp_workspace("reject-legacy-managed-processes-block");
+fn run_manifest_task_builtin_test_exec_uses_configured_package_manager() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("builtin-test-exec-package-manager");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-
-[tasks.dev.processes.api]
-run = "printf api"
+        r#"[package_manager]
+js = "bun"
 "#,
     );
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let bin_dir = root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("mkdir bin");
+    let bun_stub = bin_dir.join("bun");
+    let args_log = root.join("bun-args.log");
+    fs::write(
+        &bun_stub,
+        "#!/bin/sh\nprintf \"%s\\n\" \"$@\" > \"$EFFIGY_TEST_BUN_ARGS_FILE\"\n",
+    )
+    .expect("write bun stub");
+    let mut perms = fs::metadata(&bun_stub).expect("metadata").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&bun_stub, perms).expect("chmod");
 
-    match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `processes`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), prior_path);
+    let _env = EnvGuard::set_many(&[
+        ("PATH", Some(path)),
+        ("SHELL", Some("/bin/sh".to_owned())),
+        (
+            "EFFIGY_TEST_BUN_ARGS_FILE",
+            Some(args_log.display().to_string()),
+        ),
+    ]);
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["vitest".to_owned()],
+        },
+        root,
+    )
+    .expect("run builtin test");
+
+    assert!(out.contains("Test Results"));
+    let args = fs::read_to_string(args_log).expect("read bun args");
+    assert!(
+        args.starts_with("x\nvitest\nrun\n--reporter=default\n--reporter=json\n--outputFile.json=")
+    );
 }
 
 #[test]
-fn run_tasks_rejects_legacy_managed_profile_list_entry() {
-    let root = temp_workspace("reject-legacy-managed-profile-list-entry");
+fn run_manifest_task_builtin_test_plan_respects_runner_command_override() {
+    let root = temp_workspace("builtin-test-plan-runner-override");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-
-[tasks.dev.profiles]
-default = ["farmyard/api"]
+        r#"[test.runners]
+vitest = "pnpm exec vitest run --config vitest.config.ts"
 "#,
     );
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned(), "vitest".to_owned()],
+        },
+        root,
+    )
+    .expect("run test --plan");
 
-    match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            let rendered = error.to_string();
-            assert!(rendered.contains("invalid type"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("pnpm exec vitest run --config vitest.config.ts"));
+    assert!(out.contains("test.runners.vitest command override applied"));
 }
 
 #[test]
-fn run_tasks_rejects_unknown_run_step_field() {
-    let root = temp_workspace("reject-unknown-run-step-field");
+fn run_manifest_task_builtin_test_runner_override_wins_over_package_manager() {
+    let root = temp_workspace("builtin-test-plan-override-precedence");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.reset-db]
-run = [
-  { run = "echo one", rnu = "echo two" }
-]
+        r#"[package_manager]
+js = "bun"
+
+[test.runners]
+vitest = "npx vitest run --reporter=dot"
 "#,
     );
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned(), "vitest".to_owned()],
+        },
+        root,
+    )
+    .expect("run test --plan");
 
-    match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `rnu`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("npx vitest run --reporter=dot"));
+    assert!(out.contains("package_manager.js=bun"));
+    assert!(out.contains("test.runners.vitest command override applied"));
 }
 
 #[test]
-fn run_tasks_rejects_unknown_catalog_field() {
-    let root = temp_workspace("reject-unknown-catalog-field");
+fn run_manifest_task_builtin_test_plan_applies_overrides_to_detected_runners() {
+    let root = temp_workspace("builtin-test-plan-more-runners");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
-aliass = "dup"
+        r#"[package_manager]
+js = "pnpm"
+
+[test.runners]
+pytest = "uv run pytest -q"
 "#,
     );
+    fs::write(root.join("jest.config.js"), "module.exports = {};\n").expect("write jest config");
+    fs::write(root.join("pytest.ini"), "[pytest]\n").expect("write pytest.ini");
 
-    let err = run_tasks(TasksArgs {
-        repo_override: Some(root),
-        task_name: None,
-        resolve_selector: None,
-        output_json: false,
-        pretty_json: true,
-    })
-    .expect_err("expected manifest parse failure");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned()],
+        },
+        root,
+    )
+    .expect("run test --plan");
 
-    match err {
-        RunnerError::TaskManifestParse { error, .. } => {
-            assert!(error.to_string().contains("unknown field `aliass`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("pnpm exec jest"), "{out}");
+    assert!(out.contains("uv run pytest -q"), "{out}");
+    assert!(out.contains("test.runners.pytest command override applied"));
 }
 
 #[test]
-fn run_doctor_executes_discovered_health_task() {
-    let root = temp_workspace("doctor-health-delegation");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.health]\nrun = \"printf farmyard-health-ok\"\n",
-    );
+fn run_manifest_task_builtin_config_prints_reference() {
+    let root = temp_workspace("builtin-config");
+    write_manifest(&root.join("effigy.toml"), "");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
+            name: "config".to_owned(),
             args: Vec::new(),
         },
         root,
     )
-    .expect("doctor run");
+    .expect("run config");
 
-    assert!(out.contains("health.task.discovery"));
-    assert!(out.contains("health.task.execute"));
-    assert!(out.contains("health task executed successfully"));
+    assert!(out.contains("effigy.toml Reference"));
+    assert!(out.contains("[test.runners]"));
+    assert!(out.contains("[tasks]"));
+    assert!(out.contains("task = \"test vitest \\\"user service\\\"\""));
+    assert!(out.contains(
+        "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]"
+    ));
 }
 
 #[test]
-fn run_doctor_reports_error_when_health_task_fails() {
-    let root = temp_workspace("doctor-health-failure");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.health]\nrun = \"sh -lc 'printf health-failed; exit 3'\"\n",
-    );
+fn run_manifest_task_builtin_test_plan_has_blank_line_between_sections() {
+    let root = temp_workspace("builtin-test-plan-section-spacing");
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: Vec::new(),
+            name: "test".to_owned(),
+            args: vec!["--plan".to_owned()],
         },
         root,
     )
-    .expect_err("doctor should fail when health task fails");
+    .expect("run test --plan");
 
-    match err {
-        RunnerError::DoctorNonZero { rendered, .. } => {
-            assert!(rendered.contains("health.task.execute"));
-            assert!(rendered.contains("health task execution failed"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("\n\nTarget Summary\n"));
+    assert!(out.contains("\n\nTarget: root\n"));
 }
 
 #[test]
-fn run_doctor_fix_scaffolds_health_task_when_missing() {
-    let root = temp_workspace("doctor-fix-scaffold-health");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.build]\nrun = \"printf ok\"\n",
-    );
+fn run_manifest_task_builtin_config_has_blank_line_between_sections() {
+    let root = temp_workspace("builtin-config-section-spacing");
+    write_manifest(&root.join("effigy.toml"), "");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: vec!["--fix".to_owned()],
+            name: "config".to_owned(),
+            args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect("doctor --fix");
+    .expect("run config");
 
-    let manifest = fs::read_to_string(root.join("effigy.toml")).expect("read manifest");
-    assert!(manifest.contains("health = \"printf health-check-placeholder\""));
-    assert!(out.contains("Fix Actions"));
-    assert!(out.contains("manifest.health_task_scaffold"));
-    assert!(out.contains("applied"));
+    assert!(out.contains("\n\nGlobal\n"));
+    assert!(out.contains("\n\nBuilt-in Test\n"));
+    assert!(out.contains("\n\nTasks\n"));
 }
 
 #[test]
-fn run_doctor_fix_reports_skipped_when_manifest_invalid() {
-    let root = temp_workspace("doctor-fix-invalid-manifest");
-    fs::write(root.join("effigy.toml"), "[tasks\nbad = true\n").expect("write bad manifest");
+fn run_manifest_task_builtin_config_schema_prints_canonical_template() {
+    let root = temp_workspace("builtin-config-schema");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let err = with_cwd(&root, || {
-        run_doctor(DoctorArgs {
-            repo_override: None,
-            output_json: false,
-            fix: true,
-            verbose: false,
-            explain: None,
-        })
-    })
-    .expect_err("doctor should still fail");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "config".to_owned(),
+            args: vec!["--schema".to_owned()],
+        },
+        root,
+    )
+    .expect("run config --schema");
 
-    match err {
-        RunnerError::DoctorNonZero { rendered, .. } => {
-            assert!(rendered.contains("Fix Actions"));
-            assert!(rendered.contains("manifest.health_task_scaffold"));
-            assert!(rendered.contains("skipped"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("Canonical strict-valid effigy.toml schema template"));
+    assert!(out.contains("[package_manager]"));
+    assert!(out.contains("[test.runners]"));
+    assert!(out.contains("concurrent = ["));
+    assert!(out.contains("task = \"test vitest \\\"user service\\\"\""));
+    assert!(out.contains(
+        "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]"
+    ));
+    assert!(out.contains("{ task = \"catalog-a/api\", start = 1, tab = 3 }"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_failure_keeps_rendered_results_summary() {
-    let root = temp_workspace("builtin-test-fanout-failure-summary");
-    let farmyard = root.join("farmyard");
-    let dairy = root.join("dairy");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.dev]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.ping]\nrun = \"printf ok\"\n",
-    );
-    write_manifest(
-        &dairy.join("effigy.toml"),
-        "[catalog]\nalias = \"dairy\"\n[tasks.ping]\nrun = \"printf ok\"\n",
-    );
+fn run_manifest_task_builtin_config_schema_minimal_prints_starter_template() {
+    let root = temp_workspace("builtin-config-schema-minimal");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    fs::write(
-        farmyard.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write farmyard package");
-    fs::write(
-        dairy.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "config".to_owned(),
+            args: vec!["--schema".to_owned(), "--minimal".to_owned()],
+        },
+        root,
     )
-    .expect("write dairy package");
-
-    let farmyard_bin = farmyard.join("node_modules/.bin");
-    fs::create_dir_all(&farmyard_bin).expect("mkdir farmyard bin");
-    let dairy_bin = dairy.join("node_modules/.bin");
-    fs::create_dir_all(&dairy_bin).expect("mkdir dairy bin");
+    .expect("run config --schema --minimal");
 
-    let farmyard_vitest = farmyard_bin.join("vitest");
-    fs::write(&farmyard_vitest, "#!/bin/sh\nexit 1\n").expect("write farmyard vitest");
-    let mut farmyard_perms = fs::metadata(&farmyard_vitest).expect("stat").permissions();
-    farmyard_perms.set_mode(0o755);
-    fs::set_permissions(&farmyard_vitest, farmyard_perms).expect("chmod");
+    assert!(out.contains("Minimal strict-valid effigy.toml starter"));
+    assert!(out.contains("[package_manager]"));
+    assert!(out.contains("[test.runners]"));
+    assert!(out.contains("[tasks]"));
+    assert!(!out.contains("concurrent = ["));
+}
 
-    let dairy_vitest = dairy_bin.join("vitest");
-    fs::write(&dairy_vitest, "#!/bin/sh\nexit 0\n").expect("write dairy vitest");
-    let mut dairy_perms = fs::metadata(&dairy_vitest).expect("stat").permissions();
-    dairy_perms.set_mode(0o755);
-    fs::set_permissions(&dairy_vitest, dairy_perms).expect("chmod");
+#[test]
+fn run_manifest_task_builtin_config_schema_target_prints_selected_section() {
+    let root = temp_workspace("builtin-config-schema-target");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: Vec::new(),
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "test".to_owned(),
+            ],
         },
         root,
     )
-    .expect_err("builtin test fanout should fail");
+    .expect("run config --schema --target test");
 
-    match err {
-        RunnerError::BuiltinTestNonZero { failures, rendered } => {
-            assert_eq!(failures, vec![("farmyard".to_owned(), Some(1))]);
-            assert!(rendered.contains("Test Results"));
-            assert!(rendered.contains("dairy"));
-            assert!(rendered.contains("ok"));
-            assert!(rendered.contains("farmyard"));
-            assert!(rendered.contains("exit=1"));
-            assert!(!rendered.contains("runner:vitest"));
-            assert!(!rendered.contains("command:"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("(test target)"));
+    assert!(out.contains("[test.runners]"));
+    assert!(!out.contains("[tasks]"));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_failure_with_suite_filter_shows_no_match_hint() {
-    let root = temp_workspace("builtin-test-filtered-failure-hint");
-    fs::write(
-        root.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write package");
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
-    fs::write(&vitest, "#!/bin/sh\nexit 1\n").expect("write vitest");
-    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&vitest, perms).expect("chmod");
+fn run_manifest_task_builtin_config_schema_target_tasks_includes_quoted_task_ref_examples() {
+    let root = temp_workspace("builtin-config-schema-target-tasks");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["vitest".to_owned(), "user-service".to_owned()],
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "tasks".to_owned(),
+            ],
         },
         root,
     )
-    .expect_err("filtered suite run should fail");
+    .expect("run config --schema --target tasks");
 
-    match err {
-        RunnerError::BuiltinTestNonZero { rendered, .. } => {
-            assert!(rendered.contains("Hint"));
-            assert!(rendered.contains("often means no tests matched"));
-            assert!(rendered.contains("vitest run 'user-service'"));
-            assert!(rendered.contains("Try again without the filter"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("(tasks target)"));
+    assert!(out.contains("[tasks]"));
+    assert!(out.contains("task = \"test vitest \\\"user service\\\"\""));
+    assert!(out.contains(
+        "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]"
+    ));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_verbose_results_include_runner_root_and_command() {
-    let root = temp_workspace("builtin-test-verbose-results");
-    fs::write(
-        root.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
-    )
-    .expect("write package");
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
-    fs::write(&vitest, "#!/bin/sh\nexit 0\n").expect("write vitest");
-    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&vitest, perms).expect("chmod");
+fn run_manifest_task_builtin_config_schema_target_test_runner_prints_single_runner_snippet() {
+    let root = temp_workspace("builtin-config-schema-target-test-runner");
+    write_manifest(&root.join("effigy.toml"), "");
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--verbose-results".to_owned(), "--run".to_owned()],
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "test".to_owned(),
+                "--runner".to_owned(),
+                "nextest".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run builtin test");
+    .expect("run config --schema --target test --runner nextest");
 
-    assert!(out.contains("Test Results"));
-    assert!(out.contains("runner:vitest"));
-    assert!(out.contains("root:"));
-    assert!(out.contains("command:vitest run '--run'"));
+    assert!(out.contains("(test target, runner: cargo-nextest)"));
+    assert!(out.contains("\"cargo-nextest\" = \"cargo nextest run\""));
+    assert!(!out.contains("vitest = "));
+    assert!(!out.contains("\"cargo-test\" = \"cargo test\""));
 }
 
 #[test]
-fn run_manifest_task_builtin_test_tui_flag_falls_back_to_text_when_non_interactive() {
-    let root = temp_workspace("builtin-test-tui-fallback");
-    fs::write(
-        root.join("package.json"),
-        "{ \"scripts\": { \"test\": \"vitest\" } }\n",
+fn run_manifest_task_builtin_config_schema_target_test_covers_every_runner() {
+    let root = temp_workspace("builtin-config-schema-target-test-runners");
+    write_manifest(&root.join("effigy.toml"), "");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "test".to_owned(),
+                "--runner".to_owned(),
+                "go-test".to_owned(),
+            ],
+        },
+        root.clone(),
     )
-    .expect("write package");
-    let local_bin = root.join("node_modules/.bin");
-    fs::create_dir_all(&local_bin).expect("mkdir local bin");
-    let vitest = local_bin.join("vitest");
-    fs::write(&vitest, "#!/bin/sh\nexit 0\n").expect("write vitest");
-    let mut perms = fs::metadata(&vitest).expect("stat").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&vitest, perms).expect("chmod");
+    .expect("run config --schema --target test --runner go-test");
+    assert!(out.contains("(test target, runner: go-test)"));
+    assert!(out.contains("\"go-test\" = \"go test ./...\""));
+    assert!(!out.contains("pytest = "));
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--tui".to_owned()],
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "test".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run builtin test with tui flag");
-
-    assert!(out.contains("Test Results"));
-    assert!(out.contains("root"));
+    .expect("run config --schema --target test");
+    for line in [
+        "jest = ",
+        "\"bun-test\" = \"bun test\"",
+        "pytest = ",
+        "pest = ",
+        "phpunit = \"vendor/bin/phpunit\"",
+    ] {
+        assert!(out.contains(line), "missing `{line}` in:\n{out}");
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_respects_configured_package_manager() {
-    let root = temp_workspace("builtin-test-plan-package-manager");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[package_manager]
-js = "pnpm"
-"#,
-    );
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
+fn run_manifest_task_builtin_config_target_requires_schema_flag() {
+    let root = temp_workspace("builtin-config-target-requires-schema");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned()],
+            name: "config".to_owned(),
+            args: vec!["--target".to_owned(), "test".to_owned()],
         },
         root,
     )
-    .expect("run test --plan");
+    .expect_err("expected --target precondition failure");
 
-    assert!(out.contains("pnpm exec vitest run"));
-    assert!(out.contains("package_manager.js=pnpm"));
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--target` requires `--schema`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_test_exec_uses_configured_package_manager() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("builtin-test-exec-package-manager");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[package_manager]
-js = "bun"
-"#,
-    );
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
+fn run_manifest_task_builtin_config_runner_requires_schema_flag() {
+    let root = temp_workspace("builtin-config-runner-requires-schema");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let bin_dir = root.join("bin");
-    fs::create_dir_all(&bin_dir).expect("mkdir bin");
-    let bun_stub = bin_dir.join("bun");
-    let args_log = root.join("bun-args.log");
-    fs::write(
-        &bun_stub,
-        "#!/bin/sh\nprintf \"%s\\n\" \"$@\" > \"$EFFIGY_TEST_BUN_ARGS_FILE\"\n",
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "config".to_owned(),
+            args: vec!["--runner".to_owned(), "vitest".to_owned()],
+        },
+        root,
     )
-    .expect("write bun stub");
-    let mut perms = fs::metadata(&bun_stub).expect("metadata").permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&bun_stub, perms).expect("chmod");
+    .expect_err("expected --runner precondition failure");
 
-    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), prior_path);
-    let _env = EnvGuard::set_many(&[
-        ("PATH", Some(path)),
-        ("SHELL", Some("/bin/sh".to_owned())),
-        (
-            "EFFIGY_TEST_BUN_ARGS_FILE",
-            Some(args_log.display().to_string()),
-        ),
-    ]);
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--runner` requires `--schema`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
 
-    let out = run_manifest_task_with_cwd(
+#[test]
+fn run_manifest_task_builtin_config_runner_requires_test_target() {
+    let root = temp_workspace("builtin-config-runner-requires-test-target");
+    write_manifest(&root.join("effigy.toml"), "");
+
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["vitest".to_owned()],
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "tasks".to_owned(),
+                "--runner".to_owned(),
+                "vitest".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run builtin test");
+    .expect_err("expected --runner target guard");
 
-    assert!(out.contains("Test Results"));
-    let args = fs::read_to_string(args_log).expect("read bun args");
-    assert_eq!(args, "x\nvitest\nrun\n");
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--runner` requires `--target test`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_respects_runner_command_override() {
-    let root = temp_workspace("builtin-test-plan-runner-override");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[test.runners]
-vitest = "pnpm exec vitest run --config vitest.config.ts"
-"#,
-    );
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
+fn run_manifest_task_builtin_config_rejects_invalid_runner_value() {
+    let root = temp_workspace("builtin-config-invalid-runner");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned(), "vitest".to_owned()],
+            name: "config".to_owned(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "test".to_owned(),
+                "--runner".to_owned(),
+                "mocha".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run test --plan");
+    .expect_err("expected invalid --runner failure");
 
-    assert!(out.contains("pnpm exec vitest run --config vitest.config.ts"));
-    assert!(out.contains("test.runners.vitest command override applied"));
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("invalid `--runner` value `mocha`"));
+            assert!(message.contains("jest, bun-test, cargo-nextest"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_test_runner_override_wins_over_package_manager() {
-    let root = temp_workspace("builtin-test-plan-override-precedence");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[package_manager]
-js = "bun"
-
-[test.runners]
-vitest = "npx vitest run --reporter=dot"
-"#,
-    );
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
+fn run_manifest_task_builtin_config_target_requires_value() {
+    let root = temp_workspace("builtin-config-target-requires-value");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned(), "vitest".to_owned()],
+            name: "config".to_owned(),
+            args: vec!["--schema".to_owned(), "--target".to_owned()],
         },
         root,
     )
-    .expect("run test --plan");
+    .expect_err("expected --target value failure");
 
-    assert!(out.contains("npx vitest run --reporter=dot"));
-    assert!(out.contains("package_manager.js=bun"));
-    assert!(out.contains("test.runners.vitest command override applied"));
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--target` requires a value"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_config_prints_reference() {
-    let root = temp_workspace("builtin-config");
+fn run_manifest_task_builtin_config_rejects_invalid_target_value() {
+    let root = temp_workspace("builtin-config-invalid-target");
     write_manifest(&root.join("effigy.toml"), "");
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "config".to_owned(),
-            args: Vec::new(),
+            args: vec![
+                "--schema".to_owned(),
+                "--target".to_owned(),
+                "python".to_owned(),
+            ],
         },
         root,
     )
-    .expect("run config");
+    .expect_err("expected invalid --target failure");
 
-    assert!(out.contains("effigy.toml Reference"));
-    assert!(out.contains("[test.runners]"));
-    assert!(out.contains("[tasks]"));
-    assert!(out.contains("task = \"test vitest \\\"user service\\\"\""));
-    assert!(out.contains(
-        "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]"
-    ));
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("invalid `--target` value `python`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_test_plan_has_blank_line_between_sections() {
-    let root = temp_workspace("builtin-test-plan-section-spacing");
-    fs::write(
-        root.join("package.json"),
-        r#"{
-  "devDependencies": {
-    "vitest": "^2.0.0"
-  }
-}"#,
-    )
-    .expect("write package");
+fn run_manifest_task_builtin_config_minimal_requires_schema_flag() {
+    let root = temp_workspace("builtin-config-minimal-requires-schema");
+    write_manifest(&root.join("effigy.toml"), "");
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "test".to_owned(),
-            args: vec!["--plan".to_owned()],
+            name: "config".to_owned(),
+            args: vec!["--minimal".to_owned()],
         },
         root,
     )
-    .expect("run test --plan");
+    .expect_err("expected --minimal precondition failure");
 
-    assert!(out.contains("\n\nTarget Summary\n"));
-    assert!(out.contains("\n\nTarget: root\n"));
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--minimal` requires `--schema`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_config_has_blank_line_between_sections() {
-    let root = temp_workspace("builtin-config-section-spacing");
+fn run_manifest_task_builtin_config_rejects_unknown_args() {
+    let root = temp_workspace("builtin-config-unknown-args");
     write_manifest(&root.join("effigy.toml"), "");
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "config".to_owned(),
-            args: Vec::new(),
+            args: vec!["--wat".to_owned()],
         },
         root,
     )
-    .expect("run config");
+    .expect_err("expected config argument failure");
 
-    assert!(out.contains("\n\nGlobal\n"));
-    assert!(out.contains("\n\nBuilt-in Test\n"));
-    assert!(out.contains("\n\nTasks\n"));
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("unknown argument(s) for built-in `config`: --wat"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_config_schema_prints_canonical_template() {
-    let root = temp_workspace("builtin-config-schema");
-    write_manifest(&root.join("effigy.toml"), "");
+fn builtin_test_max_parallel_reads_root_manifest_config() {
+    let root = temp_workspace("builtin-test-max-parallel-config");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[test]
+max_parallel = 1
+"#,
+    );
+    let catalogs = discover_catalogs(&root).expect("discover catalogs");
+    assert_eq!(builtin_test_max_parallel(&catalogs, &root), 1);
+}
+
+#[test]
+fn builtin_test_max_parallel_falls_back_when_invalid_or_missing() {
+    let root = temp_workspace("builtin-test-max-parallel-default");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[test]
+max_parallel = 0
+"#,
+    );
+    let catalogs = discover_catalogs(&root).expect("discover catalogs");
+    assert_eq!(builtin_test_max_parallel(&catalogs, &root), 3);
+}
+
+#[cfg(unix)]
+#[test]
+fn discover_catalogs_includes_symlinked_catalog_directories() {
+    let root = temp_workspace("catalog-symlink-discovery");
+    let external = root.join("external");
+    let underlay_src = external.join("underlay");
+    fs::create_dir_all(&underlay_src).expect("mkdir underlay src");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[catalog]
+alias = "acowtancy"
+"#,
+    );
+    write_manifest(
+        &underlay_src.join("effigy.toml"),
+        r#"[catalog]
+alias = "underlay"
+
+[tasks.ping]
+run = "printf underlay"
+"#,
+    );
+    symlink(&underlay_src, root.join("underlay")).expect("symlink underlay");
+
+    let catalogs = discover_catalogs(&root).expect("discover catalogs");
+    assert!(
+        catalogs.iter().any(|catalog| catalog.alias == "underlay"),
+        "symlinked underlay catalog should be discovered"
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--schema".to_owned()],
+            name: "underlay/ping".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect("run config --schema");
+    .expect("run symlinked prefixed task");
+    assert_eq!(out, "");
+}
 
-    assert!(out.contains("Canonical strict-valid effigy.toml schema template"));
-    assert!(out.contains("[package_manager]"));
-    assert!(out.contains("[test.runners]"));
-    assert!(out.contains("concurrent = ["));
-    assert!(out.contains("task = \"test vitest \\\"user service\\\"\""));
-    assert!(out.contains(
-        "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]"
-    ));
-    assert!(out.contains("{ task = \"catalog-a/api\", start = 1, tab = 3 }"));
+#[cfg(unix)]
+#[test]
+fn discover_catalogs_reports_alias_conflict_for_symlinked_catalog() {
+    let root = temp_workspace("catalog-symlink-alias-conflict");
+    let dairy = root.join("dairy");
+    let external = root.join("external");
+    let underlay_src = external.join("underlay");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
+    fs::create_dir_all(&underlay_src).expect("mkdir underlay src");
+
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        r#"[catalog]
+alias = "dairy"
+"#,
+    );
+    write_manifest(
+        &underlay_src.join("effigy.toml"),
+        r#"[catalog]
+alias = "dairy"
+"#,
+    );
+    symlink(&underlay_src, root.join("underlay")).expect("symlink underlay");
+
+    let err = discover_catalogs(&root).expect_err("expected alias conflict");
+    match err {
+        RunnerError::TaskCatalogAliasConflict {
+            alias,
+            first_path,
+            second_path,
+        } => {
+            assert_eq!(alias, "dairy");
+            assert!(first_path.ends_with("effigy.toml"));
+            assert!(second_path.ends_with("effigy.toml"));
+            assert_ne!(first_path, second_path);
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_builtin_config_schema_minimal_prints_starter_template() {
-    let root = temp_workspace("builtin-config-schema-minimal");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_text_output_has_blank_line_between_sections() {
+    let root = temp_workspace("doctor-section-spacing");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.health]\nrun = \"printf ok\"\n",
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--schema".to_owned(), "--minimal".to_owned()],
+            name: "doctor".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect("run config --schema --minimal");
+    .expect("run doctor");
 
-    assert!(out.contains("Minimal strict-valid effigy.toml starter"));
-    assert!(out.contains("[package_manager]"));
-    assert!(out.contains("[test.runners]"));
-    assert!(out.contains("[tasks]"));
-    assert!(!out.contains("concurrent = ["));
+    assert!(out.starts_with("Doctor's Report\n"));
+    assert!(out.contains("workspace.root-resolution"));
+    assert!(out.contains("\n\nsummary  ok:"));
+    assert!(!out.contains("\n\nRoot Resolution\n"));
 }
 
 #[test]
-fn run_manifest_task_builtin_config_schema_target_prints_selected_section() {
-    let root = temp_workspace("builtin-config-schema-target");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_explain_text_reports_resolution_selection() {
+    let root = temp_workspace("doctor-explain-selection");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.root]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.build]\nrun = \"printf farmyard\"\n",
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec![
-                "--schema".to_owned(),
-                "--target".to_owned(),
-                "test".to_owned(),
-            ],
+            name: "doctor".to_owned(),
+            args: vec!["farmyard/build".to_owned()],
         },
         root,
     )
-    .expect("run config --schema --target test");
+    .expect("doctor explain run");
 
-    assert!(out.contains("(test target)"));
-    assert!(out.contains("[test.runners]"));
-    assert!(!out.contains("[tasks]"));
+    assert!(out.contains("Doctor Explain"));
+    assert!(out.contains("selection-status: ok"));
+    assert!(out.contains("selected-catalog: farmyard"));
+    assert!(out.contains("selected-mode: explicit_prefix"));
+    assert!(out.contains("selection-reasoning:"));
+    assert!(out.contains("candidate-catalogs"));
+    assert!(out.contains("selection-evidence"));
 }
 
 #[test]
-fn run_manifest_task_builtin_config_schema_target_tasks_includes_quoted_task_ref_examples() {
-    let root = temp_workspace("builtin-config-schema-target-tasks");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_explain_text_snapshot_prefix_block_is_stable() {
+    let root = temp_workspace("doctor-explain-snapshot-prefix");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.root]\nrun = \"printf root\"\n",
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[tasks.build]\nrun = \"printf farmyard\"\n",
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
+            name: "doctor".to_owned(),
             args: vec![
-                "--schema".to_owned(),
-                "--target".to_owned(),
-                "tasks".to_owned(),
+                "farmyard/build".to_owned(),
+                "--".to_owned(),
+                "--watch".to_owned(),
             ],
         },
-        root,
+        root.clone(),
     )
-    .expect("run config --schema --target tasks");
+    .expect("doctor explain run");
 
-    assert!(out.contains("(tasks target)"));
-    assert!(out.contains("[tasks]"));
-    assert!(out.contains("task = \"test vitest \\\"user service\\\"\""));
-    assert!(out.contains(
-        "run = [{ id = \"tests\", task = \"test vitest \\\"user service\\\"\" }, { id = \"report\", run = \"printf validate-ok\", depends_on = [\"tests\"] }]"
-    ));
+    let (prefix_block, _) = out
+        .split_once("\ncandidate-catalogs:\n")
+        .expect("expected candidate-catalogs section");
+    let lines = prefix_block.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 12);
+    assert_eq!(lines[0], "Doctor Explain");
+    assert_eq!(lines[1], "──────────────");
+    assert_eq!(lines[2], "request: farmyard/build");
+    assert_eq!(lines[3], "args: -- --watch");
+    assert!(
+        lines[4].starts_with("resolved-root: "),
+        "resolved-root line changed: {}",
+        lines[4]
+    );
+    assert!(
+        lines[4].contains("doctor-explain-snapshot-prefix"),
+        "resolved-root should include workspace marker: {}",
+        lines[4]
+    );
+    assert_eq!(lines[5], "selection-status: ok");
+    assert_eq!(lines[6], "selected-catalog: farmyard");
+    assert_eq!(lines[7], "selected-mode: explicit_prefix");
+    assert_eq!(
+        lines[8],
+        "selection-reasoning: selected catalog by explicit task prefix"
+    );
+    assert_eq!(lines[9], "deferral-considered: false");
+    assert_eq!(lines[10], "deferral-selected: false");
+    assert_eq!(
+        lines[11],
+        "deferral-reasoning: deferral was not considered because the selection outcome does not trigger deferral"
+    );
 }
 
 #[test]
-fn run_manifest_task_builtin_config_schema_target_test_runner_prints_single_runner_snippet() {
-    let root = temp_workspace("builtin-config-schema-target-test-runner");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_explain_text_reports_deferral_reasoning_on_missing_task() {
+    let root = temp_workspace("doctor-explain-deferral");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[defer]\nrun = \"printf deferred\"\n",
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec![
-                "--schema".to_owned(),
-                "--target".to_owned(),
-                "test".to_owned(),
-                "--runner".to_owned(),
-                "nextest".to_owned(),
-            ],
+            name: "doctor".to_owned(),
+            args: vec!["missing-task".to_owned()],
         },
         root,
     )
-    .expect("run config --schema --target test --runner nextest");
+    .expect("doctor explain missing");
 
-    assert!(out.contains("(test target, runner: cargo-nextest)"));
-    assert!(out.contains("\"cargo-nextest\" = \"cargo nextest run\""));
-    assert!(!out.contains("vitest = "));
-    assert!(!out.contains("\"cargo-test\" = \"cargo test\""));
+    assert!(out.contains("Doctor Explain"));
+    assert!(out.contains("selection-status: error"));
+    assert!(out.contains("deferral-considered: true"));
+    assert!(out.contains("deferral-selected: true"));
+    assert!(out.contains("deferral-reasoning:"));
+    assert!(out.contains("deferral-source"));
 }
 
 #[test]
-fn run_manifest_task_builtin_config_target_requires_schema_flag() {
-    let root = temp_workspace("builtin-config-target-requires-schema");
+fn run_doctor_explain_rejects_fix_mode() {
+    let root = temp_workspace("doctor-explain-fix-invalid");
     write_manifest(&root.join("effigy.toml"), "");
 
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--target".to_owned(), "test".to_owned()],
+            name: "doctor".to_owned(),
+            args: vec!["--fix".to_owned(), "build".to_owned()],
         },
         root,
     )
-    .expect_err("expected --target precondition failure");
+    .expect_err("doctor explain --fix should fail");
 
     match err {
         RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--target` requires `--schema`"));
+            assert!(message.contains("`--fix` is not supported with explain mode"));
         }
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn run_manifest_task_builtin_config_runner_requires_schema_flag() {
-    let root = temp_workspace("builtin-config-runner-requires-schema");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_verbose_text_output_includes_per_finding_entries() {
+    let root = temp_workspace("doctor-verbose-entries");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.alpha]
+run = [{ task = "missing/task" }]
+
+[tasks.beta]
+run = [{ task = "missing/task" }]
+"#,
+    );
 
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--runner".to_owned(), "vitest".to_owned()],
+            name: "doctor".to_owned(),
+            args: vec!["--verbose".to_owned()],
         },
         root,
     )
-    .expect_err("expected --runner precondition failure");
+    .expect_err("doctor should fail for unresolved task references");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--runner` requires `--schema`"));
-        }
+    let rendered = match err {
+        RunnerError::DoctorNonZero { rendered, .. } => rendered,
         other => panic!("unexpected error: {other}"),
-    }
+    };
+
+    assert!(rendered.contains("tasks.references.resolve"));
+    assert!(rendered.contains("findings: 2"));
+    assert!(rendered.contains("entry: 1"));
+    assert!(rendered.contains("entry: 2"));
+    assert!(rendered.contains("entry-evidence"));
+    assert!(rendered.contains("entry-remediation"));
 }
 
 #[test]
-fn run_manifest_task_builtin_config_runner_requires_test_target() {
-    let root = temp_workspace("builtin-config-runner-requires-test-target");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_groups_findings_in_severity_first_order() {
+    let root = temp_workspace("doctor-severity-order");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[catalog]\nalias = \"root\"\nunknown_key = true\n",
+    );
 
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec![
-                "--schema".to_owned(),
-                "--target".to_owned(),
-                "tasks".to_owned(),
-                "--runner".to_owned(),
-                "vitest".to_owned(),
-            ],
-        },
-        root,
-    )
-    .expect_err("expected --runner target guard");
+    let err = with_cwd(&root, || {
+        run_doctor(DoctorArgs {
+            repo_override: None,
+            output_json: false,
+            fix: false,
+            verbose: false,
+            explain: None,
+        })
+    })
+    .expect_err("doctor should fail for unsupported manifest key");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--runner` requires `--target test`"));
-        }
+    let rendered = match err {
+        RunnerError::DoctorNonZero { rendered, .. } => rendered,
         other => panic!("unexpected error: {other}"),
-    }
+    };
+
+    let error_idx = rendered
+        .find("manifest.parse")
+        .or_else(|| rendered.find("manifest.schema.unsupported_key"))
+        .expect("expected error finding");
+    let warning_idx = rendered
+        .find("health.task.discovery")
+        .expect("expected warning finding");
+    let info_idx = rendered
+        .find("workspace.root-resolution")
+        .expect("expected info finding");
+
+    assert!(
+        error_idx < warning_idx,
+        "error should be rendered before warning"
+    );
+    assert!(
+        warning_idx < info_idx,
+        "warning should be rendered before info"
+    );
 }
 
 #[test]
-fn run_manifest_task_builtin_config_rejects_invalid_runner_value() {
-    let root = temp_workspace("builtin-config-invalid-runner");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_doctor_groups_same_severity_findings_in_alphabetical_order() {
+    let root = temp_workspace("doctor-same-severity-order");
+    let broken = root.join("broken");
+    fs::create_dir_all(&broken).expect("mkdir broken");
 
-    let err = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec![
-                "--schema".to_owned(),
-                "--target".to_owned(),
-                "test".to_owned(),
-                "--runner".to_owned(),
-                "jest".to_owned(),
-            ],
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[tasks.health]\nrun = \"sh -lc 'printf health-failed; exit 3'\"\n",
+    );
+    fs::write(broken.join("effigy.toml"), "[tasks\nbad = true\n").expect("write bad manifest");
+
+    let err = with_cwd(&root, || {
+        run_doctor(DoctorArgs {
+            repo_override: None,
+            output_json: false,
+            fix: false,
+            verbose: false,
+            explain: None,
+        })
+    })
+    .expect_err("doctor should fail for health execution and parse errors");
+
+    let rendered = match err {
+        RunnerError::DoctorNonZero { rendered, .. } => rendered,
+        other => panic!("unexpected error: {other}"),
+    };
+
+    let health_error_idx = rendered
+        .find("health.task.execute")
+        .expect("expected health execute error finding");
+    let parse_error_idx = rendered
+        .find("manifest.parse")
+        .expect("expected manifest parse error finding");
+
+    assert!(
+        health_error_idx < parse_error_idx,
+        "same-severity error groups should be ordered alphabetically by check_id"
+    );
+}
+
+#[test]
+fn run_doctor_text_output_snapshot_mixed_findings_and_fix_actions() {
+    let root = temp_workspace("doctor-text-snapshot-mixed");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        r#"[catalog]
+alias = "farmyard"
+
+[tasks.build]
+run = [{ task = "missing/task" }]
+"#,
+    );
+
+    let err = with_cwd(&root, || {
+        run_doctor(DoctorArgs {
+            repo_override: None,
+            output_json: false,
+            fix: true,
+            verbose: false,
+            explain: None,
+        })
+    })
+    .expect_err("doctor should fail with unresolved task reference");
+
+    let rendered = match err {
+        RunnerError::DoctorNonZero { rendered, .. } => rendered,
+        other => panic!("unexpected error: {other}"),
+    };
+
+    assert!(rendered.starts_with("Doctor's Report\n"));
+    assert!(rendered.contains("\n\nFix Actions\n"));
+    assert!(rendered.contains("status"));
+    assert!(rendered.contains("manifest.health_task_scaffold"));
+    assert!(rendered.contains("applied"));
+    assert!(rendered.contains("\n\nsummary  ok:"));
+
+    let error_idx = rendered
+        .find("tasks.references.resolve")
+        .expect("expected error check");
+    let discovery_idx = rendered
+        .find("health.task.discovery")
+        .expect("expected health discovery check");
+    let info_idx = rendered
+        .find("workspace.root-resolution")
+        .expect("expected info check");
+    assert!(error_idx < discovery_idx);
+    assert!(discovery_idx < info_idx);
+}
+
+#[test]
+fn run_manifest_task_defers_when_unprefixed_task_missing() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("defer-missing");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[defer]\nrun = \"printf deferred\"\n",
+    );
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "unknown-task".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect_err("expected invalid --runner failure");
+    .expect("deferred run should succeed");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("invalid `--runner` value `jest`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert_eq!(out, "");
 }
 
 #[test]
-fn run_manifest_task_builtin_config_target_requires_value() {
-    let root = temp_workspace("builtin-config-target-requires-value");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_manifest_task_defers_and_supports_request_and_args_tokens() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("defer-tokens");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[defer]\nrun = \"test {request} = 'unknown-task' && test {args} = '--dry-run'\"\n",
+    );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--schema".to_owned(), "--target".to_owned()],
+            name: "unknown-task".to_owned(),
+            args: vec!["--dry-run".to_owned()],
         },
         root,
     )
-    .expect_err("expected --target value failure");
+    .expect("deferred token substitution should succeed");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--target` requires a value"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert_eq!(out, "");
 }
 
 #[test]
-fn run_manifest_task_builtin_config_rejects_invalid_target_value() {
-    let root = temp_workspace("builtin-config-invalid-target");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_manifest_task_defers_for_path_like_request_when_prefix_not_found() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("defer-path-like-request");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[defer]\nrun = \"test {request} = 'services/api/dev' && test {args} = '--watch'\"\n",
+    );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec![
-                "--schema".to_owned(),
-                "--target".to_owned(),
-                "python".to_owned(),
-            ],
+            name: "services/api/dev".to_owned(),
+            args: vec!["--watch".to_owned()],
         },
         root,
     )
-    .expect_err("expected invalid --target failure");
+    .expect("path-like deferred request should succeed");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("invalid `--target` value `python`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert_eq!(out, "");
 }
 
 #[test]
-fn run_manifest_task_builtin_config_minimal_requires_schema_flag() {
-    let root = temp_workspace("builtin-config-minimal-requires-schema");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_manifest_task_defers_to_prefixed_catalog_handler() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("defer-prefixed");
+    let farmyard = root.join("farmyard");
+    fs::create_dir_all(&farmyard).expect("mkdir");
+    write_manifest(&root.join("effigy.toml"), "[defer]\nrun = \"false\"\n");
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        "[catalog]\nalias = \"farmyard\"\n[defer]\nrun = \"printf farmyard-deferred\"\n",
+    );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--minimal".to_owned()],
+            name: "farmyard/missing".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect_err("expected --minimal precondition failure");
+    .expect("prefixed deferral should succeed");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--minimal` requires `--schema`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert_eq!(out, "");
 }
 
 #[test]
-fn run_manifest_task_builtin_config_rejects_unknown_args() {
-    let root = temp_workspace("builtin-config-unknown-args");
-    write_manifest(&root.join("effigy.toml"), "");
+fn run_manifest_task_deferral_loop_guard_fails() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("defer-loop");
+    write_manifest(
+        &root.join("effigy.toml"),
+        "[defer]\nrun = \"printf deferred\"\n",
+    );
 
+    std::env::set_var("EFFIGY_DEFER_DEPTH", "1");
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "config".to_owned(),
-            args: vec!["--wat".to_owned()],
+            name: "unknown-task".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect_err("expected config argument failure");
+    .expect_err("loop guard should fail");
+    std::env::remove_var("EFFIGY_DEFER_DEPTH");
 
     match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("unknown argument(s) for built-in `config`: --wat"));
-        }
+        RunnerError::DeferLoopDetected { depth } => assert_eq!(depth, 1),
         other => panic!("unexpected error: {other}"),
     }
 }
 
 #[test]
-fn builtin_test_max_parallel_reads_root_manifest_config() {
-    let root = temp_workspace("builtin-test-max-parallel-config");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[test]
-max_parallel = 1
-"#,
-    );
-    let catalogs = discover_catalogs(&root).expect("discover catalogs");
-    assert_eq!(builtin_test_max_parallel(&catalogs, &root), 1);
-}
-
-#[test]
-fn builtin_test_max_parallel_falls_back_when_invalid_or_missing() {
-    let root = temp_workspace("builtin-test-max-parallel-default");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[test]
-max_parallel = 0
-"#,
-    );
-    let catalogs = discover_catalogs(&root).expect("discover catalogs");
-    assert_eq!(builtin_test_max_parallel(&catalogs, &root), 3);
-}
-
-#[cfg(unix)]
-#[test]
-fn discover_catalogs_includes_symlinked_catalog_directories() {
-    let root = temp_workspace("catalog-symlink-discovery");
-    let external = root.join("external");
-    let underlay_src = external.join("underlay");
-    fs::create_dir_all(&underlay_src).expect("mkdir underlay src");
-    write_manifest(
-        &root.join("effigy.toml"),
-        r#"[catalog]
-alias = "acowtancy"
-"#,
-    );
-    write_manifest(
-        &underlay_src.join("effigy.toml"),
-        r#"[catalog]
-alias = "underlay"
+fn run_manifest_task_implicitly_defers_to_root_when_no_configured_deferral() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("implicit-root-defer");
+    fs::write(root.join("effigy.json"), "{}\n").expect("write effigy marker");
+    fs::write(root.join("composer.json"), "{}\n").expect("write composer marker");
 
-[tasks.ping]
-run = "printf underlay"
-"#,
-    );
-    symlink(&underlay_src, root.join("underlay")).expect("symlink underlay");
+    let bin_dir = root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("mkdir bin");
+    let composer_stub = bin_dir.join("composer");
+    let args_log = root.join("composer-args.log");
+    fs::write(
+        &composer_stub,
+        "#!/bin/sh\nprintf \"%s\\n\" \"$@\" > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\n",
+    )
+    .expect("write composer stub");
+    let mut perms = fs::metadata(&composer_stub)
+        .expect("metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&composer_stub, perms).expect("chmod");
 
-    let catalogs = discover_catalogs(&root).expect("discover catalogs");
-    assert!(
-        catalogs.iter().any(|catalog| catalog.alias == "underlay"),
-        "symlinked underlay catalog should be discovered"
-    );
+    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), prior_path);
+    let _env = EnvGuard::set_many(&[
+        ("PATH", Some(path)),
+        ("SHELL", Some("/bin/sh".to_owned())),
+        (
+            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
+            Some(args_log.display().to_string()),
+        ),
+    ]);
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "underlay/ping".to_owned(),
-            args: Vec::new(),
+            name: "version".to_owned(),
+            args: vec!["--dry-run".to_owned()],
         },
-        root,
+        root.clone(),
     )
-    .expect("run symlinked prefixed task");
+    .expect("implicit root deferral should succeed");
+
     assert_eq!(out, "");
+    let args = fs::read_to_string(args_log).expect("read composer args");
+    assert_eq!(args, "global\nexec\neffigy\n--\nversion\n--dry-run\n");
 }
 
-#[cfg(unix)]
 #[test]
-fn discover_catalogs_reports_alias_conflict_for_symlinked_catalog() {
-    let root = temp_workspace("catalog-symlink-alias-conflict");
-    let dairy = root.join("dairy");
-    let external = root.join("external");
-    let underlay_src = external.join("underlay");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
-    fs::create_dir_all(&underlay_src).expect("mkdir underlay src");
-
-    write_manifest(
-        &dairy.join("effigy.toml"),
-        r#"[catalog]
-alias = "dairy"
-"#,
-    );
-    write_manifest(
-        &underlay_src.join("effigy.toml"),
-        r#"[catalog]
-alias = "dairy"
-"#,
-    );
-    symlink(&underlay_src, root.join("underlay")).expect("symlink underlay");
+fn run_manifest_task_does_not_implicitly_defer_without_effigy_json_marker() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("implicit-root-defer-missing-effigy-json");
+    fs::write(root.join("composer.json"), "{}\n").expect("write composer marker");
 
-    let err = discover_catalogs(&root).expect_err("expected alias conflict");
-    match err {
-        RunnerError::TaskCatalogAliasConflict {
-            alias,
-            first_path,
-            second_path,
-        } => {
-            assert_eq!(alias, "dairy");
-            assert!(first_path.ends_with("effigy.toml"));
-            assert!(second_path.ends_with("effigy.toml"));
-            assert_ne!(first_path, second_path);
-        }
-        other => panic!("unexpected error: {other}"),
-    }
-}
+    let bin_dir = root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("mkdir bin");
+    let composer_stub = bin_dir.join("composer");
+    let marker = root.join("composer-called.log");
+    fs::write(
+        &composer_stub,
+        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 0\n",
+    )
+    .expect("write composer stub");
+    let mut perms = fs::metadata(&composer_stub)
+        .expect("metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&composer_stub, perms).expect("chmod");
 
-#[test]
-fn run_doctor_text_output_has_blank_line_between_sections() {
-    let root = temp_workspace("doctor-section-spacing");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.health]\nrun = \"printf ok\"\n",
-    );
+    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), prior_path);
+    let _env = EnvGuard::set_many(&[
+        ("PATH", Some(path)),
+        ("SHELL", Some("/bin/sh".to_owned())),
+        (
+            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
+            Some(marker.display().to_string()),
+        ),
+    ]);
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
+            name: "version".to_owned(),
             args: Vec::new(),
         },
         root,
     )
-    .expect("run doctor");
+    .expect_err("implicit deferral should not run without effigy.json marker");
 
-    assert!(out.starts_with("Doctor's Report\n"));
-    assert!(out.contains("workspace.root-resolution"));
-    assert!(out.contains("\n\nsummary  ok:"));
-    assert!(!out.contains("\n\nRoot Resolution\n"));
+    match err {
+        RunnerError::TaskNotFoundAny { .. } => {}
+        other => panic!("unexpected error: {other}"),
+    }
+    assert!(
+        !marker.exists(),
+        "composer fallback should not be invoked when effigy.json is missing"
+    );
 }
 
 #[test]
-fn run_doctor_explain_text_reports_resolution_selection() {
-    let root = temp_workspace("doctor-explain-selection");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.build]\nrun = \"printf farmyard\"\n",
-    );
+fn run_manifest_task_does_not_implicitly_defer_without_composer_json_marker() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("implicit-root-defer-missing-composer-json");
+    fs::write(root.join("effigy.json"), "{}\n").expect("write effigy marker");
 
-    let out = run_manifest_task_with_cwd(
+    let bin_dir = root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("mkdir bin");
+    let composer_stub = bin_dir.join("composer");
+    let marker = root.join("composer-called.log");
+    fs::write(
+        &composer_stub,
+        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 0\n",
+    )
+    .expect("write composer stub");
+    let mut perms = fs::metadata(&composer_stub)
+        .expect("metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&composer_stub, perms).expect("chmod");
+
+    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), prior_path);
+    let _env = EnvGuard::set_many(&[
+        ("PATH", Some(path)),
+        ("SHELL", Some("/bin/sh".to_owned())),
+        (
+            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
+            Some(marker.display().to_string()),
+        ),
+    ]);
+
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: vec!["farmyard/build".to_owned()],
+            name: "version".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect("doctor explain run");
+    .expect_err("implicit deferral should not run without composer.json marker");
 
-    assert!(out.contains("Doctor Explain"));
-    assert!(out.contains("selection-status: ok"));
-    assert!(out.contains("selected-catalog: farmyard"));
-    assert!(out.contains("selected-mode: explicit_prefix"));
-    assert!(out.contains("selection-reasoning:"));
-    assert!(out.contains("candidate-catalogs"));
-    assert!(out.contains("selection-evidence"));
+    match err {
+        RunnerError::TaskNotFoundAny { .. } => {}
+        other => panic!("unexpected error: {other}"),
+    }
+    assert!(
+        !marker.exists(),
+        "composer fallback should not be invoked when composer.json is missing"
+    );
 }
 
 #[test]
-fn run_doctor_explain_text_snapshot_prefix_block_is_stable() {
-    let root = temp_workspace("doctor-explain-snapshot-prefix");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    write_manifest(
-        &root.join("effigy.toml"),
-        "[tasks.root]\nrun = \"printf root\"\n",
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[tasks.build]\nrun = \"printf farmyard\"\n",
-    );
+fn run_manifest_task_does_not_implicitly_defer_when_markers_exist_only_in_nested_directory() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("implicit-root-defer-nested-markers-only");
+    let nested = root.join("nested");
+    fs::create_dir_all(&nested).expect("mkdir nested");
+    fs::write(nested.join("effigy.json"), "{}\n").expect("write nested effigy marker");
+    fs::write(nested.join("composer.json"), "{}\n").expect("write nested composer marker");
 
-    let out = run_manifest_task_with_cwd(
+    let bin_dir = root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("mkdir bin");
+    let composer_stub = bin_dir.join("composer");
+    let marker = root.join("composer-called.log");
+    fs::write(
+        &composer_stub,
+        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 0\n",
+    )
+    .expect("write composer stub");
+    let mut perms = fs::metadata(&composer_stub)
+        .expect("metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&composer_stub, perms).expect("chmod");
+
+    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), prior_path);
+    let _env = EnvGuard::set_many(&[
+        ("PATH", Some(path)),
+        ("SHELL", Some("/bin/sh".to_owned())),
+        (
+            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
+            Some(marker.display().to_string()),
+        ),
+    ]);
+
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: vec![
-                "farmyard/build".to_owned(),
-                "--".to_owned(),
-                "--watch".to_owned(),
-            ],
+            name: "version".to_owned(),
+            args: Vec::new(),
         },
-        root.clone(),
+        root,
     )
-    .expect("doctor explain run");
+    .expect_err("implicit deferral should not run from nested marker files");
 
-    let (prefix_block, _) = out
-        .split_once("\ncandidate-catalogs:\n")
-        .expect("expected candidate-catalogs section");
-    let lines = prefix_block.lines().collect::<Vec<&str>>();
-    assert_eq!(lines.len(), 12);
-    assert_eq!(lines[0], "Doctor Explain");
-    assert_eq!(lines[1], "──────────────");
-    assert_eq!(lines[2], "request: farmyard/build");
-    assert_eq!(lines[3], "args: -- --watch");
-    assert!(
-        lines[4].starts_with("resolved-root: "),
-        "resolved-root line changed: {}",
-        lines[4]
-    );
+    match err {
+        RunnerError::TaskNotFoundAny { .. } => {}
+        other => panic!("unexpected error: {other}"),
+    }
     assert!(
-        lines[4].contains("doctor-explain-snapshot-prefix"),
-        "resolved-root should include workspace marker: {}",
-        lines[4]
-    );
-    assert_eq!(lines[5], "selection-status: ok");
-    assert_eq!(lines[6], "selected-catalog: farmyard");
-    assert_eq!(lines[7], "selected-mode: explicit_prefix");
-    assert_eq!(
-        lines[8],
-        "selection-reasoning: selected catalog by explicit task prefix"
-    );
-    assert_eq!(lines[9], "deferral-considered: false");
-    assert_eq!(lines[10], "deferral-selected: false");
-    assert_eq!(
-        lines[11],
-        "deferral-reasoning: deferral was not considered because the selection outcome does not trigger deferral"
+        !marker.exists(),
+        "composer fallback should not be invoked when markers are only nested"
     );
 }
 
 #[test]
-fn run_doctor_explain_text_reports_deferral_reasoning_on_missing_task() {
-    let root = temp_workspace("doctor-explain-deferral");
+fn run_manifest_task_explicit_deferral_wins_over_implicit_root_deferral() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("explicit-over-implicit");
+    fs::write(root.join("effigy.json"), "{}\n").expect("write effigy marker");
+    fs::write(root.join("composer.json"), "{}\n").expect("write composer marker");
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"printf deferred\"\n",
+        "[defer]\nrun = \"printf explicit\"\n",
     );
 
-    let out = run_manifest_task_with_cwd(
-        &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: vec!["missing-task".to_owned()],
-        },
-        root,
+    let bin_dir = root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("mkdir bin");
+    let composer_stub = bin_dir.join("composer");
+    let marker = root.join("composer-called.log");
+    fs::write(
+        &composer_stub,
+        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 99\n",
     )
-    .expect("doctor explain missing");
-
-    assert!(out.contains("Doctor Explain"));
-    assert!(out.contains("selection-status: error"));
-    assert!(out.contains("deferral-considered: true"));
-    assert!(out.contains("deferral-selected: true"));
-    assert!(out.contains("deferral-reasoning:"));
-    assert!(out.contains("deferral-source"));
-}
+    .expect("write composer stub");
+    let mut perms = fs::metadata(&composer_stub)
+        .expect("metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&composer_stub, perms).expect("chmod");
 
-#[test]
-fn run_doctor_explain_rejects_fix_mode() {
-    let root = temp_workspace("doctor-explain-fix-invalid");
-    write_manifest(&root.join("effigy.toml"), "");
+    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), prior_path);
+    let _env = EnvGuard::set_many(&[
+        ("PATH", Some(path)),
+        ("SHELL", Some("/bin/sh".to_owned())),
+        (
+            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
+            Some(marker.display().to_string()),
+        ),
+    ]);
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: vec!["--fix".to_owned(), "build".to_owned()],
+            name: "missing".to_owned(),
+            args: Vec::new(),
         },
         root,
     )
-    .expect_err("doctor explain --fix should fail");
+    .expect("explicit deferral should succeed");
 
-    match err {
-        RunnerError::TaskInvocation(message) => {
-            assert!(message.contains("`--fix` is not supported with explain mode"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert_eq!(out, "");
+    assert!(!marker.exists(), "composer fallback should not be invoked");
 }
 
 #[test]
-fn run_doctor_verbose_text_output_includes_per_finding_entries() {
-    let root = temp_workspace("doctor-verbose-entries");
+fn run_manifest_task_managed_tui_uses_default_profile_when_not_specified() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("managed-default-profile");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.alpha]
-run = [{ task = "missing/task" }]
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
+  { name = "front", run = "vite dev", start = 2, tab = 2 },
+  { name = "admin", run = "vite dev --config admin", start = 3, tab = 3 }
+]
 
-[tasks.beta]
-run = [{ task = "missing/task" }]
+[tasks.dev.profiles.admin]
+concurrent = [
+  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
+  { name = "admin", run = "vite dev --config admin", start = 2, tab = 2 }
+]
 "#,
     );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "doctor".to_owned(),
-            args: vec!["--verbose".to_owned()],
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
-        root,
+        root.clone(),
     )
-    .expect_err("doctor should fail for unresolved task references");
-
-    let rendered = match err {
-        RunnerError::DoctorNonZero { rendered, .. } => rendered,
-        other => panic!("unexpected error: {other}"),
-    };
+    .expect("managed plan should render");
 
-    assert!(rendered.contains("tasks.references.resolve"));
-    assert!(rendered.contains("findings: 2"));
-    assert!(rendered.contains("entry: 1"));
-    assert!(rendered.contains("entry: 2"));
-    assert!(rendered.contains("entry-evidence"));
-    assert!(rendered.contains("entry-remediation"));
+    assert!(out.contains("Managed Task Plan"));
+    assert!(out.contains("profile: default"));
+    assert!(out.contains("api"));
+    assert!(out.contains("front"));
+    assert!(out.contains("admin"));
+    assert!(out.contains("fail-on-non-zero: enabled"));
 }
 
 #[test]
-fn run_doctor_groups_findings_in_severity_first_order() {
-    let root = temp_workspace("doctor-severity-order");
+fn run_manifest_task_managed_tui_accepts_named_profile_argument() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("managed-named-profile");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
     write_manifest(
         &root.join("effigy.toml"),
-        "[catalog]\nalias = \"root\"\nunknown_key = true\n",
-    );
-
-    let err = with_cwd(&root, || {
-        run_doctor(DoctorArgs {
-            repo_override: None,
-            output_json: false,
-            fix: false,
-            verbose: false,
-            explain: None,
-        })
-    })
-    .expect_err("doctor should fail for unsupported manifest key");
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
+  { name = "front", run = "vite dev", start = 2, tab = 2 },
+  { name = "admin", run = "vite dev --config admin", start = 3, tab = 3 }
+]
 
-    let rendered = match err {
-        RunnerError::DoctorNonZero { rendered, .. } => rendered,
-        other => panic!("unexpected error: {other}"),
-    };
+[tasks.dev.profiles.admin]
+concurrent = [
+  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
+  { name = "admin", run = "vite dev --config admin", start = 2, tab = 2 }
+]
+"#,
+    );
 
-    let error_idx = rendered
-        .find("manifest.parse")
-        .or_else(|| rendered.find("manifest.schema.unsupported_key"))
-        .expect("expected error finding");
-    let warning_idx = rendered
-        .find("health.task.discovery")
-        .expect("expected warning finding");
-    let info_idx = rendered
-        .find("workspace.root-resolution")
-        .expect("expected info finding");
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "dev".to_owned(),
+            args: vec!["admin".to_owned()],
+        },
+        root,
+    )
+    .expect("managed plan should render");
 
-    assert!(
-        error_idx < warning_idx,
-        "error should be rendered before warning"
-    );
-    assert!(
-        warning_idx < info_idx,
-        "warning should be rendered before info"
-    );
+    assert!(out.contains("profile: admin"));
+    assert!(out.contains("api"));
+    assert!(out.contains("admin"));
+    assert!(!out.contains("front"));
 }
 
 #[test]
-fn run_doctor_groups_same_severity_findings_in_alphabetical_order() {
-    let root = temp_workspace("doctor-same-severity-order");
-    let broken = root.join("broken");
-    fs::create_dir_all(&broken).expect("mkdir broken");
-
+fn run_manifest_task_managed_tui_supports_concurrent_entries() {
+    let _guard = test_lock().lock().expect("lock");
+    let root = temp_workspace("managed-concurrent-entries");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
     write_manifest(
         &root.join("effigy.toml"),
-        "[tasks.health]\nrun = \"sh -lc 'printf health-failed; exit 3'\"\n",
-    );
-    fs::write(broken.join("effigy.toml"), "[tasks\nbad = true\n").expect("write bad manifest");
-
-    let err = with_cwd(&root, || {
-        run_doctor(DoctorArgs {
-            repo_override: None,
-            output_json: false,
-            fix: false,
-            verbose: false,
-            explain: None,
-        })
-    })
-    .expect_err("doctor should fail for health execution and parse errors");
-
-    let rendered = match err {
-        RunnerError::DoctorNonZero { rendered, .. } => rendered,
-        other => panic!("unexpected error: {other}"),
-    };
-
-    let health_error_idx = rendered
-        .find("health.task.execute")
-        .expect("expected health execute error finding");
-    let parse_error_idx = rendered
-        .find("manifest.parse")
-        .expect("expected manifest parse error finding");
-
-    assert!(
-        health_error_idx < parse_error_idx,
-        "same-severity error groups should be ordered alphabetically by check_id"
-    );
-}
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { task = "api", start = 1, tab = 3 },
+  { run = "printf background", start = 2, tab = 2, start_after_ms = 250 },
+  { task = "front", start = 3, tab = 1 }
+]
 
-#[test]
-fn run_doctor_text_output_snapshot_mixed_findings_and_fix_actions() {
-    let root = temp_workspace("doctor-text-snapshot-mixed");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
+[tasks.api]
+run = "printf api"
 
-[tasks.build]
-run = [{ task = "missing/task" }]
+[tasks.front]
+run = "printf front"
 "#,
     );
 
-    let err = with_cwd(&root, || {
-        run_doctor(DoctorArgs {
-            repo_override: None,
-            output_json: false,
-            fix: true,
-            verbose: false,
-            explain: None,
-        })
-    })
-    .expect_err("doctor should fail with unresolved task reference");
-
-    let rendered = match err {
-        RunnerError::DoctorNonZero { rendered, .. } => rendered,
-        other => panic!("unexpected error: {other}"),
-    };
-
-    assert!(rendered.starts_with("Doctor's Report\n"));
-    assert!(rendered.contains("\n\nFix Actions\n"));
-    assert!(rendered.contains("status"));
-    assert!(rendered.contains("manifest.health_task_scaffold"));
-    assert!(rendered.contains("applied"));
-    assert!(rendered.contains("\n\nsummary  ok:"));
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
+        },
+        root,
+    )
+    .expect("managed plan should render");
 
-    let error_idx = rendered
-        .find("tasks.references.resolve")
-        .expect("expected error check");
-    let discovery_idx = rendered
-        .find("health.task.discovery")
-        .expect("expected health discovery check");
-    let info_idx = rendered
-        .find("workspace.root-resolution")
-        .expect("expected info check");
-    assert!(error_idx < discovery_idx);
-    assert!(discovery_idx < info_idx);
+    assert!(out.contains("Managed Task Plan"));
+    assert!(out.contains("profile: default"));
+    assert!(out.contains("tab-order: front, process-2, api"));
+    assert!(out.contains("printf api"));
+    assert!(out.contains("printf background"));
+    assert!(out.contains("printf front"));
+    assert!(out.contains("250"));
 }
 
 #[test]
-fn run_manifest_task_defers_when_unprefixed_task_missing() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("defer-missing");
+fn run_manifest_task_managed_tui_rejects_concurrent_entry_with_both_task_and_run() {
+    let root = temp_workspace("managed-concurrent-invalid-entry");
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"printf deferred\"\n",
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { task = "api", run = "printf oops", start = 1, tab = 1 }
+]
+
+[tasks.api]
+run = "printf api"
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "unknown-task".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect("deferred run should succeed");
+    .expect_err("invalid concurrent entry should fail");
 
-    assert_eq!(out, "");
+    match err {
+        RunnerError::TaskManagedProcessInvalidDefinition {
+            process, detail, ..
+        } => {
+            assert_eq!(process, "api");
+            assert!(detail.contains("either `task` or `run`"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
 }
 
 #[test]
-fn run_manifest_task_defers_and_supports_request_and_args_tokens() {
+fn run_manifest_task_managed_tui_supports_profile_specific_concurrent_entries() {
     let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("defer-tokens");
+    let root = temp_workspace("managed-concurrent-profile-specific");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"test {request} = 'unknown-task' && test {args} = '--dry-run'\"\n",
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { run = "printf default-api", start = 1, tab = 2 },
+  { run = "printf default-front", start = 2, tab = 1 }
+]
+
+[tasks.dev.profiles.admin]
+concurrent = [
+  { run = "printf admin-api", start = 1, tab = 1 }
+]
+"#,
     );
 
-    let out = run_manifest_task_with_cwd(
+    let out_default = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "unknown-task".to_owned(),
-            args: vec!["--dry-run".to_owned()],
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
-        root,
+        root.clone(),
     )
-    .expect("deferred token substitution should succeed");
+    .expect("default managed plan should render");
+    assert!(out_default.contains("profile: default"));
+    assert!(out_default.contains("default-api"));
+    assert!(out_default.contains("default-front"));
+    assert!(!out_default.contains("admin-api"));
 
-    assert_eq!(out, "");
+    let out_admin = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "dev".to_owned(),
+            args: vec!["admin".to_owned()],
+        },
+        root,
+    )
+    .expect("admin managed plan should render");
+    assert!(out_admin.contains("profile: admin"));
+    assert!(out_admin.contains("admin-api"));
+    assert!(!out_admin.contains("default-front"));
 }
 
 #[test]
-fn run_manifest_task_defers_for_path_like_request_when_prefix_not_found() {
+fn run_manifest_task_managed_tui_supports_independent_tab_order() {
     let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("defer-path-like-request");
+    let root = temp_workspace("managed-tab-order");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"test {request} = 'services/api/dev' && test {args} = '--watch'\"\n",
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { name = "api", run = "printf api", start = 1, tab = 3 },
+  { name = "jobs", run = "printf jobs", start = 2, tab = 4 },
+  { name = "cream", run = "printf cream", start = 3, tab = 2 },
+  { name = "dairy", run = "printf dairy", start = 4, tab = 1 }
+]
+"#,
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "services/api/dev".to_owned(),
-            args: vec!["--watch".to_owned()],
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect("path-like deferred request should succeed");
+    .expect("managed plan should render");
 
-    assert_eq!(out, "");
+    assert!(out.contains("tab-order: dairy, cream, api, jobs"));
 }
 
 #[test]
-fn run_manifest_task_defers_to_prefixed_catalog_handler() {
+fn run_manifest_task_managed_tui_supports_ranked_tab_order_map() {
     let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("defer-prefixed");
-    let farmyard = root.join("farmyard");
-    fs::create_dir_all(&farmyard).expect("mkdir");
-    write_manifest(&root.join("effigy.toml"), "[defer]\nrun = \"false\"\n");
+    let root = temp_workspace("managed-tab-order-ranked");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
     write_manifest(
-        &farmyard.join("effigy.toml"),
-        "[catalog]\nalias = \"farmyard\"\n[defer]\nrun = \"printf farmyard-deferred\"\n",
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { name = "api", run = "printf api", start = 1, tab = 3 },
+  { name = "jobs", run = "printf jobs", start = 2, tab = 4 },
+  { name = "cream", run = "printf cream", start = 3, tab = 2 },
+  { name = "dairy", run = "printf dairy", start = 4, tab = 1 }
+]
+"#,
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "farmyard/missing".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect("prefixed deferral should succeed");
+    .expect("managed plan should render");
 
-    assert_eq!(out, "");
+    assert!(out.contains("tab-order: dairy, cream, api, jobs"));
 }
 
 #[test]
-fn run_manifest_task_deferral_loop_guard_fails() {
+fn run_manifest_task_managed_tui_supports_ranked_tab_order_map_with_task_refs() {
     let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("defer-loop");
+    let root = temp_workspace("managed-tab-order-ranked-refs");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+    let farmyard = root.join("farmyard");
+    let cream = root.join("cream");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&cream).expect("mkdir cream");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"printf deferred\"\n",
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { task = "farmyard/api", start = 1, tab = 3 },
+  { task = "farmyard/jobs", start = 2, tab = 4 },
+  { task = "cream/dev", start = 3, tab = 2 },
+  { task = "dairy/dev", start = 4, tab = 1 }
+]
+"#,
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        r#"[catalog]
+alias = "farmyard"
+[tasks.api]
+run = "printf farmyard-api"
+[tasks.jobs]
+run = "printf farmyard-jobs"
+"#,
+    );
+    write_manifest(
+        &cream.join("effigy.toml"),
+        r#"[catalog]
+alias = "cream"
+[tasks.dev]
+run = "printf cream-dev"
+"#,
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        r#"[catalog]
+alias = "dairy"
+[tasks.dev]
+run = "printf dairy-dev"
+"#,
     );
 
-    std::env::set_var("EFFIGY_DEFER_DEPTH", "1");
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "unknown-task".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect_err("loop guard should fail");
-    std::env::remove_var("EFFIGY_DEFER_DEPTH");
+    .expect("managed plan should render");
 
-    match err {
-        RunnerError::DeferLoopDetected { depth } => assert_eq!(depth, 1),
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("tab-order: dairy/dev, cream/dev, farmyard/api, farmyard/jobs"));
 }
 
 #[test]
-fn run_manifest_task_implicitly_defers_to_root_when_no_configured_deferral() {
+fn run_manifest_task_managed_tui_supports_single_definition_ordered_profile_entries() {
     let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("implicit-root-defer");
-    fs::write(root.join("effigy.json"), "{}\n").expect("write effigy marker");
-    fs::write(root.join("composer.json"), "{}\n").expect("write composer marker");
-
-    let bin_dir = root.join("bin");
-    fs::create_dir_all(&bin_dir).expect("mkdir bin");
-    let composer_stub = bin_dir.join("composer");
-    let args_log = root.join("composer-args.log");
-    fs::write(
-        &composer_stub,
-        "#!/bin/sh\nprintf \"%s\\n\" \"$@\" > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\n",
-    )
-    .expect("write composer stub");
-    let mut perms = fs::metadata(&composer_stub)
-        .expect("metadata")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&composer_stub, perms).expect("chmod");
+    let root = temp_workspace("managed-single-definition-ordered-profile");
+    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+    let farmyard = root.join("farmyard");
+    let cream = root.join("cream");
+    let dairy = root.join("dairy");
+    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
+    fs::create_dir_all(&cream).expect("mkdir cream");
+    fs::create_dir_all(&dairy).expect("mkdir dairy");
 
-    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), prior_path);
-    let _env = EnvGuard::set_many(&[
-        ("PATH", Some(path)),
-        ("SHELL", Some("/bin/sh".to_owned())),
-        (
-            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
-            Some(args_log.display().to_string()),
-        ),
-    ]);
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  { task = "farmyard/api", start = 1, tab = 3 },
+  { task = "farmyard/jobs", start = 2, tab = 4, start_after_ms = 1200 },
+  { task = "cream/dev", start = 3, tab = 2 },
+  { task = "dairy/dev", start = 4, tab = 1 }
+]
+"#,
+    );
+    write_manifest(
+        &farmyard.join("effigy.toml"),
+        r#"[catalog]
+alias = "farmyard"
+[tasks.api]
+run = "printf farmyard-api"
+[tasks.jobs]
+run = "printf farmyard-jobs"
+"#,
+    );
+    write_manifest(
+        &cream.join("effigy.toml"),
+        r#"[catalog]
+alias = "cream"
+[tasks.dev]
+run = "printf cream-dev"
+"#,
+    );
+    write_manifest(
+        &dairy.join("effigy.toml"),
+        r#"[catalog]
+alias = "dairy"
+[tasks.dev]
+run = "printf dairy-dev"
+"#,
+    );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "version".to_owned(),
-            args: vec!["--dry-run".to_owned()],
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
-        root.clone(),
+        root,
     )
-    .expect("implicit root deferral should succeed");
+    .expect("managed plan should render");
 
-    assert_eq!(out, "");
-    let args = fs::read_to_string(args_log).expect("read composer args");
-    assert_eq!(args, "global\nexec\neffigy\n--\nversion\n--dry-run\n");
+    assert!(out.contains("tab-order: dairy/dev, cream/dev, farmyard/api, farmyard/jobs"));
+    assert!(out.contains("start-after-ms"));
+    assert!(out.contains("1200"));
 }
 
 #[test]
-fn run_manifest_task_does_not_implicitly_defer_without_effigy_json_marker() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("implicit-root-defer-missing-effigy-json");
-    fs::write(root.join("composer.json"), "{}\n").expect("write composer marker");
-
-    let bin_dir = root.join("bin");
-    fs::create_dir_all(&bin_dir).expect("mkdir bin");
-    let composer_stub = bin_dir.join("composer");
-    let marker = root.join("composer-called.log");
-    fs::write(
-        &composer_stub,
-        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 0\n",
-    )
-    .expect("write composer stub");
-    let mut perms = fs::metadata(&composer_stub)
-        .expect("metadata")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&composer_stub, perms).expect("chmod");
-
-    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), prior_path);
-    let _env = EnvGuard::set_many(&[
-        ("PATH", Some(path)),
-        ("SHELL", Some("/bin/sh".to_owned())),
-        (
-            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
-            Some(marker.display().to_string()),
-        ),
-    ]);
+fn run_manifest_task_managed_tui_errors_when_concurrent_entry_missing_task_and_run() {
+    let root = temp_workspace("managed-tab-order-invalid");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ name = "jobs" }]
+"#,
+    );
 
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "version".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect_err("implicit deferral should not run without effigy.json marker");
+    .expect_err("invalid concurrent entry should fail");
 
     match err {
-        RunnerError::TaskNotFoundAny { .. } => {}
+        RunnerError::TaskManagedProcessInvalidDefinition {
+            task,
+            process,
+            detail,
+        } => {
+            assert_eq!(task, "dev");
+            assert_eq!(process, "jobs");
+            assert!(detail.contains("missing both `task` and `run`"));
+        }
         other => panic!("unexpected error: {other}"),
     }
-    assert!(
-        !marker.exists(),
-        "composer fallback should not be invoked when effigy.json is missing"
-    );
 }
 
 #[test]
-fn run_manifest_task_does_not_implicitly_defer_without_composer_json_marker() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("implicit-root-defer-missing-composer-json");
-    fs::write(root.join("effigy.json"), "{}\n").expect("write effigy marker");
-
-    let bin_dir = root.join("bin");
-    fs::create_dir_all(&bin_dir).expect("mkdir bin");
-    let composer_stub = bin_dir.join("composer");
-    let marker = root.join("composer-called.log");
-    fs::write(
-        &composer_stub,
-        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 0\n",
-    )
-    .expect("write composer stub");
-    let mut perms = fs::metadata(&composer_stub)
-        .expect("metadata")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&composer_stub, perms).expect("chmod");
-
-    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), prior_path);
-    let _env = EnvGuard::set_many(&[
-        ("PATH", Some(path)),
-        ("SHELL", Some("/bin/sh".to_owned())),
-        (
-            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
-            Some(marker.display().to_string()),
+fn run_manifest_task_managed_tui_renders_ports_and_interpolates_run() {
+    let root = temp_workspace("managed-ports-render");
+    let port = free_local_port();
+    write_manifest(
+        &root.join("effigy.toml"),
+        &format!(
+            r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  {{ name = "api", run = "serve --port {{port.API_PORT}}", ports = {{ API_PORT = {port} }} }},
+  {{ name = "web", run = "vite --proxy http://localhost:{{port.API_PORT}}" }}
+]
+"#
         ),
-    ]);
+    );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "version".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect_err("implicit deferral should not run without composer.json marker");
+    .expect("managed plan should render");
 
-    match err {
-        RunnerError::TaskNotFoundAny { .. } => {}
-        other => panic!("unexpected error: {other}"),
-    }
-    assert!(
-        !marker.exists(),
-        "composer fallback should not be invoked when composer.json is missing"
-    );
+    assert!(out.contains("ports"));
+    assert!(out.contains(&format!("serve --port {port}")));
+    assert!(out.contains(&format!("vite --proxy http://localhost:{port}")));
+    assert!(out.contains(&format!("API_PORT={port}")));
 }
 
 #[test]
-fn run_manifest_task_does_not_implicitly_defer_when_markers_exist_only_in_nested_directory() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("implicit-root-defer-nested-markers-only");
-    let nested = root.join("nested");
-    fs::create_dir_all(&nested).expect("mkdir nested");
-    fs::write(nested.join("effigy.json"), "{}\n").expect("write nested effigy marker");
-    fs::write(nested.join("composer.json"), "{}\n").expect("write nested composer marker");
-
-    let bin_dir = root.join("bin");
-    fs::create_dir_all(&bin_dir).expect("mkdir bin");
-    let composer_stub = bin_dir.join("composer");
-    let marker = root.join("composer-called.log");
-    fs::write(
-        &composer_stub,
-        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 0\n",
-    )
-    .expect("write composer stub");
-    let mut perms = fs::metadata(&composer_stub)
-        .expect("metadata")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&composer_stub, perms).expect("chmod");
-
-    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), prior_path);
-    let _env = EnvGuard::set_many(&[
-        ("PATH", Some(path)),
-        ("SHELL", Some("/bin/sh".to_owned())),
-        (
-            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
-            Some(marker.display().to_string()),
+fn run_manifest_task_managed_tui_errors_when_fixed_port_is_in_use() {
+    let root = temp_workspace("managed-ports-in-use");
+    let listener = std::net::TcpListener::bind(("0.0.0.0", 0)).expect("bind listener");
+    let port = listener.local_addr().expect("local addr").port();
+    write_manifest(
+        &root.join("effigy.toml"),
+        &format!(
+            r#"[tasks.dev]
+mode = "tui"
+concurrent = [{{ name = "api", run = "serve", ports = {{ API_PORT = {port} }} }}]
+"#
         ),
-    ]);
+    );
 
     let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "version".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect_err("implicit deferral should not run from nested marker files");
+    .expect_err("busy fixed port should fail");
 
     match err {
-        RunnerError::TaskNotFoundAny { .. } => {}
+        RunnerError::TaskManagedPortUnavailable {
+            task,
+            process,
+            name,
+            port: reported,
+            detail,
+        } => {
+            assert_eq!(task, "dev");
+            assert_eq!(process, "api");
+            assert_eq!(name, "API_PORT");
+            assert_eq!(reported, port);
+            assert!(detail.contains("auto = true"));
+        }
         other => panic!("unexpected error: {other}"),
     }
-    assert!(
-        !marker.exists(),
-        "composer fallback should not be invoked when markers are only nested"
-    );
+    drop(listener);
 }
 
 #[test]
-fn run_manifest_task_explicit_deferral_wins_over_implicit_root_deferral() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("explicit-over-implicit");
-    fs::write(root.join("effigy.json"), "{}\n").expect("write effigy marker");
-    fs::write(root.join("composer.json"), "{}\n").expect("write composer marker");
+fn run_manifest_task_managed_tui_auto_port_skips_busy_and_claimed_ports() {
+    let root = temp_workspace("managed-ports-auto");
+    let listener = std::net::TcpListener::bind(("0.0.0.0", 0)).expect("bind listener");
+    let port = listener.local_addr().expect("local addr").port();
     write_manifest(
         &root.join("effigy.toml"),
-        "[defer]\nrun = \"printf explicit\"\n",
+        &format!(
+            r#"[tasks.dev]
+mode = "tui"
+concurrent = [
+  {{ name = "api", run = "serve", ports = {{ API_PORT = {{ port = {port}, auto = true }} }} }},
+  {{ name = "admin", run = "serve", ports = {{ ADMIN_PORT = {{ port = {port}, auto = true }} }} }}
+]
+"#
+        ),
     );
 
-    let bin_dir = root.join("bin");
-    fs::create_dir_all(&bin_dir).expect("mkdir bin");
-    let composer_stub = bin_dir.join("composer");
-    let marker = root.join("composer-called.log");
-    fs::write(
-        &composer_stub,
-        "#!/bin/sh\nprintf called > \"$EFFIGY_TEST_COMPOSER_ARGS_FILE\"\nexit 99\n",
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
+        },
+        root,
     )
-    .expect("write composer stub");
-    let mut perms = fs::metadata(&composer_stub)
-        .expect("metadata")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&composer_stub, perms).expect("chmod");
+    .expect("managed plan should render");
 
-    let prior_path = std::env::var("PATH").ok().unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), prior_path);
-    let _env = EnvGuard::set_many(&[
-        ("PATH", Some(path)),
-        ("SHELL", Some("/bin/sh".to_owned())),
-        (
-            "EFFIGY_TEST_COMPOSER_ARGS_FILE",
-            Some(marker.display().to_string()),
-        ),
-    ]);
+    assert!(!out.contains(&format!("API_PORT={port}")));
+    assert!(!out.contains(&format!("ADMIN_PORT={port}")));
+    let api = extract_port(&out, "API_PORT=");
+    let admin = extract_port(&out, "ADMIN_PORT=");
+    assert!(api > port);
+    assert!(admin > port);
+    assert_ne!(api, admin);
+    drop(listener);
+}
 
-    let out = run_manifest_task_with_cwd(
+#[test]
+fn run_manifest_task_managed_tui_errors_for_unknown_port_placeholder() {
+    let root = temp_workspace("managed-ports-unknown-placeholder");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[tasks.dev]
+mode = "tui"
+concurrent = [{ name = "web", run = "vite --port {port.WEB_PORT}" }]
+"#,
+    );
+
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "missing".to_owned(),
-            args: Vec::new(),
+            name: "dev".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect("explicit deferral should succeed");
+    .expect_err("unknown port placeholder should fail");
 
-    assert_eq!(out, "");
-    assert!(!marker.exists(), "composer fallback should not be invoked");
+    match err {
+        RunnerError::TaskManagedProcessInvalidDefinition {
+            process, detail, ..
+        } => {
+            assert_eq!(process, "web");
+            assert!(detail.contains("{port.WEB_PORT}"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_managed_tui_validates_stop_and_depends_on() {
+    let cases = [
+        (
+            r#"{ name = "api", run = "api", stop = { signal = "SIGNOPE" } }"#,
+            "api",
+            "`SIGNOPE` is not a known signal",
+        ),
+        (
+            r#"{ name = "db", run = "db", stop = { run = "  " } }"#,
+            "db",
+            "`stop.run` must not be empty",
+        ),
+        (
+            r#"{ name = "api", run = "api", watch = { include = ["src/**"], restart = false } }"#,
+            "api",
+            "always restarts the process",
+        ),
+        (
+            r#"{ name = "api", run = "api", depends_on = ["cache"] }"#,
+            "api",
+            "unknown process `cache`",
+        ),
+        (
+            r#"{ name = "api", run = "api", depends_on = ["db"] }, { name = "db", run = "db", depends_on = ["api"] }"#,
+            "api",
+            "cycle involving `api`, `db`",
+        ),
+    ];
+    for (index, (entries, expected_process, expected_detail)) in cases.into_iter().enumerate() {
+        let root = temp_workspace(&format!("managed-stop-validation-{index}"));
+        write_manifest(
+            &root.join("effigy.toml"),
+            &format!("[tasks.dev]\nmode = \"tui\"\nconcurrent = [{entries}]\n"),
+        );
+
+        let err = run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "dev".to_owned(),
+                args: vec!["--repo".to_owned(), root.display().to_string()],
+            },
+            root,
+        )
+        .expect_err("invalid stop definition should fail");
+
+        match err {
+            RunnerError::TaskManagedProcessInvalidDefinition {
+                process, detail, ..
+            } => {
+                assert_eq!(process, expected_process);
+                assert!(detail.contains(expected_detail), "{detail}");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
 }
 
 #[test]
-fn run_manifest_task_managed_tui_uses_default_profile_when_not_specified() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-default-profile");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+fn run_manifest_task_managed_tui_accepts_stop_command_with_signal() {
+    let root = temp_workspace("managed-stop-run-and-signal");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
 concurrent = [
-  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
-  { name = "front", run = "vite dev", start = 2, tab = 2 },
-  { name = "admin", run = "vite dev --config admin", start = 3, tab = 3 }
-]
-
-[tasks.dev.profiles.admin]
-concurrent = [
-  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
-  { name = "admin", run = "vite dev --config admin", start = 2, tab = 2 }
+  { name = "db", run = "docker compose up db", stop = { signal = "SIGINT", timeout_ms = 10000, run = "docker compose stop db" } }
 ]
 "#,
     );
@@ -5064,37 +6797,23 @@ concurrent = [
             name: "dev".to_owned(),
             args: vec!["--repo".to_owned(), root.display().to_string()],
         },
-        root.clone(),
+        root,
     )
-    .expect("managed plan should render");
+    .expect("stop with both `signal` and `run` should be accepted");
 
-    assert!(out.contains("Managed Task Plan"));
-    assert!(out.contains("profile: default"));
-    assert!(out.contains("api"));
-    assert!(out.contains("front"));
-    assert!(out.contains("admin"));
-    assert!(out.contains("fail-on-non-zero: enabled"));
+    assert!(out.contains("db"), "{out}");
 }
 
 #[test]
-fn run_manifest_task_managed_tui_accepts_named_profile_argument() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-named-profile");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+fn run_manifest_task_managed_tui_runs_watched_processes_under_watch_restart() {
+    let root = temp_workspace("managed-watch-entry");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
 concurrent = [
-  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
-  { name = "front", run = "vite dev", start = 2, tab = 2 },
-  { name = "admin", run = "vite dev --config admin", start = 3, tab = 3 }
-]
-
-[tasks.dev.profiles.admin]
-concurrent = [
-  { name = "api", run = "cargo run -p api", start = 1, tab = 1 },
-  { name = "admin", run = "vite dev --config admin", start = 2, tab = 2 }
+  { name = "api", run = "serve --port 1", watch = { include = ["src/**"], debounce_ms = 250 }, stop = { signal = "INT" } },
+  { name = "web", run = "web" }
 ]
 "#,
     );
@@ -5102,187 +6821,252 @@ concurrent = [
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "dev".to_owned(),
-            args: vec!["admin".to_owned()],
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
     .expect("managed plan should render");
 
-    assert!(out.contains("profile: admin"));
-    assert!(out.contains("api"));
-    assert!(out.contains("admin"));
-    assert!(!out.contains("front"));
+    assert!(out.contains(
+        "watch --owner effigy --restart --include 'src/**' --debounce-ms 250 --signal SIGINT --run 'serve --port 1'"
+    ));
+    assert!(!out.contains("--run 'web'"));
 }
 
 #[test]
-fn run_manifest_task_managed_tui_supports_concurrent_entries() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-concurrent-entries");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+fn run_manifest_task_managed_tui_strips_record_flag_from_profile_args() {
+    let root = temp_workspace("managed-record-flag");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
-concurrent = [
-  { task = "api", start = 1, tab = 3 },
-  { run = "printf background", start = 2, tab = 2, start_after_ms = 250 },
-  { task = "front", start = 3, tab = 1 }
-]
-
-[tasks.api]
-run = "printf api"
-
-[tasks.front]
-run = "printf front"
+concurrent = [{ name = "api", run = "serve" }]
 "#,
     );
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "dev".to_owned(),
-            args: vec!["--repo".to_owned(), root.display().to_string()],
+            args: vec![
+                "--repo".to_owned(),
+                root.display().to_string(),
+                "--record".to_owned(),
+                "session.effigy-rec".to_owned(),
+            ],
         },
-        root,
+        root.clone(),
     )
     .expect("managed plan should render");
+    assert!(out.contains("default"), "{out}");
+    assert!(!out.contains("--record"), "{out}");
 
-    assert!(out.contains("Managed Task Plan"));
-    assert!(out.contains("profile: default"));
-    assert!(out.contains("tab-order: front, process-2, api"));
-    assert!(out.contains("printf api"));
-    assert!(out.contains("printf background"));
-    assert!(out.contains("printf front"));
-    assert!(out.contains("250"));
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "dev".to_owned(),
+            args: vec![
+                "--repo".to_owned(),
+                root.display().to_string(),
+                "--record".to_owned(),
+            ],
+        },
+        root,
+    )
+    .expect_err("--record without a path should fail");
+    assert!(err.to_string().contains("--record requires a file path"));
 }
 
 #[test]
-fn run_manifest_task_managed_tui_rejects_concurrent_entry_with_both_task_and_run() {
-    let root = temp_workspace("managed-concurrent-invalid-entry");
+fn builtin_replay_reports_missing_recording() {
+    let root = temp_workspace("replay-missing");
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "replay".to_owned(),
+            args: vec![
+                "missing.effigy-rec".to_owned(),
+                "--speed".to_owned(),
+                "2".to_owned(),
+            ],
+        },
+        root,
+    )
+    .expect_err("missing recording should fail");
+    match err {
+        RunnerError::SessionRecordingIo { path, .. } => {
+            assert!(path.ends_with("missing.effigy-rec"), "{}", path.display());
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_managed_tui_renders_limits_with_task_defaults() {
+    let root = temp_workspace("managed-limits-render");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
+limits = { memory_mb = 2048, cpu_seconds = 600 }
 concurrent = [
-  { task = "api", run = "printf oops", start = 1, tab = 1 }
+  { name = "api", run = "serve", limits = { memory_mb = 512, nofile = 4096 } },
+  { name = "web", run = "vite" }
 ]
-
-[tasks.api]
-run = "printf api"
 "#,
     );
 
-    let err = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "dev".to_owned(),
             args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect_err("invalid concurrent entry should fail");
+    .expect("managed plan should render");
 
-    match err {
-        RunnerError::TaskManagedProcessInvalidDefinition {
-            process, detail, ..
-        } => {
-            assert_eq!(process, "api");
-            assert!(detail.contains("either `task` or `run`"));
-        }
-        other => panic!("unexpected error: {other}"),
-    }
+    assert!(out.contains("limits"));
+    assert!(out.contains("memory=512MB, cpu=600s, nofile=4096"));
+    assert!(out.contains("memory=2048MB, cpu=600s"));
 }
 
 #[test]
-fn run_manifest_task_managed_tui_supports_profile_specific_concurrent_entries() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-concurrent-profile-specific");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+fn run_manifest_task_managed_tui_renders_profile_grid_layout() {
+    let root = temp_workspace("managed-layout-render");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
 concurrent = [
-  { run = "printf default-api", start = 1, tab = 2 },
-  { run = "printf default-front", start = 2, tab = 1 }
+  { name = "api", run = "serve" },
+  { name = "web", run = "vite" }
 ]
 
-[tasks.dev.profiles.admin]
+[tasks.dev.profiles.full]
+layout = "grid"
+panes = [["api", "web"], ["worker"]]
 concurrent = [
-  { run = "printf admin-api", start = 1, tab = 1 }
+  { name = "api", run = "serve" },
+  { name = "web", run = "vite" },
+  { name = "worker", run = "work" }
 ]
 "#,
     );
 
-    let out_default = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "dev".to_owned(),
-            args: vec!["--repo".to_owned(), root.display().to_string()],
+            args: vec![
+                "full".to_owned(),
+                "--repo".to_owned(),
+                root.display().to_string(),
+            ],
         },
         root.clone(),
     )
-    .expect("default managed plan should render");
-    assert!(out_default.contains("profile: default"));
-    assert!(out_default.contains("default-api"));
-    assert!(out_default.contains("default-front"));
-    assert!(!out_default.contains("admin-api"));
+    .expect("managed plan should render");
+    assert!(out.contains("grid (panes: api, web | worker)"));
 
-    let out_admin = run_manifest_task_with_cwd(
+    let out = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "dev".to_owned(),
-            args: vec!["admin".to_owned()],
+            args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect("admin managed plan should render");
-    assert!(out_admin.contains("profile: admin"));
-    assert!(out_admin.contains("admin-api"));
-    assert!(!out_admin.contains("default-front"));
+    .expect("managed plan should render");
+    assert!(out.contains("tabs"));
+    assert!(!out.contains("panes:"));
 }
 
 #[test]
-fn run_manifest_task_managed_tui_supports_independent_tab_order() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-tab-order");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+fn run_manifest_task_managed_tui_rejects_invalid_panes() {
+    for (panes, expected) in [
+        (
+            r#"[["api", "missing"]]"#,
+            "pane `missing` does not match a process",
+        ),
+        (
+            r#"[["api"], ["api"]]"#,
+            "pane `api` is listed more than once",
+        ),
+        (
+            r#"[["api", "web"], ["a", "b", "c"]]"#,
+            "at most 4 are supported",
+        ),
+        (r#"[[]]"#, "rows must not be empty"),
+    ] {
+        let root = temp_workspace("managed-layout-invalid");
+        write_manifest(
+            &root.join("effigy.toml"),
+            &format!(
+                r#"[tasks.dev]
+mode = "tui"
+layout = "grid"
+panes = {panes}
+concurrent = [
+  {{ name = "api", run = "serve" }},
+  {{ name = "web", run = "vite" }},
+  {{ name = "a", run = "a" }},
+  {{ name = "b", run = "b" }},
+  {{ name = "c", run = "c" }}
+]
+"#
+            ),
+        );
+
+        let err = run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "dev".to_owned(),
+                args: vec!["--repo".to_owned(), root.display().to_string()],
+            },
+            root,
+        )
+        .expect_err("invalid panes should fail");
+        match err {
+            RunnerError::TaskManagedProfileLayoutInvalid { detail, .. } => {
+                assert!(detail.contains(expected), "{detail}");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
+
+#[test]
+fn run_manifest_task_managed_tui_rejects_unknown_layout() {
+    let root = temp_workspace("managed-layout-unknown");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
-concurrent = [
-  { name = "api", run = "printf api", start = 1, tab = 3 },
-  { name = "jobs", run = "printf jobs", start = 2, tab = 4 },
-  { name = "cream", run = "printf cream", start = 3, tab = 2 },
-  { name = "dairy", run = "printf dairy", start = 4, tab = 1 }
-]
+layout = "mosaic"
+concurrent = [{ name = "api", run = "serve" }]
 "#,
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
             name: "dev".to_owned(),
             args: vec!["--repo".to_owned(), root.display().to_string()],
         },
         root,
     )
-    .expect("managed plan should render");
-
-    assert!(out.contains("tab-order: dairy, cream, api, jobs"));
+    .expect_err("unknown layout should fail");
+    assert!(err
+        .to_string()
+        .contains("unsupported layout `mosaic`; expected `tabs` or `grid`"));
 }
 
 #[test]
-fn run_manifest_task_managed_tui_supports_ranked_tab_order_map() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-tab-order-ranked");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
+fn run_manifest_task_managed_tui_accepts_tui_key_bindings() {
+    let root = temp_workspace("managed-tui-keys");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
+        r#"[tui.keys]
+preset = "vim"
+help = ["?", "f1"]
+stats = []
+
+[tasks.dev]
 mode = "tui"
-concurrent = [
-  { name = "api", run = "printf api", start = 1, tab = 3 },
-  { name = "jobs", run = "printf jobs", start = 2, tab = 4 },
-  { name = "cream", run = "printf cream", start = 3, tab = 2 },
-  { name = "dairy", run = "printf dairy", start = 4, tab = 1 }
-]
+concurrent = [{ name = "api", run = "serve" }]
 "#,
     );
 
@@ -5291,62 +7075,68 @@ concurrent = [
             name: "dev".to_owned(),
             args: vec!["--repo".to_owned(), root.display().to_string()],
         },
-        root,
+        root.clone(),
     )
     .expect("managed plan should render");
+    assert!(out.contains("api"));
 
-    assert!(out.contains("tab-order: dairy, cream, api, jobs"));
+    let catalogs = discover_catalogs(&root).expect("discover catalogs");
+    let manifest_only = KeyMap::from_config(
+        Some("vim"),
+        &[
+            ("help".to_owned(), vec!["?".to_owned(), "f1".to_owned()]),
+            ("stats".to_owned(), Vec::new()),
+        ],
+    )
+    .expect("expected keymap");
+    assert_eq!(
+        catalog_tui_keymap(&catalogs[0], None).expect("resolve keymap"),
+        manifest_only
+    );
+
+    let config_dir = root.join("user-config");
+    fs::create_dir_all(&config_dir).expect("mkdir config dir");
+    fs::write(
+        config_dir.join("tui.toml"),
+        "[tui.keys]\npreset = \"emacs\"\nhelp = \"f2\"\n",
+    )
+    .expect("write user tui config");
+    let with_user = KeyMap::from_config(
+        Some("emacs"),
+        &[
+            ("help".to_owned(), vec!["f2".to_owned()]),
+            ("stats".to_owned(), Vec::new()),
+        ],
+    )
+    .expect("expected keymap");
+    assert_eq!(
+        catalog_tui_keymap(&catalogs[0], Some(&config_dir)).expect("resolve keymap"),
+        with_user
+    );
 }
 
 #[test]
-fn run_manifest_task_managed_tui_supports_ranked_tab_order_map_with_task_refs() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-tab-order-ranked-refs");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
-    let farmyard = root.join("farmyard");
-    let cream = root.join("cream");
-    let dairy = root.join("dairy");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&cream).expect("mkdir cream");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
+fn run_manifest_task_managed_tui_reads_user_tui_config_only_at_launch() {
+    let root = temp_workspace("managed-tui-keys-user-config");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
 mode = "tui"
-concurrent = [
-  { task = "farmyard/api", start = 1, tab = 3 },
-  { task = "farmyard/jobs", start = 2, tab = 4 },
-  { task = "cream/dev", start = 3, tab = 2 },
-  { task = "dairy/dev", start = 4, tab = 1 }
-]
-"#,
-    );
-    write_manifest(
-        &farmyard.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
-[tasks.api]
-run = "printf farmyard-api"
-[tasks.jobs]
-run = "printf farmyard-jobs"
-"#,
-    );
-    write_manifest(
-        &cream.join("effigy.toml"),
-        r#"[catalog]
-alias = "cream"
-[tasks.dev]
-run = "printf cream-dev"
-"#,
-    );
-    write_manifest(
-        &dairy.join("effigy.toml"),
-        r#"[catalog]
-alias = "dairy"
-[tasks.dev]
-run = "printf dairy-dev"
+concurrent = [{ name = "api", run = "serve" }]
 "#,
     );
+    let config_dir = root.join("user-config");
+    fs::create_dir_all(&config_dir).expect("mkdir config dir");
+    fs::write(
+        config_dir.join("tui.toml"),
+        "[tui.keys]\nhelp = \"hyper+h\"\n",
+    )
+    .expect("write user tui config");
+
+    let catalogs = discover_catalogs(&root).expect("discover catalogs");
+    let err = catalog_tui_keymap(&catalogs[0], Some(&config_dir))
+        .expect_err("broken user config should fail at launch");
+    assert!(err.to_string().contains("unknown modifier `hyper`"));
 
     let out = run_manifest_task_with_cwd(
         &TaskInvocation {
@@ -5355,84 +7145,183 @@ run = "printf dairy-dev"
         },
         root,
     )
-    .expect("managed plan should render");
-
-    assert!(out.contains("tab-order: dairy/dev, cream/dev, farmyard/api, farmyard/jobs"));
+    .expect("plan should not read the user tui config");
+    assert!(out.contains("api"));
 }
 
 #[test]
-fn run_manifest_task_managed_tui_supports_single_definition_ordered_profile_entries() {
-    let _guard = test_lock().lock().expect("lock");
-    let root = temp_workspace("managed-single-definition-ordered-profile");
-    let _env = EnvGuard::set_many(&[("EFFIGY_MANAGED_TUI", Some("0".to_owned()))]);
-    let farmyard = root.join("farmyard");
-    let cream = root.join("cream");
-    let dairy = root.join("dairy");
-    fs::create_dir_all(&farmyard).expect("mkdir farmyard");
-    fs::create_dir_all(&cream).expect("mkdir cream");
-    fs::create_dir_all(&dairy).expect("mkdir dairy");
+fn run_manifest_task_managed_tui_rejects_invalid_key_bindings() {
+    for (keys, expected) in [
+        (r#"preset = "helix""#, "unknown key preset `helix`"),
+        (r#"launch = "x""#, "unknown action `launch`"),
+        (r#"help = "hyper+h""#, "unknown modifier `hyper`"),
+        (
+            r#"grid = "o""#,
+            "key `o` is bound to both `options` and `grid`",
+        ),
+    ] {
+        let root = temp_workspace("managed-tui-keys-invalid");
+        write_manifest(
+            &root.join("effigy.toml"),
+            &format!(
+                r#"[tui.keys]
+{keys}
+
+[tasks.dev]
+mode = "tui"
+concurrent = [{{ name = "api", run = "serve" }}]
+"#
+            ),
+        );
+
+        let err = run_manifest_task_with_cwd(
+            &TaskInvocation {
+                name: "dev".to_owned(),
+                args: vec!["--repo".to_owned(), root.display().to_string()],
+            },
+            root,
+        )
+        .expect_err("invalid key bindings should fail");
+        match err {
+            RunnerError::TuiKeyBindingsInvalid { path, detail } => {
+                assert!(path.ends_with("effigy.toml"), "{}", path.display());
+                assert!(detail.contains(expected), "{detail}");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
 
+#[test]
+fn run_manifest_task_reports_cpu_limit_breach() {
+    let root = temp_workspace("task-limits-cpu");
     write_manifest(
         &root.join("effigy.toml"),
-        r#"[tasks.dev]
-mode = "tui"
-concurrent = [
-  { task = "farmyard/api", start = 1, tab = 3 },
-  { task = "farmyard/jobs", start = 2, tab = 4, start_after_ms = 1200 },
-  { task = "cream/dev", start = 3, tab = 2 },
-  { task = "dairy/dev", start = 4, tab = 1 }
-]
+        r#"[tasks.spin]
+run = "exec sh -c 'while :; do :; done'"
+limits = { cpu_seconds = 1 }
 "#,
     );
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "spin".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
+        },
+        root,
+    )
+    .expect_err("cpu limit should stop the task");
+
+    match err {
+        RunnerError::TaskResourceLimitExceeded { detail, .. } => {
+            assert_eq!(detail, "cpu limit of 1s exceeded");
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_notes_memory_limit_on_crash_without_claiming_a_breach() {
+    let root = temp_workspace("task-limits-crash");
     write_manifest(
-        &farmyard.join("effigy.toml"),
-        r#"[catalog]
-alias = "farmyard"
-[tasks.api]
-run = "printf farmyard-api"
-[tasks.jobs]
-run = "printf farmyard-jobs"
+        &root.join("effigy.toml"),
+        r#"[tasks.crash]
+run = "kill -SEGV $$"
+limits = { memory_mb = 2048 }
 "#,
     );
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "crash".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
+        },
+        root,
+    )
+    .expect_err("crashing task should fail");
+
+    match err {
+        RunnerError::TaskCommandCrashed { detail, .. } => {
+            assert_eq!(
+                detail,
+                "crashed with SIGSEGV (a memory limit of 2048MB is set)"
+            );
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn run_manifest_task_memory_limit_caps_data_segment_not_address_space() {
+    let root = temp_workspace("task-limits-memory");
     write_manifest(
-        &cream.join("effigy.toml"),
-        r#"[catalog]
-alias = "cream"
-[tasks.dev]
-run = "printf cream-dev"
+        &root.join("effigy.toml"),
+        r#"[tasks.limits]
+run = "printf '%s %s' \"$(ulimit -d)\" \"$(ulimit -v)\" > limits.txt"
+limits = { memory_mb = 64 }
 "#,
     );
+
+    run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "limits".to_owned(),
+            args: vec!["--repo".to_owned(), root.display().to_string()],
+        },
+        root.clone(),
+    )
+    .expect("run limits task");
+
+    let limits = fs::read_to_string(root.join("limits.txt")).expect("read limits");
+    let (data, address_space) = limits.split_once(' ').expect("two limits");
+    assert_eq!(data, "65536");
+    assert_ne!(address_space, "65536");
+}
+
+#[test]
+fn run_manifest_task_failure_runs_notify_command() {
+    let root = temp_workspace("task-notify-command");
     write_manifest(
-        &dairy.join("effigy.toml"),
-        r#"[catalog]
-alias = "dairy"
-[tasks.dev]
-run = "printf dairy-dev"
+        &root.join("effigy.toml"),
+        r#"[tasks.broken]
+run = "exit 3"
+notify = { command = "printf '%s|%s' \"$EFFIGY_NOTIFY_EVENT\" \"$EFFIGY_NOTIFY_MESSAGE\" > notified.txt" }
 "#,
     );
 
-    let out = run_manifest_task_with_cwd(
+    let err = run_manifest_task_with_cwd(
         &TaskInvocation {
-            name: "dev".to_owned(),
+            name: "broken".to_owned(),
             args: vec!["--repo".to_owned(), root.display().to_string()],
         },
-        root,
+        root.clone(),
     )
-    .expect("managed plan should render");
+    .expect_err("task should fail");
+    assert!(matches!(
+        err,
+        RunnerError::TaskCommandFailure { code: Some(3), .. }
+    ));
 
-    assert!(out.contains("tab-order: dairy/dev, cream/dev, farmyard/api, farmyard/jobs"));
-    assert!(out.contains("start-after-ms"));
-    assert!(out.contains("1200"));
+    let notified = root.join("notified.txt");
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !fs::read_to_string(&notified).is_ok_and(|text| !text.is_empty())
+        && std::time::Instant::now() < deadline
+    {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert_eq!(
+        fs::read_to_string(&notified).expect("notify hook output"),
+        "task-failed|task `broken` failed (exit=3)"
+    );
 }
 
 #[test]
-fn run_manifest_task_managed_tui_errors_when_concurrent_entry_missing_task_and_run() {
-    let root = temp_workspace("managed-tab-order-invalid");
+fn run_manifest_task_rejects_unknown_notify_channel() {
+    let root = temp_workspace("task-notify-invalid");
     write_manifest(
         &root.join("effigy.toml"),
         r#"[tasks.dev]
-mode = "tui"
-concurrent = [{ name = "jobs" }]
+run = "true"
+notify = ["bell", "toast"]
 "#,
     );
 
@@ -5443,17 +7332,13 @@ concurrent = [{ name = "jobs" }]
         },
         root,
     )
-    .expect_err("invalid concurrent entry should fail");
-
+    .expect_err("unknown channel should fail");
     match err {
-        RunnerError::TaskManagedProcessInvalidDefinition {
-            task,
-            process,
-            detail,
-        } => {
-            assert_eq!(task, "dev");
-            assert_eq!(process, "jobs");
-            assert!(detail.contains("missing both `task` and `run`"));
+        RunnerError::TaskManifestParse { error, .. } => {
+            assert!(
+                error.to_string().contains("unknown variant `toast`"),
+                "{error}"
+            );
         }
         other => panic!("unexpected error: {other}"),
     }
@@ -6284,6 +8169,23 @@ fn temp_workspace(name: &str) -> PathBuf {
     root
 }
 
+fn free_local_port() -> u16 {
+    std::net::TcpListener::bind(("0.0.0.0", 0))
+        .and_then(|listener| listener.local_addr())
+        .expect("reserve local port")
+        .port()
+}
+
+fn extract_port(out: &str, prefix: &str) -> u16 {
+    let start = out.find(prefix).expect("port prefix") + prefix.len();
+    out[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .expect("port number")
+}
+
 fn with_cwd<F, T>(cwd: &PathBuf, f: F) -> T
 where
     F: FnOnce() -> T,
@@ -6329,3 +8231,216 @@ impl Drop for EnvGuard {
         }
     }
 }
+
+#[test]
+fn run_manifest_task_builtin_test_runner_override_gets_no_reporter_args() {
+    let root = temp_workspace("builtin-test-vitest-override-args");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[test.runners]
+vitest = "sh ./fake-vitest.sh --reporter=dot"
+"#,
+    );
+    fs::write(
+        root.join("package.json"),
+        r#"{
+  "devDependencies": {
+    "vitest": "^2.0.0"
+  }
+}"#,
+    )
+    .expect("write package");
+    fs::write(root.join("fake-vitest.sh"), "echo \"$@\" > args.log\n").expect("write fake vitest");
+
+    let out = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--json".to_owned()],
+        },
+        root.clone(),
+    )
+    .expect("run builtin test");
+
+    let args = fs::read_to_string(root.join("args.log")).expect("read args");
+    assert_eq!(args.trim(), "--reporter=dot");
+    let parsed: serde_json::Value = serde_json::from_str(&out).expect("json");
+    assert!(parsed["targets"][0]["tests"].is_null());
+}
+
+#[test]
+fn run_manifest_task_builtin_test_parses_libtest_output_from_cargo_test() {
+    let root = temp_workspace("builtin-test-libtest-report");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[test.runners]
+"cargo-test" = "sh ./fake-cargo-test.sh"
+"#,
+    );
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"fake\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+    fs::write(
+        root.join("fake-cargo-test.sh"),
+        r#"echo "running 3 tests"
+echo "test parser::reads_header ... ok"
+echo "test parser::slow_case ... ignored"
+echo "test parser::rejects_empty ... FAILED"
+exit 101
+"#,
+    )
+    .expect("write fake cargo");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--junit".to_owned(),
+                "reports/junit.xml".to_owned(),
+            ],
+        },
+        root.clone(),
+    )
+    .expect_err("failing cargo test should fail");
+
+    let RunnerError::BuiltinTestNonZero { rendered, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    let parsed: serde_json::Value = serde_json::from_str(&rendered).expect("json");
+    let tests = &parsed["targets"][0]["tests"];
+    assert_eq!(tests["passed"], 1);
+    assert_eq!(tests["failed"], 1);
+    assert_eq!(tests["skipped"], 1);
+    assert_eq!(
+        tests["failing"],
+        serde_json::json!(["parser::rejects_empty"])
+    );
+    assert_eq!(
+        parsed["failures"][0]["failing_tests"],
+        serde_json::json!(["parser::rejects_empty"])
+    );
+    assert!(parsed["junit"]
+        .as_str()
+        .is_some_and(|path| path.ends_with("reports/junit.xml")));
+
+    let junit = fs::read_to_string(root.join("reports/junit.xml")).expect("read junit");
+    assert!(junit.contains(r#"tests="3" failures="1" skipped="1""#));
+    assert!(junit.contains(r#"name="parser::rejects_empty""#));
+}
+
+#[test]
+fn run_manifest_task_builtin_test_falls_back_to_exit_status_for_unparsed_cargo_test_output() {
+    let root = temp_workspace("builtin-test-libtest-unparsed");
+    write_manifest(
+        &root.join("effigy.toml"),
+        r#"[test.runners]
+"cargo-test" = "sh ./fake-cargo-test.sh"
+"#,
+    );
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"fake\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+    fs::write(
+        root.join("fake-cargo-test.sh"),
+        r#"echo "custom harness: 2 checks"
+echo "[PASS] parser reads header"
+echo "[FAIL] parser rejects empty"
+exit 101
+"#,
+    )
+    .expect("write fake cargo");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec![
+                "--json".to_owned(),
+                "--junit".to_owned(),
+                "reports/junit.xml".to_owned(),
+            ],
+        },
+        root.clone(),
+    )
+    .expect_err("failing cargo test should fail");
+
+    let RunnerError::BuiltinTestNonZero { failures, rendered } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(failures, vec![("root".to_owned(), Some(101))]);
+    let parsed: serde_json::Value = serde_json::from_str(&rendered).expect("json");
+    assert!(parsed["targets"][0]["tests"].is_null());
+    assert_eq!(parsed["targets"][0]["code"], 101);
+    assert_eq!(
+        parsed["failures"][0]["failing_tests"],
+        serde_json::json!([])
+    );
+
+    let junit = fs::read_to_string(root.join("reports/junit.xml")).expect("read junit");
+    assert!(junit.contains(r#"tests="1" failures="1""#), "{junit}");
+}
+
+#[test]
+fn run_manifest_task_builtin_test_junit_requires_a_path() {
+    let root = temp_workspace("builtin-test-junit-missing-path");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"fake\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write cargo toml");
+
+    let err = run_manifest_task_with_cwd(
+        &TaskInvocation {
+            name: "test".to_owned(),
+            args: vec!["--junit".to_owned()],
+        },
+        root,
+    )
+    .expect_err("missing --junit path should fail");
+
+    match err {
+        RunnerError::TaskInvocation(message) => {
+            assert!(message.contains("`--junit` requires an output path"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn junit_report_summary_reads_nextest_cases() {
+    let raw = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run" tests="3" failures="1" errors="0">
+    <testsuite name="effigy" tests="3" disabled="1" errors="0" failures="1">
+        <testcase name="parser::reads_header" classname="effigy" time="0.004"/>
+        <testcase name="parser::rejects_&lt;empty&gt;" classname="effigy" time="0.010">
+            <failure message="thread panicked" type="test failure">assertion failed</failure>
+        </testcase>
+        <testcase name="parser::slow_case" classname="effigy" time="0.000">
+            <skipped/>
+        </testcase>
+    </testsuite>
+</testsuites>
+"#;
+
+    let (summary, failing) = junit_report_summary(raw).expect("parse junit");
+    assert_eq!(summary, "1 passed, 1 failed, 1 skipped");
+    assert_eq!(failing, vec!["parser::rejects_<empty>".to_owned()]);
+    assert!(junit_report_summary("not xml").is_none());
+}
+
+#[test]
+fn jest_report_summary_reads_vitest_cases() {
+    let raw = r#"{"testResults":[{"name":"src/math.test.ts","assertionResults":[
+  {"fullName":"math adds","status":"passed","duration":3},
+  {"fullName":"math divides","status":"failed","duration":2,"failureMessages":["expected 2 to be 3"]},
+  {"fullName":"math rounds","status":"todo"}
+]}]}"#;
+
+    let (summary, failing) = jest_report_summary(raw, Path::new("/repo")).expect("parse json");
+    assert_eq!(summary, "1 passed, 1 failed, 1 skipped");
+    assert_eq!(failing, vec!["math divides".to_owned()]);
+    assert!(jest_report_summary("not json", Path::new("/repo")).is_none());
+}
@@ -1,4 +1,6 @@
-use super::{detect_test_runner, detect_test_runner_detailed, TestRunner};
+use super::{
+    detect_test_runner, detect_test_runner_detailed, detect_test_runner_plans, TestRunner,
+};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
@@ -139,13 +141,142 @@ fn detect_test_runner_detailed_includes_candidate_chain_with_rejections() {
     let report = detect_test_runner_detailed(&root);
     let selected = report.selected.expect("selected plan");
     assert_eq!(selected.runner, TestRunner::CargoTest);
-    assert_eq!(report.candidates.len(), 3);
+    assert_eq!(report.candidates.len(), TestRunner::ALL.len());
     assert_eq!(report.candidates[0].runner, TestRunner::Vitest);
     assert!(!report.candidates[0].available);
-    assert_eq!(report.candidates[1].runner, TestRunner::CargoNextest);
-    assert!(!report.candidates[1].available);
-    assert_eq!(report.candidates[2].runner, TestRunner::CargoTest);
-    assert!(report.candidates[2].available);
+    assert_eq!(report.candidates[3].runner, TestRunner::CargoNextest);
+    assert!(!report.candidates[3].available);
+    assert_eq!(report.candidates[4].runner, TestRunner::CargoTest);
+    assert!(report.candidates[4].available);
+    assert!(report
+        .candidates
+        .iter()
+        .filter(|candidate| candidate.runner != TestRunner::CargoTest)
+        .all(|candidate| !candidate.available));
+}
+
+#[test]
+fn detect_test_runner_detects_each_runner_from_its_markers() {
+    struct DetectCase {
+        name: &'static str,
+        files: &'static [(&'static str, &'static str)],
+        runner: TestRunner,
+        evidence: &'static str,
+    }
+
+    let cases = [
+        DetectCase {
+            name: "jest",
+            files: &[("jest.config.js", "module.exports = {};\n")],
+            runner: TestRunner::Jest,
+            evidence: "jest.config.js",
+        },
+        DetectCase {
+            name: "bun-test",
+            files: &[("package.json", r#"{ "scripts": { "test": "bun test" } }"#)],
+            runner: TestRunner::BunTest,
+            evidence: "bun test",
+        },
+        DetectCase {
+            name: "pytest",
+            files: &[(
+                "pyproject.toml",
+                "[project]\nname = \"app\"\n\n[tool.pytest.ini_options]\naddopts = \"-q\"\n",
+            )],
+            runner: TestRunner::Pytest,
+            evidence: "[tool.pytest.ini_options]",
+        },
+        DetectCase {
+            name: "go-test",
+            files: &[("go.mod", "module example.com/app\n")],
+            runner: TestRunner::GoTest,
+            evidence: "go.mod",
+        },
+        DetectCase {
+            name: "pest",
+            files: &[(
+                "composer.json",
+                r#"{ "require-dev": { "pestphp/pest": "^2.0", "phpunit/phpunit": "^10.0" } }"#,
+            )],
+            runner: TestRunner::Pest,
+            evidence: "pestphp/pest",
+        },
+        DetectCase {
+            name: "phpunit",
+            files: &[("phpunit.xml.dist", "<phpunit/>\n")],
+            runner: TestRunner::Phpunit,
+            evidence: "phpunit.xml.dist",
+        },
+    ];
+    for DetectCase {
+        name,
+        files,
+        runner,
+        evidence,
+    } in cases
+    {
+        let root = temp_workspace(&format!("test-detect-{name}"));
+        for (file, contents) in files {
+            fs::write(root.join(file), contents).expect("write marker");
+        }
+        let plan = detect_test_runner(&root).expect("plan");
+        assert_eq!(plan.runner, runner, "{name}");
+        assert_eq!(plan.command, runner.default_command(), "{name}");
+        assert!(
+            plan.evidence.iter().any(|line| line.contains(evidence)),
+            "{name}: {:?}",
+            plan.evidence
+        );
+    }
+}
+
+#[test]
+fn detect_test_runner_plans_keeps_first_runner_per_ecosystem() {
+    let root = temp_workspace("test-detect-ecosystems");
+    fs::write(
+        root.join("package.json"),
+        r#"{ "devDependencies": { "vitest": "^2.0.0", "jest": "^29.0.0" } }"#,
+    )
+    .expect("write package");
+    fs::write(root.join("go.mod"), "module example.com/app\n").expect("write go.mod");
+    fs::write(root.join("phpunit.xml"), "<phpunit/>\n").expect("write phpunit");
+    fs::create_dir_all(root.join("tests")).expect("mkdir tests");
+    fs::write(root.join("tests/Pest.php"), "<?php\n").expect("write pest");
+
+    let runners = detect_test_runner_plans(&root)
+        .into_iter()
+        .map(|plan| plan.runner)
+        .collect::<Vec<TestRunner>>();
+    assert_eq!(
+        runners,
+        vec![TestRunner::Vitest, TestRunner::GoTest, TestRunner::Pest]
+    );
+
+    let report = detect_test_runner_detailed(&root);
+    assert_eq!(
+        report.selected.expect("selected").runner,
+        TestRunner::Vitest
+    );
+    assert!(report
+        .candidates
+        .iter()
+        .any(|candidate| candidate.runner == TestRunner::Jest && candidate.available));
+    assert!(report
+        .candidates
+        .iter()
+        .any(|candidate| candidate.runner == TestRunner::Phpunit && candidate.available));
+}
+
+#[test]
+fn test_runner_labels_round_trip() {
+    for runner in TestRunner::ALL {
+        assert_eq!(TestRunner::from_label(runner.label()), Some(runner));
+    }
+    assert_eq!(
+        TestRunner::from_label("nextest"),
+        Some(TestRunner::CargoNextest)
+    );
+    assert_eq!(TestRunner::from_label("mocha"), None);
 }
 
 fn temp_dir(name: &str) -> PathBuf {
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum InputMode {
@@ -23,6 +24,18 @@ pub(crate) enum ProcessExitState {
 pub(crate) struct LogEntry {
     pub(crate) kind: LogEntryKind,
     pub(crate) line: String,
+    /// When the line reached the TUI.
+    pub(crate) at: Instant,
+}
+
+impl LogEntry {
+    pub(crate) fn new(kind: LogEntryKind, line: String) -> Self {
+        Self {
+            kind,
+            line,
+            at: Instant::now(),
+        }
+    }
 }
 
 pub(crate) fn next_index(current: usize, len: usize) -> usize {
@@ -1,6 +1,13 @@
 pub(crate) mod core;
 pub mod multiprocess;
+mod picker;
+mod watch;
 
 pub use multiprocess::{
-    run_multiprocess_tui, MultiProcessTuiError, MultiProcessTuiOptions, MultiProcessTuiOutcome,
+    replay_multiprocess_tui, run_multiprocess_tui, KeyMap, MultiProcessTuiError,
+    MultiProcessTuiOptions, MultiProcessTuiOutcome, PaletteProfile, KEY_PRESETS, MAX_GRID_PANES,
+};
+pub use picker::{run_task_picker, PickerEntry, PickerSelection};
+pub(crate) use watch::{
+    WatchDashboard, WatchDashboardCommand, WatchDashboardTerminal, WatchRunStatus,
 };
@@ -0,0 +1,352 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use serde_json::{Map, Value};
+
+use super::terminal_text::strip_ansi;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) enum TimestampMode {
+    #[default]
+    Off,
+    /// Time since the process (re)started, e.g. `+1m02.5s`.
+    Relative,
+    /// Local wall-clock time, e.g. `14:03:07.215`.
+    Absolute,
+}
+
+impl TimestampMode {
+    pub(super) fn next(self) -> Self {
+        match self {
+            TimestampMode::Off => TimestampMode::Relative,
+            TimestampMode::Relative => TimestampMode::Absolute,
+            TimestampMode::Absolute => TimestampMode::Off,
+        }
+    }
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            TimestampMode::Off => "off",
+            TimestampMode::Relative => "relative",
+            TimestampMode::Absolute => "absolute",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "error" | "err" | "fatal" | "panic" | "critical" | "crit" => Some(LogLevel::Error),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "info" | "notice" => Some(LogLevel::Info),
+            "debug" | "trace" => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+
+    /// Numeric levels as used by pino and bunyan (`50` = error).
+    fn from_number(value: f64) -> Self {
+        if value >= 50.0 {
+            LogLevel::Error
+        } else if value >= 40.0 {
+            LogLevel::Warn
+        } else if value >= 30.0 {
+            LogLevel::Info
+        } else {
+            LogLevel::Debug
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            LogLevel::Error => Color::Red,
+            LogLevel::Warn => Color::Yellow,
+            LogLevel::Info => Color::Cyan,
+            LogLevel::Debug => Color::DarkGray,
+        }
+    }
+}
+
+/// Per-view display options for timestamps and level colouring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Annotations {
+    pub(super) timestamps: TimestampMode,
+    pub(super) levels: bool,
+    pub(super) started_at: Instant,
+}
+
+/// Level of a plain-text line. A level word counts when it is uppercase
+/// (`ERROR`), or followed by `:` or `]`, or follows `=` (`level=warn`).
+pub(super) fn detect_level(text: &str) -> Option<LogLevel> {
+    let bytes = text.as_bytes();
+    let mut start = None;
+    for (index, byte) in bytes.iter().chain(std::iter::once(&b' ')).enumerate() {
+        if byte.is_ascii_alphabetic() {
+            start.get_or_insert(index);
+            continue;
+        }
+        let Some(word_start) = start.take() else {
+            continue;
+        };
+        let word = &text[word_start..index];
+        let Some(level) = LogLevel::from_name(word) else {
+            continue;
+        };
+        let uppercase = word.bytes().all(|byte| byte.is_ascii_uppercase());
+        let before = word_start
+            .checked_sub(1)
+            .and_then(|at| bytes.get(at))
+            .copied();
+        let after = bytes.get(index).copied();
+        if uppercase || matches!(after, Some(b':' | b']')) || before == Some(b'=') {
+            return Some(level);
+        }
+    }
+    None
+}
+
+/// Level of a line, reading the `level`/`lvl`/`severity` field of JSON lines.
+pub(super) fn line_level(text: &str) -> Option<LogLevel> {
+    match parse_json_line(text) {
+        Some(fields) => json_level(&fields),
+        None => detect_level(text),
+    }
+}
+
+/// Number of error-level lines in a raw output payload.
+pub(super) fn count_error_lines(payload: &str) -> usize {
+    payload
+        .lines()
+        .filter(|line| line_level(&strip_ansi(line)) == Some(LogLevel::Error))
+        .count()
+}
+
+/// Renders a JSON log line as `LEVEL message  key=value ...`.
+pub(super) fn pretty_json_line(text: &str) -> Option<String> {
+    let mut fields = parse_json_line(text)?;
+    let level = ["level", "lvl", "severity"]
+        .iter()
+        .find_map(|key| fields.remove(*key))
+        .map(|value| match value {
+            Value::String(name) => name.to_ascii_uppercase(),
+            Value::Number(number) => match number.as_f64().map(LogLevel::from_number) {
+                Some(LogLevel::Error) => "ERROR".to_owned(),
+                Some(LogLevel::Warn) => "WARN".to_owned(),
+                Some(LogLevel::Info) => "INFO".to_owned(),
+                _ => "DEBUG".to_owned(),
+            },
+            other => other.to_string(),
+        });
+    let message = ["msg", "message"]
+        .iter()
+        .find_map(|key| fields.remove(*key))
+        .map(|value| json_text(&value));
+    let mut parts = level.into_iter().chain(message).collect::<Vec<String>>();
+    if !fields.is_empty() {
+        let rest = fields
+            .iter()
+            .map(|(key, value)| format!("{key}={}", json_text(value)))
+            .collect::<Vec<String>>()
+            .join(" ");
+        parts.push(if parts.is_empty() {
+            rest
+        } else {
+            format!(" {rest}")
+        });
+    }
+    Some(parts.join(" "))
+}
+
+/// Colours `raw` by its level and pretty-prints JSON lines. `render` turns
+/// the (possibly rewritten) text into a styled line.
+pub(super) fn levelled_line(raw: &str, render: impl Fn(&str) -> Line<'static>) -> Line<'static> {
+    let text = strip_ansi(raw);
+    let line = match pretty_json_line(&text) {
+        Some(pretty) => render(&pretty),
+        None => render(raw),
+    };
+    let Some(level) = line_level(&text) else {
+        return line;
+    };
+    // Colours the program chose itself take precedence over the level colour.
+    let base = Style::default().fg(level.color());
+    Line::from(
+        line.spans
+            .into_iter()
+            .map(|span| {
+                let style = base.patch(span.style);
+                span.style(style)
+            })
+            .collect::<Vec<Span>>(),
+    )
+}
+
+/// Dimmed timestamp prefix for a line that arrived at `at`.
+pub(super) fn timestamp_span(at: Instant, annotations: Annotations) -> Option<Span<'static>> {
+    if annotations.timestamps == TimestampMode::Off {
+        return None;
+    }
+    let stamp = timestamp_label(
+        annotations.timestamps,
+        at,
+        annotations.started_at,
+        Instant::now(),
+        SystemTime::now(),
+    );
+    Some(Span::styled(
+        format!("{stamp} "),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
+fn timestamp_label(
+    mode: TimestampMode,
+    at: Instant,
+    started_at: Instant,
+    now: Instant,
+    wall_now: SystemTime,
+) -> String {
+    match mode {
+        TimestampMode::Off => String::new(),
+        TimestampMode::Relative => format_relative(at.saturating_duration_since(started_at)),
+        TimestampMode::Absolute => {
+            let wall = wall_now
+                .checked_sub(now.saturating_duration_since(at))
+                .unwrap_or(wall_now);
+            format_clock(wall)
+        }
+    }
+}
+
+fn format_relative(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    let tenths = elapsed.subsec_millis() / 100;
+    if secs >= 3600 {
+        format!(
+            "+{}h{:02}m{:02}s",
+            secs / 3600,
+            (secs % 3600) / 60,
+            secs % 60
+        )
+    } else if secs >= 60 {
+        format!("+{}m{:02}.{tenths}s", secs / 60, secs % 60)
+    } else {
+        format!("+{secs}.{tenths}s")
+    }
+}
+
+/// `HH:MM:SS.mmm` in the local time zone.
+fn format_clock(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let millis = since_epoch.subsec_millis();
+    let seconds = since_epoch.as_secs() as libc::time_t;
+    // SAFETY: `localtime_r` only writes into the `tm` we own.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let converted = unsafe { !libc::localtime_r(&seconds, &mut tm).is_null() };
+    if !converted {
+        let day_seconds = since_epoch.as_secs() % 86_400;
+        return format!(
+            "{:02}:{:02}:{:02}.{millis:03}",
+            day_seconds / 3600,
+            (day_seconds % 3600) / 60,
+            day_seconds % 60
+        );
+    }
+    format!(
+        "{:02}:{:02}:{:02}.{millis:03}",
+        tm.tm_hour, tm.tm_min, tm.tm_sec
+    )
+}
+
+fn parse_json_line(text: &str) -> Option<Map<String, Value>> {
+    let trimmed = text.trim();
+    if !trimmed.starts_with('{') || !trimmed.ends_with('}') {
+        return None;
+    }
+    match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::Object(fields)) => Some(fields),
+        _ => None,
+    }
+}
+
+fn json_level(fields: &Map<String, Value>) -> Option<LogLevel> {
+    ["level", "lvl", "severity"]
+        .iter()
+        .find_map(|key| fields.get(*key))
+        .and_then(|value| match value {
+            Value::String(name) => LogLevel::from_name(name),
+            Value::Number(number) => number.as_f64().map(LogLevel::from_number),
+            _ => None,
+        })
+}
+
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant, SystemTime};
+
+    use super::{
+        count_error_lines, detect_level, format_relative, line_level, pretty_json_line,
+        timestamp_label, LogLevel, TimestampMode,
+    };
+
+    #[test]
+    fn detect_level_prefers_explicit_level_words() {
+        assert_eq!(detect_level("2024 ERROR db down"), Some(LogLevel::Error));
+        assert_eq!(detect_level("warning: unused"), Some(LogLevel::Warn));
+        assert_eq!(detect_level("[info] ready"), Some(LogLevel::Info));
+        assert_eq!(detect_level("ts=1 level=debug"), Some(LogLevel::Debug));
+        assert_eq!(detect_level("no error here"), None);
+        assert_eq!(detect_level("0 errors, 2 warnings"), None);
+    }
+
+    #[test]
+    fn json_lines_are_pretty_printed_with_their_level() {
+        let line = r#"{"level":"error","msg":"db down","retry":3,"host":"db1"}"#;
+        assert_eq!(line_level(line), Some(LogLevel::Error));
+        assert_eq!(
+            pretty_json_line(line).as_deref(),
+            Some("ERROR db down  host=db1 retry=3")
+        );
+        assert_eq!(
+            pretty_json_line(r#"{"level":40,"msg":"slow"}"#).as_deref(),
+            Some("WARN slow")
+        );
+        assert_eq!(pretty_json_line("{not json}"), None);
+        assert_eq!(
+            count_error_lines("ok\n\u{1b}[31mERROR\u{1b}[0m x\n{\"level\":50}\n"),
+            2
+        );
+    }
+
+    #[test]
+    fn timestamps_render_relative_and_absolute() {
+        assert_eq!(format_relative(Duration::from_millis(4_250)), "+4.2s");
+        assert_eq!(format_relative(Duration::from_millis(62_500)), "+1m02.5s");
+        assert_eq!(format_relative(Duration::from_secs(3_725)), "+1h02m05s");
+
+        let started = Instant::now();
+        let at = started + Duration::from_secs(2);
+        let label = timestamp_label(TimestampMode::Relative, at, started, at, SystemTime::now());
+        assert_eq!(label, "+2.0s");
+        let clock = timestamp_label(TimestampMode::Absolute, at, started, at, SystemTime::now());
+        assert_eq!(clock.len(), "00:00:00.000".len());
+        assert_eq!(TimestampMode::Off.next(), TimestampMode::Relative);
+        assert_eq!(TimestampMode::Absolute.next(), TimestampMode::Off);
+    }
+}
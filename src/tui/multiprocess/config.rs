@@ -10,3 +10,8 @@ pub(super) const VT_PARSER_SCROLLBACK: usize = 8000;
 pub(super) const EVENT_DRAIN_WAIT: Duration = Duration::from_millis(1);
 pub(super) const INPUT_POLL_WAIT: Duration = Duration::from_millis(50);
 pub(super) const SHUTDOWN_GRACE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Also the cap on a managed task's `panes` list, checked by the runner.
+pub const MAX_GRID_PANES: usize = 4;
+pub(super) const LOGS_DIR: &str = ".effigy/logs";
+pub(super) const MOUSE_SCROLL_LINES: usize = 3;
+pub(super) const STATS_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
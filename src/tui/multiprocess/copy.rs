@@ -0,0 +1,355 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::tui::core::{LogEntry, LogEntryKind};
+
+use super::keymap::{KeyAction, KeyMap};
+use super::search::searchable_entries;
+use super::state::SessionState;
+
+/// Inclusive line range marked in copy mode, plus the line under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct CopySelection {
+    pub(super) start: usize,
+    pub(super) end: usize,
+    pub(super) cursor: usize,
+}
+
+impl CopySelection {
+    pub(super) fn len(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct CopyState {
+    pub(super) active: bool,
+    pub(super) cursor: usize,
+    /// Line where `space` set the mark; without a mark only the cursor line is selected.
+    pub(super) anchor: Option<usize>,
+    /// Text waiting to be sent to the terminal clipboard after the key is handled.
+    pub(super) pending_clipboard: Option<String>,
+}
+
+impl CopyState {
+    pub(super) fn selection(&self) -> Option<CopySelection> {
+        if !self.active {
+            return None;
+        }
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        Some(CopySelection {
+            start: anchor.min(self.cursor),
+            end: anchor.max(self.cursor),
+            cursor: self.cursor,
+        })
+    }
+}
+
+/// Lines copy mode selects from: the plain-text lines search reads, narrowed
+/// by an active search filter so the selection matches what is on screen.
+pub(super) fn copy_entries(state: &mut SessionState, process: &str) -> Vec<LogEntry> {
+    let entries = searchable_entries(state, process);
+    match state.search.filter_matcher() {
+        Some(matcher) => entries
+            .into_iter()
+            .filter(|entry| matcher.is_match(&entry.line))
+            .collect(),
+        None => entries,
+    }
+}
+
+/// Starts copy mode on the last line of the active process and pauses follow.
+pub(super) fn enter_copy_mode(state: &mut SessionState) {
+    let active = state.active_process().to_owned();
+    let line_count = copy_entries(state, &active).len();
+    state.copy = CopyState {
+        active: true,
+        cursor: line_count.saturating_sub(1),
+        anchor: None,
+        pending_clipboard: None,
+    };
+    state.show_help = false;
+    state.show_stats = false;
+    state.follow_mode.insert(active, false);
+}
+
+pub(super) fn handle_copy_key(key: &KeyEvent, state: &mut SessionState, keymap: &KeyMap) {
+    let active = state.active_process().to_owned();
+    let entries = copy_entries(state, &active);
+    let last = entries.len().saturating_sub(1);
+    let cursor = state.copy.cursor.min(last);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            state.copy = CopyState::default();
+            return;
+        }
+        KeyCode::Char(' ') => {
+            state.copy.anchor = match state.copy.anchor {
+                Some(_) => None,
+                None => Some(cursor),
+            };
+            return;
+        }
+        KeyCode::Char('y') | KeyCode::Enter => {
+            if let Some(selection) = state.copy.selection() {
+                let text = selected_lines(&entries, selection)
+                    .iter()
+                    .map(export_line)
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                state.notice = Some(format!(
+                    "copied {} line(s) to the clipboard",
+                    selection.len()
+                ));
+                state.copy = CopyState {
+                    pending_clipboard: Some(text),
+                    ..CopyState::default()
+                };
+            }
+            return;
+        }
+        KeyCode::Char('w') | KeyCode::Char('W') => {
+            let whole_buffer = key.code == KeyCode::Char('W');
+            let (lines, suffix) = match state.copy.selection() {
+                Some(selection) if !whole_buffer => {
+                    (selected_lines(&entries, selection), "-selection")
+                }
+                _ => (entries.as_slice(), ""),
+            };
+            let stem = format!("{}-{}", file_stem(&active), unix_stamp_millis());
+            state.notice = Some(match write_log_file(&state.log_dir, &stem, suffix, lines) {
+                Ok(path) => format!("saved {} line(s) to {}", lines.len(), path.display()),
+                Err(err) => format!("save failed: {err}"),
+            });
+            state.copy = CopyState::default();
+            return;
+        }
+        _ => {}
+    }
+
+    let next = match keymap.action_for(key) {
+        Some(KeyAction::ScrollUp) => cursor.saturating_sub(1),
+        Some(KeyAction::ScrollDown) => cursor.saturating_add(1).min(last),
+        Some(KeyAction::PageUp) => cursor.saturating_sub(10),
+        Some(KeyAction::PageDown) => cursor.saturating_add(10).min(last),
+        Some(KeyAction::Top) => 0,
+        Some(KeyAction::Bottom) => last,
+        _ => cursor,
+    };
+    state.copy.cursor = next;
+}
+
+/// Writes every process buffer to `<log_dir>/<process>-<unix millis>.log`.
+pub(super) fn save_all_logs(state: &mut SessionState) -> io::Result<usize> {
+    let stamp = unix_stamp_millis();
+    let names = state.process_names.clone();
+    for name in &names {
+        let entries = searchable_entries(state, name);
+        let stem = format!("{}-{stamp}", file_stem(name));
+        write_log_file(&state.log_dir, &stem, "", &entries)?;
+    }
+    Ok(names.len())
+}
+
+/// Sends `text` to the terminal clipboard with an OSC 52 escape sequence.
+pub(super) fn write_osc52(out: &mut impl Write, text: &str) -> io::Result<()> {
+    write!(out, "\u{1b}]52;c;{}\u{7}", BASE64.encode(text))?;
+    out.flush()
+}
+
+fn selected_lines(entries: &[LogEntry], selection: CopySelection) -> &[LogEntry] {
+    let end = selection.end.min(entries.len().saturating_sub(1));
+    entries.get(selection.start..=end).unwrap_or(&[])
+}
+
+fn export_line(entry: &LogEntry) -> String {
+    match entry.kind {
+        LogEntryKind::Stdout => entry.line.clone(),
+        LogEntryKind::Stderr => format!("[stderr] {}", entry.line),
+        LogEntryKind::Exit => format!("[exit] {}", entry.line),
+    }
+}
+
+/// Writes `entries` to `<dir>/<stem><suffix>.log`. An existing file is never
+/// overwritten: later saves with the same stem get a `-1`, `-2`, ... counter.
+fn write_log_file(
+    dir: &Path,
+    stem: &str,
+    suffix: &str,
+    entries: &[LogEntry],
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let mut contents = entries
+        .iter()
+        .map(export_line)
+        .collect::<Vec<String>>()
+        .join("\n");
+    contents.push('\n');
+    for attempt in 0usize.. {
+        let path = match attempt {
+            0 => dir.join(format!("{stem}{suffix}.log")),
+            n => dir.join(format!("{stem}-{n}{suffix}.log")),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes())?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!("log file attempts are unbounded")
+}
+
+fn file_stem(process: &str) -> String {
+    process
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn unix_stamp_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::tui::core::{LogEntry, LogEntryKind};
+    use crate::tui::multiprocess::keymap::KeyMap;
+    use crate::tui::multiprocess::state::SessionState;
+
+    use super::{enter_copy_mode, handle_copy_key, save_all_logs, write_osc52};
+
+    fn state_with_lines(lines: &[(LogEntryKind, &str)]) -> SessionState {
+        let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
+        let buffer = state.logs.get_mut("api").expect("api log buffer");
+        for (kind, line) in lines {
+            buffer.push_back(LogEntry::new(kind.clone(), (*line).to_owned()));
+        }
+        state
+    }
+
+    fn press(state: &mut SessionState, code: KeyCode) {
+        handle_copy_key(
+            &KeyEvent::new(code, KeyModifiers::NONE),
+            state,
+            &KeyMap::default(),
+        );
+    }
+
+    fn temp_log_dir(label: &str) -> std::path::PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        std::env::temp_dir().join(format!("effigy-copy-{label}-{nanos}"))
+    }
+
+    #[test]
+    fn osc52_sends_base64_payload() {
+        let mut out = Vec::new();
+        write_osc52(&mut out, "hi").expect("write");
+        assert_eq!(out, b"\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn marking_a_range_copies_the_selected_lines() {
+        let mut state = state_with_lines(&[
+            (LogEntryKind::Stdout, "boot"),
+            (LogEntryKind::Stderr, "panic: boom"),
+            (LogEntryKind::Stderr, "  at main.rs:3"),
+            (LogEntryKind::Stdout, "done"),
+        ]);
+        enter_copy_mode(&mut state);
+        assert_eq!(state.copy.cursor, 3);
+        assert_eq!(state.follow_mode.get("api"), Some(&false));
+
+        press(&mut state, KeyCode::Up);
+        press(&mut state, KeyCode::Char(' '));
+        press(&mut state, KeyCode::Up);
+        let selection = state.copy.selection().expect("selection");
+        assert_eq!(
+            (selection.start, selection.end, selection.cursor),
+            (1, 2, 1)
+        );
+
+        press(&mut state, KeyCode::Char('y'));
+        assert!(!state.copy.active);
+        assert_eq!(
+            state.copy.pending_clipboard.as_deref(),
+            Some("[stderr] panic: boom\n[stderr]   at main.rs:3")
+        );
+        assert_eq!(
+            state.notice.as_deref(),
+            Some("copied 2 line(s) to the clipboard")
+        );
+    }
+
+    #[test]
+    fn saving_writes_selection_and_every_buffer() {
+        let mut state =
+            state_with_lines(&[(LogEntryKind::Stdout, "one"), (LogEntryKind::Stdout, "two")]);
+        let dir = temp_log_dir("save");
+        state.log_dir = dir.clone();
+
+        enter_copy_mode(&mut state);
+        press(&mut state, KeyCode::Char('w'));
+        let saved = fs::read_dir(&dir)
+            .expect("log dir")
+            .map(|entry| entry.expect("entry").path())
+            .collect::<Vec<_>>();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0]
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("api-") && name.ends_with("-selection.log")));
+        assert_eq!(fs::read_to_string(&saved[0]).expect("read"), "two\n");
+
+        assert_eq!(save_all_logs(&mut state).expect("save all"), 1);
+        let full = fs::read_dir(&dir)
+            .expect("log dir")
+            .map(|entry| entry.expect("entry").path())
+            .find(|path| !path.to_string_lossy().ends_with("-selection.log"))
+            .expect("full log");
+        assert_eq!(fs::read_to_string(full).expect("read"), "one\ntwo\n");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn saving_twice_in_a_row_keeps_both_files() {
+        let mut state = state_with_lines(&[(LogEntryKind::Stdout, "one")]);
+        let dir = temp_log_dir("save-twice");
+        state.log_dir = dir.clone();
+
+        for _ in 0..2 {
+            enter_copy_mode(&mut state);
+            press(&mut state, KeyCode::Char('W'));
+        }
+        assert_eq!(save_all_logs(&mut state).expect("save all"), 1);
+        assert_eq!(save_all_logs(&mut state).expect("save all"), 1);
+        let saved = fs::read_dir(&dir).expect("log dir").count();
+        assert_eq!(saved, 4);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
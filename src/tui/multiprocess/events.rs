@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::time::Instant;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -8,9 +9,14 @@ use crate::tui::core::{
     ProcessExitState,
 };
 
-use super::config::{EVENT_DRAIN_WAIT, VT_PARSER_COLS, VT_PARSER_ROWS, VT_PARSER_SCROLLBACK};
+use super::annotate::count_error_lines;
+use super::config::EVENT_DRAIN_WAIT;
+use super::copy::{enter_copy_mode, handle_copy_key, save_all_logs};
 use super::diagnostics::RuntimeDiagnostics;
+use super::keymap::KeyAction;
+use super::palette::handle_palette_key;
 use super::render::options_actions;
+use super::search::{jump_to_match, SearchDirection};
 use super::state::{OptionsAction, SessionState};
 use super::terminal_text::{
     ingest_log_payload, is_expected_shutdown_diagnostic, push_entry, sanitize_log_text,
@@ -35,6 +41,22 @@ pub(super) fn drain_process_events(
             break;
         };
         drained_events += 1;
+        // Live sessions update tabs where they add or remove the process;
+        // replays rebuild them from the recorded membership events.
+        match event_item.kind {
+            ProcessEventKind::Added if supervisor.is_replay() => {
+                state.add_tab(&event_item.process);
+                continue;
+            }
+            ProcessEventKind::Removed if supervisor.is_replay() => {
+                if state.process_names.len() > 1 {
+                    state.remove_tab(&event_item.process);
+                }
+                continue;
+            }
+            ProcessEventKind::Added | ProcessEventKind::Removed => continue,
+            _ => {}
+        }
         if let Some(buffer) = state.logs.get_mut(&event_item.process) {
             match event_item.kind {
                 ProcessEventKind::StdoutChunk | ProcessEventKind::StderrChunk => {
@@ -45,14 +67,8 @@ pub(super) fn drain_process_events(
                     state.output_seen.insert(event_item.process.clone(), true);
                     if vt_emulator_enabled {
                         if !had_output {
-                            state.vt_parsers.insert(
-                                event_item.process.clone(),
-                                vt100::Parser::new(
-                                    VT_PARSER_ROWS,
-                                    VT_PARSER_COLS,
-                                    VT_PARSER_SCROLLBACK,
-                                ),
-                            );
+                            let parser = state.new_vt_parser(&event_item.process);
+                            state.vt_parsers.insert(event_item.process.clone(), parser);
                             state.vt_saw_chunk.insert(event_item.process.clone(), false);
                             diagnostics.record_vt_reset(&event_item.process);
                         }
@@ -72,35 +88,47 @@ pub(super) fn drain_process_events(
                     }
                 }
                 ProcessEventKind::Stdout => {
-                    if vt_emulator_enabled
+                    record_error_lines(
+                        &mut state.error_counts,
+                        &event_item.process,
+                        &event_item.payload,
+                    );
+                    // VT sessions render from the parser; the line buffer still
+                    // backs timestamps and level colouring.
+                    if !(vt_emulator_enabled
                         && *state
                             .vt_saw_chunk
                             .get(&event_item.process)
-                            .unwrap_or(&false)
+                            .unwrap_or(&false))
                     {
-                        continue;
+                        state
+                            .restart_pending
+                            .insert(event_item.process.clone(), false);
+                        state.output_seen.insert(event_item.process.clone(), true);
+                        diagnostics.record_stdout_lines(payload_line_count(&event_item.payload));
                     }
-                    state
-                        .restart_pending
-                        .insert(event_item.process.clone(), false);
-                    state.output_seen.insert(event_item.process.clone(), true);
-                    diagnostics.record_stdout_lines(payload_line_count(&event_item.payload));
                     ingest_log_payload(buffer, LogEntryKind::Stdout, &event_item.payload);
                 }
                 ProcessEventKind::Stderr => {
-                    if vt_emulator_enabled
+                    record_error_lines(
+                        &mut state.error_counts,
+                        &event_item.process,
+                        &event_item.payload,
+                    );
+                    // VT sessions render from the parser; the line buffer still
+                    // backs timestamps and level colouring.
+                    if !(vt_emulator_enabled
                         && *state
                             .vt_saw_chunk
                             .get(&event_item.process)
-                            .unwrap_or(&false)
+                            .unwrap_or(&false))
                     {
-                        continue;
+                        state
+                            .restart_pending
+                            .insert(event_item.process.clone(), false);
+                        state.output_seen.insert(event_item.process.clone(), true);
+                        diagnostics.record_stderr_lines(payload_line_count(&event_item.payload));
                     }
-                    state
-                        .restart_pending
-                        .insert(event_item.process.clone(), false);
-                    state.output_seen.insert(event_item.process.clone(), true);
-                    diagnostics.record_stderr_lines(payload_line_count(&event_item.payload));
                     ingest_log_payload(buffer, LogEntryKind::Stderr, &event_item.payload);
                 }
                 ProcessEventKind::Exit => {
@@ -126,6 +154,11 @@ pub(super) fn drain_process_events(
                             .exit_states
                             .insert(event_item.process.clone(), ProcessExitState::Success);
                     } else {
+                        state.pending_notifications.push(format!(
+                            "process `{}` exited ({})",
+                            event_item.process,
+                            event_item.payload.trim()
+                        ));
                         state
                             .observed_non_zero
                             .insert(event_item.process.clone(), event_item.payload.clone());
@@ -135,12 +168,10 @@ pub(super) fn drain_process_events(
                     }
                     push_entry(
                         buffer,
-                        LogEntry {
-                            kind: LogEntryKind::Exit,
-                            line: sanitize_log_text(&event_item.payload),
-                        },
+                        LogEntry::new(LogEntryKind::Exit, sanitize_log_text(&event_item.payload)),
                     );
                 }
+                ProcessEventKind::Added | ProcessEventKind::Removed => {}
             };
         }
     }
@@ -152,10 +183,11 @@ pub(super) fn handle_key_event(
     supervisor: &ProcessSupervisor,
     state: &mut SessionState,
     diagnostics: &mut RuntimeDiagnostics,
-    options: MultiProcessTuiOptions,
+    options: &MultiProcessTuiOptions,
     max_offset: usize,
 ) -> Result<LoopControl, MultiProcessTuiError> {
     diagnostics.record_keypress(key);
+    state.notice = None;
     let active_process = state.active_process().to_owned();
     let active_is_shell = active_process == "shell";
 
@@ -166,10 +198,7 @@ pub(super) fn handle_key_event(
         }
         return Ok(LoopControl::Quit);
     }
-    if active_is_shell
-        && key.modifiers.contains(KeyModifiers::CONTROL)
-        && matches!(key.code, KeyCode::Char('g'))
-    {
+    if active_is_shell && options.keymap.matches(KeyAction::ShellCapture, key) {
         state.shell_capture_mode = !state.shell_capture_mode;
         state.input_mode = InputMode::Command;
         return Ok(LoopControl::Continue);
@@ -180,7 +209,19 @@ pub(super) fn handle_key_event(
         }
         return Ok(LoopControl::Continue);
     }
-    if matches!(key.code, KeyCode::Esc)
+    if state.search.editing {
+        handle_search_key(key, state);
+        return Ok(LoopControl::Continue);
+    }
+    if state.copy.active {
+        handle_copy_key(key, state, &options.keymap);
+        return Ok(LoopControl::Continue);
+    }
+    if state.palette.editing {
+        handle_palette_key(key, supervisor, state, options)?;
+        return Ok(LoopControl::Continue);
+    }
+    if options.keymap.matches(KeyAction::Close, key)
         && options.esc_quit_on_complete
         && !state.show_help
         && !state.show_options
@@ -189,7 +230,12 @@ pub(super) fn handle_key_event(
     {
         return Ok(LoopControl::Quit);
     }
-    if matches!(key.code, KeyCode::Tab) {
+    let toggle_input = if state.input_mode == InputMode::Insert {
+        matches!(key.code, KeyCode::Tab)
+    } else {
+        options.keymap.matches(KeyAction::ToggleInput, key)
+    };
+    if toggle_input {
         if active_is_shell {
             if !state.shell_capture_mode {
                 state.shell_capture_mode = true;
@@ -214,13 +260,14 @@ pub(super) fn handle_key_event(
             .unwrap_or(&true);
         let actions = options_actions(follow_active);
         let active = state.process_names[state.active_index].clone();
+        if options.keymap.matches(KeyAction::Options, key) {
+            state.show_options = false;
+            return Ok(LoopControl::Continue);
+        }
         match key.code {
             KeyCode::Esc => {
                 state.show_options = false;
             }
-            KeyCode::Char('o') => {
-                state.show_options = false;
-            }
             KeyCode::Up => {
                 state.options_index = state.options_index.saturating_sub(1);
             }
@@ -263,6 +310,18 @@ pub(super) fn handle_key_event(
                 }
                 state.show_options = false;
             }
+            KeyCode::Char('l') => {
+                if apply_options_action(
+                    OptionsAction::SaveLogs,
+                    &active,
+                    supervisor,
+                    state,
+                    max_offset,
+                )? {
+                    return Ok(LoopControl::Quit);
+                }
+                state.show_options = false;
+            }
             KeyCode::Char('q') => {
                 if apply_options_action(
                     OptionsAction::Quit,
@@ -313,43 +372,104 @@ pub(super) fn handle_key_event(
         return Ok(LoopControl::Continue);
     }
 
-    match key.code {
-        KeyCode::Char('i') => {
+    let Some(action) = options.keymap.action_for(key) else {
+        return Ok(LoopControl::Continue);
+    };
+    match action {
+        KeyAction::Insert => {
             if state.process_names[state.active_index] != "shell" {
                 state.input_mode = InputMode::Insert;
                 state.show_help = false;
                 state.show_options = false;
             }
         }
-        KeyCode::Char('h') => {
+        KeyAction::Help => {
             state.show_help = !state.show_help;
             if state.show_help {
                 state.show_options = false;
+                state.show_stats = false;
             }
         }
-        KeyCode::Char('o') => {
-            state.show_options = !state.show_options;
-            if state.show_options {
+        KeyAction::Stats => {
+            state.show_stats = !state.show_stats;
+            if state.show_stats {
                 state.show_help = false;
-                state.options_index = 0;
+                state.show_options = false;
+            }
+        }
+        KeyAction::StatsSort if state.show_stats => {
+            state.stats_sort = state.stats_sort.next();
+        }
+        KeyAction::Search => {
+            let anchor = *state
+                .scroll_offsets
+                .get(state.active_process())
+                .unwrap_or(&0);
+            state.search.open(anchor);
+            state.show_help = false;
+            state.show_stats = false;
+        }
+        KeyAction::SearchNext if state.search.is_active() => {
+            jump_to_match(state, SearchDirection::Next);
+        }
+        KeyAction::SearchPrev if state.search.is_active() => {
+            jump_to_match(state, SearchDirection::Previous);
+        }
+        KeyAction::Copy => {
+            state.shell_capture_mode = false;
+            state.show_options = false;
+            enter_copy_mode(state);
+        }
+        KeyAction::Palette => {
+            state.shell_capture_mode = false;
+            state.show_help = false;
+            state.show_options = false;
+            state.show_stats = false;
+            state.palette.editing = true;
+            state.palette.input.clear();
+        }
+        KeyAction::Timestamps => {
+            state.timestamps = state.timestamps.next();
+        }
+        KeyAction::Levels => {
+            state.levels = !state.levels;
+        }
+        KeyAction::Grid => {
+            state.shell_capture_mode = false;
+            state.set_grid_mode(!state.grid_mode);
+            if state.grid_mode {
+                state.show_help = false;
+                state.show_stats = false;
             }
         }
-        KeyCode::BackTab => {
+        KeyAction::PrevTab if state.grid_mode => {
             state.shell_capture_mode = false;
             state.input_mode = InputMode::Command;
-            state.active_index = prev_index(state.active_index, state.process_names.len());
+            state.cycle_grid_focus(false);
         }
-        KeyCode::Right => {
+        KeyAction::NextTab if state.grid_mode => {
+            state.shell_capture_mode = false;
+            state.input_mode = InputMode::Command;
+            state.cycle_grid_focus(true);
+        }
+        KeyAction::Options => {
+            state.show_options = !state.show_options;
+            if state.show_options {
+                state.show_help = false;
+                state.options_index = 0;
+            }
+        }
+        KeyAction::NextTab => {
             state.shell_capture_mode = false;
             state.input_mode = InputMode::Command;
             state.active_index = next_index(state.active_index, state.process_names.len());
         }
-        KeyCode::Left => {
+        KeyAction::PrevTab => {
             state.shell_capture_mode = false;
             state.input_mode = InputMode::Command;
             state.active_index = prev_index(state.active_index, state.process_names.len());
         }
-        KeyCode::Up => {
+        KeyAction::ScrollUp => {
             let active = &state.process_names[state.active_index];
             if let Some(follow) = state.follow_mode.get_mut(active) {
                 *follow = false;
@@ -358,13 +478,13 @@ pub(super) fn handle_key_event(
                 *offset = offset.saturating_sub(1);
             }
         }
-        KeyCode::Down => {
+        KeyAction::ScrollDown => {
             let active = &state.process_names[state.active_index];
             if let Some(offset) = state.scroll_offsets.get_mut(active) {
                 *offset = offset.saturating_add(1).min(max_offset);
             }
         }
-        KeyCode::PageUp => {
+        KeyAction::PageUp => {
             let active = &state.process_names[state.active_index];
             if let Some(follow) = state.follow_mode.get_mut(active) {
                 *follow = false;
@@ -373,13 +493,13 @@ pub(super) fn handle_key_event(
                 *offset = offset.saturating_sub(10);
             }
         }
-        KeyCode::PageDown => {
+        KeyAction::PageDown => {
             let active = &state.process_names[state.active_index];
             if let Some(offset) = state.scroll_offsets.get_mut(active) {
                 *offset = offset.saturating_add(10).min(max_offset);
             }
         }
-        KeyCode::Home => {
+        KeyAction::Top => {
             let active = &state.process_names[state.active_index];
             if let Some(follow) = state.follow_mode.get_mut(active) {
                 *follow = false;
@@ -388,7 +508,7 @@ pub(super) fn handle_key_event(
                 *offset = 0;
             }
         }
-        KeyCode::End => {
+        KeyAction::Bottom => {
             let active = &state.process_names[state.active_index];
             if let Some(follow) = state.follow_mode.get_mut(active) {
                 *follow = true;
@@ -397,16 +517,62 @@ pub(super) fn handle_key_event(
                 *offset = max_offset;
             }
         }
-        KeyCode::Esc => {
+        KeyAction::Close => {
             state.show_help = false;
             state.show_options = false;
+            state.show_stats = false;
+            state.search.clear();
         }
-        _ => {}
+        KeyAction::ToggleInput
+        | KeyAction::ShellCapture
+        | KeyAction::StatsSort
+        | KeyAction::SearchNext
+        | KeyAction::SearchPrev => {}
     }
 
     Ok(LoopControl::Continue)
 }
 
+fn handle_search_key(key: &KeyEvent, state: &mut SessionState) {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('r') => {
+                state.search.regex = !state.search.regex;
+                state.search.recompile();
+                jump_to_match(state, SearchDirection::FromAnchor);
+            }
+            KeyCode::Char('f') => {
+                state.search.filter = !state.search.filter;
+                jump_to_match(state, SearchDirection::FromAnchor);
+            }
+            _ => {}
+        }
+        return;
+    }
+    match key.code {
+        KeyCode::Enter => {
+            state.search.editing = false;
+            if !state.search.is_active() {
+                state.search.clear();
+            }
+        }
+        KeyCode::Esc => {
+            state.search.clear();
+        }
+        KeyCode::Backspace => {
+            state.search.query.pop();
+            state.search.recompile();
+            jump_to_match(state, SearchDirection::FromAnchor);
+        }
+        KeyCode::Char(c) => {
+            state.search.query.push(c);
+            state.search.recompile();
+            jump_to_match(state, SearchDirection::FromAnchor);
+        }
+        _ => {}
+    }
+}
+
 fn all_processes_exited(
     exit_states: &std::collections::HashMap<String, ProcessExitState>,
     process_count: usize,
@@ -414,6 +580,13 @@ fn all_processes_exited(
     process_count > 0 && exit_states.len() >= process_count
 }
 
+fn record_error_lines(error_counts: &mut HashMap<String, usize>, process: &str, payload: &str) {
+    let errors = count_error_lines(payload);
+    if errors > 0 {
+        *error_counts.entry(process.to_owned()).or_insert(0) += errors;
+    }
+}
+
 fn payload_line_count(raw: &str) -> usize {
     raw.lines().count().max(1)
 }
@@ -446,7 +619,7 @@ fn shell_key_input(key: &KeyEvent) -> Option<String> {
     Some(mapped.to_owned())
 }
 
-fn apply_options_action(
+pub(super) fn apply_options_action(
     action: OptionsAction,
     active: &str,
     supervisor: &ProcessSupervisor,
@@ -482,7 +655,7 @@ fn apply_options_action(
                         &mut state.logs,
                         active,
                         LogEntryKind::Stdout,
-                        "[effigy] restarted process".to_owned(),
+                        "[effigy] restart requested".to_owned(),
                     );
                 }
                 Err(err) => push_log_line(
@@ -511,6 +684,16 @@ fn apply_options_action(
             }
             Ok(false)
         }
+        OptionsAction::SaveLogs => {
+            state.notice = Some(match save_all_logs(state) {
+                Ok(count) => format!(
+                    "saved {count} process log(s) to {}",
+                    state.log_dir.display()
+                ),
+                Err(err) => format!("save logs failed: {err}"),
+            });
+            Ok(false)
+        }
         OptionsAction::Cancel => Ok(false),
         OptionsAction::Quit => Ok(true),
     }
@@ -523,13 +706,7 @@ fn push_log_line(
     line: String,
 ) {
     if let Some(buffer) = logs.get_mut(process) {
-        push_entry(
-            buffer,
-            LogEntry {
-                kind,
-                line: sanitize_log_text(&line),
-            },
-        );
+        push_entry(buffer, LogEntry::new(kind, sanitize_log_text(&line)));
     }
 }
 
@@ -540,7 +717,15 @@ mod tests {
     use crate::tui::core::{next_index, prev_index, toggle_follow_for_active};
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-    use super::{all_processes_exited, shell_key_input, ProcessExitState};
+    use super::{
+        all_processes_exited, drain_process_events, handle_search_key, shell_key_input,
+        ProcessExitState,
+    };
+    use crate::process_manager::ProcessSupervisor;
+    use crate::recording::SessionRecording;
+    use crate::tui::core::{LogEntry, LogEntryKind};
+    use crate::tui::multiprocess::diagnostics::RuntimeDiagnostics;
+    use crate::tui::multiprocess::state::SessionState;
 
     #[test]
     fn all_processes_exited_requires_full_count() {
@@ -579,4 +764,109 @@ mod tests {
         let key = KeyEvent::new(KeyCode::Left, KeyModifiers::NONE);
         assert_eq!(shell_key_input(&key), Some("\u{1b}[D".to_owned()));
     }
+
+    #[test]
+    fn search_prompt_jumps_incrementally_and_toggles_modes() {
+        let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
+        let buffer = state.logs.get_mut("api").expect("api log buffer");
+        for line in ["boot", "warn: slow", "ERROR 500", "ok"] {
+            buffer.push_back(LogEntry::new(LogEntryKind::Stdout, line.to_owned()));
+        }
+        state.search.open(0);
+        for c in "err".chars() {
+            handle_search_key(
+                &KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+                &mut state,
+            );
+        }
+        assert_eq!(state.scroll_offsets.get("api"), Some(&2));
+        assert_eq!(state.search.match_count, 1);
+
+        handle_search_key(
+            &KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            &mut state,
+        );
+        assert!(state.search.regex);
+        handle_search_key(
+            &KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL),
+            &mut state,
+        );
+        assert!(state.search.filter);
+        handle_search_key(
+            &KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+            &mut state,
+        );
+        assert!(!state.search.editing);
+        assert!(state.search.is_active());
+
+        state.search.open(0);
+        handle_search_key(&KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &mut state);
+        assert!(!state.search.is_active());
+        assert!(!state.search.filter);
+    }
+    #[test]
+    fn replayed_recording_drives_the_vt_pipeline() {
+        let recording = SessionRecording::parse(concat!(
+            r#"{"schema":"effigy.tui.recording.v1","processes":["api"]}"#,
+            "\n",
+            r#"{"at_ms":0,"process":"api","kind":"stdout_chunk","payload":"booting...\r"}"#,
+            "\n",
+            r#"{"at_ms":5,"process":"api","kind":"stdout_chunk","payload":"\u001b[2Kready\r\n"}"#,
+            "\n",
+            r#"{"at_ms":9,"process":"api","kind":"exit","payload":"exit=2"}"#,
+            "\n",
+        ))
+        .expect("recording");
+        let supervisor = ProcessSupervisor::replay(recording, None);
+        let mut state = SessionState::new(vec!["api".to_owned()], 24, 80, 100);
+        let mut diagnostics = RuntimeDiagnostics::from_env();
+        for _ in 0..3 {
+            drain_process_events(&supervisor, &mut state, &mut diagnostics, 64, true);
+        }
+
+        let screen = state.vt_parsers.get("api").expect("api parser").screen();
+        assert_eq!(screen.contents().trim_end(), "ready");
+        assert_eq!(
+            state.exit_states.get("api"),
+            Some(&ProcessExitState::Failure)
+        );
+        assert_eq!(
+            state.pending_notifications,
+            vec!["process `api` exited (exit=2)".to_owned()]
+        );
+    }
+
+    #[test]
+    fn replayed_membership_events_open_and_close_tabs() {
+        let recording = SessionRecording::parse(concat!(
+            r#"{"schema":"effigy.tui.recording.v1","processes":["api","web"]}"#,
+            "\n",
+            r#"{"at_ms":0,"process":"web","kind":"removed","payload":""}"#,
+            "\n",
+            r#"{"at_ms":1,"process":"lint","kind":"added","payload":""}"#,
+            "\n",
+            r#"{"at_ms":2,"process":"lint","kind":"stdout","payload":"clean"}"#,
+            "\n",
+        ))
+        .expect("recording");
+        let tabs = recording.initial_tabs();
+        let supervisor = ProcessSupervisor::replay(recording, None);
+        let mut state = SessionState::new(tabs, 24, 80, 100);
+        let mut diagnostics = RuntimeDiagnostics::from_env();
+        for _ in 0..3 {
+            drain_process_events(&supervisor, &mut state, &mut diagnostics, 64, false);
+        }
+
+        assert_eq!(
+            state.process_names,
+            vec!["api".to_owned(), "lint".to_owned()]
+        );
+        let lint = state.logs.get("lint").expect("lint buffer");
+        assert_eq!(
+            lint.iter()
+                .map(|entry| entry.line.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["clean"]
+        );
+    }
 }
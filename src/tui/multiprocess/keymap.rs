@@ -0,0 +1,543 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+pub const KEY_PRESETS: [&str; 3] = ["default", "vim", "emacs"];
+
+/// Command-mode actions that can be rebound through `[tui.keys]`.
+/// Insert mode, shell capture passthrough, the options menu, the search
+/// prompt and copy mode keep fixed keys, and `ctrl+c` always quits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum KeyAction {
+    ToggleInput,
+    ShellCapture,
+    Insert,
+    NextTab,
+    PrevTab,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    Help,
+    Options,
+    Stats,
+    StatsSort,
+    Grid,
+    Search,
+    SearchNext,
+    SearchPrev,
+    Copy,
+    Timestamps,
+    Levels,
+    Palette,
+    Close,
+}
+
+const KEY_ACTIONS: [KeyAction; 24] = [
+    KeyAction::ToggleInput,
+    KeyAction::ShellCapture,
+    KeyAction::Insert,
+    KeyAction::NextTab,
+    KeyAction::PrevTab,
+    KeyAction::ScrollUp,
+    KeyAction::ScrollDown,
+    KeyAction::PageUp,
+    KeyAction::PageDown,
+    KeyAction::Top,
+    KeyAction::Bottom,
+    KeyAction::Help,
+    KeyAction::Options,
+    KeyAction::Stats,
+    KeyAction::StatsSort,
+    KeyAction::Grid,
+    KeyAction::Search,
+    KeyAction::SearchNext,
+    KeyAction::SearchPrev,
+    KeyAction::Copy,
+    KeyAction::Timestamps,
+    KeyAction::Levels,
+    KeyAction::Palette,
+    KeyAction::Close,
+];
+
+impl KeyAction {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            KeyAction::ToggleInput => "toggle_input",
+            KeyAction::ShellCapture => "shell_capture",
+            KeyAction::Insert => "insert",
+            KeyAction::NextTab => "next_tab",
+            KeyAction::PrevTab => "prev_tab",
+            KeyAction::ScrollUp => "scroll_up",
+            KeyAction::ScrollDown => "scroll_down",
+            KeyAction::PageUp => "page_up",
+            KeyAction::PageDown => "page_down",
+            KeyAction::Top => "top",
+            KeyAction::Bottom => "bottom",
+            KeyAction::Help => "help",
+            KeyAction::Options => "options",
+            KeyAction::Stats => "stats",
+            KeyAction::StatsSort => "stats_sort",
+            KeyAction::Grid => "grid",
+            KeyAction::Search => "search",
+            KeyAction::SearchNext => "search_next",
+            KeyAction::SearchPrev => "search_prev",
+            KeyAction::Copy => "copy",
+            KeyAction::Timestamps => "timestamps",
+            KeyAction::Levels => "levels",
+            KeyAction::Palette => "palette",
+            KeyAction::Close => "close",
+        }
+    }
+
+    pub(super) fn description(self) -> &'static str {
+        match self {
+            KeyAction::ToggleInput => "toggle insert/command mode (shell tab: enter capture)",
+            KeyAction::ShellCapture => "toggle shell capture mode (shell tab)",
+            KeyAction::Insert => "enter insert mode",
+            KeyAction::NextTab => "next process tab (grid: next pane)",
+            KeyAction::PrevTab => "previous process tab (grid: previous pane)",
+            KeyAction::ScrollUp => "scroll output up one line",
+            KeyAction::ScrollDown => "scroll output down one line",
+            KeyAction::PageUp => "scroll output up one page",
+            KeyAction::PageDown => "scroll output down one page",
+            KeyAction::Top => "jump to top",
+            KeyAction::Bottom => "jump to bottom (re-enables follow)",
+            KeyAction::Help => "toggle this help",
+            KeyAction::Options => "open per-process options menu",
+            KeyAction::Stats => "toggle process resource overview",
+            KeyAction::StatsSort => "cycle overview sort (name/cpu/rss)",
+            KeyAction::Grid => "toggle grid layout (side-by-side panes)",
+            KeyAction::Search => "search output (ctrl+r regex, ctrl+f filter)",
+            KeyAction::SearchNext => "jump to next match",
+            KeyAction::SearchPrev => "jump to previous match",
+            KeyAction::Copy => "copy mode: select lines to copy or save",
+            KeyAction::Timestamps => "cycle timestamps (off/relative/absolute)",
+            KeyAction::Levels => "toggle level colouring and JSON pretty-printing",
+            KeyAction::Palette => "command palette: run a task, close a tab, switch profile",
+            KeyAction::Close => "close panels and clear search",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        KEY_ACTIONS
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let trimmed = raw.trim();
+        // A lone `+` is a key; otherwise `+` separates modifiers from the key.
+        let (modifier_part, key_part) = match trimmed.rsplit_once('+') {
+            Some((mods, "")) => (mods.strip_suffix('+').unwrap_or(mods), "+"),
+            Some((mods, key)) => (mods, key),
+            None => ("", trimmed),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+        for modifier in modifier_part.split('+').filter(|part| !part.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                other => return Err(format!("unknown modifier `{other}` in key `{raw}`")),
+            }
+        }
+
+        let lower = key_part.to_ascii_lowercase();
+        let code = match lower.as_str() {
+            "tab" if shift => KeyCode::BackTab,
+            "backtab" => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "pageup" => KeyCode::PageUp,
+            "pgdn" | "pagedown" => KeyCode::PageDown,
+            _ if lower.len() > 1 && lower.starts_with('f') => lower[1..]
+                .parse::<u8>()
+                .ok()
+                .filter(|number| (1..=12).contains(number))
+                .map(KeyCode::F)
+                .ok_or_else(|| format!("unknown key `{raw}`"))?,
+            _ => {
+                let mut chars = key_part.chars();
+                let (Some(ch), None) = (chars.next(), chars.next()) else {
+                    return Err(format!("unknown key `{raw}`"));
+                };
+                let ch = if shift { ch.to_ascii_uppercase() } else { ch };
+                // Terminals report control chords with the lowercase letter.
+                let ch = if modifiers.contains(KeyModifiers::CONTROL) {
+                    ch.to_ascii_lowercase()
+                } else {
+                    ch
+                };
+                KeyCode::Char(ch)
+            }
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    fn from_event(key: &KeyEvent) -> Self {
+        // Shift is carried by the character itself (`N`) or by `BackTab`.
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match key.code {
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(ch.to_ascii_lowercase())
+            }
+            other => other,
+        };
+        if matches!(code, KeyCode::BackTab) {
+            modifiers = KeyModifiers::NONE;
+        }
+        Self { code, modifiers }
+    }
+
+    fn label(self) -> String {
+        let key = match self.code {
+            KeyCode::BackTab => "shift+tab".to_owned(),
+            KeyCode::Tab => "tab".to_owned(),
+            KeyCode::Esc => "esc".to_owned(),
+            KeyCode::Enter => "enter".to_owned(),
+            KeyCode::Char(' ') => "space".to_owned(),
+            KeyCode::Backspace => "backspace".to_owned(),
+            KeyCode::Delete => "delete".to_owned(),
+            KeyCode::Left => "left".to_owned(),
+            KeyCode::Right => "right".to_owned(),
+            KeyCode::Up => "up".to_owned(),
+            KeyCode::Down => "down".to_owned(),
+            KeyCode::Home => "home".to_owned(),
+            KeyCode::End => "end".to_owned(),
+            KeyCode::PageUp => "pgup".to_owned(),
+            KeyCode::PageDown => "pgdn".to_owned(),
+            KeyCode::F(number) => format!("f{number}"),
+            KeyCode::Char(ch) => ch.to_string(),
+            _ => "?".to_owned(),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("alt+");
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+/// Resolved command-mode key bindings for the multiprocess TUI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMap {
+    bindings: Vec<(KeyAction, Vec<KeyBinding>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::from_config(None, &[]).expect("default key preset must be valid")
+    }
+}
+
+impl KeyMap {
+    /// Builds a key map from a preset (`default`, `vim` or `emacs`) and
+    /// per-action overrides such as `("help", vec!["?"])`. An override
+    /// replaces every key of that action; an empty list unbinds it.
+    pub fn from_config(
+        preset: Option<&str>,
+        overrides: &[(String, Vec<String>)],
+    ) -> Result<Self, String> {
+        let preset = preset.unwrap_or("default");
+        let defaults = preset_bindings(preset).ok_or_else(|| {
+            format!(
+                "unknown key preset `{preset}` (available: {})",
+                KEY_PRESETS.join(", ")
+            )
+        })?;
+        let mut bindings = Vec::with_capacity(KEY_ACTIONS.len());
+        for action in KEY_ACTIONS {
+            let keys = defaults
+                .iter()
+                .find(|(candidate, _)| *candidate == action)
+                .map(|(_, keys)| *keys)
+                .unwrap_or(&[]);
+            bindings.push((action, parse_keys(keys.iter().copied())?));
+        }
+        for (name, keys) in overrides {
+            let action = KeyAction::from_name(name).ok_or_else(|| {
+                format!(
+                    "unknown action `{name}` (available: {})",
+                    KEY_ACTIONS
+                        .iter()
+                        .map(|action| action.name())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                )
+            })?;
+            let parsed = parse_keys(keys.iter().map(String::as_str))?;
+            if let Some((_, existing)) = bindings
+                .iter_mut()
+                .find(|(candidate, _)| *candidate == action)
+            {
+                *existing = parsed;
+            }
+        }
+
+        let keymap = Self { bindings };
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    fn check_conflicts(&self) -> Result<(), String> {
+        for (index, (action, keys)) in self.bindings.iter().enumerate() {
+            for key in keys {
+                if let Some((other, _)) = self.bindings[index + 1..]
+                    .iter()
+                    .find(|(_, other_keys)| other_keys.contains(key))
+                {
+                    return Err(format!(
+                        "key `{}` is bound to both `{}` and `{}`",
+                        key.label(),
+                        action.name(),
+                        other.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn action_for(&self, key: &KeyEvent) -> Option<KeyAction> {
+        let pressed = KeyBinding::from_event(key);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&pressed))
+            .map(|(action, _)| *action)
+    }
+
+    pub(super) fn matches(&self, action: KeyAction, key: &KeyEvent) -> bool {
+        let pressed = KeyBinding::from_event(key);
+        self.bindings
+            .iter()
+            .any(|(candidate, keys)| *candidate == action && keys.contains(&pressed))
+    }
+
+    /// Keys for `action` joined with `/`, or `unbound`.
+    pub(super) fn label(&self, action: KeyAction) -> String {
+        let labels = self
+            .bindings
+            .iter()
+            .find(|(candidate, _)| *candidate == action)
+            .map(|(_, keys)| keys.iter().map(|key| key.label()).collect::<Vec<String>>())
+            .unwrap_or_default();
+        if labels.is_empty() {
+            "unbound".to_owned()
+        } else {
+            labels.join("/")
+        }
+    }
+
+    /// `(keys, description)` rows for the help overlay, in display order.
+    pub(super) fn help_entries(&self) -> Vec<(String, &'static str)> {
+        self.bindings
+            .iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(action, _)| (self.label(*action), action.description()))
+            .collect()
+    }
+}
+
+fn parse_keys<'a>(keys: impl Iterator<Item = &'a str>) -> Result<Vec<KeyBinding>, String> {
+    keys.map(KeyBinding::parse).collect()
+}
+
+type PresetBindings = &'static [(KeyAction, &'static [&'static str])];
+
+fn preset_bindings(preset: &str) -> Option<PresetBindings> {
+    const DEFAULT: PresetBindings = &[
+        (KeyAction::ToggleInput, &["tab"]),
+        (KeyAction::ShellCapture, &["ctrl+g"]),
+        (KeyAction::Insert, &["i"]),
+        (KeyAction::NextTab, &["right"]),
+        (KeyAction::PrevTab, &["left", "shift+tab"]),
+        (KeyAction::ScrollUp, &["up"]),
+        (KeyAction::ScrollDown, &["down"]),
+        (KeyAction::PageUp, &["pgup"]),
+        (KeyAction::PageDown, &["pgdn"]),
+        (KeyAction::Top, &["home"]),
+        (KeyAction::Bottom, &["end"]),
+        (KeyAction::Help, &["h"]),
+        (KeyAction::Options, &["o"]),
+        (KeyAction::Stats, &["p"]),
+        (KeyAction::StatsSort, &["s"]),
+        (KeyAction::Grid, &["g"]),
+        (KeyAction::Search, &["/"]),
+        (KeyAction::SearchNext, &["n"]),
+        (KeyAction::SearchPrev, &["N"]),
+        (KeyAction::Copy, &["v"]),
+        (KeyAction::Timestamps, &["t"]),
+        (KeyAction::Levels, &["c"]),
+        (KeyAction::Palette, &[":"]),
+        (KeyAction::Close, &["esc"]),
+    ];
+    const VIM: PresetBindings = &[
+        (KeyAction::ToggleInput, &["tab"]),
+        (KeyAction::ShellCapture, &["ctrl+g"]),
+        (KeyAction::Insert, &["i", "a"]),
+        (KeyAction::NextTab, &["l", "right"]),
+        (KeyAction::PrevTab, &["h", "left", "shift+tab"]),
+        (KeyAction::ScrollUp, &["k", "up"]),
+        (KeyAction::ScrollDown, &["j", "down"]),
+        (KeyAction::PageUp, &["ctrl+u", "ctrl+b", "pgup"]),
+        (KeyAction::PageDown, &["ctrl+d", "ctrl+f", "pgdn"]),
+        (KeyAction::Top, &["home"]),
+        (KeyAction::Bottom, &["G", "end"]),
+        (KeyAction::Help, &["?"]),
+        (KeyAction::Options, &["o"]),
+        (KeyAction::Stats, &["p"]),
+        (KeyAction::StatsSort, &["s"]),
+        (KeyAction::Grid, &["ctrl+w"]),
+        (KeyAction::Search, &["/"]),
+        (KeyAction::SearchNext, &["n"]),
+        (KeyAction::SearchPrev, &["N"]),
+        (KeyAction::Copy, &["v"]),
+        (KeyAction::Timestamps, &["t"]),
+        (KeyAction::Levels, &["c"]),
+        (KeyAction::Palette, &[":"]),
+        (KeyAction::Close, &["esc"]),
+    ];
+    const EMACS: PresetBindings = &[
+        (KeyAction::ToggleInput, &["tab"]),
+        (KeyAction::ShellCapture, &["ctrl+g"]),
+        (KeyAction::Insert, &["i"]),
+        (KeyAction::NextTab, &["ctrl+f", "right"]),
+        (KeyAction::PrevTab, &["ctrl+b", "left", "shift+tab"]),
+        (KeyAction::ScrollUp, &["ctrl+p", "up"]),
+        (KeyAction::ScrollDown, &["ctrl+n", "down"]),
+        (KeyAction::PageUp, &["alt+v", "pgup"]),
+        (KeyAction::PageDown, &["ctrl+v", "pgdn"]),
+        (KeyAction::Top, &["alt+<", "home"]),
+        (KeyAction::Bottom, &["alt+>", "end"]),
+        (KeyAction::Help, &["f1", "?"]),
+        (KeyAction::Options, &["o"]),
+        (KeyAction::Stats, &["p"]),
+        (KeyAction::StatsSort, &["s"]),
+        (KeyAction::Grid, &["g"]),
+        (KeyAction::Search, &["ctrl+s", "/"]),
+        (KeyAction::SearchNext, &["n"]),
+        (KeyAction::SearchPrev, &["N"]),
+        (KeyAction::Copy, &["ctrl+space", "v"]),
+        (KeyAction::Timestamps, &["t"]),
+        (KeyAction::Levels, &["c"]),
+        (KeyAction::Palette, &[":"]),
+        (KeyAction::Close, &["esc"]),
+    ];
+    match preset {
+        "default" => Some(DEFAULT),
+        "vim" => Some(VIM),
+        "emacs" => Some(EMACS),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{KeyAction, KeyMap, KEY_PRESETS};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn every_preset_is_conflict_free() {
+        for preset in KEY_PRESETS {
+            KeyMap::from_config(Some(preset), &[]).expect("preset should be valid");
+        }
+    }
+
+    #[test]
+    fn default_preset_matches_builtin_keys() {
+        let keymap = KeyMap::default();
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('h'), KeyModifiers::NONE)),
+            Some(KeyAction::Help)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('N'), KeyModifiers::SHIFT)),
+            Some(KeyAction::SearchPrev)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(KeyAction::PrevTab)
+        );
+        assert!(keymap.matches(
+            KeyAction::ShellCapture,
+            &key(KeyCode::Char('g'), KeyModifiers::CONTROL)
+        ));
+        assert_eq!(keymap.label(KeyAction::PrevTab), "left/shift+tab");
+    }
+
+    #[test]
+    fn overrides_replace_action_keys() {
+        let keymap = KeyMap::from_config(
+            Some("vim"),
+            &[
+                ("help".to_owned(), vec!["f1".to_owned()]),
+                ("shell_capture".to_owned(), vec!["ctrl+space".to_owned()]),
+                ("grid".to_owned(), Vec::new()),
+            ],
+        )
+        .expect("overrides should apply");
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::F(1), KeyModifiers::NONE)),
+            Some(KeyAction::Help)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('?'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(KeyAction::ScrollDown)
+        );
+        assert_eq!(keymap.label(KeyAction::ShellCapture), "ctrl+space");
+        assert_eq!(keymap.label(KeyAction::Grid), "unbound");
+        assert!(keymap
+            .help_entries()
+            .iter()
+            .all(|(_, description)| !description.contains("grid layout")));
+    }
+
+    #[test]
+    fn invalid_configs_report_the_problem() {
+        let err = KeyMap::from_config(Some("nano"), &[]).expect_err("unknown preset");
+        assert!(err.contains("unknown key preset `nano`"));
+        let err = KeyMap::from_config(None, &[("jump".to_owned(), vec!["x".to_owned()])])
+            .expect_err("unknown action");
+        assert!(err.contains("unknown action `jump`"));
+        let err = KeyMap::from_config(None, &[("help".to_owned(), vec!["hyper+x".to_owned()])])
+            .expect_err("unknown modifier");
+        assert!(err.contains("unknown modifier `hyper`"));
+        let err = KeyMap::from_config(None, &[("grid".to_owned(), vec!["o".to_owned()])])
+            .expect_err("conflict");
+        assert_eq!(err, "key `o` is bound to both `options` and `grid`");
+    }
+}
@@ -3,6 +3,7 @@ use std::io;
 use std::io::IsTerminal;
 use std::time::Instant;
 
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnableLineWrap, EnterAlternateScreen, LeaveAlternateScreen,
@@ -19,15 +20,19 @@ use crate::ui::{KeyValue, OutputMode, PlainRenderer, Renderer};
 
 use super::config::SHUTDOWN_GRACE_TIMEOUT;
 use super::diagnostics::RuntimeDiagnostics;
+use super::stats::{format_bytes, ProcessStats};
 use super::terminal_text::{format_elapsed, is_expected_shutdown_diagnostic, styled_text};
 use super::MultiProcessTuiError;
 
 pub(super) type TuiTerminal = Terminal<CrosstermBackend<std::io::Stdout>>;
 
-pub(super) fn init_terminal() -> Result<TuiTerminal, io::Error> {
+pub(super) fn init_terminal(mouse_capture: bool) -> Result<TuiTerminal, io::Error> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if mouse_capture {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
@@ -39,18 +44,30 @@ pub(super) fn shutdown_and_render_summary(
     supervisor: &ProcessSupervisor,
     observed_non_zero: HashMap<String, String>,
     process_started_at: &HashMap<String, Instant>,
+    process_stats: &HashMap<String, ProcessStats>,
     diagnostics: &RuntimeDiagnostics,
 ) -> Result<Vec<(String, String)>, MultiProcessTuiError> {
     supervisor.terminate_all_graceful_with_progress(SHUTDOWN_GRACE_TIMEOUT, |progress| {
         let label = match progress {
-            ShutdownProgress::SendingTerm => "Shutdown: sending SIGTERM to managed processes...",
-            ShutdownProgress::Waiting => "Shutdown: waiting for managed processes to exit...",
-            ShutdownProgress::ForceKilling => {
-                "Shutdown: forcing remaining managed processes to stop..."
+            ShutdownProgress::Stopping { process, method } => {
+                format!("Shutdown: stopping `{process}` ({method})...")
+            }
+            ShutdownProgress::Waiting { processes } => {
+                format!("Shutdown: waiting for {} to exit...", processes.join(", "))
+            }
+            ShutdownProgress::ForceKilling { process } => {
+                format!("Shutdown: forcing `{process}` to stop...")
             }
-            ShutdownProgress::Complete { .. } => "Shutdown: complete.",
+            ShutdownProgress::Stopped { process, forced } => {
+                if forced {
+                    format!("Shutdown: `{process}` force-stopped.")
+                } else {
+                    format!("Shutdown: `{process}` stopped.")
+                }
+            }
+            ShutdownProgress::Complete { .. } => "Shutdown: complete.".to_owned(),
         };
-        let _ = draw_shutdown_status(terminal, label);
+        let _ = draw_shutdown_status(terminal, &label);
     });
 
     let mut non_zero_map = observed_non_zero;
@@ -67,7 +84,12 @@ pub(super) fn shutdown_and_render_summary(
     non_zero_exits.sort_by(|a, b| a.0.cmp(&b.0));
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, EnableLineWrap)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        EnableLineWrap
+    )?;
     terminal.show_cursor()?;
 
     let mut renderer = PlainRenderer::stdout(OutputMode::from_env());
@@ -105,6 +127,24 @@ pub(super) fn shutdown_and_render_summary(
     }
     renderer.text("")?;
 
+    if !process_stats.is_empty() {
+        renderer.section("Process Resources")?;
+        let mut names = process_stats.keys().cloned().collect::<Vec<String>>();
+        names.sort();
+        for name in names {
+            let stats = &process_stats[&name];
+            let cpu_seconds = stats.cpu_time.as_secs_f64();
+            renderer.key_values(&[KeyValue::new(
+                name,
+                format!(
+                    "peak-rss {} · cpu-time {cpu_seconds:.1}s",
+                    format_bytes(stats.peak_rss_bytes)
+                ),
+            )])?;
+        }
+        renderer.text("")?;
+    }
+
     if diagnostics.enabled() {
         renderer.section("TUI Diagnostics")?;
         renderer.key_values(&[
@@ -1,30 +1,49 @@
+use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crossterm::event::{self, Event, KeyEventKind};
+use ratatui::layout::Rect;
 
-use crate::process_manager::{ProcessManagerError, ProcessSpec, ProcessSupervisor};
+use crate::notify::{Notifier, NotifyEvent};
+use crate::process_manager::{ProcessManagerError, ProcessSpec, ProcessSupervisor, PtySize};
+use crate::recording::SessionRecording;
 use crate::ui::UiError;
 
+mod annotate;
 mod config;
+mod copy;
 mod diagnostics;
 mod events;
+mod keymap;
 mod lifecycle;
+mod mouse;
+mod palette;
 mod render;
+mod search;
 mod state;
-mod terminal_text;
+mod stats;
+pub(crate) mod terminal_text;
 mod view_model;
 
+pub use config::MAX_GRID_PANES;
 use config::{
-    INPUT_POLL_WAIT, MAX_EVENTS_PER_TICK, VT_PARSER_COLS, VT_PARSER_ROWS, VT_PARSER_SCROLLBACK,
+    INPUT_POLL_WAIT, LOGS_DIR, MAX_EVENTS_PER_TICK, VT_PARSER_COLS, VT_PARSER_ROWS,
+    VT_PARSER_SCROLLBACK,
 };
+use copy::write_osc52;
 use diagnostics::RuntimeDiagnostics;
 use events::{drain_process_events, handle_key_event, LoopControl};
+pub use keymap::{KeyMap, KEY_PRESETS};
 use lifecycle::{init_terminal, shutdown_and_render_summary};
-use render::render_ui;
+use mouse::handle_mouse_event;
+use palette::palette_hint;
+use render::{body_layout, grid_pane_areas, render_ui};
 pub(super) use state::OptionsAction;
 use state::SessionState;
-use view_model::build_active_view_model;
+use view_model::{
+    build_active_view_model, build_process_view_model, vt_panel_size, ActiveViewModel,
+};
 
 #[derive(Debug)]
 pub enum MultiProcessTuiError {
@@ -39,9 +58,33 @@ pub struct MultiProcessTuiOutcome {
     pub non_zero_exits: Vec<(String, String)>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct MultiProcessTuiOptions {
     pub esc_quit_on_complete: bool,
+    pub process_ports: HashMap<String, Vec<(String, u16)>>,
+    /// Rows of process names shown side by side in grid layout. Empty uses a
+    /// default grid of the first processes.
+    pub panes: Vec<Vec<String>>,
+    pub start_in_grid: bool,
+    pub keymap: KeyMap,
+    /// Notified when a process exits non-zero.
+    pub notify: Option<Notifier>,
+    /// Records every process event to this file for `effigy replay`.
+    pub record: Option<PathBuf>,
+    /// Catalog tasks the command palette can start as new tabs.
+    pub palette_tasks: Vec<ProcessSpec>,
+    /// Other profiles of the running task, for `profile <name>` in the palette.
+    pub palette_profiles: Vec<PaletteProfile>,
+}
+
+/// A process set the command palette can switch the session to.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PaletteProfile {
+    pub name: String,
+    pub processes: Vec<ProcessSpec>,
+    pub tab_order: Vec<String>,
+    pub panes: Vec<Vec<String>>,
+    pub process_ports: HashMap<String, Vec<(String, u16)>>,
 }
 
 impl std::fmt::Display for MultiProcessTuiError {
@@ -94,14 +137,59 @@ pub fn run_multiprocess_tui(
     } else {
         tab_order
     };
-    let supervisor = ProcessSupervisor::spawn(repo_root, processes)?;
-    let mut terminal = init_terminal()?;
+    let supervisor = ProcessSupervisor::spawn(repo_root.clone(), processes)?;
+    if let Some(path) = options.record.as_ref() {
+        supervisor.record_to(path, &process_names)?;
+    }
+    run_session(&repo_root, supervisor, process_names, options, None)
+}
+
+/// Replays a recorded session through the same event and render pipeline.
+/// Event timing is divided by `speed`; `None` replays every event at once.
+pub fn replay_multiprocess_tui(
+    repo_root: PathBuf,
+    recording: SessionRecording,
+    speed: Option<f64>,
+    options: MultiProcessTuiOptions,
+) -> Result<MultiProcessTuiOutcome, MultiProcessTuiError> {
+    let process_names = recording.initial_tabs();
+    if process_names.is_empty() {
+        return Err(MultiProcessTuiError::NoProcesses);
+    }
+    let notice = format!(
+        "replaying {} event(s) at {}",
+        recording.events.len(),
+        speed.map_or_else(|| "full speed".to_owned(), |speed| format!("{speed}x"))
+    );
+    let supervisor = ProcessSupervisor::replay(recording, speed);
+    run_session(&repo_root, supervisor, process_names, options, Some(notice))
+}
+
+fn run_session(
+    repo_root: &Path,
+    supervisor: ProcessSupervisor,
+    process_names: Vec<String>,
+    options: MultiProcessTuiOptions,
+    notice: Option<String>,
+) -> Result<MultiProcessTuiOutcome, MultiProcessTuiError> {
+    let log_dir = repo_root.join(LOGS_DIR);
+    let mouse_capture = std::env::var("EFFIGY_TUI_MOUSE")
+        .ok()
+        .is_none_or(|value| value != "0" && !value.eq_ignore_ascii_case("false"));
+    let mut terminal = init_terminal(mouse_capture)?;
     let mut state = SessionState::new(
         process_names,
         VT_PARSER_ROWS,
         VT_PARSER_COLS,
         VT_PARSER_SCROLLBACK,
     );
+    if !options.panes.is_empty() {
+        state.grid_panes = options.panes.clone();
+    }
+    state.set_grid_mode(options.start_in_grid);
+    state.process_ports = options.process_ports.clone();
+    state.log_dir = log_dir;
+    state.notice = notice;
     let mut diagnostics = RuntimeDiagnostics::from_env();
 
     let vt_emulator_enabled = std::env::var("EFFIGY_TUI_VT100")
@@ -116,13 +204,85 @@ pub fn run_multiprocess_tui(
             MAX_EVENTS_PER_TICK,
             vt_emulator_enabled,
         );
+        for message in state.pending_notifications.drain(..) {
+            if let Some(notifier) = options.notify.as_ref() {
+                notifier.send(repo_root, NotifyEvent::ProcessFailed, &message);
+            }
+        }
         state.spinner_tick = state.spinner_tick.wrapping_add(1);
+        state.stats.sample_if_due(&supervisor.process_ids());
+        state.stopping = supervisor.stopping_processes().into_iter().collect();
 
         let size = terminal.size()?;
         let output_height = size.height.saturating_sub(9) as usize;
         let output_width = size.width.saturating_sub(4) as usize;
-        let active_view =
-            build_active_view_model(&mut state, output_height, output_width, vt_emulator_enabled);
+        let mut pane_sizes = HashMap::new();
+        let grid_views = if state.grid_mode {
+            let body = body_layout(
+                Rect::new(0, 0, size.width, size.height),
+                state.active_process() == "shell",
+                state.input_mode,
+            );
+            let pane_areas = grid_pane_areas(body[1], &state.grid_panes)
+                .into_iter()
+                .filter(|(name, _)| state.logs.contains_key(name))
+                .collect::<Vec<(String, Rect)>>();
+            pane_areas
+                .into_iter()
+                .map(|(name, area)| {
+                    let pane_height = area.height.saturating_sub(2) as usize;
+                    let pane_width = area.width.saturating_sub(3) as usize;
+                    pane_sizes.insert(name.clone(), vt_panel_size(pane_height, pane_width));
+                    let view = build_process_view_model(
+                        &mut state,
+                        name,
+                        pane_height,
+                        pane_width,
+                        vt_emulator_enabled,
+                    );
+                    (area, view)
+                })
+                .collect::<Vec<(Rect, ActiveViewModel)>>()
+        } else {
+            Vec::new()
+        };
+        let single_view = if grid_views
+            .iter()
+            .any(|(_, view)| view.active_process == state.active_process())
+        {
+            None
+        } else {
+            Some(build_active_view_model(
+                &mut state,
+                output_height,
+                output_width,
+                vt_emulator_enabled,
+            ))
+        };
+        sync_pty_sizes(
+            &supervisor,
+            &mut state,
+            &pane_sizes,
+            vt_panel_size(output_height, output_width),
+        );
+        let active_view = single_view
+            .as_ref()
+            .or_else(|| {
+                grid_views
+                    .iter()
+                    .map(|(_, view)| view)
+                    .find(|view| view.active_process == state.active_process())
+            })
+            .expect("active process view missing unexpectedly");
+        let active_ports = state
+            .process_ports
+            .get(&active_view.active_process)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        let palette_prompt = state
+            .palette
+            .editing
+            .then(|| (state.palette.input.clone(), palette_hint(&state, &options)));
 
         terminal.draw(|frame| {
             render_ui(
@@ -140,6 +300,7 @@ pub fn run_multiprocess_tui(
                 state.input_mode,
                 state.shell_capture_mode,
                 &state.exit_states,
+                &state.stopping,
                 state.show_help,
                 state.show_options,
                 state.options_index,
@@ -148,26 +309,55 @@ pub fn run_multiprocess_tui(
                 active_view.active_elapsed,
                 active_view.active_restart_count,
                 active_view.shell_cursor,
+                active_ports,
+                state.stats.stats(),
+                state.show_stats,
+                state.stats_sort,
+                &grid_views,
+                &state.search,
+                &options.keymap,
+                active_view.selection,
+                state.notice.as_deref(),
+                active_view.annotations,
+                state.timestamps,
+                state.levels,
+                &state.error_counts,
+                palette_prompt
+                    .as_ref()
+                    .map(|(input, hint)| (input.as_str(), hint.as_str())),
             )
         })?;
         diagnostics.record_frame();
 
         if event::poll(INPUT_POLL_WAIT)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
+            match event::read()? {
+                Event::Key(key) => {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match handle_key_event(
+                        &key,
+                        &supervisor,
+                        &mut state,
+                        &mut diagnostics,
+                        &options,
+                        active_view.max_offset,
+                    )? {
+                        LoopControl::Continue => {}
+                        LoopControl::Quit => break Ok(()),
+                    }
+                    if let Some(text) = state.copy.pending_clipboard.take() {
+                        write_osc52(&mut io::stdout(), &text)?;
+                    }
                 }
-                match handle_key_event(
-                    &key,
+                Event::Mouse(mouse) => handle_mouse_event(
+                    &mouse,
+                    Rect::new(0, 0, size.width, size.height),
                     &supervisor,
                     &mut state,
-                    &mut diagnostics,
-                    options,
                     active_view.max_offset,
-                )? {
-                    LoopControl::Continue => {}
-                    LoopControl::Quit => break Ok(()),
-                }
+                )?,
+                _ => {}
             }
         }
     };
@@ -177,9 +367,27 @@ pub fn run_multiprocess_tui(
         &supervisor,
         state.observed_non_zero,
         &state.process_started_at,
+        state.stats.stats(),
         &diagnostics,
     )?;
 
     result?;
     Ok(MultiProcessTuiOutcome { non_zero_exits })
 }
+
+/// Resizes each process's pseudo-terminal to the grid pane it is drawn in, or
+/// to the single-tab output area when it has no pane on screen.
+fn sync_pty_sizes(
+    supervisor: &ProcessSupervisor,
+    state: &mut SessionState,
+    pane_sizes: &HashMap<String, PtySize>,
+    tab_size: PtySize,
+) {
+    for name in &state.process_names {
+        let size = pane_sizes.get(name).copied().unwrap_or(tab_size);
+        if state.pty_sizes.get(name) != Some(&size) {
+            supervisor.resize_process(name, size);
+            state.pty_sizes.insert(name.clone(), size);
+        }
+    }
+}
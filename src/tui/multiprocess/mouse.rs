@@ -0,0 +1,359 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use vt100::{MouseProtocolEncoding, MouseProtocolMode};
+
+use crate::process_manager::ProcessSupervisor;
+use crate::tui::core::InputMode;
+
+use super::config::MOUSE_SCROLL_LINES;
+use super::render::{body_layout, grid_pane_areas, tab_at};
+use super::state::SessionState;
+use super::MultiProcessTuiError;
+
+/// Handles a mouse event for a frame of size `area`.
+///
+/// Clicking a tab switches to it, clicking a grid pane focuses it, and the
+/// wheel scrolls the pane under the pointer (which pauses follow). While the
+/// shell tab is captured and its program has enabled mouse reporting, events
+/// inside the shell pane are forwarded to it instead.
+pub(super) fn handle_mouse_event(
+    mouse: &MouseEvent,
+    area: Rect,
+    supervisor: &ProcessSupervisor,
+    state: &mut SessionState,
+    max_offset: usize,
+) -> Result<(), MultiProcessTuiError> {
+    if state.show_options || state.copy.active {
+        return Ok(());
+    }
+    if let Some((process, input)) = shell_mouse_input(mouse, area, state) {
+        supervisor.send_input(&process, &input)?;
+        return Ok(());
+    }
+    apply_mouse_event(mouse, area, state, max_offset);
+    Ok(())
+}
+
+fn apply_mouse_event(mouse: &MouseEvent, area: Rect, state: &mut SessionState, max_offset: usize) {
+    let chunks = body_layout(area, state.active_process() == "shell", state.input_mode);
+    let hovered = pane_at(state, chunks[1], mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let clicked = tab_at(
+                chunks[0],
+                &state.process_names,
+                state.shell_capture_mode,
+                mouse.column,
+                mouse.row,
+            )
+            .map(|index| state.process_names[index].clone())
+            .or_else(|| hovered.filter(|_| state.grid_mode));
+            if let Some(name) = clicked {
+                if name != state.active_process() {
+                    state.shell_capture_mode = false;
+                    state.input_mode = InputMode::Command;
+                    state.focus_process(&name);
+                }
+            }
+        }
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            if state.show_help || state.show_stats {
+                return;
+            }
+            let Some(name) = hovered else {
+                return;
+            };
+            let up = matches!(mouse.kind, MouseEventKind::ScrollUp);
+            if up {
+                state.follow_mode.insert(name.clone(), false);
+            }
+            // Only the active view's bound is known here; other panes are
+            // clamped when their view is next built.
+            let limit = if name == state.active_process() {
+                max_offset
+            } else {
+                usize::MAX
+            };
+            if let Some(offset) = state.scroll_offsets.get_mut(&name) {
+                *offset = if up {
+                    offset.saturating_sub(MOUSE_SCROLL_LINES)
+                } else {
+                    offset.saturating_add(MOUSE_SCROLL_LINES).min(limit)
+                };
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Process whose output pane contains `(column, row)`.
+fn pane_at(state: &SessionState, output: Rect, column: u16, row: u16) -> Option<String> {
+    let position = Position::new(column, row);
+    if state.grid_mode {
+        return grid_pane_areas(output, &state.grid_panes)
+            .into_iter()
+            .find(|(name, pane)| state.logs.contains_key(name) && pane.contains(position))
+            .map(|(name, _)| name);
+    }
+    output
+        .contains(position)
+        .then(|| state.active_process().to_owned())
+}
+
+/// Escape sequence for the captured shell, when it asked for mouse reports
+/// and the event lands inside its pane.
+fn shell_mouse_input(
+    mouse: &MouseEvent,
+    area: Rect,
+    state: &SessionState,
+) -> Option<(String, String)> {
+    if state.active_process() != "shell" || !state.shell_capture_mode || state.show_help {
+        return None;
+    }
+    let screen = state.vt_parsers.get("shell")?.screen();
+    let mode = screen.mouse_protocol_mode();
+    if mode == MouseProtocolMode::None {
+        return None;
+    }
+    let chunks = body_layout(area, true, state.input_mode);
+    let pane = if state.grid_mode {
+        grid_pane_areas(chunks[1], &state.grid_panes)
+            .into_iter()
+            .find(|(name, _)| name == "shell")
+            .map(|(_, pane)| pane)?
+    } else {
+        chunks[1]
+    };
+    let inner = Rect::new(
+        pane.x.saturating_add(1),
+        pane.y.saturating_add(1),
+        pane.width.saturating_sub(2),
+        pane.height.saturating_sub(2),
+    );
+    if !inner.contains(Position::new(mouse.column, mouse.row)) {
+        return None;
+    }
+    let input = encode_mouse_event(
+        mouse,
+        mode,
+        screen.mouse_protocol_encoding(),
+        mouse.column - inner.x + 1,
+        mouse.row - inner.y + 1,
+    )?;
+    Some(("shell".to_owned(), input))
+}
+
+/// Encodes a mouse event the way xterm reports it, for the reporting `mode`
+/// and `encoding` the child enabled. `column` and `row` are 1-based.
+fn encode_mouse_event(
+    mouse: &MouseEvent,
+    mode: MouseProtocolMode,
+    encoding: MouseProtocolEncoding,
+    column: u16,
+    row: u16,
+) -> Option<String> {
+    let button_code = |button: MouseButton| match button {
+        MouseButton::Left => 0u16,
+        MouseButton::Middle => 1,
+        MouseButton::Right => 2,
+    };
+    let (mut code, release) = match mouse.kind {
+        MouseEventKind::Down(button) => (button_code(button), false),
+        MouseEventKind::Up(button) if mode != MouseProtocolMode::Press => {
+            (button_code(button), true)
+        }
+        MouseEventKind::Drag(button)
+            if matches!(
+                mode,
+                MouseProtocolMode::ButtonMotion | MouseProtocolMode::AnyMotion
+            ) =>
+        {
+            (button_code(button) + 32, false)
+        }
+        MouseEventKind::Moved if mode == MouseProtocolMode::AnyMotion => (35, false),
+        MouseEventKind::ScrollUp => (64, false),
+        MouseEventKind::ScrollDown => (65, false),
+        _ => return None,
+    };
+    if mouse.modifiers.contains(KeyModifiers::SHIFT) {
+        code += 4;
+    }
+    if mouse.modifiers.contains(KeyModifiers::ALT) {
+        code += 8;
+    }
+    if mouse.modifiers.contains(KeyModifiers::CONTROL) {
+        code += 16;
+    }
+
+    if encoding == MouseProtocolEncoding::Sgr {
+        let suffix = if release { 'm' } else { 'M' };
+        return Some(format!("\u{1b}[<{code};{column};{row}{suffix}"));
+    }
+    // Legacy encodings cannot say which button was released.
+    if release {
+        code = (code & !3) | 3;
+    }
+    // Input is sent as text, so the default encoding is limited to values
+    // that stay single-byte ASCII.
+    let limit = if encoding == MouseProtocolEncoding::Utf8 {
+        2015
+    } else {
+        95
+    };
+    if column > limit || row > limit {
+        return None;
+    }
+    let encode = |value: u16| char::from_u32(u32::from(value) + 32);
+    Some(format!(
+        "\u{1b}[M{}{}{}",
+        encode(code)?,
+        encode(column)?,
+        encode(row)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+    use ratatui::layout::Rect;
+    use vt100::{MouseProtocolEncoding, MouseProtocolMode};
+
+    use super::{apply_mouse_event, encode_mouse_event, shell_mouse_input};
+    use crate::tui::multiprocess::state::SessionState;
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| (*value).to_owned()).collect()
+    }
+
+    const AREA: Rect = Rect::new(0, 0, 100, 40);
+
+    #[test]
+    fn clicking_tabs_and_grid_panes_moves_focus() {
+        let mut state = SessionState::new(names(&["api", "web", "shell"]), 24, 80, 100);
+        // Tabs render as "│ api │ web │ shell" inside the header border.
+        apply_mouse_event(
+            &mouse(MouseEventKind::Down(MouseButton::Left), 8, 1),
+            AREA,
+            &mut state,
+            0,
+        );
+        assert_eq!(state.active_process(), "web");
+
+        state.grid_panes = vec![names(&["api", "web"])];
+        state.set_grid_mode(true);
+        apply_mouse_event(
+            &mouse(MouseEventKind::Down(MouseButton::Left), 10, 10),
+            AREA,
+            &mut state,
+            0,
+        );
+        assert_eq!(state.active_process(), "api");
+    }
+
+    #[test]
+    fn wheel_scrolls_the_hovered_pane_and_pauses_follow() {
+        let mut state = SessionState::new(names(&["api", "web"]), 24, 80, 100);
+        state.scroll_offsets.insert("api".to_owned(), 10);
+        apply_mouse_event(
+            &mouse(MouseEventKind::ScrollUp, 10, 10),
+            AREA,
+            &mut state,
+            10,
+        );
+        assert_eq!(state.scroll_offsets.get("api"), Some(&7));
+        assert_eq!(state.follow_mode.get("api"), Some(&false));
+        apply_mouse_event(
+            &mouse(MouseEventKind::ScrollDown, 10, 10),
+            AREA,
+            &mut state,
+            8,
+        );
+        assert_eq!(state.scroll_offsets.get("api"), Some(&8));
+
+        state.grid_panes = vec![names(&["api", "web"])];
+        state.set_grid_mode(true);
+        state.scroll_offsets.insert("web".to_owned(), 5);
+        apply_mouse_event(
+            &mouse(MouseEventKind::ScrollUp, 80, 10),
+            AREA,
+            &mut state,
+            8,
+        );
+        assert_eq!(state.scroll_offsets.get("web"), Some(&2));
+        assert_eq!(state.active_process(), "api");
+    }
+
+    #[test]
+    fn mouse_events_encode_for_sgr_and_legacy_protocols() {
+        let press = mouse(MouseEventKind::Down(MouseButton::Left), 0, 0);
+        assert_eq!(
+            encode_mouse_event(
+                &press,
+                MouseProtocolMode::PressRelease,
+                MouseProtocolEncoding::Sgr,
+                5,
+                2
+            ),
+            Some("\u{1b}[<0;5;2M".to_owned())
+        );
+        let release = mouse(MouseEventKind::Up(MouseButton::Left), 0, 0);
+        assert_eq!(
+            encode_mouse_event(
+                &release,
+                MouseProtocolMode::PressRelease,
+                MouseProtocolEncoding::Default,
+                1,
+                1
+            ),
+            Some("\u{1b}[M#!!".to_owned())
+        );
+        assert_eq!(
+            encode_mouse_event(
+                &release,
+                MouseProtocolMode::Press,
+                MouseProtocolEncoding::Sgr,
+                1,
+                1
+            ),
+            None
+        );
+        let wheel = mouse(MouseEventKind::ScrollDown, 0, 0);
+        assert_eq!(
+            encode_mouse_event(
+                &wheel,
+                MouseProtocolMode::Press,
+                MouseProtocolEncoding::Sgr,
+                3,
+                4
+            ),
+            Some("\u{1b}[<65;3;4M".to_owned())
+        );
+    }
+
+    #[test]
+    fn captured_shell_receives_mouse_only_when_reporting_is_enabled() {
+        let mut state = SessionState::new(names(&["shell"]), 24, 80, 100);
+        state.shell_capture_mode = true;
+        let click = mouse(MouseEventKind::Down(MouseButton::Left), 3, 5);
+        assert_eq!(shell_mouse_input(&click, AREA, &state), None);
+
+        let parser = state.vt_parsers.get_mut("shell").expect("shell parser");
+        parser.process(b"\x1b[?1000h\x1b[?1006h");
+        assert_eq!(
+            shell_mouse_input(&click, AREA, &state),
+            Some(("shell".to_owned(), "\u{1b}[<0;3;2M".to_owned()))
+        );
+
+        state.shell_capture_mode = false;
+        assert_eq!(shell_mouse_input(&click, AREA, &state), None);
+    }
+}
@@ -0,0 +1,406 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::process_manager::{preflight_process, ProcessSupervisor};
+
+use super::events::apply_options_action;
+use super::state::{default_grid_panes, OptionsAction, SessionState};
+use super::{MultiProcessTuiError, MultiProcessTuiOptions, PaletteProfile};
+
+const PALETTE_COMMANDS: [&str; 3] = ["run", "close", "profile"];
+const MAX_HINT_CANDIDATES: usize = 8;
+
+#[derive(Debug, Default)]
+pub(super) struct PaletteState {
+    pub(super) editing: bool,
+    pub(super) input: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum PaletteCommand {
+    /// Starts a catalog task as a new tab, or reruns its finished tab.
+    Run(String),
+    /// Closes a finished tab; the active tab when no name is given.
+    Close(Option<String>),
+    /// Replaces the session's processes with another profile's.
+    Profile(String),
+}
+
+impl PaletteCommand {
+    pub(super) fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.next().map(str::to_owned);
+        if let Some(extra) = words.next() {
+            return Err(format!("unexpected argument `{extra}`"));
+        }
+        match (command, argument) {
+            ("run", Some(task)) => Ok(PaletteCommand::Run(task)),
+            ("run", None) => Err("`run` needs a task name".to_owned()),
+            ("close", tab) => Ok(PaletteCommand::Close(tab)),
+            ("profile", Some(name)) => Ok(PaletteCommand::Profile(name)),
+            ("profile", None) => Err("`profile` needs a profile name".to_owned()),
+            ("", _) => Err("commands: run <task> · close [tab] · profile <name>".to_owned()),
+            (other, _) => Err(format!(
+                "unknown command `{other}` (available: {})",
+                PALETTE_COMMANDS.join(", ")
+            )),
+        }
+    }
+}
+
+pub(super) fn handle_palette_key(
+    key: &KeyEvent,
+    supervisor: &ProcessSupervisor,
+    state: &mut SessionState,
+    options: &MultiProcessTuiOptions,
+) -> Result<(), MultiProcessTuiError> {
+    match key.code {
+        KeyCode::Esc => {
+            state.palette = PaletteState::default();
+        }
+        KeyCode::Backspace => {
+            state.palette.input.pop();
+        }
+        KeyCode::Tab => {
+            if let Some(completed) = complete(&state.palette.input, state, options) {
+                state.palette.input = completed;
+            }
+        }
+        KeyCode::Enter => {
+            let input = std::mem::take(&mut state.palette.input);
+            state.palette.editing = false;
+            let notice = match PaletteCommand::parse(&input) {
+                Ok(command) => run_palette_command(command, supervisor, state, options)?,
+                Err(message) => message,
+            };
+            state.notice = Some(notice);
+        }
+        KeyCode::Char(ch) => {
+            state.palette.input.push(ch);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Matching commands, tasks, tabs or profiles for the word being typed.
+pub(super) fn palette_hint(state: &SessionState, options: &MultiProcessTuiOptions) -> String {
+    let (_, candidates) = candidates(&state.palette.input, state, options);
+    if candidates.is_empty() {
+        return "no matches · esc cancel".to_owned();
+    }
+    let mut shown = candidates
+        .iter()
+        .take(MAX_HINT_CANDIDATES)
+        .cloned()
+        .collect::<Vec<String>>();
+    if candidates.len() > MAX_HINT_CANDIDATES {
+        shown.push("…".to_owned());
+    }
+    format!(
+        "{}  ·  tab complete · enter run · esc cancel",
+        shown.join(" ")
+    )
+}
+
+/// Completes the word being typed to the longest prefix shared by its
+/// candidates. Returns `None` when there is nothing to add.
+fn complete(input: &str, state: &SessionState, options: &MultiProcessTuiOptions) -> Option<String> {
+    let (typed, candidates) = candidates(input, state, options);
+    let first = candidates.first()?;
+    let mut common = first.clone();
+    for candidate in &candidates[1..] {
+        while !candidate.starts_with(common.as_str()) {
+            common.pop();
+        }
+    }
+    let completing_command = !input.contains(' ');
+    let mut completed = format!("{typed}{common}");
+    if candidates.len() == 1 && completing_command {
+        completed.push(' ');
+    }
+    (completed.len() > input.len()).then_some(completed)
+}
+
+/// Splits `input` into the text before the word being typed and the values
+/// that word can complete to.
+fn candidates(
+    input: &str,
+    state: &SessionState,
+    options: &MultiProcessTuiOptions,
+) -> (String, Vec<String>) {
+    let Some((command, partial)) = input.split_once(' ') else {
+        let commands = PALETTE_COMMANDS
+            .iter()
+            .filter(|command| command.starts_with(input))
+            .map(|command| (*command).to_owned())
+            .collect();
+        return (String::new(), commands);
+    };
+    let partial = partial.trim_start();
+    let values = match command {
+        "run" => options
+            .palette_tasks
+            .iter()
+            .map(|spec| spec.name.clone())
+            .collect::<Vec<String>>(),
+        "close" => state
+            .process_names
+            .iter()
+            .filter(|name| state.exit_states.contains_key(*name))
+            .cloned()
+            .collect(),
+        "profile" => options
+            .palette_profiles
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let matching = values
+        .into_iter()
+        .filter(|value| value.starts_with(partial))
+        .collect();
+    (format!("{command} "), matching)
+}
+
+pub(super) fn run_palette_command(
+    command: PaletteCommand,
+    supervisor: &ProcessSupervisor,
+    state: &mut SessionState,
+    options: &MultiProcessTuiOptions,
+) -> Result<String, MultiProcessTuiError> {
+    if supervisor.is_replay() {
+        return Ok("palette commands are unavailable while replaying".to_owned());
+    }
+    match command {
+        PaletteCommand::Run(task) => run_task(&task, supervisor, state, options),
+        PaletteCommand::Close(tab) => {
+            let tab = tab.unwrap_or_else(|| state.active_process().to_owned());
+            Ok(close_tab(&tab, supervisor, state))
+        }
+        PaletteCommand::Profile(name) => {
+            let Some(profile) = options
+                .palette_profiles
+                .iter()
+                .find(|profile| profile.name == name)
+            else {
+                let available = options
+                    .palette_profiles
+                    .iter()
+                    .map(|profile| profile.name.as_str())
+                    .collect::<Vec<&str>>();
+                return Ok(if available.is_empty() {
+                    "this session has no other profiles".to_owned()
+                } else {
+                    format!(
+                        "unknown profile `{name}` (available: {})",
+                        available.join(", ")
+                    )
+                });
+            };
+            Ok(switch_profile(profile, supervisor, state))
+        }
+    }
+}
+
+fn run_task(
+    task: &str,
+    supervisor: &ProcessSupervisor,
+    state: &mut SessionState,
+    options: &MultiProcessTuiOptions,
+) -> Result<String, MultiProcessTuiError> {
+    let Some(spec) = options.palette_tasks.iter().find(|spec| spec.name == task) else {
+        return Ok(format!("unknown task `{task}`"));
+    };
+    if state.process_names.iter().any(|name| name == task) {
+        state.focus_process(task);
+        if !state.exit_states.contains_key(task) {
+            return Ok(format!("`{task}` is already running"));
+        }
+        apply_options_action(OptionsAction::Restart, task, supervisor, state, 0)?;
+        return Ok(format!("restarted `{task}`"));
+    }
+    Ok(match supervisor.add_process(spec.clone()) {
+        Ok(()) => {
+            state.add_tab(task);
+            state.focus_process(task);
+            format!("started `{task}`")
+        }
+        Err(error) => format!("start failed: {error}"),
+    })
+}
+
+fn close_tab(tab: &str, supervisor: &ProcessSupervisor, state: &mut SessionState) -> String {
+    if !state.process_names.iter().any(|name| name == tab) {
+        return format!("no tab named `{tab}`");
+    }
+    if !state.exit_states.contains_key(tab) {
+        return format!("`{tab}` is still running; stop it first");
+    }
+    if state.process_names.len() == 1 {
+        return "cannot close the last tab".to_owned();
+    }
+    if let Err(error) = supervisor.remove_process(tab) {
+        return format!("close failed: {error}");
+    }
+    state.remove_tab(tab);
+    format!("closed `{tab}`")
+}
+
+/// Stops the processes the new profile does not share (same name and spec),
+/// starts the rest and adopts the profile's tab order and grid. The stops
+/// and starts run on the supervisor's worker thread. A profile none of whose
+/// processes can start leaves the session as it was.
+fn switch_profile(
+    profile: &PaletteProfile,
+    supervisor: &ProcessSupervisor,
+    state: &mut SessionState,
+) -> String {
+    let active = state.active_process().to_owned();
+    let kept = state
+        .process_names
+        .iter()
+        .filter(|name| {
+            supervisor
+                .spec(name)
+                .is_some_and(|current| profile.processes.contains(&current))
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+    let removed = state
+        .process_names
+        .iter()
+        .filter(|name| !kept.contains(name))
+        .cloned()
+        .collect::<Vec<String>>();
+    let mut failed = Vec::new();
+    let mut added = Vec::new();
+    for spec in &profile.processes {
+        if kept.contains(&spec.name) {
+            continue;
+        }
+        match preflight_process(spec) {
+            Ok(()) => added.push(spec.clone()),
+            Err(error) => failed.push(error.to_string()),
+        }
+    }
+    if kept.is_empty() && added.is_empty() {
+        return format!(
+            "profile `{}` cannot start, keeping the current processes; {}",
+            profile.name,
+            failed.join("; ")
+        );
+    }
+    let added_names = added
+        .iter()
+        .map(|spec| spec.name.clone())
+        .collect::<Vec<String>>();
+    if let Err(error) = supervisor.replace_processes(&removed, added) {
+        return format!("profile switch failed: {error}");
+    }
+    for name in &removed {
+        state.remove_tab(name);
+    }
+    for name in &added_names {
+        state.add_tab(name);
+    }
+
+    let rank = |name: &String| {
+        profile
+            .tab_order
+            .iter()
+            .position(|candidate| candidate == name)
+            .unwrap_or(usize::MAX)
+    };
+    state.process_names.sort_by_key(rank);
+    state.active_index = 0;
+    state.focus_process(&active);
+    state.grid_panes = if profile.panes.is_empty() {
+        default_grid_panes(&state.process_names)
+    } else {
+        profile.panes.clone()
+    };
+    state.process_ports = profile.process_ports.clone();
+
+    let mut notice = format!(
+        "switched to profile `{}` ({} process(es))",
+        profile.name,
+        state.process_names.len()
+    );
+    if !failed.is_empty() {
+        notice.push_str(&format!("; {}", failed.join("; ")));
+    }
+    notice
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::process_manager::{ProcessSpec, ProcessSupervisor};
+
+    use super::super::state::SessionState;
+    use super::super::PaletteProfile;
+    use super::{switch_profile, PaletteCommand};
+
+    fn spec(name: &str, cwd: PathBuf) -> ProcessSpec {
+        ProcessSpec {
+            name: name.to_owned(),
+            run: "sleep 30".to_owned(),
+            cwd,
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: Default::default(),
+            stop: Default::default(),
+            depends_on: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn switching_to_a_profile_that_cannot_start_keeps_the_session() {
+        let cwd = std::env::temp_dir();
+        let supervisor =
+            ProcessSupervisor::spawn(cwd.clone(), vec![spec("api", cwd.clone())]).expect("spawn");
+        let mut state = SessionState::new(vec!["api".to_owned()], 24, 80, 100);
+        let profile = PaletteProfile {
+            name: "admin".to_owned(),
+            processes: vec![spec("admin", cwd.join("effigy-missing-profile-dir"))],
+            ..PaletteProfile::default()
+        };
+
+        let notice = switch_profile(&profile, &supervisor, &mut state);
+
+        assert!(notice.contains("cannot start"), "{notice}");
+        assert!(notice.contains("effigy-missing-profile-dir"), "{notice}");
+        assert_eq!(state.process_names, vec!["api".to_owned()]);
+        assert!(supervisor.spec("api").is_some());
+        assert!(!supervisor.is_stopping("api"));
+        supervisor.terminate_all();
+    }
+
+    #[test]
+    fn parse_reads_commands_and_reports_missing_arguments() {
+        assert_eq!(
+            PaletteCommand::parse(" run  lint "),
+            Ok(PaletteCommand::Run("lint".to_owned()))
+        );
+        assert_eq!(
+            PaletteCommand::parse("close"),
+            Ok(PaletteCommand::Close(None))
+        );
+        assert_eq!(
+            PaletteCommand::parse("profile admin"),
+            Ok(PaletteCommand::Profile("admin".to_owned()))
+        );
+        assert_eq!(
+            PaletteCommand::parse("run"),
+            Err("`run` needs a task name".to_owned())
+        );
+        let err = PaletteCommand::parse("open api").expect_err("unknown command");
+        assert!(err.contains("`open`"), "{err}");
+        let err = PaletteCommand::parse("close api web").expect_err("extra argument");
+        assert!(err.contains("`web`"), "{err}");
+    }
+}
@@ -1,26 +1,60 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 use std::time::Duration;
 
 use crate::tui::core::{InputMode, LogEntry, ProcessExitState};
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Color;
 use ratatui::Frame;
 
+use super::annotate::{Annotations, TimestampMode};
+use super::copy::CopySelection;
+use super::keymap::KeyMap;
+use super::search::SearchState;
+use super::stats::{ProcessStats, StatsSort};
+use super::view_model::ActiveViewModel;
 use super::OptionsAction;
 
 mod footer;
+mod grid;
 mod header;
 mod help_overlay;
+mod overview;
 mod panes;
 
 use footer::render_footer;
+pub(super) use grid::grid_pane_areas;
+use grid::render_grid;
 use header::render_tabs;
+pub(super) use header::tab_at;
 use help_overlay::{render_help_overlay, render_options_overlay};
-use panes::{render_input_pane, render_output_pane};
+use overview::render_stats_overview;
+use panes::{render_input_pane, render_output_pane, OutputPaneView};
 
 pub(super) fn options_actions(follow_enabled: bool) -> Vec<OptionsAction> {
     help_overlay::options_actions(follow_enabled)
 }
 
+/// Header, output, input and footer areas for a frame of the given size.
+pub(super) fn body_layout(area: Rect, active_is_shell: bool, input_mode: InputMode) -> Rc<[Rect]> {
+    let input_height = if active_is_shell {
+        0
+    } else if input_mode == InputMode::Insert {
+        3
+    } else {
+        0
+    };
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(input_height),
+            Constraint::Length(1),
+        ])
+        .split(area)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(super) fn render_ui(
     frame: &mut Frame<'_>,
@@ -37,6 +71,7 @@ pub(super) fn render_ui(
     input_mode: InputMode,
     shell_capture_mode: bool,
     exit_states: &HashMap<String, ProcessExitState>,
+    stopping: &HashSet<String>,
     show_help: bool,
     show_options: bool,
     options_index: usize,
@@ -45,24 +80,23 @@ pub(super) fn render_ui(
     active_elapsed: Duration,
     active_restart_count: usize,
     shell_cursor: Option<(u16, u16)>,
+    active_ports: &[(String, u16)],
+    stats: &HashMap<String, ProcessStats>,
+    show_stats: bool,
+    stats_sort: StatsSort,
+    grid_views: &[(Rect, ActiveViewModel)],
+    search: &SearchState,
+    keymap: &KeyMap,
+    selection: Option<CopySelection>,
+    notice: Option<&str>,
+    annotations: Option<Annotations>,
+    timestamps: TimestampMode,
+    levels: bool,
+    error_counts: &HashMap<String, usize>,
+    palette: Option<(&str, &str)>,
 ) {
     let active_is_shell = active_process == "shell";
-    let input_height = if active_is_shell {
-        0
-    } else if input_mode == InputMode::Insert {
-        3
-    } else {
-        0
-    };
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(1),
-            Constraint::Length(input_height),
-            Constraint::Length(1),
-        ])
-        .split(frame.area());
+    let chunks = body_layout(frame.area(), active_is_shell, input_mode);
 
     render_tabs(
         frame,
@@ -71,28 +105,58 @@ pub(super) fn render_ui(
         active_index,
         shell_capture_mode,
         exit_states,
+        stopping,
+        active_ports,
+        stats.get(active_process),
     );
 
     if show_help {
-        render_help_overlay(frame, chunks[1]);
-    } else {
-        render_output_pane(
+        render_help_overlay(frame, chunks[1], keymap);
+    } else if show_stats {
+        render_stats_overview(
             frame,
             chunks[1],
-            active_logs,
-            scroll_offset,
-            max_offset,
-            render_scroll_offset,
-            scrollbar_total,
+            process_names,
+            active_process,
+            stats,
+            stats_sort,
+            exit_states,
+        );
+    } else if !grid_views.is_empty() {
+        render_grid(
+            frame,
+            grid_views,
             active_process,
-            &process_names[active_index],
             shell_capture_mode,
-            active_output_seen,
             spinner_tick,
-            active_elapsed,
-            active_restart_count,
             exit_states,
-            shell_cursor,
+            search.matcher.as_ref(),
+        );
+    } else {
+        render_output_pane(
+            frame,
+            chunks[1],
+            OutputPaneView {
+                active_logs,
+                scroll_offset,
+                max_offset,
+                render_scroll_offset,
+                scrollbar_total,
+                active_process,
+                process_name: &process_names[active_index],
+                shell_capture_mode,
+                active_output_seen,
+                spinner_tick,
+                active_elapsed,
+                active_restart_count,
+                exit_states,
+                shell_cursor,
+                title: None,
+                border_color: Color::DarkGray,
+                highlight: search.matcher.as_ref(),
+                selection,
+                annotations,
+            },
         );
     }
 
@@ -114,5 +178,29 @@ pub(super) fn render_ui(
         shell_capture_mode,
         show_help,
         show_options,
+        show_stats,
+        !grid_views.is_empty(),
+        search,
+        keymap,
+        selection,
+        notice,
+        timestamps,
+        levels,
+        &error_summary(process_names, error_counts),
+        palette,
     );
 }
+
+/// `api 3 · web 1` for processes with error-level lines, in tab order.
+fn error_summary(process_names: &[String], error_counts: &HashMap<String, usize>) -> String {
+    process_names
+        .iter()
+        .filter_map(|name| {
+            error_counts
+                .get(name)
+                .filter(|count| **count > 0)
+                .map(|count| format!("{name} {count}"))
+        })
+        .collect::<Vec<String>>()
+        .join(" · ")
+}
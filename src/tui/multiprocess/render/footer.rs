@@ -5,6 +5,12 @@ use ratatui::Frame;
 
 use crate::tui::core::InputMode;
 
+use super::super::annotate::TimestampMode;
+use super::super::copy::CopySelection;
+use super::super::keymap::{KeyAction, KeyMap};
+use super::super::search::SearchState;
+
+#[allow(clippy::too_many_arguments)]
 pub(super) fn render_footer(
     frame: &mut Frame<'_>,
     area: ratatui::layout::Rect,
@@ -13,19 +19,31 @@ pub(super) fn render_footer(
     shell_capture_mode: bool,
     show_help: bool,
     show_options: bool,
+    show_stats: bool,
+    grid_mode: bool,
+    search: &SearchState,
+    keymap: &KeyMap,
+    selection: Option<CopySelection>,
+    notice: Option<&str>,
+    timestamps: TimestampMode,
+    levels: bool,
+    error_summary: &str,
+    palette: Option<(&str, &str)>,
 ) {
     let mode_label = if input_mode == InputMode::Insert {
         "insert"
     } else {
         "command"
     };
+    let capture_key = keymap.label(KeyAction::ShellCapture);
+    let toggle_key = keymap.label(KeyAction::ToggleInput);
     let muted = Style::default().fg(Color::DarkGray);
     let active = Style::default().fg(Color::Yellow);
     let mut footer_spans = vec![
         Span::styled(
             if active_is_shell {
                 format!(
-                    "mode:{} (ctrl+g)",
+                    "mode:{} ({capture_key})",
                     if shell_capture_mode {
                         "shell"
                     } else {
@@ -33,7 +51,7 @@ pub(super) fn render_footer(
                     }
                 )
             } else {
-                format!("mode:{mode_label} (tab)")
+                format!("mode:{mode_label} ({toggle_key})")
             },
             if (active_is_shell && shell_capture_mode) || input_mode == InputMode::Insert {
                 active
@@ -42,21 +60,108 @@ pub(super) fn render_footer(
             },
         ),
         Span::styled("  |  ", muted),
-        Span::styled("help (h)", if show_help { active } else { muted }),
+        Span::styled(
+            format!("help ({})", keymap.label(KeyAction::Help)),
+            if show_help { active } else { muted },
+        ),
+        Span::styled("  |  ", muted),
+        Span::styled(
+            format!("options ({})", keymap.label(KeyAction::Options)),
+            if show_options { active } else { muted },
+        ),
+        Span::styled("  |  ", muted),
+        Span::styled(
+            format!("stats ({})", keymap.label(KeyAction::Stats)),
+            if show_stats { active } else { muted },
+        ),
         Span::styled("  |  ", muted),
-        Span::styled("options (o)", if show_options { active } else { muted }),
+        Span::styled(
+            format!("grid ({})", keymap.label(KeyAction::Grid)),
+            if grid_mode { active } else { muted },
+        ),
     ];
+    if timestamps != TimestampMode::Off {
+        footer_spans.push(Span::styled("  |  ", muted));
+        footer_spans.push(Span::styled(
+            format!(
+                "time:{} ({})",
+                timestamps.label(),
+                keymap.label(KeyAction::Timestamps)
+            ),
+            active,
+        ));
+    }
+    if levels {
+        footer_spans.push(Span::styled("  |  ", muted));
+        footer_spans.push(Span::styled(
+            format!("levels ({})", keymap.label(KeyAction::Levels)),
+            active,
+        ));
+    }
+    if !error_summary.is_empty() {
+        footer_spans.push(Span::styled("  |  ", muted));
+        footer_spans.push(Span::styled(
+            format!("errors: {error_summary}"),
+            Style::default().fg(Color::Red),
+        ));
+    }
     if active_is_shell {
         footer_spans.push(Span::styled("  |  ", muted));
         footer_spans.push(Span::styled(
             if shell_capture_mode {
-                "shell: live (ctrl+g to exit)"
+                format!("shell: live ({capture_key} to exit)")
             } else {
-                "shell: command (tab/ctrl+g to enter)"
+                format!("shell: command ({toggle_key}/{capture_key} to enter)")
             },
             active,
         ));
     }
+    if search.editing {
+        footer_spans = vec![
+            Span::styled(format!("{}▏", search.status()), active),
+            Span::styled(
+                "  enter keep · esc clear · ctrl+r regex · ctrl+f filter",
+                muted,
+            ),
+        ];
+    } else if search.is_active() {
+        footer_spans.push(Span::styled("  |  ", muted));
+        footer_spans.push(Span::styled(
+            format!(
+                "{} ({}/{})",
+                search.status(),
+                keymap.label(KeyAction::SearchNext),
+                keymap.label(KeyAction::SearchPrev)
+            ),
+            active,
+        ));
+    }
+    if let Some((input, hint)) = palette {
+        footer_spans = vec![
+            Span::styled(format!(":{input}▏"), active),
+            Span::styled(format!("  {hint}"), muted),
+        ];
+    }
+    if let Some(selection) = selection {
+        footer_spans = vec![
+            Span::styled(
+                format!(
+                    "copy: line {} · {} selected",
+                    selection.cursor + 1,
+                    selection.len()
+                ),
+                active,
+            ),
+            Span::styled(
+                "  space mark · y copy · w save selection · W save buffer · esc exit",
+                muted,
+            ),
+        ];
+    }
+    if let Some(notice) = notice {
+        footer_spans.push(Span::styled("  |  ", muted));
+        footer_spans.push(Span::styled(notice.to_owned(), active));
+    }
     let footer = Paragraph::new(Line::from(footer_spans));
     frame.render_widget(footer, area);
 }
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Color;
+use ratatui::Frame;
+
+use crate::tui::core::ProcessExitState;
+
+use super::super::search::SearchMatcher;
+use super::super::view_model::ActiveViewModel;
+use super::panes::{render_output_pane, OutputPaneView};
+
+/// Splits the output area into one row per `panes` entry and one column per
+/// process in that row. Rows and columns share the space evenly.
+pub(in super::super) fn grid_pane_areas(area: Rect, panes: &[Vec<String>]) -> Vec<(String, Rect)> {
+    let rows = panes
+        .iter()
+        .filter(|row| !row.is_empty())
+        .collect::<Vec<&Vec<String>>>();
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, rows.len() as u32); rows.len()])
+        .split(area);
+    let mut areas = Vec::new();
+    for (row, row_area) in rows.into_iter().zip(row_areas.iter()) {
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, row.len() as u32); row.len()])
+            .split(*row_area);
+        areas.extend(row.iter().cloned().zip(column_areas.iter().copied()));
+    }
+    areas
+}
+
+pub(super) fn render_grid(
+    frame: &mut Frame<'_>,
+    views: &[(Rect, ActiveViewModel)],
+    active_process: &str,
+    shell_capture_mode: bool,
+    spinner_tick: usize,
+    exit_states: &HashMap<String, ProcessExitState>,
+    highlight: Option<&SearchMatcher>,
+) {
+    for (area, view) in views {
+        let focused = view.active_process == active_process;
+        let status = match exit_states.get(&view.active_process) {
+            Some(ProcessExitState::Success) => " · exited",
+            Some(ProcessExitState::Failure) => " · failed",
+            None if !view.is_follow => " · paused",
+            None => "",
+        };
+        let title = format!(" {}{status} ", view.active_process);
+        render_output_pane(
+            frame,
+            *area,
+            OutputPaneView {
+                active_logs: &view.active_logs,
+                scroll_offset: view.scroll_offset,
+                max_offset: view.max_offset,
+                render_scroll_offset: view.render_scroll_offset,
+                scrollbar_total: view.scrollbar_total,
+                active_process: &view.active_process,
+                process_name: &view.active_process,
+                shell_capture_mode: shell_capture_mode && focused,
+                active_output_seen: view.active_output_seen,
+                spinner_tick,
+                active_elapsed: view.active_elapsed,
+                active_restart_count: view.active_restart_count,
+                exit_states,
+                shell_cursor: if focused { view.shell_cursor } else { None },
+                title: Some(title.as_str()),
+                border_color: if focused {
+                    Color::Yellow
+                } else {
+                    Color::DarkGray
+                },
+                highlight,
+                selection: view.selection,
+                annotations: view.annotations,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use super::grid_pane_areas;
+
+    #[test]
+    fn grid_pane_areas_split_rows_then_columns() {
+        let panes = vec![
+            vec!["api".to_owned(), "web".to_owned()],
+            vec!["worker".to_owned()],
+        ];
+        let areas = grid_pane_areas(Rect::new(0, 3, 100, 40), &panes);
+        assert_eq!(areas.len(), 3);
+        assert_eq!(areas[0], ("api".to_owned(), Rect::new(0, 3, 50, 20)));
+        assert_eq!(areas[1], ("web".to_owned(), Rect::new(50, 3, 50, 20)));
+        assert_eq!(areas[2], ("worker".to_owned(), Rect::new(0, 23, 100, 20)));
+    }
+}
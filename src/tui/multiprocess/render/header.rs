@@ -1,12 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::tui::core::ProcessExitState;
+
+use super::super::stats::{stats_summary, ProcessStats};
+use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::symbols::border;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Tabs};
 use ratatui::Frame;
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn render_tabs(
     frame: &mut Frame<'_>,
     area: ratatui::layout::Rect,
@@ -14,21 +18,19 @@ pub(super) fn render_tabs(
     active_index: usize,
     shell_capture_mode: bool,
     exit_states: &HashMap<String, ProcessExitState>,
+    stopping: &HashSet<String>,
+    active_ports: &[(String, u16)],
+    active_stats: Option<&ProcessStats>,
 ) {
     let titles = process_names
         .iter()
         .enumerate()
         .map(|(idx, name)| {
-            let label = if name == "shell" {
-                if shell_capture_mode {
-                    "shell [live]".to_owned()
-                } else {
-                    "shell".to_owned()
-                }
-            } else {
-                name.clone()
-            };
+            let label = tab_label(name, shell_capture_mode);
             let style = match exit_states.get(name) {
+                _ if stopping.contains(name) => Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::ITALIC),
                 Some(ProcessExitState::Success) => Style::default().fg(Color::Green),
                 Some(ProcessExitState::Failure) => Style::default().fg(Color::Red),
                 None => {
@@ -47,13 +49,81 @@ pub(super) fn render_tabs(
         })
         .collect::<Vec<Line>>();
 
+    let mut block = panel_block(Some(" EFFIGY "), true, Color::Magenta);
+    let mut meta = Vec::<Span>::new();
+    if process_names
+        .get(active_index)
+        .is_some_and(|name| stopping.contains(name))
+    {
+        meta.push(Span::styled(
+            " stopping… ",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if !active_ports.is_empty() {
+        let ports = active_ports
+            .iter()
+            .map(|(name, port)| format!("{name}={port}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        meta.push(Span::styled(
+            format!(" {ports} "),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    if let Some(stats) = active_stats {
+        meta.push(Span::styled(
+            format!(" {} ", stats_summary(stats)),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    if !meta.is_empty() {
+        block = block.title_top(Line::from(meta).right_aligned());
+    }
     let tabs = Tabs::new(titles)
         .select(active_index)
-        .block(panel_block(Some(" EFFIGY "), true, Color::Magenta))
+        .block(block)
         .highlight_style(Style::default().add_modifier(Modifier::BOLD));
     frame.render_widget(tabs, area);
 }
 
+fn tab_label(name: &str, shell_capture_mode: bool) -> String {
+    if name == "shell" && shell_capture_mode {
+        "shell [live]".to_owned()
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Index of the tab drawn at `(column, row)` by `render_tabs` in `area`.
+/// Mirrors the `Tabs` widget layout: one space of padding on each side of a
+/// title and a one-column divider between titles.
+pub(in super::super) fn tab_at(
+    area: Rect,
+    process_names: &[String],
+    shell_capture_mode: bool,
+    column: u16,
+    row: u16,
+) -> Option<usize> {
+    if row != area.y.saturating_add(1) {
+        return None;
+    }
+    let right = area.right().saturating_sub(1);
+    let mut x = area.x.saturating_add(1);
+    for (index, name) in process_names.iter().enumerate() {
+        if x >= right {
+            break;
+        }
+        let width = Line::from(tab_label(name, shell_capture_mode)).width() as u16;
+        let end = x.saturating_add(width + 2).min(right);
+        if (x..end).contains(&column) {
+            return Some(index);
+        }
+        x = end.saturating_add(1);
+    }
+    None
+}
+
 pub(super) fn panel_block<'a>(
     title: Option<&'a str>,
     show_version: bool,
@@ -86,3 +156,85 @@ pub(super) fn panel_block<'a>(
     }
     block
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::layout::Rect;
+    use ratatui::style::Color;
+    use ratatui::Terminal;
+
+    use super::{render_tabs, tab_at};
+
+    #[test]
+    fn tab_at_matches_rendered_tab_positions() {
+        let names = vec!["api".to_owned(), "web".to_owned(), "shell".to_owned()];
+        let area = Rect::new(0, 0, 60, 3);
+        let mut terminal = Terminal::new(TestBackend::new(60, 3)).expect("terminal");
+        terminal
+            .draw(|frame| {
+                render_tabs(
+                    frame,
+                    area,
+                    &names,
+                    0,
+                    true,
+                    &Default::default(),
+                    &Default::default(),
+                    &[],
+                    None,
+                )
+            })
+            .expect("draw");
+        let row = terminal.backend().buffer().content()[60..120]
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        for (index, label) in ["api", "web", "shell [live]"].into_iter().enumerate() {
+            let column = row.find(label).expect("label rendered");
+            let column = row[..column].chars().count() as u16;
+            assert_eq!(tab_at(area, &names, true, column, 1), Some(index));
+        }
+        assert_eq!(tab_at(area, &names, true, 2, 0), None);
+        assert_eq!(tab_at(area, &names, true, 55, 1), None);
+    }
+
+    #[test]
+    fn stopping_tab_is_marked_in_the_header() {
+        let names = vec!["api".to_owned(), "web".to_owned()];
+        let stopping = HashSet::from(["api".to_owned()]);
+        let area = Rect::new(0, 0, 60, 3);
+        let mut terminal = Terminal::new(TestBackend::new(60, 3)).expect("terminal");
+        terminal
+            .draw(|frame| {
+                render_tabs(
+                    frame,
+                    area,
+                    &names,
+                    0,
+                    false,
+                    &Default::default(),
+                    &stopping,
+                    &[],
+                    None,
+                )
+            })
+            .expect("draw");
+        let buffer = terminal.backend().buffer();
+        let top = buffer.content()[..60]
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(top.contains("stopping…"), "header: {top}");
+        let row = buffer.content()[60..120]
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        let api = row.find("api").expect("api tab") as u16;
+        let web = row.find("web").expect("web tab") as u16;
+        assert_eq!(buffer[(api, 1)].fg, Color::Yellow);
+        assert_ne!(buffer[(web, 1)].fg, Color::Yellow);
+    }
+}
@@ -4,13 +4,15 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Clear, Paragraph};
 use ratatui::Frame;
 
+use super::super::keymap::KeyMap;
 use super::super::OptionsAction;
 use super::header::panel_block;
 
-const OPTIONS_ACTIONS: [OptionsAction; 5] = [
+const OPTIONS_ACTIONS: [OptionsAction; 6] = [
     OptionsAction::ToggleFollow,
     OptionsAction::Restart,
     OptionsAction::Stop,
+    OptionsAction::SaveLogs,
     OptionsAction::Cancel,
     OptionsAction::Quit,
 ];
@@ -19,39 +21,49 @@ pub(super) fn options_actions(_follow_enabled: bool) -> Vec<OptionsAction> {
     OPTIONS_ACTIONS.to_vec()
 }
 
-pub(super) fn render_help_overlay(frame: &mut Frame<'_>, area: Rect) {
-    let help_lines = vec![
-        Line::from(vec![Span::styled(
-            "Command Mode",
-            Style::default()
-                .fg(Color::Magenta)
-                .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from("tab             toggle insert/command mode"),
-        Line::from("tab             enter shell capture mode (shell tab)"),
-        Line::from("ctrl+g          toggle shell capture mode (shell tab)"),
-        Line::from("left/right       switch process tabs"),
-        Line::from("up/down          scroll output line-by-line"),
-        Line::from("pgup/pgdn        scroll output by page"),
-        Line::from("home/end         jump to top/bottom (end re-enables follow)"),
-        Line::from("h               toggle this help"),
-        Line::from("o               open per-process options menu"),
-        Line::from("ctrl+c          quit and shut down managed processes"),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "Insert Mode",
-            Style::default()
-                .fg(Color::Magenta)
-                .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from("type            send input text to active process"),
-        Line::from("enter           submit input"),
-        Line::from("esc             return to command mode"),
-    ];
-    let help = Paragraph::new(help_lines).block(panel_block(Some("Help"), false, Color::Magenta));
+pub(super) fn render_help_overlay(frame: &mut Frame<'_>, area: Rect, keymap: &KeyMap) {
+    let help =
+        Paragraph::new(help_lines(keymap)).block(panel_block(Some("Help"), false, Color::Magenta));
     frame.render_widget(help, area);
 }
 
+fn help_lines(keymap: &KeyMap) -> Vec<Line<'static>> {
+    let heading = Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    let entries = keymap.help_entries();
+    let key_width = entries
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(16);
+    let row =
+        |keys: &str, description: &str| Line::from(format!("{keys:<key_width$} {description}"));
+
+    let mut lines = vec![Line::from(Span::styled("Command Mode", heading))];
+    lines.extend(
+        entries
+            .iter()
+            .map(|(keys, description)| row(keys, description)),
+    );
+    lines.push(row("ctrl+c", "quit and shut down managed processes"));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Insert Mode", heading)));
+    lines.push(row("type", "send input text to active process"));
+    lines.push(row("enter", "submit input"));
+    lines.push(row("esc/tab", "return to command mode"));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Copy Mode", heading)));
+    lines.push(row("scroll keys", "move the cursor line"));
+    lines.push(row("space", "set or clear the selection mark"));
+    lines.push(row("y/enter", "copy selection to the clipboard (OSC 52)"));
+    lines.push(row("w", "save selection to .effigy/logs"));
+    lines.push(row("W", "save the whole buffer to .effigy/logs"));
+    lines.push(row("esc/q", "leave copy mode"));
+    lines
+}
+
 fn options_action_label(action: OptionsAction, follow_enabled: bool) -> &'static str {
     match action {
         OptionsAction::ToggleFollow => {
@@ -63,6 +75,7 @@ fn options_action_label(action: OptionsAction, follow_enabled: bool) -> &'static
         }
         OptionsAction::Restart => "Restart process (r)",
         OptionsAction::Stop => "Stop process (s)",
+        OptionsAction::SaveLogs => "Save logs to .effigy/logs (l)",
         OptionsAction::Cancel => "Cancel (o)",
         OptionsAction::Quit => "Quit (q)",
     }
@@ -124,3 +137,36 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::help_lines;
+    use crate::tui::multiprocess::keymap::KeyMap;
+
+    fn rendered(keymap: &KeyMap) -> Vec<String> {
+        help_lines(keymap)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn help_lines_follow_active_bindings() {
+        let lines = rendered(&KeyMap::default());
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("h ") && line.ends_with("toggle this help")));
+
+        let vim = KeyMap::from_config(Some("vim"), &[]).expect("vim preset");
+        let lines = rendered(&vim);
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("? ") && line.ends_with("toggle this help")));
+        assert!(lines.iter().any(|line| line.starts_with("j/down ")));
+    }
+}
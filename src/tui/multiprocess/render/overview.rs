@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::tui::core::ProcessExitState;
+
+use super::super::stats::{format_bytes, sorted_process_names, ProcessStats, StatsSort};
+use super::header::panel_block;
+
+pub(super) fn render_stats_overview(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    process_names: &[String],
+    active_process: &str,
+    stats: &HashMap<String, ProcessStats>,
+    sort: StatsSort,
+    exit_states: &HashMap<String, ProcessExitState>,
+) {
+    let name_width = process_names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(7)
+        .max(7);
+    let header_style = Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "{:<name_width$}  {:<8}  {:>7}  {:>9}  {:>9}  {:>9}  {:>5}",
+                "process", "status", "cpu%", "rss", "peak-rss", "cpu-time", "procs"
+            ),
+            header_style,
+        )),
+        Line::from(""),
+    ];
+    for name in sorted_process_names(process_names, stats, sort) {
+        let status = match exit_states.get(&name) {
+            Some(ProcessExitState::Success) => "exited",
+            Some(ProcessExitState::Failure) => "failed",
+            None => "running",
+        };
+        let row = match stats.get(&name) {
+            Some(stats) => format!(
+                "{:<name_width$}  {:<8}  {:>7.1}  {:>9}  {:>9}  {:>8.1}s  {:>5}",
+                name,
+                status,
+                stats.cpu_percent,
+                format_bytes(stats.rss_bytes),
+                format_bytes(stats.peak_rss_bytes),
+                stats.cpu_time.as_secs_f64(),
+                stats.tasks,
+            ),
+            None => format!(
+                "{:<name_width$}  {:<8}  {:>7}  {:>9}  {:>9}  {:>9}  {:>5}",
+                name, status, "-", "-", "-", "-", "-"
+            ),
+        };
+        let style = if name == active_process {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        lines.push(Line::from(Span::styled(row, style)));
+    }
+    let title = format!(" Processes · sort: {} (s) ", sort.label());
+    let overview =
+        Paragraph::new(lines).block(panel_block(Some(title.as_str()), false, Color::Magenta));
+    frame.render_widget(overview, area);
+}
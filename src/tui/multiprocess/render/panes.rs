@@ -9,47 +9,118 @@ use ratatui::Frame;
 
 use crate::tui::core::{InputMode, LogEntry, LogEntryKind, ProcessExitState};
 
-use super::super::terminal_text::{ansi_line, runtime_meta_line};
+use super::super::annotate::{levelled_line, timestamp_span, Annotations};
+use super::super::copy::CopySelection;
+use super::super::search::SearchMatcher;
+use super::super::terminal_text::{ansi_line, highlight_line, runtime_meta_line};
 use super::header::panel_block;
 
-pub(super) fn render_output_pane(
-    frame: &mut Frame<'_>,
-    area: Rect,
-    active_logs: &[LogEntry],
-    scroll_offset: usize,
-    max_offset: usize,
-    render_scroll_offset: usize,
-    scrollbar_total: usize,
-    active_process: &str,
-    process_name: &str,
-    shell_capture_mode: bool,
-    active_output_seen: bool,
-    spinner_tick: usize,
-    active_elapsed: Duration,
-    active_restart_count: usize,
-    exit_states: &HashMap<String, ProcessExitState>,
-    shell_cursor: Option<(u16, u16)>,
-) {
+/// Everything the output pane draws besides the frame and its area. The
+/// single-pane view and every grid cell fill one of these.
+pub(super) struct OutputPaneView<'a> {
+    pub(super) active_logs: &'a [LogEntry],
+    pub(super) scroll_offset: usize,
+    pub(super) max_offset: usize,
+    pub(super) render_scroll_offset: usize,
+    pub(super) scrollbar_total: usize,
+    pub(super) active_process: &'a str,
+    pub(super) process_name: &'a str,
+    pub(super) shell_capture_mode: bool,
+    pub(super) active_output_seen: bool,
+    pub(super) spinner_tick: usize,
+    pub(super) active_elapsed: Duration,
+    pub(super) active_restart_count: usize,
+    pub(super) exit_states: &'a HashMap<String, ProcessExitState>,
+    pub(super) shell_cursor: Option<(u16, u16)>,
+    pub(super) title: Option<&'a str>,
+    pub(super) border_color: Color,
+    pub(super) highlight: Option<&'a SearchMatcher>,
+    pub(super) selection: Option<CopySelection>,
+    pub(super) annotations: Option<Annotations>,
+}
+
+pub(super) fn render_output_pane(frame: &mut Frame<'_>, area: Rect, view: OutputPaneView<'_>) {
+    let OutputPaneView {
+        active_logs,
+        scroll_offset,
+        max_offset,
+        render_scroll_offset,
+        scrollbar_total,
+        active_process,
+        process_name,
+        shell_capture_mode,
+        active_output_seen,
+        spinner_tick,
+        active_elapsed,
+        active_restart_count,
+        exit_states,
+        shell_cursor,
+        title,
+        border_color,
+        highlight,
+        selection,
+        annotations,
+    } = view;
     let active_is_shell = active_process == "shell";
     let output_height = area.height.saturating_sub(2) as usize;
     let mut lines = Vec::with_capacity(active_logs.len() + 1);
     if !active_is_shell {
         lines.push(runtime_meta_line(active_elapsed, active_restart_count));
     }
-    lines.extend(active_logs.iter().map(|entry| match entry.kind {
-        LogEntryKind::Stdout => ansi_line(&entry.line, Style::default()),
-        LogEntryKind::Stderr => {
-            let mut spans = vec![Span::styled("[stderr] ", Style::default().fg(Color::Red))];
-            spans.extend(ansi_line(&entry.line, Style::default()).spans);
-            Line::from(spans)
+    let match_style = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let styled_line = |raw: &str| {
+        let line = ansi_line(raw, Style::default());
+        match highlight {
+            Some(matcher) => {
+                let text = line
+                    .spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>();
+                highlight_line(line, &matcher.find_ranges(&text), match_style)
+            }
+            None => line,
+        }
+    };
+    let selected_style = Style::default().bg(Color::DarkGray);
+    let cursor_style = Style::default()
+        .bg(Color::Blue)
+        .add_modifier(Modifier::BOLD);
+    let output_line = |raw: &str| match annotations {
+        Some(annotations) if annotations.levels => levelled_line(raw, styled_line),
+        _ => styled_line(raw),
+    };
+    lines.extend(active_logs.iter().enumerate().map(|(index, entry)| {
+        let mut line = match entry.kind {
+            LogEntryKind::Stdout => output_line(&entry.line),
+            LogEntryKind::Stderr => {
+                let mut spans = vec![Span::styled("[stderr] ", Style::default().fg(Color::Red))];
+                spans.extend(output_line(&entry.line).spans);
+                Line::from(spans)
+            }
+            LogEntryKind::Exit => Line::from(vec![
+                Span::styled("[exit] ", Style::default().fg(Color::Yellow)),
+                Span::styled(entry.line.clone(), Style::default().fg(Color::Gray)),
+            ]),
+        };
+        if let Some(stamp) =
+            annotations.and_then(|annotations| timestamp_span(entry.at, annotations))
+        {
+            line.spans.insert(0, stamp);
+        }
+        match selection {
+            Some(selection) if index == selection.cursor => line.patch_style(cursor_style),
+            Some(selection) if (selection.start..=selection.end).contains(&index) => {
+                line.patch_style(selected_style)
+            }
+            _ => line,
         }
-        LogEntryKind::Exit => Line::from(vec![
-            Span::styled("[exit] ", Style::default().fg(Color::Yellow)),
-            Span::styled(entry.line.clone(), Style::default().fg(Color::Gray)),
-        ]),
     }));
 
-    let panel = panel_block(None, false, Color::DarkGray);
+    let panel = panel_block(title, false, border_color);
     let shell_inactive_style = if active_is_shell && !shell_capture_mode {
         Style::default()
             .fg(Color::DarkGray)
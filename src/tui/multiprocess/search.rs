@@ -0,0 +1,368 @@
+use regex_automata::meta::Regex;
+use regex_automata::util::syntax;
+
+use crate::tui::core::{LogEntry, LogEntryKind};
+
+use super::state::SessionState;
+use super::terminal_text::strip_ansi;
+
+/// Compiled form of the active search query.
+/// Plain queries use smart case: they ignore case unless the query has an uppercase letter.
+#[derive(Debug, Clone)]
+pub(super) enum SearchMatcher {
+    Plain { needle: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl SearchMatcher {
+    pub(super) fn compile(query: &str, regex: bool) -> Result<Option<Self>, String> {
+        if query.is_empty() {
+            return Ok(None);
+        }
+        let ignore_case = !query.chars().any(char::is_uppercase);
+        if regex {
+            return Regex::builder()
+                .syntax(syntax::Config::new().case_insensitive(ignore_case))
+                .build(query)
+                .map(|compiled| Some(SearchMatcher::Regex(compiled)))
+                .map_err(|error| error.to_string());
+        }
+        let needle = if ignore_case {
+            query.to_lowercase()
+        } else {
+            query.to_owned()
+        };
+        Ok(Some(SearchMatcher::Plain {
+            needle,
+            ignore_case,
+        }))
+    }
+
+    pub(super) fn is_match(&self, text: &str) -> bool {
+        !self.find_ranges(text).is_empty()
+    }
+
+    /// Byte ranges of every non-overlapping match in `text`.
+    pub(super) fn find_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        match self {
+            SearchMatcher::Plain {
+                needle,
+                ignore_case,
+            } => {
+                // Lowercasing can change byte lengths outside ASCII, so only
+                // fold case in place when it is safe to map offsets back.
+                let haystack = if *ignore_case && text.is_ascii() {
+                    text.to_ascii_lowercase()
+                } else if *ignore_case {
+                    return fold_case_ranges(text, needle);
+                } else {
+                    text.to_owned()
+                };
+                haystack
+                    .match_indices(needle.as_str())
+                    .map(|(start, matched)| (start, start + matched.len()))
+                    .collect()
+            }
+            SearchMatcher::Regex(regex) => regex
+                .find_iter(text)
+                .filter(|found| found.start() < found.end())
+                .map(|found| (found.start(), found.end()))
+                .collect(),
+        }
+    }
+}
+
+fn fold_case_ranges(text: &str, needle: &str) -> Vec<(usize, usize)> {
+    let needle_len = needle.chars().count();
+    let chars = text.char_indices().collect::<Vec<(usize, char)>>();
+    let mut ranges = Vec::new();
+    let mut index = 0usize;
+    while index + needle_len <= chars.len() {
+        let candidate = chars[index..index + needle_len]
+            .iter()
+            .map(|(_, ch)| *ch)
+            .collect::<String>()
+            .to_lowercase();
+        if candidate == needle {
+            let start = chars[index].0;
+            let end = chars
+                .get(index + needle_len)
+                .map(|(offset, _)| *offset)
+                .unwrap_or(text.len());
+            ranges.push((start, end));
+            index += needle_len.max(1);
+        } else {
+            index += 1;
+        }
+    }
+    ranges
+}
+
+#[derive(Debug, Default)]
+pub(super) struct SearchState {
+    pub(super) editing: bool,
+    pub(super) query: String,
+    pub(super) regex: bool,
+    pub(super) filter: bool,
+    pub(super) matcher: Option<SearchMatcher>,
+    pub(super) error: Option<String>,
+    /// Scroll offset when the prompt opened; incremental matches start here.
+    pub(super) anchor: usize,
+    pub(super) current_line: Option<usize>,
+    pub(super) current_match: usize,
+    pub(super) match_count: usize,
+}
+
+impl SearchState {
+    pub(super) fn open(&mut self, anchor: usize) {
+        self.editing = true;
+        self.query.clear();
+        self.matcher = None;
+        self.error = None;
+        self.anchor = anchor;
+        self.current_line = None;
+        self.current_match = 0;
+        self.match_count = 0;
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.editing = false;
+        self.query.clear();
+        self.filter = false;
+        self.matcher = None;
+        self.error = None;
+        self.current_line = None;
+        self.current_match = 0;
+        self.match_count = 0;
+    }
+
+    pub(super) fn recompile(&mut self) {
+        match SearchMatcher::compile(&self.query, self.regex) {
+            Ok(matcher) => {
+                self.matcher = matcher;
+                self.error = None;
+            }
+            Err(error) => {
+                self.matcher = None;
+                self.error = Some(error);
+            }
+        }
+    }
+
+    pub(super) fn is_active(&self) -> bool {
+        self.matcher.is_some()
+    }
+
+    pub(super) fn filter_matcher(&self) -> Option<&SearchMatcher> {
+        if self.filter {
+            self.matcher.as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn status(&self) -> String {
+        let mut flags = Vec::new();
+        if self.regex {
+            flags.push("regex");
+        }
+        if self.filter {
+            flags.push("filter");
+        }
+        let flags = if flags.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", flags.join(","))
+        };
+        let result = if let Some(error) = self.error.as_deref() {
+            format!("invalid regex: {}", error.lines().next().unwrap_or(error))
+        } else if self.query.is_empty() {
+            String::new()
+        } else if self.match_count == 0 {
+            "no matches".to_owned()
+        } else {
+            format!("{}/{}", self.current_match + 1, self.match_count)
+        };
+        format!("/{}{flags}  {result}", self.query)
+            .trim_end()
+            .to_owned()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SearchDirection {
+    /// First match at or after the prompt anchor (used while typing).
+    FromAnchor,
+    Next,
+    Previous,
+}
+
+/// Plain-text lines for a process in scroll order. VT sessions read the
+/// parser's reachable scrollback plus the visible screen; other sessions, and
+/// annotated views, read the log buffer.
+pub(super) fn searchable_entries(state: &mut SessionState, process: &str) -> Vec<LogEntry> {
+    if *state.vt_saw_chunk.get(process).unwrap_or(&false) && !state.annotating() {
+        if let Some(parser) = state.vt_parsers.get_mut(process) {
+            let restore = parser.screen().scrollback();
+            parser.set_scrollback(usize::MAX);
+            let max_scrollback = parser.screen().scrollback();
+            let (rows, cols) = parser.screen().size();
+            // Mirror the clamp in `vt_logs`; larger offsets can panic in vt100 0.15.
+            let reachable = max_scrollback.min(usize::from(rows).saturating_sub(1));
+            parser.set_scrollback(reachable);
+            let mut lines = parser.screen().rows(0, cols).collect::<Vec<String>>();
+            parser.set_scrollback(0);
+            let tail = parser.screen().rows(0, cols).collect::<Vec<String>>();
+            let overlap = tail.len().saturating_sub(reachable);
+            lines.extend(tail.into_iter().skip(overlap));
+            parser.set_scrollback(restore);
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
+            }
+            return lines
+                .into_iter()
+                .map(|line| LogEntry::new(LogEntryKind::Stdout, line))
+                .collect();
+        }
+    }
+    state
+        .logs
+        .get(process)
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|entry| LogEntry {
+                    line: strip_ansi(&entry.line),
+                    ..entry.clone()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Moves the active process's scroll position to a match and updates the
+/// match counter. Returns `false` when nothing matches.
+pub(super) fn jump_to_match(state: &mut SessionState, direction: SearchDirection) -> bool {
+    let Some(matcher) = state.search.matcher.clone() else {
+        state.search.match_count = 0;
+        return false;
+    };
+    let active = state.active_process().to_owned();
+    let matches = searchable_entries(state, &active)
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| matcher.is_match(&entry.line))
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    state.search.match_count = matches.len();
+    if matches.is_empty() {
+        state.search.current_line = None;
+        return false;
+    }
+
+    let ordinal = match (direction, state.search.current_line) {
+        (SearchDirection::FromAnchor, _) | (_, None) => matches
+            .iter()
+            .position(|line| *line >= state.search.anchor)
+            .unwrap_or(0),
+        (SearchDirection::Next, Some(current)) => {
+            matches.iter().position(|line| *line > current).unwrap_or(0)
+        }
+        (SearchDirection::Previous, Some(current)) => matches
+            .iter()
+            .rposition(|line| *line < current)
+            .unwrap_or(matches.len() - 1),
+    };
+    state.search.current_line = Some(matches[ordinal]);
+    state.search.current_match = ordinal;
+
+    // Filtered views list only matching lines, so scroll by match ordinal.
+    let offset = if state.search.filter {
+        ordinal
+    } else {
+        matches[ordinal]
+    };
+    state.follow_mode.insert(active.clone(), false);
+    state.scroll_offsets.insert(active, offset);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tui::core::{LogEntry, LogEntryKind};
+    use crate::tui::multiprocess::state::SessionState;
+
+    use super::{jump_to_match, searchable_entries, SearchDirection, SearchMatcher};
+
+    fn state_with_lines(lines: &[&str]) -> SessionState {
+        let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
+        let buffer = state.logs.get_mut("api").expect("api log buffer");
+        for line in lines {
+            buffer.push_back(LogEntry::new(LogEntryKind::Stdout, (*line).to_owned()));
+        }
+        state
+    }
+
+    #[test]
+    fn plain_matcher_uses_smart_case() {
+        let matcher = SearchMatcher::compile("error", false)
+            .expect("compile")
+            .expect("matcher");
+        assert_eq!(
+            matcher.find_ranges("ERROR and error"),
+            vec![(0, 5), (10, 15)]
+        );
+        let matcher = SearchMatcher::compile("Error", false)
+            .expect("compile")
+            .expect("matcher");
+        assert_eq!(matcher.find_ranges("ERROR and Error"), vec![(10, 15)]);
+    }
+
+    #[test]
+    fn regex_matcher_reports_ranges_and_errors() {
+        let matcher = SearchMatcher::compile(r"req-\d+", true)
+            .expect("compile")
+            .expect("matcher");
+        assert_eq!(
+            matcher.find_ranges("req-12 ok req-7"),
+            vec![(0, 6), (10, 15)]
+        );
+        assert!(SearchMatcher::compile("(unclosed", true).is_err());
+        assert!(SearchMatcher::compile("", true).expect("empty").is_none());
+    }
+
+    #[test]
+    fn jump_to_match_cycles_forward_and_backward() {
+        let mut state = state_with_lines(&["boot", "ERROR one", "ok", "ERROR two", "done"]);
+        state.search.query = "ERROR".to_owned();
+        state.search.recompile();
+
+        assert!(jump_to_match(&mut state, SearchDirection::FromAnchor));
+        assert_eq!(state.scroll_offsets.get("api"), Some(&1));
+        assert_eq!(state.follow_mode.get("api"), Some(&false));
+        assert!(jump_to_match(&mut state, SearchDirection::Next));
+        assert_eq!(state.scroll_offsets.get("api"), Some(&3));
+        assert!(jump_to_match(&mut state, SearchDirection::Next));
+        assert_eq!(state.scroll_offsets.get("api"), Some(&1));
+        assert!(jump_to_match(&mut state, SearchDirection::Previous));
+        assert_eq!(state.scroll_offsets.get("api"), Some(&3));
+        assert_eq!(state.search.status(), "/ERROR  2/2");
+    }
+
+    #[test]
+    fn searchable_entries_strip_ansi_and_read_vt_screen() {
+        let mut state = state_with_lines(&["\u{1b}[31mred\u{1b}[0m text"]);
+        let entries = searchable_entries(&mut state, "api");
+        assert_eq!(entries[0].line, "red text");
+
+        let parser = state.vt_parsers.get_mut("api").expect("api vt parser");
+        parser.process(b"first\r\nsecond\r\n");
+        state.vt_saw_chunk.insert("api".to_owned(), true);
+        let entries = searchable_entries(&mut state, "api");
+        let lines = entries
+            .iter()
+            .map(|entry| entry.line.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(lines, vec!["first", "second"]);
+    }
+}
@@ -1,15 +1,27 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::time::Instant;
 
 use vt100::Parser as VtParser;
 
+use crate::process_manager::PtySize;
 use crate::tui::core::{InputMode, LogEntry, ProcessExitState};
 
+use super::annotate::{Annotations, TimestampMode};
+use super::config::{
+    LOGS_DIR, MAX_GRID_PANES, VT_PARSER_COLS, VT_PARSER_ROWS, VT_PARSER_SCROLLBACK,
+};
+use super::copy::CopyState;
+use super::palette::PaletteState;
+use super::search::SearchState;
+use super::stats::{StatsSampler, StatsSort};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum OptionsAction {
     ToggleFollow,
     Restart,
     Stop,
+    SaveLogs,
     Cancel,
     Quit,
 }
@@ -35,6 +47,30 @@ pub(super) struct SessionState {
     pub(super) spinner_tick: usize,
     pub(super) vt_parsers: HashMap<String, VtParser>,
     pub(super) vt_saw_chunk: HashMap<String, bool>,
+    pub(super) stats: StatsSampler,
+    pub(super) show_stats: bool,
+    pub(super) stats_sort: StatsSort,
+    pub(super) grid_mode: bool,
+    pub(super) grid_panes: Vec<Vec<String>>,
+    pub(super) search: SearchState,
+    pub(super) copy: CopyState,
+    /// One-shot footer message, cleared by the next keypress.
+    pub(super) notice: Option<String>,
+    /// Directory that copy mode and `save logs` write to.
+    pub(super) log_dir: PathBuf,
+    pub(super) timestamps: TimestampMode,
+    pub(super) levels: bool,
+    /// Error-level lines seen per process since the session started.
+    pub(super) error_counts: HashMap<String, usize>,
+    /// Non-zero exits waiting to be sent to the task's notifier.
+    pub(super) pending_notifications: Vec<String>,
+    pub(super) palette: PaletteState,
+    /// Named ports per process, shown in the tab header.
+    pub(super) process_ports: HashMap<String, Vec<(String, u16)>>,
+    /// Last pane size sent to each process's pseudo-terminal.
+    pub(super) pty_sizes: HashMap<String, PtySize>,
+    /// Processes a stop or restart is still winding down, refreshed each tick.
+    pub(super) stopping: HashSet<String>,
 }
 
 impl SessionState {
@@ -76,6 +112,7 @@ impl SessionState {
             .iter()
             .map(|name| (name.clone(), VtParser::new(vt_rows, vt_cols, vt_scrollback)))
             .collect::<HashMap<String, VtParser>>();
+        let grid_panes = default_grid_panes(&process_names);
         let vt_saw_chunk = process_names
             .iter()
             .map(|name| (name.clone(), false))
@@ -102,10 +139,254 @@ impl SessionState {
             spinner_tick: 0,
             vt_parsers,
             vt_saw_chunk,
+            stats: StatsSampler::new(),
+            show_stats: false,
+            stats_sort: StatsSort::default(),
+            grid_mode: false,
+            grid_panes,
+            search: SearchState::default(),
+            copy: CopyState::default(),
+            notice: None,
+            log_dir: PathBuf::from(LOGS_DIR),
+            timestamps: TimestampMode::Off,
+            levels: false,
+            error_counts: HashMap::new(),
+            pending_notifications: Vec::new(),
+            palette: PaletteState::default(),
+            process_ports: HashMap::new(),
+            pty_sizes: HashMap::new(),
+            stopping: HashSet::new(),
+        }
+    }
+
+    /// A fresh terminal emulator for `name`, sized like its pane once the
+    /// pane has been drawn.
+    pub(super) fn new_vt_parser(&self, name: &str) -> VtParser {
+        let (rows, cols) = self
+            .pty_sizes
+            .get(name)
+            .map_or((VT_PARSER_ROWS, VT_PARSER_COLS), |size| {
+                (size.rows, size.cols)
+            });
+        VtParser::new(rows, cols, VT_PARSER_SCROLLBACK)
+    }
+
+    /// Adds a tab for a process started while the session is running. The
+    /// process also gets a grid pane when the grid has room.
+    pub(super) fn add_tab(&mut self, name: &str) {
+        if self.process_names.iter().any(|process| process == name) {
+            return;
+        }
+        let name = name.to_owned();
+        self.process_names.push(name.clone());
+        self.logs.insert(name.clone(), VecDeque::new());
+        self.scroll_offsets.insert(name.clone(), 0);
+        self.follow_mode.insert(name.clone(), true);
+        self.output_seen.insert(name.clone(), false);
+        self.restart_pending.insert(name.clone(), false);
+        self.process_started_at.insert(name.clone(), Instant::now());
+        self.process_restart_count.insert(name.clone(), 0);
+        self.vt_parsers
+            .insert(name.clone(), self.new_vt_parser(&name));
+        self.vt_saw_chunk.insert(name.clone(), false);
+        if self.grid_panes.iter().flatten().count() < MAX_GRID_PANES {
+            match self.grid_panes.last_mut() {
+                Some(row) if row.len() < 2 => row.push(name),
+                _ => self.grid_panes.push(vec![name]),
+            }
+        }
+    }
+
+    /// Drops a tab and its buffers. Failures already observed for the process
+    /// are kept so closing a tab does not hide a non-zero exit.
+    pub(super) fn remove_tab(&mut self, name: &str) {
+        let Some(index) = self
+            .process_names
+            .iter()
+            .position(|process| process == name)
+        else {
+            return;
+        };
+        let active = self.active_process().to_owned();
+        self.process_names.remove(index);
+        self.logs.remove(name);
+        self.scroll_offsets.remove(name);
+        self.follow_mode.remove(name);
+        self.output_seen.remove(name);
+        self.restart_pending.remove(name);
+        self.process_started_at.remove(name);
+        self.process_restart_count.remove(name);
+        self.exit_states.remove(name);
+        self.vt_parsers.remove(name);
+        self.vt_saw_chunk.remove(name);
+        self.error_counts.remove(name);
+        self.process_ports.remove(name);
+        self.pty_sizes.remove(name);
+        for row in &mut self.grid_panes {
+            row.retain(|pane| pane != name);
+        }
+        self.grid_panes.retain(|row| !row.is_empty());
+        if active == name {
+            self.active_index = index.min(self.process_names.len().saturating_sub(1));
+        } else {
+            self.focus_process(&active);
         }
     }
 
     pub(super) fn active_process(&self) -> &str {
         &self.process_names[self.active_index]
     }
+
+    /// Whether timestamps or level colouring are on. Annotated views render
+    /// the line buffer instead of the terminal emulator screen.
+    pub(super) fn annotating(&self) -> bool {
+        self.timestamps != TimestampMode::Off || self.levels
+    }
+
+    pub(super) fn annotations(&self, process: &str) -> Option<Annotations> {
+        self.annotating().then(|| Annotations {
+            timestamps: self.timestamps,
+            levels: self.levels,
+            started_at: self
+                .process_started_at
+                .get(process)
+                .copied()
+                .unwrap_or_else(Instant::now),
+        })
+    }
+
+    pub(super) fn grid_pane_names(&self) -> Vec<&str> {
+        self.grid_panes
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|name| self.process_names.iter().any(|process| process == name))
+            .collect()
+    }
+
+    pub(super) fn set_grid_mode(&mut self, enabled: bool) {
+        self.grid_mode = enabled;
+        if !enabled {
+            return;
+        }
+        let active = self.active_process().to_owned();
+        let panes = self.grid_pane_names();
+        if panes.contains(&active.as_str()) {
+            return;
+        }
+        if let Some(first) = panes.first().map(|name| (*name).to_owned()) {
+            self.focus_process(&first);
+        }
+    }
+
+    /// Moves focus to the next (or previous) process shown in the grid.
+    pub(super) fn cycle_grid_focus(&mut self, forward: bool) {
+        let panes = self
+            .grid_pane_names()
+            .into_iter()
+            .map(str::to_owned)
+            .collect::<Vec<String>>();
+        if panes.is_empty() {
+            return;
+        }
+        let current = panes
+            .iter()
+            .position(|name| name == self.active_process())
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % panes.len()
+        } else {
+            (current + panes.len() - 1) % panes.len()
+        };
+        self.focus_process(&panes[next]);
+    }
+
+    pub(super) fn focus_process(&mut self, name: &str) {
+        if let Some(index) = self
+            .process_names
+            .iter()
+            .position(|process| process == name)
+        {
+            self.active_index = index;
+        }
+    }
+}
+
+/// Fallback grid used when a profile does not declare `panes`: the first
+/// processes (shell last) laid out two per row.
+pub(super) fn default_grid_panes(process_names: &[String]) -> Vec<Vec<String>> {
+    let ordered = process_names
+        .iter()
+        .filter(|name| name.as_str() != "shell")
+        .chain(process_names.iter().filter(|name| name.as_str() == "shell"))
+        .take(MAX_GRID_PANES)
+        .cloned()
+        .collect::<Vec<String>>();
+    ordered.chunks(2).map(<[String]>::to_vec).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_grid_panes, SessionState};
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| (*value).to_owned()).collect()
+    }
+
+    #[test]
+    fn default_grid_panes_pairs_processes_and_puts_shell_last() {
+        let panes = default_grid_panes(&names(&["shell", "api", "web", "worker", "jobs"]));
+        assert_eq!(
+            panes,
+            vec![names(&["api", "web"]), names(&["worker", "jobs"])]
+        );
+        let panes = default_grid_panes(&names(&["api", "shell"]));
+        assert_eq!(panes, vec![names(&["api", "shell"])]);
+    }
+
+    #[test]
+    fn enabling_grid_moves_focus_into_a_pane() {
+        let mut state = SessionState::new(names(&["shell", "api", "web", "worker"]), 24, 80, 100);
+        state.grid_panes = vec![names(&["api"]), names(&["worker"])];
+        state.set_grid_mode(true);
+        assert_eq!(state.active_process(), "api");
+
+        state.cycle_grid_focus(true);
+        assert_eq!(state.active_process(), "worker");
+        state.cycle_grid_focus(true);
+        assert_eq!(state.active_process(), "api");
+        state.cycle_grid_focus(false);
+        assert_eq!(state.active_process(), "worker");
+    }
+
+    #[test]
+    fn tabs_can_be_added_and_removed_during_a_session() {
+        let mut state = SessionState::new(names(&["api", "web", "shell"]), 24, 80, 100);
+        state.focus_process("shell");
+        state.add_tab("lint");
+        assert_eq!(state.process_names, names(&["api", "web", "shell", "lint"]));
+        assert!(state.logs.contains_key("lint"));
+        assert_eq!(
+            state.grid_panes,
+            vec![names(&["api", "web"]), names(&["shell", "lint"])]
+        );
+        assert_eq!(state.active_process(), "shell");
+
+        state
+            .observed_non_zero
+            .insert("web".to_owned(), "exit=1".to_owned());
+        state.remove_tab("web");
+        assert_eq!(state.process_names, names(&["api", "shell", "lint"]));
+        assert!(!state.logs.contains_key("web"));
+        assert!(state.observed_non_zero.contains_key("web"));
+        assert_eq!(state.active_process(), "shell");
+
+        state.remove_tab("shell");
+        assert_eq!(state.active_process(), "lint");
+        assert!(state
+            .grid_panes
+            .iter()
+            .flatten()
+            .all(|name| name != "shell"));
+    }
 }
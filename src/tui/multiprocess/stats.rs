@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::config::STATS_SAMPLE_INTERVAL;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) enum StatsSort {
+    #[default]
+    Name,
+    Cpu,
+    Memory,
+}
+
+impl StatsSort {
+    pub(super) fn next(self) -> Self {
+        match self {
+            StatsSort::Name => StatsSort::Cpu,
+            StatsSort::Cpu => StatsSort::Memory,
+            StatsSort::Memory => StatsSort::Name,
+        }
+    }
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            StatsSort::Name => "name",
+            StatsSort::Cpu => "cpu",
+            StatsSort::Memory => "rss",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(super) struct ProcessStats {
+    pub(super) cpu_percent: f64,
+    pub(super) rss_bytes: u64,
+    pub(super) peak_rss_bytes: u64,
+    pub(super) cpu_time: Duration,
+    pub(super) tasks: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GroupUsage {
+    cpu_ticks: u64,
+    rss_pages: u64,
+    tasks: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcStat {
+    pgrp: i64,
+    cpu_ticks: u64,
+    rss_pages: u64,
+}
+
+/// Samples CPU and RSS per managed process group from `/proc`.
+/// Each supervised child is a process-group leader, so grandchildren spawned by
+/// `sh -lc` (dev servers, test workers) are folded into their tab's totals.
+#[derive(Debug)]
+pub(super) struct StatsSampler {
+    last_sample: Option<Instant>,
+    previous_ticks: HashMap<String, u64>,
+    stats: HashMap<String, ProcessStats>,
+    clock_ticks: u64,
+    page_size: u64,
+}
+
+impl StatsSampler {
+    pub(super) fn new() -> Self {
+        let (clock_ticks, page_size) = system_units();
+        Self {
+            last_sample: None,
+            previous_ticks: HashMap::new(),
+            stats: HashMap::new(),
+            clock_ticks,
+            page_size,
+        }
+    }
+
+    pub(super) fn stats(&self) -> &HashMap<String, ProcessStats> {
+        &self.stats
+    }
+
+    pub(super) fn sample_if_due(&mut self, process_ids: &[(String, u32)]) {
+        let now = Instant::now();
+        let elapsed = match self.last_sample {
+            Some(previous) if now.saturating_duration_since(previous) < STATS_SAMPLE_INTERVAL => {
+                return;
+            }
+            Some(previous) => Some(now.saturating_duration_since(previous)),
+            None => None,
+        };
+        self.last_sample = Some(now);
+
+        let proc_stats = read_all_proc_stats();
+        for (name, pid) in process_ids {
+            let Some(usage) = group_usage(&proc_stats, i64::from(*pid)) else {
+                if let Some(stats) = self.stats.get_mut(name) {
+                    stats.cpu_percent = 0.0;
+                    stats.rss_bytes = 0;
+                    stats.tasks = 0;
+                }
+                self.previous_ticks.remove(name);
+                continue;
+            };
+            let previous_ticks = self.previous_ticks.insert(name.clone(), usage.cpu_ticks);
+            let cpu_percent = match (previous_ticks, elapsed) {
+                (Some(previous), Some(elapsed)) => cpu_percent(
+                    usage.cpu_ticks.saturating_sub(previous),
+                    elapsed,
+                    self.clock_ticks,
+                ),
+                _ => 0.0,
+            };
+            let rss_bytes = usage.rss_pages.saturating_mul(self.page_size);
+            let entry = self.stats.entry(name.clone()).or_default();
+            let cpu_time = Duration::from_millis(
+                usage.cpu_ticks.saturating_mul(1000) / self.clock_ticks.max(1),
+            );
+            entry.cpu_percent = cpu_percent;
+            entry.rss_bytes = rss_bytes;
+            entry.peak_rss_bytes = entry.peak_rss_bytes.max(rss_bytes);
+            entry.cpu_time = entry.cpu_time.max(cpu_time);
+            entry.tasks = usage.tasks;
+        }
+    }
+}
+
+pub(super) fn sorted_process_names(
+    process_names: &[String],
+    stats: &HashMap<String, ProcessStats>,
+    sort: StatsSort,
+) -> Vec<String> {
+    let mut names = process_names.to_vec();
+    match sort {
+        StatsSort::Name => names.sort(),
+        StatsSort::Cpu => names.sort_by(|a, b| {
+            let a_cpu = stats.get(a).map(|s| s.cpu_percent).unwrap_or(0.0);
+            let b_cpu = stats.get(b).map(|s| s.cpu_percent).unwrap_or(0.0);
+            b_cpu.total_cmp(&a_cpu).then_with(|| a.cmp(b))
+        }),
+        StatsSort::Memory => names.sort_by(|a, b| {
+            let a_rss = stats.get(a).map(|s| s.rss_bytes).unwrap_or(0);
+            let b_rss = stats.get(b).map(|s| s.rss_bytes).unwrap_or(0);
+            b_rss.cmp(&a_rss).then_with(|| a.cmp(b))
+        }),
+    }
+    names
+}
+
+pub(super) fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value >= KIB * KIB * KIB {
+        format!("{:.1}GB", value / (KIB * KIB * KIB))
+    } else if value >= KIB * KIB {
+        format!("{:.1}MB", value / (KIB * KIB))
+    } else if value >= KIB {
+        format!("{:.0}KB", value / KIB)
+    } else {
+        format!("{bytes}B")
+    }
+}
+
+pub(super) fn stats_summary(stats: &ProcessStats) -> String {
+    format!(
+        "cpu {:.1}% · rss {}",
+        stats.cpu_percent,
+        format_bytes(stats.rss_bytes)
+    )
+}
+
+fn cpu_percent(delta_ticks: u64, elapsed: Duration, clock_ticks: u64) -> f64 {
+    let elapsed_secs = elapsed.as_secs_f64();
+    if elapsed_secs <= 0.0 || clock_ticks == 0 {
+        return 0.0;
+    }
+    (delta_ticks as f64 / clock_ticks as f64) / elapsed_secs * 100.0
+}
+
+fn group_usage(proc_stats: &[ProcStat], pgrp: i64) -> Option<GroupUsage> {
+    let members = proc_stats
+        .iter()
+        .filter(|stat| stat.pgrp == pgrp)
+        .collect::<Vec<&ProcStat>>();
+    if members.is_empty() {
+        return None;
+    }
+    Some(GroupUsage {
+        cpu_ticks: members.iter().map(|stat| stat.cpu_ticks).sum(),
+        rss_pages: members.iter().map(|stat| stat.rss_pages).sum(),
+        tasks: members.len(),
+    })
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(raw: &str) -> Option<ProcStat> {
+    // `comm` may contain spaces and parens, so fields are read after the last `)`.
+    let rest = &raw[raw.rfind(')')? + 1..];
+    let fields = rest.split_whitespace().collect::<Vec<&str>>();
+    let pgrp = fields.get(2)?.parse::<i64>().ok()?;
+    let utime = fields.get(11)?.parse::<u64>().ok()?;
+    let stime = fields.get(12)?.parse::<u64>().ok()?;
+    let rss_pages = fields.get(21)?.parse::<i64>().ok()?.max(0) as u64;
+    Some(ProcStat {
+        pgrp,
+        cpu_ticks: utime + stime,
+        rss_pages,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn read_all_proc_stats() -> Vec<ProcStat> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.bytes().all(|byte| byte.is_ascii_digit()))
+        })
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("stat")).ok())
+        .filter_map(|raw| parse_proc_stat(&raw))
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_all_proc_stats() -> Vec<ProcStat> {
+    Vec::new()
+}
+
+fn system_units() -> (u64, u64) {
+    #[cfg(unix)]
+    {
+        use nix::unistd::{sysconf, SysconfVar};
+        let clock_ticks = sysconf(SysconfVar::CLK_TCK)
+            .ok()
+            .flatten()
+            .filter(|value| *value > 0)
+            .map(|value| value as u64)
+            .unwrap_or(100);
+        let page_size = sysconf(SysconfVar::PAGE_SIZE)
+            .ok()
+            .flatten()
+            .filter(|value| *value > 0)
+            .map(|value| value as u64)
+            .unwrap_or(4096);
+        (clock_ticks, page_size)
+    }
+    #[cfg(not(unix))]
+    {
+        (100, 4096)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proc_stat_reads_fields_after_comm() {
+        let raw = "4242 (node (dev) server) S 1 4200 4200 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 11 0 900 123456 2048 18446744073709551615";
+        let stat = parse_proc_stat(raw).expect("stat parse");
+        assert_eq!(stat.pgrp, 4200);
+        assert_eq!(stat.cpu_ticks, 300);
+        assert_eq!(stat.rss_pages, 2048);
+    }
+
+    #[test]
+    fn group_usage_sums_members_of_process_group() {
+        let stats = vec![
+            ProcStat {
+                pgrp: 10,
+                cpu_ticks: 5,
+                rss_pages: 100,
+            },
+            ProcStat {
+                pgrp: 10,
+                cpu_ticks: 7,
+                rss_pages: 50,
+            },
+            ProcStat {
+                pgrp: 11,
+                cpu_ticks: 99,
+                rss_pages: 999,
+            },
+        ];
+        let usage = group_usage(&stats, 10).expect("group usage");
+        assert_eq!(usage.cpu_ticks, 12);
+        assert_eq!(usage.rss_pages, 150);
+        assert_eq!(usage.tasks, 2);
+        assert!(group_usage(&stats, 12).is_none());
+    }
+
+    #[test]
+    fn cpu_percent_scales_ticks_by_elapsed_wall_time() {
+        let percent = cpu_percent(50, Duration::from_secs(1), 100);
+        assert!((percent - 50.0).abs() < f64::EPSILON);
+        assert_eq!(cpu_percent(50, Duration::ZERO, 100), 0.0);
+    }
+
+    #[test]
+    fn sorted_process_names_orders_by_selected_metric() {
+        let names = vec!["api".to_owned(), "web".to_owned(), "jobs".to_owned()];
+        let mut stats = HashMap::new();
+        stats.insert(
+            "api".to_owned(),
+            ProcessStats {
+                cpu_percent: 5.0,
+                rss_bytes: 300,
+                ..ProcessStats::default()
+            },
+        );
+        stats.insert(
+            "web".to_owned(),
+            ProcessStats {
+                cpu_percent: 40.0,
+                rss_bytes: 100,
+                ..ProcessStats::default()
+            },
+        );
+        assert_eq!(
+            sorted_process_names(&names, &stats, StatsSort::Name),
+            vec!["api", "jobs", "web"]
+        );
+        assert_eq!(
+            sorted_process_names(&names, &stats, StatsSort::Cpu),
+            vec!["web", "api", "jobs"]
+        );
+        assert_eq!(
+            sorted_process_names(&names, &stats, StatsSort::Memory),
+            vec!["api", "web", "jobs"]
+        );
+    }
+
+    #[test]
+    fn format_bytes_uses_binary_units() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(2048), "2KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0MB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0GB");
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::VecDeque;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anstyle::Style as AnsiStyle;
 use ratatui::style::{Color, Modifier, Style};
@@ -31,9 +31,11 @@ pub(crate) fn vt_logs(
     let rows = parser
         .screen()
         .rows_formatted(0, panel_cols.max(1) as u16)
-        .map(|row| LogEntry {
-            kind: LogEntryKind::Stdout,
-            line: String::from_utf8_lossy(&row).into_owned(),
+        .map(|row| {
+            LogEntry::new(
+                LogEntryKind::Stdout,
+                String::from_utf8_lossy(&row).into_owned(),
+            )
         })
         .collect::<Vec<LogEntry>>();
     (rows, clamped, max_offset)
@@ -73,13 +75,7 @@ pub(crate) fn ingest_log_payload(
         if cursor_up > 0 {
             replace_last_renderable_line(buffer, kind, fragments[0].clone());
         } else {
-            push_entry(
-                buffer,
-                LogEntry {
-                    kind,
-                    line: fragments[0].clone(),
-                },
-            );
+            push_entry(buffer, LogEntry::new(kind, fragments[0].clone()));
         }
         return;
     }
@@ -91,25 +87,13 @@ pub(crate) fn ingest_log_payload(
             if cursor_up > 0 {
                 replace_last_renderable_line(buffer, kind.clone(), fragment);
             } else {
-                push_entry(
-                    buffer,
-                    LogEntry {
-                        kind: kind.clone(),
-                        line: fragment,
-                    },
-                );
+                push_entry(buffer, LogEntry::new(kind.clone(), fragment));
             }
             first = false;
             continue;
         }
         if append_on_first_rewrite {
-            push_entry(
-                buffer,
-                LogEntry {
-                    kind: kind.clone(),
-                    line: fragment,
-                },
-            );
+            push_entry(buffer, LogEntry::new(kind.clone(), fragment));
             append_on_first_rewrite = false;
         } else {
             replace_last_renderable_line(buffer, kind.clone(), fragment);
@@ -183,10 +167,11 @@ fn replace_last_renderable_line(buffer: &mut VecDeque<LogEntry>, kind: LogEntryK
         if matches!(last.kind, LogEntryKind::Stdout | LogEntryKind::Stderr) {
             last.kind = kind;
             last.line = line;
+            last.at = Instant::now();
             return;
         }
     }
-    push_entry(buffer, LogEntry { kind, line });
+    push_entry(buffer, LogEntry::new(kind, line));
 }
 
 pub(crate) fn sanitize_log_text(raw: &str) -> String {
@@ -244,6 +229,54 @@ pub(crate) fn ansi_line(raw: &str, base: Style) -> Line<'static> {
     Line::from(spans)
 }
 
+/// Text content of `raw` as rendered by `ansi_line`, without CSI sequences.
+pub(crate) fn strip_ansi(raw: &str) -> String {
+    ansi_line(raw, Style::default())
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Re-styles the byte `ranges` (offsets into the line's text) with `highlight`,
+/// splitting spans at range boundaries.
+pub(crate) fn highlight_line(
+    line: Line<'static>,
+    ranges: &[(usize, usize)],
+    highlight: Style,
+) -> Line<'static> {
+    if ranges.is_empty() {
+        return line;
+    }
+    let mut spans = Vec::with_capacity(line.spans.len() + ranges.len() * 2);
+    let mut span_start = 0usize;
+    for span in line.spans {
+        let content = span.content.into_owned();
+        let span_end = span_start + content.len();
+        let mut cursor = 0usize;
+        for (start, end) in ranges {
+            let start = (*start).clamp(span_start, span_end) - span_start;
+            let end = (*end).clamp(span_start, span_end) - span_start;
+            if start >= end || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+                continue;
+            }
+            if start > cursor {
+                spans.push(Span::styled(content[cursor..start].to_owned(), span.style));
+            }
+            spans.push(Span::styled(
+                content[start..end].to_owned(),
+                span.style.patch(highlight),
+            ));
+            cursor = end;
+        }
+        if cursor < content.len() {
+            spans.push(Span::styled(content[cursor..].to_owned(), span.style));
+        }
+        span_start = span_end;
+    }
+    Line::from(spans)
+}
+
 fn apply_sgr(current: Style, sgr: &str, base: Style) -> Style {
     let mut style = current;
     let parts = if sgr.is_empty() {
@@ -285,7 +318,10 @@ fn apply_sgr(current: Style, sgr: &str, base: Style) -> Style {
 }
 
 pub(crate) fn is_expected_shutdown_diagnostic(diagnostic: &str) -> bool {
-    matches!(diagnostic, "signal=15" | "signal=9")
+    matches!(
+        diagnostic,
+        "signal=15" | "signal=9" | "signal=2" | "signal=1"
+    )
 }
 
 pub(crate) fn format_elapsed(elapsed: Duration) -> String {
@@ -325,6 +361,33 @@ pub(crate) fn styled_text(style: AnsiStyle, text: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn highlight_line_splits_spans_at_match_boundaries() {
+        let line = ansi_line("\u{1b}[31merror\u{1b}[0m: disk error", Style::default());
+        assert_eq!(
+            strip_ansi("\u{1b}[31merror\u{1b}[0m: disk error"),
+            "error: disk error"
+        );
+        let highlight = Style::default().bg(Color::Yellow);
+        let line = highlight_line(line, &[(2, 4), (12, 17)], highlight);
+        let parts = line
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.bg == Some(Color::Yellow)))
+            .collect::<Vec<(&str, bool)>>();
+        assert_eq!(
+            parts,
+            vec![
+                ("er", false),
+                ("ro", true),
+                ("r", false),
+                (": disk ", false),
+                ("error", true),
+            ]
+        );
+        assert_eq!(line.spans[1].style.fg, Some(Color::Red));
+    }
+
     #[test]
     fn ansi_line_parses_basic_colour_sequence() {
         let line = ansi_line("\u{1b}[31merror\u{1b}[0m ok", Style::default());
@@ -337,6 +400,7 @@ mod tests {
     fn expected_shutdown_diagnostics_are_ignored() {
         assert!(is_expected_shutdown_diagnostic("signal=15"));
         assert!(is_expected_shutdown_diagnostic("signal=9"));
+        assert!(is_expected_shutdown_diagnostic("signal=2"));
         assert!(!is_expected_shutdown_diagnostic("exit=1"));
         assert!(!is_expected_shutdown_diagnostic("signal=11"));
     }
@@ -1,8 +1,12 @@
 use std::time::{Duration, Instant};
 
+use crate::process_manager::PtySize;
 use crate::tui::core::{LogEntry, LogEntryKind};
 use vt100::Parser as VtParser;
 
+use super::annotate::Annotations;
+use super::copy::{copy_entries, CopySelection};
+use super::search::searchable_entries;
 use super::state::SessionState;
 use super::terminal_text::vt_logs;
 
@@ -18,6 +22,20 @@ pub(super) struct ActiveViewModel {
     pub(super) active_elapsed: Duration,
     pub(super) active_restart_count: usize,
     pub(super) active_output_seen: bool,
+    /// Copy-mode selection, as indices into `active_logs`.
+    pub(super) selection: Option<CopySelection>,
+    pub(super) annotations: Option<Annotations>,
+}
+
+/// Terminal size of a process drawn in an output area of the given size. The
+/// VT parser and the process's pseudo-terminal both use it.
+pub(super) fn vt_panel_size(output_height: usize, output_width: usize) -> PtySize {
+    PtySize {
+        rows: output_height
+            .saturating_sub(1)
+            .clamp(1, usize::from(u16::MAX)) as u16,
+        cols: output_width.clamp(1, usize::from(u16::MAX)) as u16,
+    }
 }
 
 pub(super) fn build_active_view_model(
@@ -27,19 +45,62 @@ pub(super) fn build_active_view_model(
     vt_emulator_enabled: bool,
 ) -> ActiveViewModel {
     let active = state.active_process().to_owned();
+    build_process_view_model(
+        state,
+        active,
+        output_height,
+        output_width,
+        vt_emulator_enabled,
+    )
+}
+
+/// Builds the view for one process. Grid panes call this once per visible
+/// process so each pane keeps its own parser size, scroll offset and follow mode.
+pub(super) fn build_process_view_model(
+    state: &mut SessionState,
+    active: String,
+    output_height: usize,
+    output_width: usize,
+    vt_emulator_enabled: bool,
+) -> ActiveViewModel {
     let is_follow = *state.follow_mode.get(&active).unwrap_or(&true);
+    let filter = if active == state.active_process() {
+        state.search.filter_matcher().cloned()
+    } else {
+        None
+    };
+    let selection = if active == state.active_process() {
+        state.copy.selection()
+    } else {
+        None
+    };
+    let filtered = if selection.is_some() {
+        Some(copy_entries(state, &active))
+    } else {
+        filter.map(|matcher| {
+            searchable_entries(state, &active)
+                .into_iter()
+                .filter(|entry| matcher.is_match(&entry.line))
+                .collect::<Vec<LogEntry>>()
+        })
+    };
 
     let (active_logs, scroll_offset, max_offset, render_scroll_offset, scrollbar_total) =
-        if vt_emulator_enabled && *state.vt_saw_chunk.get(&active).unwrap_or(&false) {
+        if filtered.is_none()
+            && !state.annotating()
+            && vt_emulator_enabled
+            && *state.vt_saw_chunk.get(&active).unwrap_or(&false)
+        {
             let parser = state
                 .vt_parsers
                 .get_mut(&active)
-                .expect("active vt parser missing unexpectedly");
+                .expect("process vt parser missing unexpectedly");
             let stored = *state.scroll_offsets.get(&active).unwrap_or(&0usize);
+            let panel = vt_panel_size(output_height, output_width);
             let (mut rendered, clamped, max_vt) = vt_logs(
                 parser,
-                output_height.saturating_sub(1).max(1),
-                output_width.max(1),
+                usize::from(panel.rows),
+                usize::from(panel.cols),
                 stored,
                 is_follow,
             );
@@ -61,13 +122,24 @@ pub(super) fn build_active_view_model(
                 max_vt.saturating_add(output_height.max(1)),
             )
         } else {
-            let rendered = state
-                .logs
-                .get(&active)
-                .map(|entries| entries.iter().cloned().collect::<Vec<LogEntry>>())
-                .unwrap_or_default();
+            let rendered = filtered.unwrap_or_else(|| {
+                state
+                    .logs
+                    .get(&active)
+                    .map(|entries| entries.iter().cloned().collect::<Vec<LogEntry>>())
+                    .unwrap_or_default()
+            });
             let max = rendered.len().saturating_sub(output_height);
-            let stored = *state.scroll_offsets.get(&active).unwrap_or(&0usize);
+            let mut stored = *state.scroll_offsets.get(&active).unwrap_or(&0usize);
+            if let Some(selection) = selection {
+                // Keep the copy cursor on screen below the runtime meta line.
+                let rows = output_height.saturating_sub(1).max(1);
+                if selection.cursor < stored {
+                    stored = selection.cursor;
+                } else if selection.cursor >= stored + rows {
+                    stored = selection.cursor + 1 - rows;
+                }
+            }
             let clamped = stored.min(max);
             state.scroll_offsets.insert(active.clone(), clamped);
             let render = if is_follow { max } else { clamped };
@@ -98,6 +170,7 @@ pub(super) fn build_active_view_model(
         .unwrap_or_default();
     let active_restart_count = *state.process_restart_count.get(&active).unwrap_or(&0);
     let active_output_seen = *state.output_seen.get(&active).unwrap_or(&false);
+    let annotations = state.annotations(&active);
 
     ActiveViewModel {
         active_process: active,
@@ -111,13 +184,16 @@ pub(super) fn build_active_view_model(
         active_elapsed,
         active_restart_count,
         active_output_seen,
+        selection,
+        annotations,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::build_active_view_model;
+    use super::{build_active_view_model, build_process_view_model};
     use crate::tui::core::{LogEntry, LogEntryKind};
+    use crate::tui::multiprocess::copy::enter_copy_mode;
     use crate::tui::multiprocess::state::SessionState;
     use std::time::Duration;
 
@@ -126,10 +202,7 @@ mod tests {
         let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
         let buffer = state.logs.get_mut("api").expect("api log buffer");
         for idx in 0..20usize {
-            buffer.push_back(LogEntry {
-                kind: LogEntryKind::Stdout,
-                line: format!("line-{idx}"),
-            });
+            buffer.push_back(LogEntry::new(LogEntryKind::Stdout, format!("line-{idx}")));
         }
         state.scroll_offsets.insert("api".to_owned(), 999);
         state.follow_mode.insert("api".to_owned(), false);
@@ -146,10 +219,7 @@ mod tests {
         let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
         let buffer = state.logs.get_mut("api").expect("api log buffer");
         for idx in 0..12usize {
-            buffer.push_back(LogEntry {
-                kind: LogEntryKind::Stdout,
-                line: format!("line-{idx}"),
-            });
+            buffer.push_back(LogEntry::new(LogEntryKind::Stdout, format!("line-{idx}")));
         }
         state.scroll_offsets.insert("api".to_owned(), 0);
         state.follow_mode.insert("api".to_owned(), true);
@@ -176,4 +246,68 @@ mod tests {
         assert!(view.scrollbar_total >= view.max_offset);
         assert!(view.active_elapsed >= Duration::from_millis(0));
     }
+
+    #[test]
+    fn process_views_keep_independent_scroll_state() {
+        let mut state =
+            SessionState::new(vec!["api".to_owned(), "web".to_owned()], 2000, 240, 8000);
+        for name in ["api", "web"] {
+            let buffer = state.logs.get_mut(name).expect("log buffer");
+            for idx in 0..20usize {
+                buffer.push_back(LogEntry::new(LogEntryKind::Stdout, format!("{name}-{idx}")));
+            }
+        }
+        state.follow_mode.insert("api".to_owned(), false);
+        state.scroll_offsets.insert("api".to_owned(), 3);
+
+        let api = build_process_view_model(&mut state, "api".to_owned(), 5, 40, false);
+        let web = build_process_view_model(&mut state, "web".to_owned(), 10, 40, false);
+        assert_eq!(api.render_scroll_offset, 3);
+        assert_eq!(api.max_offset, 15);
+        assert!(web.is_follow);
+        assert_eq!(web.render_scroll_offset, 10);
+        assert_eq!(web.active_process, "web");
+    }
+
+    #[test]
+    fn filter_mode_shows_only_matching_lines_for_active_process() {
+        let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
+        let buffer = state.logs.get_mut("api").expect("api log buffer");
+        for line in ["boot", "ERROR disk", "ok", "ERROR net"] {
+            buffer.push_back(LogEntry::new(LogEntryKind::Stdout, line.to_owned()));
+        }
+        state.search.query = "ERROR".to_owned();
+        state.search.filter = true;
+        state.search.recompile();
+
+        let view = build_active_view_model(&mut state, 5, 80, false);
+        let lines = view
+            .active_logs
+            .iter()
+            .map(|entry| entry.line.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(lines, vec!["ERROR disk", "ERROR net"]);
+    }
+
+    #[test]
+    fn copy_mode_scrolls_to_keep_the_cursor_visible() {
+        let mut state = SessionState::new(vec!["api".to_owned()], 2000, 240, 8000);
+        let buffer = state.logs.get_mut("api").expect("api log buffer");
+        for idx in 0..20usize {
+            buffer.push_back(LogEntry::new(LogEntryKind::Stdout, format!("line-{idx}")));
+        }
+        enter_copy_mode(&mut state);
+        state.copy.cursor = 2;
+        state.copy.anchor = Some(4);
+        state.scroll_offsets.insert("api".to_owned(), 10);
+
+        let view = build_active_view_model(&mut state, 6, 80, false);
+        let selection = view.selection.expect("copy selection");
+        assert_eq!((selection.start, selection.end), (2, 4));
+        assert_eq!(view.render_scroll_offset, 2);
+
+        state.copy.cursor = 12;
+        let view = build_active_view_model(&mut state, 6, 80, false);
+        assert_eq!(view.render_scroll_offset, 8);
+    }
 }
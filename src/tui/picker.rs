@@ -0,0 +1,390 @@
+use std::io;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use super::core::{next_index, prev_index};
+
+const EVENT_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickerEntry {
+    /// Task label as typed on the command line, e.g. `api/dev`.
+    pub task: String,
+    pub description: Option<String>,
+    pub run: String,
+    /// Listed from `.effigy/history.json` ahead of the catalog order.
+    pub recent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickerSelection {
+    pub task: String,
+    /// Passthrough args exactly as typed at the prompt.
+    pub args: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PickerStage {
+    Filter,
+    Args { task: String, input: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PickerStep {
+    Continue,
+    Cancel,
+    Done(PickerSelection),
+}
+
+#[derive(Debug)]
+struct PickerState {
+    entries: Vec<PickerEntry>,
+    query: String,
+    matches: Vec<usize>,
+    selected: usize,
+    stage: PickerStage,
+}
+
+impl PickerState {
+    fn new(entries: Vec<PickerEntry>) -> Self {
+        let mut state = Self {
+            entries,
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            stage: PickerStage::Filter,
+        };
+        state.refilter();
+        state
+    }
+
+    fn refilter(&mut self) {
+        self.matches = filter_entries(&self.entries, &self.query);
+        self.selected = 0;
+    }
+
+    fn selected_entry(&self) -> Option<&PickerEntry> {
+        self.matches
+            .get(self.selected)
+            .and_then(|index| self.entries.get(*index))
+    }
+
+    fn handle_key(&mut self, key: &KeyEvent) -> PickerStep {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return PickerStep::Cancel;
+        }
+        match &mut self.stage {
+            PickerStage::Filter => match key.code {
+                KeyCode::Esc => return PickerStep::Cancel,
+                KeyCode::Enter => {
+                    if let Some(entry) = self.selected_entry() {
+                        self.stage = PickerStage::Args {
+                            task: entry.task.clone(),
+                            input: String::new(),
+                        };
+                    }
+                }
+                KeyCode::Up => {
+                    self.selected = prev_index(self.selected, self.matches.len());
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    self.selected = next_index(self.selected, self.matches.len());
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refilter();
+                }
+                KeyCode::Char(ch) => {
+                    self.query.push(ch);
+                    self.refilter();
+                }
+                _ => {}
+            },
+            PickerStage::Args { task, input } => match key.code {
+                KeyCode::Esc => self.stage = PickerStage::Filter,
+                KeyCode::Enter => {
+                    return PickerStep::Done(PickerSelection {
+                        task: task.clone(),
+                        args: input.trim().to_owned(),
+                    });
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(ch) => input.push(ch),
+                _ => {}
+            },
+        }
+        PickerStep::Continue
+    }
+}
+
+/// Scores `candidate` against `query` as an in-order, case-insensitive
+/// subsequence. Consecutive characters and characters that start a word
+/// (after `/`, `:`, `-`, `_`, `.` or a space) score higher; `None` means no
+/// match.
+pub(crate) fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query = query.to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let candidate = candidate.to_lowercase().chars().collect::<Vec<char>>();
+    let mut score = 0i64;
+    let mut position = 0usize;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars() {
+        let offset = candidate[position..].iter().position(|ch| *ch == wanted)?;
+        let index = position + offset;
+        score += 1;
+        if previous.is_some_and(|prev| prev + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || matches!(candidate[index - 1], '/' | ':' | '-' | '_' | '.' | ' ') {
+            score += 3;
+        }
+        score -= offset as i64;
+        previous = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+/// Indices of entries matching `query`, best match first. An empty query keeps
+/// the given order, which already lists recent tasks first.
+pub(crate) fn filter_entries(entries: &[PickerEntry], query: &str) -> Vec<usize> {
+    let query = query.trim();
+    let mut scored = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let task_score = fuzzy_score(query, &entry.task).map(|score| score + 10);
+            let description_score = entry
+                .description
+                .as_deref()
+                .and_then(|description| fuzzy_score(query, description));
+            let score = task_score.max(description_score)?;
+            Some((index, score))
+        })
+        .collect::<Vec<(usize, i64)>>();
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scored.into_iter().map(|(index, _)| index).collect()
+}
+
+/// Runs the full-screen task picker. Returns `None` when the user cancels.
+pub fn run_task_picker(entries: Vec<PickerEntry>) -> Result<Option<PickerSelection>, io::Error> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    terminal.clear()?;
+
+    let outcome = picker_loop(&mut terminal, PickerState::new(entries));
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    outcome
+}
+
+fn picker_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut state: PickerState,
+) -> Result<Option<PickerSelection>, io::Error> {
+    loop {
+        terminal.draw(|frame| render_picker(frame, &state))?;
+        if !event::poll(EVENT_POLL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match state.handle_key(&key) {
+            PickerStep::Continue => {}
+            PickerStep::Cancel => return Ok(None),
+            PickerStep::Done(selection) => return Ok(Some(selection)),
+        }
+    }
+}
+
+fn render_picker(frame: &mut Frame<'_>, state: &PickerState) {
+    let muted = Style::default().fg(Color::DarkGray);
+    let accent = Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let prompt = match &state.stage {
+        PickerStage::Filter => Line::from(vec![
+            Span::styled("> ", accent),
+            Span::raw(state.query.clone()),
+        ]),
+        PickerStage::Args { task, input } => Line::from(vec![
+            Span::styled(format!("effigy {task} "), accent),
+            Span::raw(input.clone()),
+        ]),
+    };
+    let prompt_title = match state.stage {
+        PickerStage::Filter => format!(" Tasks ({}/{}) ", state.matches.len(), state.entries.len()),
+        PickerStage::Args { .. } => " Passthrough args (optional) ".to_owned(),
+    };
+    frame.render_widget(
+        Paragraph::new(prompt).block(Block::default().borders(Borders::ALL).title(prompt_title)),
+        chunks[0],
+    );
+
+    let task_width = state
+        .matches
+        .iter()
+        .filter_map(|index| state.entries.get(*index))
+        .map(|entry| entry.task.chars().count())
+        .max()
+        .unwrap_or(0);
+    let items = state
+        .matches
+        .iter()
+        .filter_map(|index| state.entries.get(*index))
+        .map(|entry| {
+            let mut spans = vec![
+                Span::styled(
+                    if entry.recent { "• " } else { "  " },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!("{:<task_width$}", entry.task),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ];
+            if let Some(description) = entry.description.as_deref() {
+                spans.push(Span::raw(format!("  {description}")));
+            }
+            spans.push(Span::styled(format!("  {}", entry.run), muted));
+            ListItem::new(Line::from(spans))
+        })
+        .collect::<Vec<ListItem>>();
+    let mut list_state = ListState::default();
+    if !state.matches.is_empty() {
+        list_state.select(Some(state.selected));
+    }
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL))
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("> "),
+        chunks[1],
+        &mut list_state,
+    );
+
+    let hint = match state.stage {
+        PickerStage::Filter => "type to filter · ↑/↓ select · enter choose · esc quit · • recent",
+        PickerStage::Args { .. } => "enter run · esc back to tasks",
+    };
+    frame.render_widget(Paragraph::new(Span::styled(hint, muted)), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(task: &str, description: Option<&str>, recent: bool) -> PickerEntry {
+        PickerEntry {
+            task: task.to_owned(),
+            description: description.map(str::to_owned),
+            run: "true".to_owned(),
+            recent,
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn fuzzy_score_matches_subsequences_and_prefers_word_starts() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "api/dev"), None);
+        assert_eq!(fuzzy_score("vd", "api/dev"), None);
+        let word_start = fuzzy_score("ad", "api/dev").expect("match");
+        let mid_word = fuzzy_score("ad", "handle").expect("match");
+        assert!(word_start > mid_word);
+        let consecutive = fuzzy_score("dev", "api/dev").expect("match");
+        let scattered = fuzzy_score("dev", "db/reset-verify").expect("match");
+        assert!(consecutive > scattered);
+        assert!(fuzzy_score("API", "api/dev").is_some());
+    }
+
+    #[test]
+    fn filter_entries_keeps_recent_order_for_empty_query_and_ranks_matches() {
+        let entries = vec![
+            entry("lint", None, true),
+            entry("api/dev", Some("Run the api"), false),
+            entry("db:reset", Some("Reset the dev database"), false),
+        ];
+        assert_eq!(filter_entries(&entries, ""), vec![0, 1, 2]);
+        assert_eq!(filter_entries(&entries, "dev"), vec![1, 2]);
+        assert_eq!(filter_entries(&entries, "reset"), vec![2]);
+        assert!(filter_entries(&entries, "zzz").is_empty());
+    }
+
+    #[test]
+    fn picker_keys_filter_select_and_prompt_for_args() {
+        let mut state = PickerState::new(vec![
+            entry("build", None, false),
+            entry("api/dev", None, false),
+        ]);
+        for ch in "dev".chars() {
+            assert_eq!(
+                state.handle_key(&key(KeyCode::Char(ch))),
+                PickerStep::Continue
+            );
+        }
+        assert_eq!(state.matches, vec![1]);
+        assert_eq!(state.handle_key(&key(KeyCode::Enter)), PickerStep::Continue);
+        assert_eq!(
+            state.stage,
+            PickerStage::Args {
+                task: "api/dev".to_owned(),
+                input: String::new()
+            }
+        );
+        for ch in " --port 3000 ".chars() {
+            state.handle_key(&key(KeyCode::Char(ch)));
+        }
+        assert_eq!(
+            state.handle_key(&key(KeyCode::Enter)),
+            PickerStep::Done(PickerSelection {
+                task: "api/dev".to_owned(),
+                args: "--port 3000".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn picker_esc_steps_back_from_args_then_cancels() {
+        let mut state = PickerState::new(vec![entry("build", None, false)]);
+        state.handle_key(&key(KeyCode::Enter));
+        assert_eq!(state.handle_key(&key(KeyCode::Esc)), PickerStep::Continue);
+        assert_eq!(state.stage, PickerStage::Filter);
+        assert_eq!(state.handle_key(&key(KeyCode::Esc)), PickerStep::Cancel);
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use super::core::{LogEntry, LogEntryKind};
+use super::multiprocess::terminal_text::{ansi_line, format_elapsed, ingest_log_payload};
+
+/// Runs kept in the history panel, newest first.
+const RUN_HISTORY: usize = 8;
+/// Changed files listed per run before the rest are summarized as `+N`.
+const LISTED_FILES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WatchRunStatus {
+    Running,
+    Passed,
+    /// Exit code, or `None` when the run was killed by a signal.
+    Failed(Option<i32>),
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+struct WatchRunRecord {
+    number: usize,
+    files: Vec<String>,
+    started: Instant,
+    duration: Option<Duration>,
+    status: WatchRunStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WatchDashboardCommand {
+    Rerun,
+    TogglePause,
+    Clear,
+    Quit,
+}
+
+/// State behind `effigy watch --tui`: the run history and the output of the
+/// current run.
+#[derive(Debug)]
+pub(crate) struct WatchDashboard {
+    target: String,
+    backend: &'static str,
+    runs: VecDeque<WatchRunRecord>,
+    started_runs: usize,
+    output: VecDeque<LogEntry>,
+    paused: bool,
+}
+
+impl WatchDashboard {
+    pub(crate) fn new(target: &str, backend: &'static str) -> Self {
+        Self {
+            target: target.to_owned(),
+            backend,
+            runs: VecDeque::new(),
+            started_runs: 0,
+            output: VecDeque::new(),
+            paused: false,
+        }
+    }
+
+    /// Records a new run and clears the output of the previous one.
+    pub(crate) fn run_started(&mut self, files: Vec<String>) {
+        self.started_runs += 1;
+        self.output.clear();
+        self.runs.push_front(WatchRunRecord {
+            number: self.started_runs,
+            files,
+            started: Instant::now(),
+            duration: None,
+            status: WatchRunStatus::Running,
+        });
+        self.runs.truncate(RUN_HISTORY);
+    }
+
+    pub(crate) fn run_finished(&mut self, status: WatchRunStatus) {
+        if let Some(run) = self
+            .runs
+            .front_mut()
+            .filter(|run| run.status == WatchRunStatus::Running)
+        {
+            run.duration = Some(run.started.elapsed());
+            run.status = status;
+        }
+    }
+
+    pub(crate) fn ingest_output(&mut self, kind: LogEntryKind, payload: &str) {
+        ingest_log_payload(&mut self.output, kind, payload);
+    }
+
+    pub(crate) fn set_backend(&mut self, backend: &'static str) {
+        self.backend = backend;
+    }
+
+    pub(crate) fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub(crate) fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Clears the output and every finished run from the history.
+    pub(crate) fn clear(&mut self) {
+        self.output.clear();
+        self.runs
+            .retain(|run| run.status == WatchRunStatus::Running);
+    }
+
+    fn command_for_key(key: &KeyEvent) -> Option<WatchDashboardCommand> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(WatchDashboardCommand::Quit);
+        }
+        match key.code {
+            KeyCode::Char('r') => Some(WatchDashboardCommand::Rerun),
+            KeyCode::Char('p') => Some(WatchDashboardCommand::TogglePause),
+            KeyCode::Char('c') => Some(WatchDashboardCommand::Clear),
+            KeyCode::Char('q') | KeyCode::Esc => Some(WatchDashboardCommand::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// The alternate-screen terminal the dashboard draws on; restored on drop.
+pub(crate) struct WatchDashboardTerminal {
+    terminal: Terminal<CrosstermBackend<io::Stdout>>,
+}
+
+impl WatchDashboardTerminal {
+    pub(crate) fn enter() -> Result<Self, io::Error> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        terminal.clear()?;
+        Ok(Self { terminal })
+    }
+
+    pub(crate) fn draw(&mut self, dashboard: &WatchDashboard) -> Result<(), io::Error> {
+        self.terminal
+            .draw(|frame| render_watch_dashboard(frame, dashboard))
+            .map(|_| ())
+    }
+
+    /// The command for the next key press, waiting at most `timeout`.
+    pub(crate) fn next_command(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<WatchDashboardCommand>, io::Error> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                Ok(WatchDashboard::command_for_key(&key))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Drop for WatchDashboardTerminal {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+fn render_watch_dashboard(frame: &mut Frame<'_>, dashboard: &WatchDashboard) {
+    let muted = Style::default().fg(Color::DarkGray);
+    let history_height = dashboard.runs.len().clamp(1, RUN_HISTORY) as u16 + 2;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(history_height),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let (state, state_style) = if dashboard.paused {
+        ("paused", Style::default().fg(Color::Yellow))
+    } else {
+        ("watching", Style::default().fg(Color::Green))
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!("effigy watch {}", dashboard.target),
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("  {}", dashboard.backend), muted),
+            Span::raw("  "),
+            Span::styled(state, state_style),
+            Span::styled(format!("  {} run(s)", dashboard.started_runs), muted),
+        ])),
+        chunks[0],
+    );
+
+    let history = if dashboard.runs.is_empty() {
+        vec![Line::from(Span::styled("no runs yet", muted))]
+    } else {
+        dashboard.runs.iter().map(run_line).collect()
+    };
+    frame.render_widget(
+        Paragraph::new(history).block(Block::default().borders(Borders::ALL).title(" Runs ")),
+        chunks[1],
+    );
+
+    let output_rows = chunks[2].height.saturating_sub(2) as usize;
+    let skip = dashboard.output.len().saturating_sub(output_rows);
+    let output = dashboard
+        .output
+        .iter()
+        .skip(skip)
+        .map(|entry| {
+            let base = match entry.kind {
+                LogEntryKind::Stderr => Style::default().fg(Color::LightRed),
+                LogEntryKind::Stdout | LogEntryKind::Exit => Style::default(),
+            };
+            ansi_line(&entry.line, base)
+        })
+        .collect::<Vec<Line<'static>>>();
+    let output_title = match dashboard.runs.front() {
+        Some(run) => format!(" Output · run #{} ", run.number),
+        None => " Output ".to_owned(),
+    };
+    frame.render_widget(
+        Paragraph::new(output).block(Block::default().borders(Borders::ALL).title(output_title)),
+        chunks[2],
+    );
+
+    let pause_hint = if dashboard.paused { "resume" } else { "pause" };
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("r rerun · p {pause_hint} · c clear · q quit"),
+            muted,
+        )),
+        chunks[3],
+    );
+}
+
+fn run_line(run: &WatchRunRecord) -> Line<'static> {
+    let (status, style) = match run.status {
+        WatchRunStatus::Running => ("● running".to_owned(), Style::default().fg(Color::Cyan)),
+        WatchRunStatus::Passed => ("✓ passed".to_owned(), Style::default().fg(Color::Green)),
+        WatchRunStatus::Failed(Some(code)) => {
+            (format!("✗ exit {code}"), Style::default().fg(Color::Red))
+        }
+        WatchRunStatus::Failed(None) => ("✗ killed".to_owned(), Style::default().fg(Color::Red)),
+        WatchRunStatus::Cancelled => ("○ cancelled".to_owned(), Style::default().fg(Color::Yellow)),
+    };
+    let duration = run.duration.unwrap_or_else(|| run.started.elapsed());
+    Line::from(vec![
+        Span::styled(
+            format!("#{:<4}", run.number),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{status:<12}"), style),
+        Span::raw(format!("{:>8}  ", format_elapsed(duration))),
+        Span::styled(
+            summarize_files(&run.files),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+/// `a.ts, b.ts, c.ts +2`, or `(start)` for the first run and forced reruns.
+fn summarize_files(files: &[String]) -> String {
+    if files.is_empty() {
+        return "(start)".to_owned();
+    }
+    let mut summary = files
+        .iter()
+        .take(LISTED_FILES)
+        .cloned()
+        .collect::<Vec<String>>()
+        .join(", ");
+    if files.len() > LISTED_FILES {
+        summary.push_str(&format!(" +{}", files.len() - LISTED_FILES));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn dashboard_keeps_recent_runs_and_clears_finished_ones() {
+        let mut dashboard = WatchDashboard::new("test", "inotify");
+        for _ in 0..RUN_HISTORY + 2 {
+            dashboard.run_started(Vec::new());
+            dashboard.run_finished(WatchRunStatus::Passed);
+        }
+        assert_eq!(dashboard.runs.len(), RUN_HISTORY);
+        assert_eq!(dashboard.runs[0].number, RUN_HISTORY + 2);
+
+        dashboard.run_started(vec!["src/app.ts".to_owned()]);
+        dashboard.ingest_output(LogEntryKind::Stdout, "compiling");
+        dashboard.clear();
+        assert!(dashboard.output.is_empty());
+        assert_eq!(dashboard.runs.len(), 1);
+        assert_eq!(dashboard.runs[0].status, WatchRunStatus::Running);
+
+        dashboard.run_finished(WatchRunStatus::Cancelled);
+        dashboard.run_finished(WatchRunStatus::Passed);
+        assert_eq!(dashboard.runs[0].status, WatchRunStatus::Cancelled);
+    }
+
+    #[test]
+    fn dashboard_keys_map_to_commands() {
+        assert_eq!(
+            WatchDashboard::command_for_key(&key(KeyCode::Char('r'))),
+            Some(WatchDashboardCommand::Rerun)
+        );
+        assert_eq!(
+            WatchDashboard::command_for_key(&key(KeyCode::Char('p'))),
+            Some(WatchDashboardCommand::TogglePause)
+        );
+        assert_eq!(
+            WatchDashboard::command_for_key(&key(KeyCode::Char('c'))),
+            Some(WatchDashboardCommand::Clear)
+        );
+        assert_eq!(
+            WatchDashboard::command_for_key(&KeyEvent::new(
+                KeyCode::Char('c'),
+                KeyModifiers::CONTROL
+            )),
+            Some(WatchDashboardCommand::Quit)
+        );
+        assert_eq!(
+            WatchDashboard::command_for_key(&key(KeyCode::Char('x'))),
+            None
+        );
+    }
+
+    #[test]
+    fn dashboard_renders_history_and_current_output() {
+        let mut dashboard = WatchDashboard::new("test", "inotify");
+        dashboard.run_started(Vec::new());
+        dashboard.run_finished(WatchRunStatus::Failed(Some(2)));
+        dashboard.run_started(
+            ["a.ts", "b.ts", "c.ts", "d.ts"]
+                .iter()
+                .map(|file| format!("src/{file}"))
+                .collect(),
+        );
+        dashboard.ingest_output(LogEntryKind::Stdout, "\u{1b}[32mok\u{1b}[0m 3 tests");
+        dashboard.toggle_pause();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 14)).expect("terminal");
+        terminal
+            .draw(|frame| render_watch_dashboard(frame, &dashboard))
+            .expect("draw");
+        let screen = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(80)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n");
+        assert!(screen.contains("effigy watch test"));
+        assert!(screen.contains("paused"));
+        assert!(screen.contains("● running"));
+        assert!(screen.contains("src/a.ts, src/b.ts, src/c.ts +1"));
+        assert!(screen.contains("✗ exit 2"));
+        assert!(screen.contains("Output · run #2"));
+        assert!(screen.contains("ok 3 tests"));
+        assert!(screen.contains("r rerun · p resume · c clear · q quit"));
+    }
+}
@@ -756,6 +756,7 @@ Built-in Tasks
 - init : Initialize baseline effigy.toml scaffold with dry-run/force controls
 - migrate : Migrate package scripts into [tasks] with preview/apply flow
 - unlock : Manually clear lock scopes (`workspace`, `task:*`, `profile:*/*`)
+- replay : Replay a managed TUI session recorded with `--record <file>`
 
 ";
     assert_eq!(tail, expected);
@@ -901,6 +902,7 @@ Built-in Tasks
 - init : Initialize baseline effigy.toml scaffold with dry-run/force controls
 - migrate : Migrate package scripts into [tasks] with preview/apply flow
 - unlock : Manually clear lock scopes (`workspace`, `task:*`, `profile:*/*`)
+- replay : Replay a managed TUI session recorded with `--record <file>`
 
 ";
     assert_eq!(tail, expected);
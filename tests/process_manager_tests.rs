@@ -1,6 +1,11 @@
-use effigy::process_manager::{ProcessEventKind, ProcessSpec, ProcessSupervisor};
+use effigy::process_manager::{
+    describe_limit_breach, describe_limit_crash, ProcessEventKind, ProcessLimits, ProcessSpec,
+    ProcessStop, ProcessSupervisor, PtySize, ShutdownProgress,
+};
+use effigy::recording::SessionRecording;
 use std::fs;
 use std::path::PathBuf;
+use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[test]
@@ -15,6 +20,10 @@ fn supervisor_captures_output_and_exit_events() {
                 cwd: root.clone(),
                 start_after_ms: 0,
                 pty: false,
+                env: Vec::new(),
+                limits: ProcessLimits::default(),
+                stop: ProcessStop::default(),
+                depends_on: Vec::new(),
             },
             ProcessSpec {
                 name: "beta".to_owned(),
@@ -22,6 +31,10 @@ fn supervisor_captures_output_and_exit_events() {
                 cwd: root.clone(),
                 start_after_ms: 0,
                 pty: false,
+                env: Vec::new(),
+                limits: ProcessLimits::default(),
+                stop: ProcessStop::default(),
+                depends_on: Vec::new(),
             },
         ],
     )
@@ -44,7 +57,10 @@ fn supervisor_captures_output_and_exit_events() {
                         saw_beta_err = true;
                     }
                 }
-                ProcessEventKind::StdoutChunk | ProcessEventKind::StderrChunk => {}
+                ProcessEventKind::StdoutChunk
+                | ProcessEventKind::StderrChunk
+                | ProcessEventKind::Added
+                | ProcessEventKind::Removed => {}
                 ProcessEventKind::Exit => exits += 1,
             }
             if saw_alpha_out && saw_beta_err && exits >= 2 {
@@ -69,6 +85,10 @@ fn supervisor_forwards_input_to_target_process() {
             cwd: root.clone(),
             start_after_ms: 0,
             pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         }],
     )
     .expect("spawn");
@@ -100,6 +120,10 @@ fn supervisor_forwards_input_without_wait_lock_contention() {
             cwd: root.clone(),
             start_after_ms: 0,
             pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         }],
     )
     .expect("spawn");
@@ -145,6 +169,10 @@ fn supervisor_graceful_shutdown_terminates_long_running_process() {
             cwd: root.clone(),
             start_after_ms: 0,
             pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         }],
     )
     .expect("spawn");
@@ -179,6 +207,10 @@ fn supervisor_respects_process_start_delay() {
             cwd: root.clone(),
             start_after_ms: 150,
             pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         }],
     )
     .expect("spawn");
@@ -211,6 +243,10 @@ fn supervisor_can_terminate_individual_process() {
             cwd: root.clone(),
             start_after_ms: 0,
             pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         }],
     )
     .expect("spawn");
@@ -245,6 +281,10 @@ fn supervisor_can_restart_individual_process() {
             cwd: root.clone(),
             start_after_ms: 0,
             pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
         }],
     )
     .expect("spawn");
@@ -283,6 +323,662 @@ fn supervisor_can_restart_individual_process() {
     );
 }
 
+#[test]
+fn supervisor_restarts_slow_stopping_process_without_blocking() {
+    let root = temp_workspace("supervisor-restart-slow-stop");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "stubborn".to_owned(),
+            run: "trap '' TERM; echo booted; while :; do sleep 0.05; done".to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop {
+                timeout_ms: Some(1500),
+                ..ProcessStop::default()
+            },
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+    thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    supervisor
+        .restart_process("stubborn")
+        .expect("restart process");
+    assert!(
+        start.elapsed() < Duration::from_millis(500),
+        "expected restart to return before the stop timeout"
+    );
+    assert!(supervisor.is_stopping("stubborn"));
+    assert_eq!(supervisor.stopping_processes(), vec!["stubborn".to_owned()]);
+    let start = Instant::now();
+    let _ = supervisor.process_ids();
+    assert!(
+        start.elapsed() < Duration::from_millis(500),
+        "expected the process map to stay unlocked while stopping"
+    );
+    let err = supervisor
+        .restart_process("stubborn")
+        .expect_err("second restart while stopping");
+    assert_eq!(err.to_string(), "process `stubborn` is still stopping");
+
+    let mut booted_count = 0usize;
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while booted_count < 2 && Instant::now() < deadline {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(100)) {
+            if event.process == "stubborn" && event.payload.contains("booted") {
+                booted_count += 1;
+            }
+        }
+    }
+    assert_eq!(booted_count, 2, "expected the replacement to start");
+    while supervisor.is_stopping("stubborn") && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    assert!(!supervisor.is_stopping("stubborn"));
+    supervisor.terminate_all();
+}
+
+#[test]
+fn supervisor_replaces_processes_after_stopping_the_old_ones() {
+    let root = temp_workspace("supervisor-replace");
+    let spec = |name: &str, run: &str, cwd: PathBuf| ProcessSpec {
+        name: name.to_owned(),
+        run: run.to_owned(),
+        cwd,
+        start_after_ms: 0,
+        pty: false,
+        env: Vec::new(),
+        limits: ProcessLimits::default(),
+        stop: ProcessStop {
+            timeout_ms: Some(600),
+            ..ProcessStop::default()
+        },
+        depends_on: Vec::new(),
+    };
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![spec(
+            "old",
+            "trap '' TERM; while :; do sleep 0.05; done",
+            root.clone(),
+        )],
+    )
+    .expect("spawn");
+    thread::sleep(Duration::from_millis(200));
+
+    let err = supervisor
+        .replace_processes(
+            &["old".to_owned()],
+            vec![spec("new", "echo new-up", root.join("missing"))],
+        )
+        .expect_err("missing cwd fails before stopping anything");
+    assert!(err.to_string().contains("does not exist"), "{err}");
+    assert!(supervisor.spec("old").is_some());
+    assert!(!supervisor.is_stopping("old"));
+
+    let start = Instant::now();
+    supervisor
+        .replace_processes(
+            &["old".to_owned()],
+            vec![spec("new", "echo new-up; sleep 30", root.clone())],
+        )
+        .expect("replace");
+    assert!(
+        start.elapsed() < Duration::from_millis(400),
+        "expected replace to return before the stop timeout"
+    );
+    assert!(supervisor.spec("old").is_none());
+    assert!(supervisor.is_stopping("old"));
+
+    let mut started_after = None;
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while started_after.is_none() && Instant::now() < deadline {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(100)) {
+            if event.process == "new" && event.payload.contains("new-up") {
+                started_after = Some(start.elapsed());
+            }
+        }
+    }
+    let started_after = started_after.expect("expected the new process to start");
+    assert!(
+        started_after >= Duration::from_millis(600),
+        "expected the new process to wait for the old one's stop, started after {started_after:?}"
+    );
+    supervisor.terminate_all();
+}
+
+#[test]
+fn supervisor_applies_nofile_limit_before_exec() {
+    let root = temp_workspace("supervisor-limits-nofile");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "limited".to_owned(),
+            run: "printf 'nofile:%s\\n' \"$(ulimit -n)\"".to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits {
+                nofile: Some(64),
+                ..ProcessLimits::default()
+            },
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+
+    let mut saw = false;
+    for _ in 0..15 {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(200)) {
+            if event.kind == ProcessEventKind::Stdout && event.payload.contains("nofile:64") {
+                saw = true;
+                break;
+            }
+        }
+    }
+
+    supervisor.terminate_all();
+    assert!(saw, "expected child to observe nofile soft limit");
+}
+
+#[test]
+fn supervisor_reports_cpu_limit_breach_in_exit_diagnostic() {
+    let root = temp_workspace("supervisor-limits-cpu");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "spinner".to_owned(),
+            run: "exec sh -c 'while :; do :; done'".to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits {
+                cpu_seconds: Some(1),
+                ..ProcessLimits::default()
+            },
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut exit_payload = None::<String>;
+    while Instant::now() < deadline {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(200)) {
+            if event.kind == ProcessEventKind::Exit {
+                exit_payload = Some(event.payload);
+                break;
+            }
+        }
+    }
+
+    supervisor.terminate_all();
+    let payload = exit_payload.expect("expected exit event after cpu limit breach");
+    assert!(
+        payload.contains("cpu limit of 1s exceeded"),
+        "unexpected exit payload: {payload}"
+    );
+}
+
+#[test]
+fn supervisor_notes_memory_limit_on_crash_without_claiming_a_breach() {
+    let root = temp_workspace("supervisor-limits-crash");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "crasher".to_owned(),
+            run: "kill -SEGV $$".to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits {
+                memory_mb: Some(2048),
+                ..ProcessLimits::default()
+            },
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut exit_payload = None::<String>;
+    while Instant::now() < deadline {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(200)) {
+            if event.kind == ProcessEventKind::Exit {
+                exit_payload = Some(event.payload);
+                break;
+            }
+        }
+    }
+
+    supervisor.terminate_all();
+    let payload = exit_payload.expect("expected exit event after crash");
+    assert_eq!(
+        payload,
+        "signal=11, crashed with SIGSEGV (a memory limit of 2048MB is set)"
+    );
+}
+
+#[test]
+fn memory_breach_is_only_claimed_when_peak_rss_nears_the_limit() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let limits = ProcessLimits {
+        memory_mb: Some(100),
+        ..ProcessLimits::default()
+    };
+    let segv = std::process::ExitStatus::from_raw(11);
+    let sigkill = std::process::ExitStatus::from_raw(9);
+    let sigterm = std::process::ExitStatus::from_raw(15);
+
+    assert_eq!(describe_limit_breach(segv, limits, None), None);
+    assert_eq!(
+        describe_limit_breach(segv, limits, Some(10 * 1024 * 1024)),
+        None
+    );
+    assert_eq!(
+        describe_limit_breach(sigkill, limits, Some(95 * 1024 * 1024)).as_deref(),
+        Some("memory limit of 100MB likely exceeded (peak rss 95MB)")
+    );
+    assert_eq!(
+        describe_limit_breach(sigterm, limits, Some(99 * 1024 * 1024)),
+        None
+    );
+    assert_eq!(
+        describe_limit_crash(segv, limits).as_deref(),
+        Some("crashed with SIGSEGV (a memory limit of 100MB is set)")
+    );
+    assert_eq!(describe_limit_crash(sigkill, limits), None);
+}
+
+#[test]
+fn supervisor_records_events_that_replay_without_spawning() {
+    let root = temp_workspace("supervisor-record");
+    let recording_path = root.join("session.effigy-rec");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "alpha".to_owned(),
+            run: "printf 'one\\ntwo\\n'; exit 3".to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+    supervisor
+        .record_to(&recording_path, &["alpha".to_owned()])
+        .expect("start recording");
+
+    let mut live = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(100)) {
+            let done = event.kind == ProcessEventKind::Exit;
+            live.push(event);
+            if done {
+                break;
+            }
+        }
+    }
+    assert_eq!(
+        live.last().map(|event| event.payload.as_str()),
+        Some("exit=3")
+    );
+
+    let recording = SessionRecording::load(&recording_path).expect("load recording");
+    assert_eq!(recording.processes, vec!["alpha".to_owned()]);
+    assert_eq!(
+        recording
+            .events
+            .iter()
+            .map(|recorded| recorded.event.clone())
+            .collect::<Vec<_>>(),
+        live
+    );
+
+    let replay = ProcessSupervisor::replay(recording, None);
+    assert!(replay.is_replay());
+    let mut replayed = Vec::new();
+    while let Some(event) = replay.next_event_timeout(Duration::from_millis(200)) {
+        replayed.push(event);
+    }
+    assert_eq!(replayed, live);
+    replay.restart_process("alpha").expect("restart is a no-op");
+    assert!(replay.process_ids().is_empty());
+}
+
+#[test]
+fn supervisor_adds_delayed_process_without_waiting_for_its_start_delay() {
+    let root = temp_workspace("supervisor-add-delayed");
+    let spec = |name: &str, run: &str, start_after_ms: u64| ProcessSpec {
+        name: name.to_owned(),
+        run: run.to_owned(),
+        cwd: root.clone(),
+        start_after_ms,
+        pty: false,
+        env: Vec::new(),
+        limits: ProcessLimits::default(),
+        stop: ProcessStop::default(),
+        depends_on: Vec::new(),
+    };
+    let supervisor = ProcessSupervisor::spawn(root.clone(), vec![spec("sleeper", "sleep 30", 0)])
+        .expect("spawn");
+
+    let started = Instant::now();
+    supervisor
+        .add_process(spec("late", "printf late-out", 5_000))
+        .expect("add process");
+    assert!(
+        started.elapsed() < Duration::from_millis(1_000),
+        "expected add_process to return before the start delay, took {:?}",
+        started.elapsed()
+    );
+    assert!(supervisor.spec("late").is_some());
+    assert!(supervisor
+        .process_ids()
+        .iter()
+        .all(|(name, _)| name != "late"));
+
+    supervisor.terminate_all_graceful(Duration::from_millis(500));
+}
+
+#[test]
+fn supervisor_adds_and_removes_processes_while_running() {
+    let root = temp_workspace("supervisor-add-remove");
+    let spec = |name: &str, run: &str| ProcessSpec {
+        name: name.to_owned(),
+        run: run.to_owned(),
+        cwd: root.clone(),
+        start_after_ms: 0,
+        pty: false,
+        env: Vec::new(),
+        limits: ProcessLimits::default(),
+        stop: ProcessStop::default(),
+        depends_on: Vec::new(),
+    };
+    let supervisor =
+        ProcessSupervisor::spawn(root.clone(), vec![spec("sleeper", "sleep 30")]).expect("spawn");
+
+    supervisor
+        .add_process(spec("late", "printf late-out"))
+        .expect("add process");
+    let err = supervisor
+        .add_process(spec("late", "true"))
+        .expect_err("duplicate name");
+    assert!(err.to_string().contains("`late`"), "{err}");
+
+    let mut first_late_event = None;
+    let mut saw_late_out = false;
+    for _ in 0..20 {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(100)) {
+            if event.process == "late" {
+                first_late_event.get_or_insert(event.kind.clone());
+                if event.payload.contains("late-out") {
+                    saw_late_out = true;
+                    break;
+                }
+            }
+        }
+    }
+    assert_eq!(
+        first_late_event,
+        Some(ProcessEventKind::Added),
+        "the added event should precede the process's output"
+    );
+    assert!(saw_late_out, "expected output from the added process");
+
+    supervisor
+        .remove_process("sleeper")
+        .expect("remove process");
+    let mut saw_removed = false;
+    for _ in 0..20 {
+        if let Some(event) = supervisor.next_event_timeout(Duration::from_millis(100)) {
+            if event.process == "sleeper" && event.kind == ProcessEventKind::Removed {
+                saw_removed = true;
+                break;
+            }
+        }
+    }
+    assert!(
+        saw_removed,
+        "expected a removed event for the closed process"
+    );
+    assert!(supervisor.spec("sleeper").is_none());
+    let names = supervisor
+        .exit_diagnostics()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<String>>();
+    assert_eq!(names, vec!["late".to_owned()]);
+    assert!(supervisor.remove_process("sleeper").is_err());
+}
+
+#[test]
+fn supervisor_runs_pty_processes_on_a_resizable_terminal() {
+    let root = temp_workspace("supervisor-pty");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "tty".to_owned(),
+            run: "test -t 0 && test -t 1 && echo on-tty; echo on-stderr 1>&2; \
+                  IFS= read -r line; stty size"
+                .to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: true,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop::default(),
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+
+    let mut lines = Vec::<String>::new();
+    let mut resized = false;
+    let mut exited = false;
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline && !exited {
+        let Some(event) = supervisor.next_event_timeout(Duration::from_millis(100)) else {
+            continue;
+        };
+        match event.kind {
+            ProcessEventKind::Stdout => lines.push(event.payload),
+            ProcessEventKind::Stderr => panic!("pty output should arrive as stdout"),
+            ProcessEventKind::Exit => exited = true,
+            ProcessEventKind::StdoutChunk
+            | ProcessEventKind::StderrChunk
+            | ProcessEventKind::Added
+            | ProcessEventKind::Removed => {}
+        }
+        if !resized && lines.iter().any(|line| line == "on-stderr") {
+            supervisor.resize_process(
+                "tty",
+                PtySize {
+                    rows: 40,
+                    cols: 132,
+                },
+            );
+            supervisor.send_input("tty", "go\n").expect("send input");
+            resized = true;
+        }
+    }
+
+    assert!(exited, "pty process did not exit: {lines:?}");
+    assert!(lines.iter().any(|line| line == "on-tty"), "{lines:?}");
+    assert!(lines.iter().any(|line| line == "40 132"), "{lines:?}");
+}
+
+#[test]
+fn supervisor_stops_dependents_first_with_their_own_stop_signal() {
+    let root = temp_workspace("supervisor-stop-order");
+    let order = root.join("order.log");
+    let looping = |name: &str, signal: &str| {
+        format!(
+            "trap 'echo {name} >> \"{}\"; exit 0' {signal}; while :; do sleep 0.05; done",
+            order.display()
+        )
+    };
+    let spec = |name: &str, run: String, stop: ProcessStop, depends_on: &[&str]| ProcessSpec {
+        name: name.to_owned(),
+        run,
+        cwd: root.clone(),
+        start_after_ms: 0,
+        pty: false,
+        env: Vec::new(),
+        limits: ProcessLimits::default(),
+        stop,
+        depends_on: depends_on.iter().map(|name| (*name).to_owned()).collect(),
+    };
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![
+            spec("db", looping("db", "TERM"), ProcessStop::default(), &[]),
+            spec(
+                "api",
+                looping("api", "INT"),
+                ProcessStop {
+                    signal: Some("SIGINT".to_owned()),
+                    ..ProcessStop::default()
+                },
+                &["db"],
+            ),
+        ],
+    )
+    .expect("spawn");
+    thread::sleep(Duration::from_millis(200));
+
+    let mut progress = Vec::new();
+    supervisor
+        .terminate_all_graceful_with_progress(Duration::from_secs(3), |event| progress.push(event));
+
+    assert_eq!(fs::read_to_string(&order).expect("read order"), "api\ndb\n");
+    assert_eq!(
+        progress[0],
+        ShutdownProgress::Stopping {
+            process: "api".to_owned(),
+            method: "SIGINT".to_owned(),
+        }
+    );
+    assert!(progress.contains(&ShutdownProgress::Stopped {
+        process: "db".to_owned(),
+        forced: false,
+    }));
+    assert_eq!(
+        progress.last(),
+        Some(&ShutdownProgress::Complete {
+            total: 2,
+            forced: 0
+        })
+    );
+}
+
+#[test]
+fn supervisor_runs_stop_command_instead_of_signalling() {
+    let root = temp_workspace("supervisor-stop-command");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "compose".to_owned(),
+            run: "trap '' TERM; while [ ! -f down.flag ]; do sleep 0.05; done; echo down"
+                .to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop {
+                run: Some("touch down.flag".to_owned()),
+                timeout_ms: Some(2000),
+                ..ProcessStop::default()
+            },
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+
+    let mut progress = Vec::new();
+    supervisor.terminate_all_graceful_with_progress(Duration::from_millis(100), |event| {
+        progress.push(event)
+    });
+
+    assert!(root.join("down.flag").exists());
+    assert!(progress.contains(&ShutdownProgress::Stopping {
+        process: "compose".to_owned(),
+        method: "run `touch down.flag`".to_owned(),
+    }));
+    assert!(progress.contains(&ShutdownProgress::Stopped {
+        process: "compose".to_owned(),
+        forced: false,
+    }));
+    assert_eq!(
+        supervisor.exit_diagnostics(),
+        vec![("compose".to_owned(), "exit=0".to_owned())]
+    );
+}
+
+#[test]
+fn supervisor_sends_stop_signal_after_stop_command() {
+    let root = temp_workspace("supervisor-stop-command-then-signal");
+    let supervisor = ProcessSupervisor::spawn(
+        root.clone(),
+        vec![ProcessSpec {
+            name: "compose".to_owned(),
+            run: "trap 'echo interrupted > stopped.log; exit 0' INT; trap '' TERM; while :; do sleep 0.05; done"
+                .to_owned(),
+            cwd: root.clone(),
+            start_after_ms: 0,
+            pty: false,
+            env: Vec::new(),
+            limits: ProcessLimits::default(),
+            stop: ProcessStop {
+                signal: Some("SIGINT".to_owned()),
+                timeout_ms: Some(10000),
+                run: Some("touch down.flag".to_owned()),
+            },
+            depends_on: Vec::new(),
+        }],
+    )
+    .expect("spawn");
+    thread::sleep(Duration::from_millis(200));
+
+    let mut progress = Vec::new();
+    supervisor.terminate_all_graceful_with_progress(Duration::from_millis(100), |event| {
+        progress.push(event)
+    });
+
+    assert!(root.join("down.flag").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("stopped.log")).expect("read stopped log"),
+        "interrupted\n"
+    );
+    assert!(progress.contains(&ShutdownProgress::Stopping {
+        process: "compose".to_owned(),
+        method: "run `touch down.flag`, then SIGINT".to_owned(),
+    }));
+    assert!(progress.contains(&ShutdownProgress::Stopped {
+        process: "compose".to_owned(),
+        forced: false,
+    }));
+}
+
 fn temp_workspace(name: &str) -> PathBuf {
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)